@@ -1 +1,1149 @@
+//! Prints a parsed program as source code, in a canonical style:
+//!
+//! - keywords are uppercase, names keep the case they were written in
+//! - the bodies of blocks are indented by four spaces
+//! - every statement is on its own line, except for the statement after a
+//!   line number, a single line `IF` and a comment at the end of a line
+//! - comments start with `'`, also the ones that were written with `REM`
+//! - blank lines between statements are kept, but at most one in a row
+//!
+//! Parsing the printed program gives the same program, apart from the locations.
 
+use crate::common::*;
+use crate::lexer::Keyword;
+use crate::parser::*;
+use std::str::FromStr;
+
+const INDENT: &str = "    ";
+
+/// Prints the given program as source code.
+pub fn print_program(program: &ProgramNode) -> String {
+    let mut printer = Printer::default();
+    for (i, top_level_token_node) in program.iter().enumerate() {
+        let pos = top_level_token_node.location();
+        if i > 0 {
+            printer.blank_line_before(pos.row());
+        }
+        printer.top_level_token(top_level_token_node.as_ref(), pos);
+    }
+    printer.out
+}
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+    indent: usize,
+    /// The row in the source code of the current line, so that a comment on the
+    /// same row is printed at the end of the line
+    row: Option<u32>,
+    /// The last known row of the source code that has been printed
+    last_row: u32,
+    /// The number of lines printed after the last known row, e.g. `ELSE` and `END IF`,
+    /// whose rows are not known. They follow the last known row, unless there are
+    /// blank lines before them.
+    unknown_rows: u32,
+    /// The row of a line number, whose statement is printed on the same line
+    line_number_row: Option<u32>,
+}
+
+impl Printer {
+    fn line<S: AsRef<str>>(&mut self, row: Option<u32>, text: S) {
+        if row.is_some() && row == self.line_number_row {
+            self.out.pop();
+            self.out.push(' ');
+        } else {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+        }
+        self.out.push_str(text.as_ref());
+        self.out.push('\n');
+        self.row = row;
+        match row {
+            Some(r) => {
+                self.last_row = r;
+                self.unknown_rows = 0;
+            }
+            None => self.unknown_rows += 1,
+        }
+        self.line_number_row = None;
+    }
+
+    fn comment(&mut self, row: u32, text: &str) {
+        let is_same_row = match self.row {
+            Some(r) => r == row,
+            None => row == self.last_row + self.unknown_rows,
+        };
+        if is_same_row {
+            self.out.pop();
+            self.out.push_str(" '");
+            self.out.push_str(text);
+            self.out.push('\n');
+            self.row = Some(row);
+            self.last_row = row;
+            self.unknown_rows = 0;
+            self.line_number_row = None;
+        } else {
+            self.line(Some(row), format!("'{}", text));
+        }
+    }
+
+    /// Keeps one blank line, if there was at least one before the given row.
+    /// The blank lines at the end of a block are not kept.
+    fn blank_line_before(&mut self, row: u32) {
+        if row > self.last_row + self.unknown_rows + 1 {
+            self.out.push('\n');
+        }
+    }
+
+    fn block(&mut self, statements: &StatementNodes) {
+        self.indent += 1;
+        self.statements(statements);
+        self.indent -= 1;
+    }
+
+    fn statements(&mut self, statements: &StatementNodes) {
+        for (i, statement_node) in statements.iter().enumerate() {
+            let pos = statement_node.location();
+            if i > 0 {
+                self.blank_line_before(pos.row());
+            }
+            self.statement(statement_node.as_ref(), pos);
+        }
+    }
+
+    fn top_level_token(&mut self, top_level_token: &TopLevelToken, pos: Location) {
+        let row = Some(pos.row());
+        match top_level_token {
+            TopLevelToken::DefType(def_type) => self.line(row, def_type_to_string(def_type)),
+            TopLevelToken::FunctionDeclaration(name, params) => self.line(
+                row,
+                format!(
+                    "{} {} {}{}",
+                    Keyword::Declare,
+                    Keyword::Function,
+                    name_to_string(name.as_ref()),
+                    params_to_string(params)
+                ),
+            ),
+            TopLevelToken::FunctionImplementation(name, params, statements) => {
+                self.line(
+                    row,
+                    format!(
+                        "{} {}{}",
+                        Keyword::Function,
+                        name_to_string(name.as_ref()),
+                        params_to_string(params)
+                    ),
+                );
+                self.block(statements);
+                self.line(None, format!("{} {}", Keyword::End, Keyword::Function));
+            }
+            TopLevelToken::OptionBase(base) => self.line(
+                row,
+                format!("{} {} {}", Keyword::Option, Keyword::Base, base),
+            ),
+            TopLevelToken::Statement(statement) => self.statement(statement, pos),
+            TopLevelToken::SubDeclaration(name, params) => self.line(
+                row,
+                format!(
+                    "{} {} {}{}",
+                    Keyword::Declare,
+                    Keyword::Sub,
+                    bare_name_to_string(name.as_ref()),
+                    params_to_string(params)
+                ),
+            ),
+            TopLevelToken::SubImplementation(name, params, statements) => {
+                self.line(
+                    row,
+                    format!(
+                        "{} {}{}",
+                        Keyword::Sub,
+                        bare_name_to_string(name.as_ref()),
+                        params_to_string(params)
+                    ),
+                );
+                self.block(statements);
+                self.line(None, format!("{} {}", Keyword::End, Keyword::Sub));
+            }
+            TopLevelToken::UserDefinedType(user_defined_type) => {
+                self.user_defined_type(user_defined_type, pos)
+            }
+        }
+    }
+
+    fn user_defined_type(&mut self, user_defined_type: &UserDefinedType, pos: Location) {
+        self.line(
+            Some(pos.row()),
+            format!(
+                "{} {}",
+                Keyword::Type,
+                bare_name_to_string(user_defined_type.name.as_ref())
+            ),
+        );
+        // the elements and the comments are printed in the order of the source code
+        let mut lines: Vec<(Location, Option<String>, &str)> = vec![];
+        for element_node in user_defined_type.elements.iter() {
+            let element: &Element = element_node.as_ref();
+            lines.push((
+                element_node.location(),
+                Some(format!(
+                    "{} {} {}",
+                    element.name,
+                    Keyword::As,
+                    element_type_to_string(&element.element_type)
+                )),
+                "",
+            ));
+        }
+        for comment in user_defined_type.comments.iter() {
+            lines.push((comment.location(), None, comment.as_ref()));
+        }
+        lines.sort_by_key(|(pos, _, _)| (pos.row(), pos.col()));
+        self.indent += 1;
+        for (i, (pos, opt_element, comment)) in lines.into_iter().enumerate() {
+            if i > 0 {
+                self.blank_line_before(pos.row());
+            }
+            match opt_element {
+                Some(element) => self.line(Some(pos.row()), element),
+                None => self.comment(pos.row(), comment),
+            }
+        }
+        self.indent -= 1;
+        self.line(None, format!("{} {}", Keyword::End, Keyword::Type));
+    }
+
+    fn statement(&mut self, statement: &Statement, pos: Location) {
+        let row = Some(pos.row());
+        match statement {
+            Statement::IfBlock(i) => self.if_block(i, pos),
+            Statement::SelectCase(s) => self.select_case(s, pos),
+            Statement::ForLoop(f) => {
+                let mut header = format!(
+                    "{} {} = {} {} {}",
+                    Keyword::For,
+                    name_to_string(f.variable_name.as_ref()),
+                    expression_to_string(&f.lower_bound),
+                    Keyword::To,
+                    expression_to_string(&f.upper_bound)
+                );
+                if let Some(step) = &f.step {
+                    header.push_str(&format!(
+                        " {} {}",
+                        Keyword::Step,
+                        expression_to_string(step)
+                    ));
+                }
+                self.line(row, header);
+                self.block(&f.statements);
+                match &f.next_counter {
+                    Some(counter) => self.line(
+                        None,
+                        format!("{} {}", Keyword::Next, name_to_string(counter.as_ref())),
+                    ),
+                    None => self.line(None, Keyword::Next.to_string()),
+                }
+            }
+            Statement::While(w) => {
+                self.line(
+                    row,
+                    format!("{} {}", Keyword::While, expression_to_string(&w.condition)),
+                );
+                self.block(&w.statements);
+                self.line(None, Keyword::Wend.to_string());
+            }
+            Statement::DoLoop(d) => {
+                let condition = d.condition.as_ref().map(|c| {
+                    let keyword = match c.kind {
+                        DoLoopConditionKind::While => Keyword::While,
+                        DoLoopConditionKind::Until => Keyword::Until,
+                    };
+                    (
+                        c.position,
+                        format!(" {} {}", keyword, expression_to_string(&c.expr)),
+                    )
+                });
+                match &condition {
+                    Some((DoLoopConditionPosition::Top, c)) => {
+                        self.line(row, format!("{}{}", Keyword::Do, c))
+                    }
+                    _ => self.line(row, Keyword::Do.to_string()),
+                }
+                self.block(&d.statements);
+                match &condition {
+                    Some((DoLoopConditionPosition::Bottom, c)) => {
+                        self.line(None, format!("{}{}", Keyword::Loop, c))
+                    }
+                    _ => self.line(None, Keyword::Loop.to_string()),
+                }
+            }
+            Statement::Label(name) if pos.line_number().is_some() => {
+                // a line number, the rest of the line is the next statement
+                self.line(row, name);
+                self.line_number_row = row;
+            }
+            Statement::Comment(text) => self.comment(pos.row(), text),
+            _ => self.line(row, simple_statement_to_string(statement)),
+        }
+    }
+
+    fn if_block(&mut self, if_block: &IfBlockNode, pos: Location) {
+        let row = Some(pos.row());
+        let condition = expression_to_string(&if_block.if_block.condition);
+        let statements = &if_block.if_block.statements;
+        let is_on_if_row = |statements: &StatementNodes| {
+            statements.len() == 1 && statements[0].location().row() == pos.row()
+        };
+        let is_single_line = if_block.else_if_blocks.is_empty()
+            && is_on_if_row(statements)
+            && match &if_block.else_block {
+                Some(else_block) => is_on_if_row(else_block),
+                None => true,
+            };
+        if is_single_line {
+            let mut line = format!(
+                "{} {} {} {}",
+                Keyword::If,
+                condition,
+                Keyword::Then,
+                simple_statement_to_string(statements[0].as_ref())
+            );
+            if let Some(else_block) = &if_block.else_block {
+                line.push_str(&format!(
+                    " {} {}",
+                    Keyword::Else,
+                    simple_statement_to_string(else_block[0].as_ref())
+                ));
+            }
+            self.line(row, line);
+            return;
+        }
+        self.line(
+            row,
+            format!("{} {} {}", Keyword::If, condition, Keyword::Then),
+        );
+        self.block(statements);
+        for else_if_block in if_block.else_if_blocks.iter() {
+            self.line(
+                Some(else_if_block.condition.location().row()),
+                format!(
+                    "{} {} {}",
+                    Keyword::ElseIf,
+                    expression_to_string(&else_if_block.condition),
+                    Keyword::Then
+                ),
+            );
+            self.block(&else_if_block.statements);
+        }
+        if let Some(else_block) = &if_block.else_block {
+            self.line(None, Keyword::Else.to_string());
+            self.block(else_block);
+        }
+        self.line(None, format!("{} {}", Keyword::End, Keyword::If));
+    }
+
+    fn select_case(&mut self, select_case: &SelectCaseNode, pos: Location) {
+        self.line(
+            Some(pos.row()),
+            format!(
+                "{} {} {}",
+                Keyword::Select,
+                Keyword::Case,
+                expression_to_string(&select_case.expr)
+            ),
+        );
+        self.indent += 1;
+        self.statements(&select_case.comments);
+        for case_block in select_case.case_blocks.iter() {
+            let (case_expr, case_pos) = match &case_block.expr {
+                CaseExpression::Simple(e) => (expression_to_string(e), e.location()),
+                CaseExpression::Is(op, e) => (
+                    format!(
+                        "{} {} {}",
+                        Keyword::Is,
+                        operand_to_string(*op),
+                        expression_to_string(e)
+                    ),
+                    e.location(),
+                ),
+                CaseExpression::Range(from, to) => (
+                    format!(
+                        "{} {} {}",
+                        expression_to_string(from),
+                        Keyword::To,
+                        expression_to_string(to)
+                    ),
+                    from.location(),
+                ),
+            };
+            self.line(
+                Some(case_pos.row()),
+                format!("{} {}", Keyword::Case, case_expr),
+            );
+            self.block(&case_block.statements);
+        }
+        if let Some(else_block) = &select_case.else_block {
+            self.line(None, format!("{} {}", Keyword::Case, Keyword::Else));
+            self.block(else_block);
+        }
+        self.indent -= 1;
+        self.line(None, format!("{} {}", Keyword::End, Keyword::Select));
+    }
+}
+
+/// Prints a statement that fits in one line, e.g. an assignment or a sub call.
+fn simple_statement_to_string(statement: &Statement) -> String {
+    match statement {
+        Statement::Assignment(name, e) => {
+            format!("{} = {}", name_to_string(name), expression_to_string(e))
+        }
+        Statement::ArrayElementAssignment(name, indices, e) => format!(
+            "{}({}) = {}",
+            name_to_string(name),
+            expressions_to_string(indices),
+            expression_to_string(e)
+        ),
+        Statement::MemberAssignment(left_side, right_side) => format!(
+            "{} = {}",
+            expression_to_string(left_side),
+            expression_to_string(right_side)
+        ),
+        Statement::Const(name, e) => format!(
+            "{} {} = {}",
+            Keyword::Const,
+            name_to_string(name.as_ref()),
+            expression_to_string(e)
+        ),
+        Statement::Dim(dim_names) => format!("{} {}", Keyword::Dim, dim_names_to_string(dim_names)),
+        Statement::ReDim(dim_names) => {
+            format!("{} {}", Keyword::ReDim, dim_names_to_string(dim_names))
+        }
+        Statement::Erase(names) => format!(
+            "{} {}",
+            Keyword::Erase,
+            names
+                .iter()
+                .map(|n| name_to_string(n.as_ref()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        Statement::SubCall(name, args) => sub_call_to_string(name, args),
+        Statement::Exit(ExitObject::Do) => format!("{} {}", Keyword::Exit, Keyword::Do),
+        Statement::Exit(ExitObject::For) => format!("{} {}", Keyword::Exit, Keyword::For),
+        Statement::ErrorHandler(opt_label) => format!(
+            "{} {} {} {}",
+            Keyword::On,
+            Keyword::Error,
+            Keyword::GoTo,
+            opt_label
+                .as_ref()
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| "0".to_string())
+        ),
+        Statement::Error(e) => format!("{} {}", Keyword::Error, expression_to_string(e)),
+        Statement::Resume(ResumeOption::Bare) => Keyword::Resume.to_string(),
+        Statement::Resume(ResumeOption::Next) => format!("{} {}", Keyword::Resume, Keyword::Next),
+        Statement::Resume(ResumeOption::Label(label)) => {
+            format!("{} {}", Keyword::Resume, label)
+        }
+        Statement::Label(name) => format!("{}:", name),
+        Statement::GoTo(label) => format!("{} {}", Keyword::GoTo, label),
+        Statement::GoSub(label) => format!("{} {}", Keyword::GoSub, label),
+        Statement::Return(None) => Keyword::Return.to_string(),
+        Statement::Return(Some(label)) => format!("{} {}", Keyword::Return, label),
+        Statement::OnGoTo(e, labels) => on_to_string(e, Keyword::GoTo, labels),
+        Statement::OnGoSub(e, labels) => on_to_string(e, Keyword::GoSub, labels),
+        Statement::Comment(text) => format!("'{}", text),
+        Statement::IfBlock(_)
+        | Statement::SelectCase(_)
+        | Statement::ForLoop(_)
+        | Statement::While(_)
+        | Statement::DoLoop(_) => panic!("Block statement is not a simple statement"),
+    }
+}
+
+fn on_to_string(e: &ExpressionNode, keyword: Keyword, labels: &[CaseInsensitiveString]) -> String {
+    format!(
+        "{} {} {} {}",
+        Keyword::On,
+        expression_to_string(e),
+        keyword,
+        labels
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Prints a sub call. The parser turns some statements into calls of built-in subs,
+/// e.g. `PRINT` and `OPEN`, whose syntax is restored from their arguments.
+fn sub_call_to_string(name: &BareName, args: &[ExpressionNode]) -> String {
+    let name = bare_name_to_string(name);
+    match name.to_uppercase().as_str() {
+        "PRINT" | "LPRINT" => print_to_string(&name, args),
+        "INPUT" if matches!(args[0].as_ref(), Expression::FileHandle(_)) => {
+            format!("{} {}", name, expressions_to_string(args))
+        }
+        "INPUT" => {
+            let variables = expressions_to_string(&args[2..]);
+            match InputPrompt::from(integer_arg(&args[0])) {
+                InputPrompt::QuestionMark => format!("{} {}", name, variables),
+                InputPrompt::PromptWithQuestionMark => {
+                    format!("{} {}; {}", name, expression_to_string(&args[1]), variables)
+                }
+                InputPrompt::Prompt => {
+                    format!("{} {}, {}", name, expression_to_string(&args[1]), variables)
+                }
+            }
+        }
+        "LINE INPUT" => {
+            let line_input = format!("{} {}", Keyword::Line, Keyword::Input);
+            match args[0].as_ref() {
+                Expression::FileHandle(_) => {
+                    format!("{} {}", line_input, expressions_to_string(args))
+                }
+                Expression::StringLiteral(prompt) if prompt.is_empty() => {
+                    format!("{} {}", line_input, expression_to_string(&args[1]))
+                }
+                _ => format!(
+                    "{} {}; {}",
+                    line_input,
+                    expression_to_string(&args[0]),
+                    expression_to_string(&args[1])
+                ),
+            }
+        }
+        "OPEN" => {
+            let mode = match FileMode::from(integer_arg(&args[1])) {
+                FileMode::Input => Keyword::Input,
+                FileMode::Output => Keyword::Output,
+                FileMode::Append => Keyword::Append,
+                FileMode::Random => Keyword::Random,
+                FileMode::Binary => Keyword::Binary,
+            };
+            let mut s = format!(
+                "{} {} {} {}",
+                Keyword::Open,
+                expression_to_string(&args[0]),
+                Keyword::For,
+                mode
+            );
+            match FileAccess::from(integer_arg(&args[2])) {
+                FileAccess::Unspecified => {}
+                FileAccess::Read => s.push_str(&format!(" {} {}", Keyword::Access, Keyword::Read)),
+                FileAccess::Write => {
+                    s.push_str(&format!(" {} {}", Keyword::Access, Keyword::Write))
+                }
+                FileAccess::ReadWrite => s.push_str(&format!(
+                    " {} {} {}",
+                    Keyword::Access,
+                    Keyword::Read,
+                    Keyword::Write
+                )),
+            }
+            s.push_str(&format!(
+                " {} {}",
+                Keyword::As,
+                expression_to_string(&args[3])
+            ));
+            if !is_zero(&args[4]) {
+                s.push_str(&format!(" LEN = {}", expression_to_string(&args[4])));
+            }
+            s
+        }
+        "FIELD" => {
+            let fields: Vec<String> = args[1..]
+                .chunks(2)
+                .map(|pair| {
+                    format!(
+                        "{} {} {}",
+                        expression_to_string(&pair[0]),
+                        Keyword::As,
+                        expression_to_string(&pair[1])
+                    )
+                })
+                .collect();
+            format!(
+                "{} {}, {}",
+                Keyword::Field,
+                expression_to_string(&args[0]),
+                fields.join(", ")
+            )
+        }
+        "GET" | "PUT" => {
+            let (record, opt_variable) = if is_zero(&args[1]) {
+                (String::new(), args.get(2))
+            } else {
+                (expression_to_string(&args[2]), args.get(3))
+            };
+            match opt_variable {
+                Some(variable) => format!(
+                    "{} {}, {}, {}",
+                    name,
+                    expression_to_string(&args[0]),
+                    record,
+                    expression_to_string(variable)
+                ),
+                None if record.is_empty() => {
+                    format!("{} {}", name, expression_to_string(&args[0]))
+                }
+                None => format!("{} {}, {}", name, expression_to_string(&args[0]), record),
+            }
+        }
+        "MID$" => {
+            // the parser moves the value after the variable
+            let mut mid_args: Vec<&ExpressionNode> = vec![&args[0]];
+            mid_args.extend(args[2..].iter());
+            let mid_args: Vec<String> = mid_args.into_iter().map(expression_to_string).collect();
+            format!(
+                "{}({}) = {}",
+                name,
+                mid_args.join(", "),
+                expression_to_string(&args[1])
+            )
+        }
+        "NAME" => format!(
+            "{} {} AS {}",
+            name,
+            expression_to_string(&args[0]),
+            expression_to_string(&args[1])
+        ),
+        "LSET" | "RSET" => format!(
+            "{} {} = {}",
+            name,
+            expression_to_string(&args[0]),
+            expression_to_string(&args[1])
+        ),
+        _ if args.is_empty() => name,
+        _ => format!("{} {}", name, expressions_to_string(args)),
+    }
+}
+
+fn print_to_string(name: &str, args: &[ExpressionNode]) -> String {
+    let mut s = name.to_string();
+    let mut args = args.iter();
+    let mut printed_args = String::new();
+    while let Some(arg) = args.next() {
+        let printed_arg = match arg.as_ref() {
+            Expression::FileHandle(_) => {
+                s.push_str(&format!(" {},", expression_to_string(arg)));
+                continue;
+            }
+            _ => match PrintArg::from(integer_arg(arg)) {
+                PrintArg::Comma => {
+                    printed_args.push(',');
+                    continue;
+                }
+                PrintArg::Semicolon => {
+                    printed_args.push(';');
+                    continue;
+                }
+                PrintArg::Expression => expression_to_string(args.next().unwrap()),
+                PrintArg::Tab => format!("TAB({})", expression_to_string(args.next().unwrap())),
+                PrintArg::Spc => format!("SPC({})", expression_to_string(args.next().unwrap())),
+                PrintArg::Using => format!(
+                    "{} {};",
+                    Keyword::Using,
+                    expression_to_string(args.next().unwrap())
+                ),
+            },
+        };
+        if !printed_args.is_empty() {
+            printed_args.push(' ');
+        }
+        printed_args.push_str(&printed_arg);
+    }
+    if !printed_args.is_empty() {
+        s.push(' ');
+        s.push_str(&printed_args);
+    }
+    s
+}
+
+fn integer_arg(arg: &ExpressionNode) -> i32 {
+    match arg.as_ref() {
+        Expression::IntegerLiteral(i) => *i,
+        _ => panic!("Expected integer literal argument"),
+    }
+}
+
+fn is_zero(arg: &ExpressionNode) -> bool {
+    matches!(arg.as_ref(), Expression::IntegerLiteral(0))
+}
+
+fn expressions_to_string(expressions: &[ExpressionNode]) -> String {
+    expressions
+        .iter()
+        .map(expression_to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Prints an expression. The parentheses of the source code are part of the
+/// expression, so printing the operands in order keeps the priority of the operators.
+fn expression_to_string(expression: &ExpressionNode) -> String {
+    match expression.as_ref() {
+        Expression::SingleLiteral(f) => floating_point_to_string(f.to_string()),
+        Expression::DoubleLiteral(f) => {
+            if f.fract() == 0.0 && *f > crate::variant::MAX_LONG as f64 && *f <= u32::MAX as f64 {
+                // an integer that is too big for a long
+                format!("{}", f)
+            } else {
+                format!("{}#", floating_point_to_string(f.to_string()))
+            }
+        }
+        Expression::StringLiteral(s) => format!("\"{}\"", s),
+        Expression::IntegerLiteral(i) => i.to_string(),
+        Expression::LongLiteral(i) => i.to_string(),
+        Expression::VariableName(name) => name_to_string(name),
+        Expression::FunctionCall(name, args) => match args.get(1) {
+            // the file number of INPUT$ is parsed without the #
+            Some(file_number) if name.bare_name() == "INPUT" => format!(
+                "{}({}, #{})",
+                name_to_string(name),
+                expression_to_string(&args[0]),
+                expression_to_string(file_number)
+            ),
+            _ => format!("{}({})", name_to_string(name), expressions_to_string(args)),
+        },
+        Expression::Member(base, name) => {
+            format!("{}.{}", expression_to_string(base), name_to_string(name))
+        }
+        Expression::BinaryExpression(op, left, right) => format!(
+            "{} {} {}",
+            expression_to_string(left),
+            operand_to_string(*op),
+            expression_to_string(right)
+        ),
+        Expression::UnaryExpression(UnaryOperand::Minus, child) => {
+            format!("-{}", expression_to_string(child))
+        }
+        Expression::UnaryExpression(UnaryOperand::Not, child) => {
+            format!("{} {}", Keyword::Not, expression_to_string(child))
+        }
+        Expression::Parenthesis(child) => format!("({})", expression_to_string(child)),
+        Expression::FileHandle(child) => format!("#{}", expression_to_string(child)),
+    }
+}
+
+/// Adds the decimal point that a whole number is printed without,
+/// which makes it a floating point literal.
+fn floating_point_to_string(s: String) -> String {
+    if s.contains('.') {
+        s
+    } else {
+        format!("{}.0", s)
+    }
+}
+
+fn operand_to_string(op: Operand) -> String {
+    match op {
+        Operand::Less => "<".to_string(),
+        Operand::LessOrEqual => "<=".to_string(),
+        Operand::Equal => "=".to_string(),
+        Operand::GreaterOrEqual => ">=".to_string(),
+        Operand::Greater => ">".to_string(),
+        Operand::NotEqual => "<>".to_string(),
+        Operand::Plus => "+".to_string(),
+        Operand::Minus => "-".to_string(),
+        Operand::Multiply => "*".to_string(),
+        Operand::Divide => "/".to_string(),
+        Operand::IntegerDivide => "\\".to_string(),
+        Operand::Modulo => Keyword::Mod.to_string(),
+        Operand::Power => "^".to_string(),
+        Operand::And => Keyword::And.to_string(),
+        Operand::Or => Keyword::Or.to_string(),
+        Operand::Xor => Keyword::Xor.to_string(),
+        Operand::Eqv => Keyword::Eqv.to_string(),
+        Operand::Imp => Keyword::Imp.to_string(),
+    }
+}
+
+/// Prints a name as it was written, unless it is a keyword,
+/// e.g. the functions `INPUT$` and `STRING$` or the statement `PRINT`.
+fn bare_name_to_string(bare_name: &BareName) -> String {
+    match Keyword::from_str(bare_name.as_ref()) {
+        Ok(keyword) => keyword.to_string(),
+        Err(_) => bare_name.to_string(),
+    }
+}
+
+fn name_to_string(name: &Name) -> String {
+    match name.opt_qualifier() {
+        Some(q) => format!("{}{}", bare_name_to_string(name.bare_name()), q),
+        None => bare_name_to_string(name.bare_name()),
+    }
+}
+
+fn type_keyword(q: TypeQualifier) -> Keyword {
+    match q {
+        TypeQualifier::BangSingle => Keyword::Single,
+        TypeQualifier::HashDouble => Keyword::Double,
+        TypeQualifier::DollarString => Keyword::String,
+        TypeQualifier::PercentInteger => Keyword::Integer,
+        TypeQualifier::AmpersandLong => Keyword::Long,
+    }
+}
+
+fn dim_type_to_string(dim_type: &DimType) -> String {
+    match dim_type {
+        DimType::Bare => String::new(),
+        DimType::BuiltIn(q) => format!(" {} {}", Keyword::As, type_keyword(*q)),
+        DimType::FixedLengthString(len) => {
+            format!(" {} {} * {}", Keyword::As, Keyword::String, len)
+        }
+        DimType::UserDefined(type_name) => {
+            format!(
+                " {} {}",
+                Keyword::As,
+                bare_name_to_string(type_name.as_ref())
+            )
+        }
+    }
+}
+
+fn dim_names_to_string(dim_names: &[DimNameNode]) -> String {
+    dim_names
+        .iter()
+        .map(|dim_name_node| {
+            let dim_name: &DimName = dim_name_node.as_ref();
+            let mut s = name_to_string(&dim_name.name);
+            if !dim_name.dimensions.is_empty() {
+                let dimensions: Vec<String> = dim_name
+                    .dimensions
+                    .iter()
+                    .map(|d| match &d.lbound {
+                        Some(lbound) => format!(
+                            "{} {} {}",
+                            expression_to_string(lbound),
+                            Keyword::To,
+                            expression_to_string(&d.ubound)
+                        ),
+                        None => expression_to_string(&d.ubound),
+                    })
+                    .collect();
+                s.push_str(&format!("({})", dimensions.join(", ")));
+            }
+            s.push_str(&dim_type_to_string(&dim_name.dim_type));
+            s
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn params_to_string(params: &ParamNodes) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = params
+        .iter()
+        .map(|param_node| {
+            let param: &ParamName = param_node.as_ref();
+            format!(
+                "{}{}{}",
+                name_to_string(&param.name),
+                if param.is_array { "()" } else { "" },
+                dim_type_to_string(&param.param_type)
+            )
+        })
+        .collect();
+    format!(" ({})", params.join(", "))
+}
+
+fn element_type_to_string(element_type: &ElementType) -> String {
+    match element_type {
+        ElementType::BuiltIn(q) => type_keyword(*q).to_string(),
+        ElementType::FixedLengthString(len) => format!("{} * {}", Keyword::String, len),
+        ElementType::UserDefined(type_name) => bare_name_to_string(type_name.as_ref()),
+    }
+}
+
+fn def_type_to_string(def_type: &DefType) -> String {
+    let keyword = match def_type.qualifier() {
+        TypeQualifier::BangSingle => Keyword::DefSng,
+        TypeQualifier::HashDouble => Keyword::DefDbl,
+        TypeQualifier::DollarString => Keyword::DefStr,
+        TypeQualifier::PercentInteger => Keyword::DefInt,
+        TypeQualifier::AmpersandLong => Keyword::DefLng,
+    };
+    let ranges: Vec<String> = def_type
+        .ranges()
+        .iter()
+        .map(|r| match r {
+            LetterRange::Single(c) => c.to_string(),
+            LetterRange::Range(first, last) => format!("{}-{}", first, last),
+        })
+        .collect();
+    format!("{} {}", keyword, ranges.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use std::fs::File;
+
+    fn parse(input: &str) -> ProgramNode {
+        Parser::from(input)
+            .parse()
+            .expect("Could not parse program")
+    }
+
+    fn format(input: &str) -> String {
+        print_program(&parse(input))
+    }
+
+    /// Prints the program without the locations, in order to compare programs
+    /// that are written differently.
+    fn without_locations(program: &ProgramNode) -> String {
+        const LOCATION: &str = ", location: Location {";
+        let mut s = format!("{:?}", program);
+        while let Some(start) = s.find(LOCATION) {
+            let end = start + s[start..].find('}').unwrap() + 1;
+            s.replace_range(start..end, "");
+        }
+        s
+    }
+
+    fn assert_round_trip(input: &str) {
+        let formatted = format(input);
+        assert_eq!(
+            without_locations(&parse(&formatted)),
+            without_locations(&parse(input)),
+            "{}",
+            formatted
+        );
+        assert_eq!(format(&formatted), formatted);
+    }
+
+    #[test]
+    fn test_keywords_and_indentation() {
+        let input = r#"
+dim a as integer
+for i = 1 to 3 step 1
+if i mod 2 = 0 then
+print "even"; i
+elseif i = 1 then
+  while a < i
+          a = a + 1
+  wend
+else
+do
+a = a - 1
+loop until a <= 0
+end if
+  next i
+select case a
+case 1
+print "one"
+case is > 1
+print "more"
+case 2 to 3
+case else
+print "none"
+end select
+sub Hello (n$)
+print "Hello "; n$
+end sub
+"#;
+        assert_eq!(
+            format(input),
+            r#"DIM a AS INTEGER
+FOR i = 1 TO 3 STEP 1
+    IF i MOD 2 = 0 THEN
+        PRINT "even"; i
+    ELSEIF i = 1 THEN
+        WHILE a < i
+            a = a + 1
+        WEND
+    ELSE
+        DO
+            a = a - 1
+        LOOP UNTIL a <= 0
+    END IF
+NEXT i
+SELECT CASE a
+    CASE 1
+        PRINT "one"
+    CASE IS > 1
+        PRINT "more"
+    CASE 2 TO 3
+    CASE ELSE
+        PRINT "none"
+END SELECT
+SUB Hello (n$)
+    PRINT "Hello "; n$
+END SUB
+"#
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = r#"REM first
+' second
+x = 1 REM trailing
+IF x THEN ' after THEN
+  ' inside
+ELSE   '   else
+END IF ' end
+SELECT CASE x ' select
+' before CASE
+CASE 1
+END SELECT
+TYPE Card
+  ' suit
+  Suit AS STRING * 9 ' trailing
+END TYPE
+PRINT "'REM" ' "quoted"
+"#;
+        assert_eq!(
+            format(input),
+            r#"' first
+' second
+x = 1 ' trailing
+IF x THEN ' after THEN
+    ' inside
+ELSE '   else
+END IF ' end
+SELECT CASE x ' select
+    ' before CASE
+    CASE 1
+END SELECT
+TYPE Card
+    ' suit
+    Suit AS STRING * 9 ' trailing
+END TYPE
+PRINT "'REM" ' "quoted"
+"#
+        );
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let input = "A = 1\n\n\n\nB = 2\nIF A THEN\n\n    B = 3\n\nEND IF\n\nC = 4\n";
+        assert_eq!(
+            format(input),
+            "A = 1\n\nB = 2\nIF A THEN\n    B = 3\nEND IF\n\nC = 4\n"
+        );
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let input = "10 print \"hi\"\n20 goto 10 ' again\n30 REM done\n";
+        assert_eq!(
+            format(input),
+            "10 PRINT \"hi\"\n20 GOTO 10 ' again\n30 ' done\n"
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(
+            format("A = 1.0 + .5 + 2.50# + 3000000000 + -32768"),
+            "A = 1.0 + 0.5 + 2.5# + 3000000000 + -32768\n"
+        );
+    }
+
+    #[test]
+    fn test_built_in_statements() {
+        let input = r###"print #1, "a";tab(5);spc(2);b$,
+print using "##.##";1.5;2
+input "name";b$
+input a,b
+line input "prompt";b$
+line input #1,b$
+input #1,a,b$
+open "f.txt" for random access read write as 1 len=128
+close 1,#2
+field #1,10 as b$,20 as c$
+get #1,,p
+put #1,2
+lset b$="a"
+name "a.txt" as "b.txt"
+KILL "b.txt"
+x=string$(3,"a")+input$(2,1)
+"###;
+        assert_eq!(
+            format(input),
+            r###"PRINT #1, "a"; TAB(5); SPC(2); b$,
+PRINT USING "##.##"; 1.5; 2
+INPUT "name"; b$
+INPUT a, b
+LINE INPUT "prompt"; b$
+LINE INPUT #1, b$
+INPUT #1, a, b$
+OPEN "f.txt" FOR RANDOM ACCESS READ WRITE AS #1 LEN = 128
+CLOSE #1, #2
+FIELD #1, 10 AS b$, 20 AS c$
+GET #1, , p
+PUT #1, 2
+LSET b$ = "a"
+NAME "a.txt" AS "b.txt"
+KILL "b.txt"
+x = STRING$(3, "a") + INPUT$(2, #1)
+"###
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip(
+            r#"DEFINT A-C, Z
+OPTION BASE 1
+DECLARE FUNCTION Add% (X%, Y%)
+DECLARE SUB Greet (N$, Values() AS INTEGER)
+TYPE Point ' a point
+    X AS SINGLE
+    Other AS Card
+END TYPE
+CONST Pi# = 3.14#
+DIM A(1 TO 5, 10) AS INTEGER, B$, P AS Point
+REDIM A(3)
+ERASE A
+A(1) = -5 + (2 * 3) ^ 2 MOD 4 \ 1 / 2.0 - -(1)
+P.X = 1.5
+A(1).X = 2
+MID$(B$, 1, 1) = "z"
+MID$(B$, 2) = LEFT$(B$, 1)
+IF NOT A(1) = 3 AND B$ <> "y" OR 1 XOR 0 EQV 1 IMP 0 THEN PRINT "yes" ' single
+IF A THEN 100
+IF A THEN B$ = "y" ELSE PRINT "n"; B$
+IF A THEN 100 ELSE 200
+ON ERROR GOTO Handler
+ON X GOTO 10, 20
+ON X GOSUB Alpha
+ERROR 5
+GOSUB Alpha
+100 RETURN Alpha
+Handler:
+RESUME NEXT
+RESUME 0
+FOR I = 10 TO 1 STEP -1
+    EXIT FOR
+NEXT
+DO
+    I = I + 1
+LOOP WHILE I < 3
+Log ("message")
+WRITE
+FUNCTION Add% (X%, Y%)
+    Add% = X% + Y%
+END FUNCTION
+"#,
+        );
+    }
+
+    #[test]
+    fn test_round_trip_fixtures() {
+        for entry in std::fs::read_dir("fixtures").unwrap() {
+            let path = entry.unwrap().path();
+            let mut input = String::new();
+            std::io::Read::read_to_string(&mut File::open(&path).unwrap(), &mut input).unwrap();
+            assert_round_trip(&input);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+use basic_interpreter_rust::Program;
+
+const USAGE: &str = "Usage: basfmt [--check] [file.bas]...
+
+Formats the given files in place. Without files, it formats the standard input
+to the standard output. With --check, the files are not changed, but the ones
+that are not formatted are listed and the exit code is 1.";
+
+fn main() {
+    let mut check = false;
+    let mut filenames: Vec<String> = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => filenames.push(arg),
+        }
+    }
+    if filenames.is_empty() {
+        let mut source = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut source) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+        match format(&source) {
+            Ok(formatted) if check && formatted != source => {
+                eprintln!("The standard input is not formatted");
+                process::exit(1);
+            }
+            Ok(formatted) if !check => print!("{}", formatted),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+        return;
+    }
+    let mut exit_code = 0;
+    for filename in filenames.iter() {
+        match format_file(filename, check) {
+            Ok(true) => {}
+            Ok(false) => {
+                println!("{}", filename);
+                exit_code = exit_code.max(1);
+            }
+            Err(e) => {
+                eprintln!("{}: {}", filename, e);
+                exit_code = 2;
+            }
+        }
+    }
+    process::exit(exit_code);
+}
+
+fn format(source: &str) -> Result<String, String> {
+    Program::parse(source.as_bytes())
+        .map(|program| program.format())
+        .map_err(|e| e.to_string())
+}
+
+/// Formats a file, unless only checking it.
+/// Returns whether the file was already formatted.
+fn format_file(filename: &str, check: bool) -> Result<bool, String> {
+    let source = fs::read_to_string(filename).map_err(|e| e.to_string())?;
+    let formatted = format(&source)?;
+    if formatted == source {
+        Ok(true)
+    } else if check {
+        Ok(false)
+    } else {
+        fs::write(filename, formatted).map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,7 @@
+use std::io::{self, BufReader};
+
+use basic_interpreter_rust::LspServer;
+
+fn main() {
+    LspServer::new(BufReader::new(io::stdin()), io::stdout()).run();
+}
@@ -9,6 +9,9 @@ use crate::variant::Variant;
 // 3. casting from an integer to float will produce the floating point representation of the integer, rounded if necessary (rounding to nearest, ties to even)
 // 4. casting from an f64 to an f32 will produce the closest possible value (rounding to nearest, ties to even)
 
+// QBasic rounds to the nearest integer with ties to even (banker's rounding),
+// e.g. `CINT(2.5)` is 2 and `CINT(3.5)` is 4.
+
 trait QBNumberCast<T> {
     fn try_cast(&self) -> Result<T, String>;
 }
@@ -22,7 +25,7 @@ impl QBNumberCast<f64> for f32 {
 impl QBNumberCast<i32> for f32 {
     fn try_cast(&self) -> Result<i32, String> {
         if self.is_finite() {
-            let r = self.round();
+            let r = self.round_ties_even();
             if r >= (variant::MIN_INTEGER as f32) && r <= (variant::MAX_INTEGER as f32) {
                 Ok(r as i32)
             } else {
@@ -37,7 +40,7 @@ impl QBNumberCast<i32> for f32 {
 impl QBNumberCast<i64> for f32 {
     fn try_cast(&self) -> Result<i64, String> {
         if self.is_finite() {
-            let r = self.round();
+            let r = self.round_ties_even();
             if r >= (variant::MIN_LONG as f32) && r <= (variant::MAX_LONG as f32) {
                 Ok(r as i64)
             } else {
@@ -58,7 +61,7 @@ impl QBNumberCast<f32> for f64 {
 impl QBNumberCast<i32> for f64 {
     fn try_cast(&self) -> Result<i32, String> {
         if self.is_finite() {
-            let r = self.round();
+            let r = self.round_ties_even();
             if r >= (variant::MIN_INTEGER as f64) && r <= (variant::MAX_INTEGER as f64) {
                 Ok(r as i32)
             } else {
@@ -73,7 +76,7 @@ impl QBNumberCast<i32> for f64 {
 impl QBNumberCast<i64> for f64 {
     fn try_cast(&self) -> Result<i64, String> {
         if self.is_finite() {
-            let r = self.round();
+            let r = self.round_ties_even();
             if r >= (variant::MIN_LONG as f64) && r <= (variant::MAX_LONG as f64) {
                 Ok(r as i64)
             } else {
@@ -159,6 +162,16 @@ pub fn cast(value: Variant, target_type: TypeQualifier) -> Result<Variant, Strin
             TypeQualifier::PercentInteger => Ok(Variant::VInteger(f.try_cast()?)),
             TypeQualifier::AmpersandLong => Ok(value),
         },
+        // the linter ensures that only the same user defined type can be assigned
+        Variant::VUserDefined(_) => Ok(value),
+        Variant::VArray(ref a) => {
+            // an array can only be assigned to an array of the same type
+            if a.element_type() == target_type {
+                Ok(value)
+            } else {
+                Err("Type mismatch".to_string())
+            }
+        }
     }
 }
 
@@ -246,6 +259,22 @@ mod tests {
                 Variant::from(1_i64)
             );
         }
+
+        #[test]
+        fn to_integer_rounds_half_to_even() {
+            assert_eq!(
+                cast(Variant::from(2.5), TypeQualifier::PercentInteger).unwrap(),
+                Variant::from(2)
+            );
+            assert_eq!(
+                cast(Variant::from(3.5), TypeQualifier::PercentInteger).unwrap(),
+                Variant::from(4)
+            );
+            assert_eq!(
+                cast(Variant::from(-2.5), TypeQualifier::AmpersandLong).unwrap(),
+                Variant::from(-2_i64)
+            );
+        }
     }
 
     mod from_string {
@@ -1,4 +1,10 @@
 mod case_insensitive_string;
+mod file_constants;
+mod input_constants;
 mod location;
+mod print_constants;
 pub use self::case_insensitive_string::*;
+pub use self::file_constants::*;
+pub use self::input_constants::*;
 pub use self::location::*;
+pub use self::print_constants::*;
@@ -0,0 +1,57 @@
+/// The mode of a file that is opened with `OPEN`.
+///
+/// It is passed to the interpreter as an integer argument of the built-in `OPEN` sub.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileMode {
+    Input = 1,
+    Output = 2,
+    Append = 3,
+    Random = 4,
+    Binary = 5,
+}
+
+impl From<FileMode> for i32 {
+    fn from(f: FileMode) -> i32 {
+        f as i32
+    }
+}
+
+impl From<i32> for FileMode {
+    fn from(i: i32) -> FileMode {
+        match i {
+            1 => FileMode::Input,
+            2 => FileMode::Output,
+            3 => FileMode::Append,
+            4 => FileMode::Random,
+            5 => FileMode::Binary,
+            _ => panic!("Unsupported file mode {}", i),
+        }
+    }
+}
+
+/// The optional `ACCESS` clause of `OPEN`, e.g. `ACCESS READ`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileAccess {
+    Unspecified = 0,
+    Read = 1,
+    Write = 2,
+    ReadWrite = 3,
+}
+
+impl From<FileAccess> for i32 {
+    fn from(f: FileAccess) -> i32 {
+        f as i32
+    }
+}
+
+impl From<i32> for FileAccess {
+    fn from(i: i32) -> FileAccess {
+        match i {
+            0 => FileAccess::Unspecified,
+            1 => FileAccess::Read,
+            2 => FileAccess::Write,
+            3 => FileAccess::ReadWrite,
+            _ => panic!("Unsupported file access {}", i),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// How `INPUT` shows its prompt.
+///
+/// The parser passes it as the first argument of the built-in `INPUT` sub,
+/// followed by the prompt (empty if there is none) and the variables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputPrompt {
+    /// `INPUT A`, which shows a question mark
+    QuestionMark = 0,
+    /// `INPUT "Name"; A$`, which shows the prompt followed by a question mark
+    PromptWithQuestionMark = 1,
+    /// `INPUT "Name", A$`, which shows only the prompt
+    Prompt = 2,
+}
+
+impl From<InputPrompt> for i32 {
+    fn from(p: InputPrompt) -> i32 {
+        p as i32
+    }
+}
+
+impl From<i32> for InputPrompt {
+    fn from(i: i32) -> InputPrompt {
+        match i {
+            0 => InputPrompt::QuestionMark,
+            1 => InputPrompt::PromptWithQuestionMark,
+            2 => InputPrompt::Prompt,
+            _ => panic!("Unsupported input prompt {}", i),
+        }
+    }
+}
@@ -4,11 +4,36 @@
 pub struct Location {
     row: u32,
     col: u32,
+    /// The line number of a line-numbered program, e.g. `10 PRINT "hi"`.
+    line_number: Option<u32>,
 }
 
 impl Location {
     pub fn new(row: u32, col: u32) -> Location {
-        Location { row, col }
+        Location {
+            row,
+            col,
+            line_number: None,
+        }
+    }
+
+    pub fn with_line_number(self, line_number: u32) -> Location {
+        Location {
+            line_number: Some(line_number),
+            ..self
+        }
+    }
+
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    pub fn line_number(&self) -> Option<u32> {
+        self.line_number
     }
 
     pub fn inc_col(&mut self) {
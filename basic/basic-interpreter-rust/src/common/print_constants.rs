@@ -0,0 +1,48 @@
+/// The kind of an argument of `PRINT`.
+///
+/// The parser passes it as an integer argument of the built-in `PRINT` sub, before
+/// the argument it describes. For example, `PRINT A; TAB(10); B,` is passed as
+/// `Expression, A, Semicolon, Tab, 10, Expression, B, Comma`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PrintArg {
+    /// A value to print, which follows as the next argument.
+    Expression = 0,
+    /// The `,` separator, which moves to the next print zone.
+    Comma = 1,
+    /// The `;` separator, which prints nothing.
+    Semicolon = 2,
+    /// `TAB(n)`, which moves to column n. The column follows as the next argument.
+    Tab = 3,
+    /// `SPC(n)`, which prints n spaces. The count follows as the next argument.
+    Spc = 4,
+    /// `USING format$;`, which formats the values that follow with the format string.
+    /// The format string follows as the next argument.
+    Using = 5,
+}
+
+impl PrintArg {
+    /// Checks if the kind is followed by another argument, e.g. the column of `TAB`.
+    pub fn has_value(&self) -> bool {
+        matches!(self, Self::Expression | Self::Tab | Self::Spc | Self::Using)
+    }
+}
+
+impl From<PrintArg> for i32 {
+    fn from(p: PrintArg) -> i32 {
+        p as i32
+    }
+}
+
+impl From<i32> for PrintArg {
+    fn from(i: i32) -> PrintArg {
+        match i {
+            0 => PrintArg::Expression,
+            1 => PrintArg::Comma,
+            2 => PrintArg::Semicolon,
+            3 => PrintArg::Tab,
+            4 => PrintArg::Spc,
+            5 => PrintArg::Using,
+            _ => panic!("Unsupported print argument {}", i),
+        }
+    }
+}
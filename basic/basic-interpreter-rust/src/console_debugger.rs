@@ -0,0 +1,268 @@
+use crate::interpreter::{Debugger, InterpreterError, Resume, Stop};
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+
+const HELP: &str = r#"Commands:
+  b N, break N     stop before the statements of line N
+  d N, delete N    remove the breakpoint of line N
+  c, continue      run until the next breakpoint
+  s, step          stop at the next statement, also inside SUBs and FUNCTIONs
+  n, next          stop at the next statement, stepping over calls
+  o, out           stop after the current SUB or FUNCTION returns
+  bt, backtrace    show the call stack
+  v, vars          show the variables of the current SUB or FUNCTION
+  l, list          show the lines around the current one
+  q, quit          end the program
+  h, help          show this help"#;
+
+/// A debugger that reads its commands from a text stream, e.g. the keyboard.
+///
+/// It writes to a different stream than the program, so that the output of
+/// the program is not mixed with the prompts. When the commands run out,
+/// the debugger detaches and the program runs to its end.
+pub struct ConsoleDebugger<R: BufRead, W: Write> {
+    source: Vec<String>,
+    breakpoints: BTreeSet<u32>,
+    input: R,
+    output: W,
+    detached: bool,
+}
+
+impl<R: BufRead, W: Write> ConsoleDebugger<R, W> {
+    /// Creates a debugger for the given source code, which is used to show the lines.
+    pub fn new(source: &str, input: R, output: W) -> Self {
+        Self {
+            source: source.lines().map(|l| l.to_string()).collect(),
+            breakpoints: BTreeSet::new(),
+            input,
+            output,
+            detached: false,
+        }
+    }
+
+    /// Adds a breakpoint before the program starts.
+    pub fn add_breakpoint(&mut self, row: u32) {
+        self.breakpoints.insert(row);
+    }
+
+    fn source_line(&self, row: u32) -> &str {
+        self.source
+            .get(row as usize - 1)
+            .map(|l| l.trim())
+            .unwrap_or_default()
+    }
+
+    /// Reads commands until one of them resumes the program.
+    fn prompt(&mut self, stop: &Stop) -> Resume {
+        loop {
+            self.write("> ");
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    self.detached = true;
+                    return Resume::Continue;
+                }
+                Ok(_) => {}
+            }
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap_or_default().to_lowercase();
+            let arg = words.next().and_then(|w| w.parse::<u32>().ok());
+            match (command.as_str(), arg) {
+                ("b", Some(row)) | ("break", Some(row)) => {
+                    self.breakpoints.insert(row);
+                    self.writeln(&format!("Breakpoint at line {}", row));
+                }
+                ("d", Some(row)) | ("delete", Some(row)) => {
+                    if self.breakpoints.remove(&row) {
+                        self.writeln(&format!("Deleted breakpoint at line {}", row));
+                    } else {
+                        self.writeln(&format!("No breakpoint at line {}", row));
+                    }
+                }
+                ("c", _) | ("continue", _) => return Resume::Continue,
+                ("s", _) | ("step", _) => return Resume::StepInto,
+                ("n", _) | ("next", _) => return Resume::StepOver,
+                ("o", _) | ("out", _) => return Resume::StepOut,
+                ("q", _) | ("quit", _) => return Resume::Quit,
+                ("bt", _) | ("backtrace", _) => self.backtrace(stop),
+                ("v", _) | ("vars", _) => self.variables(stop),
+                ("l", _) | ("list", _) => self.list(stop.location.row()),
+                ("", _) => {}
+                _ => self.writeln(HELP),
+            }
+        }
+    }
+
+    fn backtrace(&mut self, stop: &Stop) {
+        let pos = stop.location;
+        self.writeln(&format!("  at {}:{}", pos.row(), pos.col()));
+        for pos in &stop.call_stack {
+            self.writeln(&format!("  called from {}:{}", pos.row(), pos.col()));
+        }
+    }
+
+    fn variables(&mut self, stop: &Stop) {
+        if stop.variables.is_empty() {
+            self.writeln("No variables");
+        }
+        for (name, value) in &stop.variables {
+            self.writeln(&format!("  {} = {}", name, value));
+        }
+    }
+
+    fn list(&mut self, row: u32) {
+        let first = row.saturating_sub(3).max(1);
+        let last = (row + 3).min(self.source.len() as u32);
+        for r in first..=last {
+            let marker = if r == row {
+                "=>"
+            } else if self.breakpoints.contains(&r) {
+                " *"
+            } else {
+                "  "
+            };
+            let line = format!("{} {:4} {}", marker, r, self.source[r as usize - 1]);
+            self.writeln(&line);
+        }
+    }
+
+    fn write(&mut self, s: &str) {
+        // the debugger can't do anything about a broken output
+        write!(self.output, "{}", s).unwrap_or_default();
+        self.output.flush().unwrap_or_default();
+    }
+
+    fn writeln(&mut self, s: &str) {
+        writeln!(self.output, "{}", s).unwrap_or_default();
+    }
+}
+
+impl<R: BufRead, W: Write> Debugger for ConsoleDebugger<R, W> {
+    fn has_breakpoint(&self, row: u32) -> bool {
+        !self.detached && self.breakpoints.contains(&row)
+    }
+
+    fn on_stop(&mut self, stop: &Stop) -> Resume {
+        if self.detached {
+            return Resume::Continue;
+        }
+        let row = stop.location.row();
+        let line = format!("Stopped at line {}: {}", row, self.source_line(row));
+        self.writeln(&line);
+        self.prompt(stop)
+    }
+
+    fn on_error(&mut self, stop: &Stop, error: &InterpreterError) {
+        if self.detached {
+            return;
+        }
+        let row = stop.location.row();
+        let line = format!(
+            "{} at line {}: {}",
+            error.message(),
+            row,
+            self.source_line(row)
+        );
+        self.writeln(&line);
+        // the program can't continue, but its state can be inspected
+        self.prompt(stop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Location;
+    use crate::variant::Variant;
+    use std::io::Cursor;
+
+    const SOURCE: &str = "A = 1\nHello\nPRINT A\nSUB Hello\n    B = 2\nEND SUB\n";
+
+    fn debugger(commands: &str) -> ConsoleDebugger<Cursor<Vec<u8>>, Vec<u8>> {
+        ConsoleDebugger::new(SOURCE, Cursor::new(commands.as_bytes().to_vec()), vec![])
+    }
+
+    fn stop(row: u32) -> Stop {
+        Stop {
+            location: Location::new(row, 5),
+            call_stack: vec![Location::new(2, 1)],
+            variables: vec![("B!".to_string(), Variant::VSingle(2.0))],
+        }
+    }
+
+    fn output(debugger: &ConsoleDebugger<Cursor<Vec<u8>>, Vec<u8>>) -> String {
+        String::from_utf8(debugger.output.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_resume_commands() {
+        let mut debugger = debugger("s\nn\no\nc\nq\n");
+        assert_eq!(debugger.on_stop(&stop(5)), Resume::StepInto);
+        assert_eq!(debugger.on_stop(&stop(5)), Resume::StepOver);
+        assert_eq!(debugger.on_stop(&stop(5)), Resume::StepOut);
+        assert_eq!(debugger.on_stop(&stop(5)), Resume::Continue);
+        assert_eq!(debugger.on_stop(&stop(5)), Resume::Quit);
+        assert!(output(&debugger).starts_with("Stopped at line 5: B = 2\n> "));
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger = debugger("b 3\nbreak 5\nd 5\nd 4\nc\n");
+        assert_eq!(debugger.on_stop(&stop(1)), Resume::Continue);
+        assert!(debugger.has_breakpoint(3));
+        assert!(!debugger.has_breakpoint(5));
+        assert_eq!(
+            output(&debugger),
+            "Stopped at line 1: A = 1\n> Breakpoint at line 3\n> Breakpoint at line 5\n\
+            > Deleted breakpoint at line 5\n> No breakpoint at line 4\n> "
+        );
+    }
+
+    #[test]
+    fn test_inspect() {
+        let mut debugger = debugger("bt\nv\nl\nc\n");
+        debugger.add_breakpoint(2);
+        debugger.on_stop(&stop(5));
+        assert_eq!(
+            output(&debugger),
+            "Stopped at line 5: B = 2\n\
+            >   at 5:5\n  called from 2:1\n\
+            >   B! = 2\n\
+            >  *    2 Hello\n      3 PRINT A\n      4 SUB Hello\n\
+            =>    5     B = 2\n      6 END SUB\n\
+            > "
+        );
+    }
+
+    #[test]
+    fn test_unknown_command_shows_help() {
+        let mut debugger = debugger("what\nc\n");
+        debugger.on_stop(&stop(1));
+        assert!(output(&debugger).contains(HELP));
+    }
+
+    #[test]
+    fn test_detaches_at_end_of_input() {
+        let mut debugger = debugger("b 3\n");
+        assert_eq!(debugger.on_stop(&stop(1)), Resume::Continue);
+        assert!(!debugger.has_breakpoint(3));
+        assert_eq!(debugger.on_stop(&stop(3)), Resume::Continue);
+        assert_eq!(
+            output(&debugger),
+            "Stopped at line 1: A = 1\n> Breakpoint at line 3\n> "
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let mut debugger = debugger("v\nc\n");
+        debugger.on_error(
+            &stop(5),
+            &InterpreterError::new_with_pos("Overflow", Location::new(5, 5)),
+        );
+        assert_eq!(
+            output(&debugger),
+            "Overflow at line 5: B = 2\n>   B! = 2\n> "
+        );
+    }
+}
@@ -0,0 +1,834 @@
+//! A server of the Debug Adapter Protocol, so that editors can debug programs.
+//!
+//! The messages are JSON objects with a `Content-Length` header, on the standard
+//! input and output. The server supports one program (thread) and these requests:
+//!
+//! - `initialize`, `launch` (with `program` and `stopOnEntry`), `configurationDone`
+//! - `setBreakpoints` (lines only), `threads`, `stackTrace`
+//! - `scopes` and `variables`, of the current stack frame only
+//! - `continue`, `next`, `stepIn`, `stepOut`, `pause`, `disconnect`
+//!
+//! The output of the program is sent as `output` events. A program can't read
+//! from the keyboard, because the standard input carries the messages.
+
+use crate::interpreter::{Debugger, DefaultStdlib, InterpreterError, Resume, Stdlib, Stop};
+use crate::json::{read_message, write_message, Json};
+use crate::program::{Interpreter, Program};
+use crate::variant::Variant;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// The id of the only thread
+const THREAD_ID: u32 = 1;
+
+/// The `variablesReference` of the variables of the current SUB or FUNCTION
+const LOCALS: u32 = 1;
+
+/// The state that the server shares with the debugger and the standard library of the program.
+struct Session<R, W> {
+    input: R,
+    output: W,
+    seq: u32,
+    /// The path of the program, as given by `launch`
+    path: String,
+    /// The rows where the parts of the program start, with the names of the SUBs and
+    /// FUNCTIONs, to find the SUB or FUNCTION of a stack frame
+    outline: Vec<(u32, Option<String>)>,
+    breakpoints: BTreeSet<u32>,
+    stop_on_entry: bool,
+    /// Whether the program has reached its first statement
+    started: bool,
+    /// Whether the client has disconnected or the input has ended
+    disconnected: bool,
+}
+
+impl<R: BufRead, W: Write> Session<R, W> {
+    fn read_request(&mut self) -> Option<Json> {
+        let request = read_message(&mut self.input);
+        if request.is_none() {
+            self.disconnected = true;
+        }
+        request
+    }
+
+    fn send(&mut self, members: Vec<(&str, Json)>) {
+        self.seq += 1;
+        let mut all = vec![("seq", Json::from(self.seq))];
+        all.extend(members);
+        write_message(&mut self.output, &Json::object(all));
+    }
+
+    fn respond(&mut self, request: &Json, body: Json) {
+        self.send(vec![
+            ("type", Json::from("response")),
+            (
+                "request_seq",
+                request.get("seq").cloned().unwrap_or(Json::Null),
+            ),
+            ("success", Json::from(true)),
+            ("command", command(request).into()),
+            ("body", body),
+        ]);
+    }
+
+    fn respond_error(&mut self, request: &Json, message: &str) {
+        self.send(vec![
+            ("type", Json::from("response")),
+            (
+                "request_seq",
+                request.get("seq").cloned().unwrap_or(Json::Null),
+            ),
+            ("success", Json::from(false)),
+            ("command", command(request).into()),
+            ("message", Json::from(message)),
+        ]);
+    }
+
+    fn event(&mut self, event: &str, body: Json) {
+        self.send(vec![
+            ("type", Json::from("event")),
+            ("event", Json::from(event)),
+            ("body", body),
+        ]);
+    }
+
+    fn output(&mut self, category: &str, text: &str) {
+        self.event(
+            "output",
+            Json::object(vec![
+                ("category", Json::from(category)),
+                ("output", Json::from(text)),
+            ]),
+        );
+    }
+
+    /// Handles the requests that are the same whether the program is stopped or not.
+    /// Returns false if the request is not one of them.
+    fn handle_common(&mut self, request: &Json) -> bool {
+        match command(request) {
+            "threads" => {
+                let thread = Json::object(vec![
+                    ("id", Json::from(THREAD_ID)),
+                    ("name", Json::from("main")),
+                ]);
+                self.respond(
+                    request,
+                    Json::object(vec![("threads", Json::from(vec![thread]))]),
+                );
+            }
+            "setBreakpoints" => {
+                let rows: Vec<u32> = arguments(request)
+                    .get("breakpoints")
+                    .and_then(Json::as_array)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|b| b.get("line").and_then(Json::as_u32))
+                    .collect();
+                self.breakpoints = rows.iter().copied().collect();
+                let breakpoints: Vec<Json> = rows
+                    .into_iter()
+                    .map(|row| {
+                        Json::object(vec![
+                            ("verified", Json::from(true)),
+                            ("line", Json::from(row)),
+                        ])
+                    })
+                    .collect();
+                self.respond(
+                    request,
+                    Json::object(vec![("breakpoints", Json::from(breakpoints))]),
+                );
+            }
+            "setExceptionBreakpoints" => self.respond(request, Json::object(vec![])),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The name of the SUB or FUNCTION that contains the given row.
+    fn frame_name(&self, row: u32) -> String {
+        self.outline
+            .iter()
+            .take_while(|(start, _)| *start <= row)
+            .last()
+            .and_then(|(_, name)| name.clone())
+            .unwrap_or_else(|| "<main>".to_string())
+    }
+
+    fn stack_trace(&self, stop: &Stop) -> Json {
+        let locations = std::iter::once(&stop.location).chain(stop.call_stack.iter());
+        let source = Json::object(vec![("path", Json::from(self.path.as_str()))]);
+        let frames: Vec<Json> = locations
+            .enumerate()
+            .map(|(i, pos)| {
+                Json::object(vec![
+                    ("id", Json::from(i as u32)),
+                    ("name", Json::from(self.frame_name(pos.row()))),
+                    ("source", source.clone()),
+                    ("line", Json::from(pos.row())),
+                    ("column", Json::from(pos.col())),
+                ])
+            })
+            .collect();
+        Json::object(vec![
+            ("totalFrames", Json::from(frames.len() as u32)),
+            ("stackFrames", Json::from(frames)),
+        ])
+    }
+
+    /// Handles the requests of the client until one of them resumes the program.
+    fn wait(&mut self, stop: &Stop) -> Resume {
+        while let Some(request) = self.read_request() {
+            if self.handle_common(&request) {
+                continue;
+            }
+            let resume = match command(&request) {
+                "stackTrace" => {
+                    let body = self.stack_trace(stop);
+                    self.respond(&request, body);
+                    continue;
+                }
+                "scopes" => {
+                    // only the variables of the current frame are known
+                    if arguments(&request).get("frameId").and_then(Json::as_u32) != Some(0) {
+                        self.respond_error(&request, "Only the current frame has variables");
+                        continue;
+                    }
+                    let scopes = vec![Json::object(vec![
+                        ("name", Json::from("Locals")),
+                        ("variablesReference", Json::from(LOCALS)),
+                        ("expensive", Json::from(false)),
+                    ])];
+                    self.respond(&request, Json::object(vec![("scopes", Json::from(scopes))]));
+                    continue;
+                }
+                "variables" => {
+                    let reference = arguments(&request)
+                        .get("variablesReference")
+                        .and_then(Json::as_u32);
+                    if reference != Some(LOCALS) {
+                        self.respond_error(&request, "Unknown variables reference");
+                        continue;
+                    }
+                    let variables: Vec<Json> = stop
+                        .variables
+                        .iter()
+                        .map(|(name, value)| {
+                            Json::object(vec![
+                                ("name", Json::from(name.as_str())),
+                                ("value", Json::from(format_value(value))),
+                                ("variablesReference", Json::from(0)),
+                            ])
+                        })
+                        .collect();
+                    self.respond(
+                        &request,
+                        Json::object(vec![("variables", Json::from(variables))]),
+                    );
+                    continue;
+                }
+                "pause" => {
+                    // already paused
+                    self.respond(&request, Json::object(vec![]));
+                    continue;
+                }
+                "continue" => {
+                    let body = Json::object(vec![("allThreadsContinued", Json::from(true))]);
+                    self.respond(&request, body);
+                    return Resume::Continue;
+                }
+                "next" => Resume::StepOver,
+                "stepIn" => Resume::StepInto,
+                "stepOut" => Resume::StepOut,
+                "disconnect" | "terminate" => {
+                    self.disconnected = true;
+                    Resume::Quit
+                }
+                _ => {
+                    self.respond_error(&request, "Unsupported request");
+                    continue;
+                }
+            };
+            self.respond(&request, Json::object(vec![]));
+            return resume;
+        }
+        Resume::Quit
+    }
+}
+
+fn command(request: &Json) -> &str {
+    request
+        .get("command")
+        .and_then(Json::as_str)
+        .unwrap_or_default()
+}
+
+fn arguments(request: &Json) -> &Json {
+    request.get("arguments").unwrap_or(&Json::Null)
+}
+
+/// Shows strings in quotes, like the editors do.
+fn format_value(value: &Variant) -> String {
+    match value {
+        Variant::VString(s) => format!("\"{}\"", s),
+        _ => value.to_string(),
+    }
+}
+
+/// Controls the program for the client.
+struct DapDebugger<R, W>(Rc<RefCell<Session<R, W>>>);
+
+impl<R: BufRead, W: Write> Debugger for DapDebugger<R, W> {
+    fn has_breakpoint(&self, row: u32) -> bool {
+        self.0.borrow().breakpoints.contains(&row)
+    }
+
+    fn on_stop(&mut self, stop: &Stop) -> Resume {
+        let mut session = self.0.borrow_mut();
+        let reason = if !session.started {
+            session.started = true;
+            if session.breakpoints.contains(&stop.location.row()) {
+                "breakpoint"
+            } else if session.stop_on_entry {
+                "entry"
+            } else {
+                return Resume::Continue;
+            }
+        } else if session.breakpoints.contains(&stop.location.row()) {
+            "breakpoint"
+        } else {
+            "step"
+        };
+        session.event(
+            "stopped",
+            Json::object(vec![
+                ("reason", Json::from(reason)),
+                ("threadId", Json::from(THREAD_ID)),
+            ]),
+        );
+        session.wait(stop)
+    }
+
+    fn on_error(&mut self, stop: &Stop, error: &InterpreterError) {
+        let mut session = self.0.borrow_mut();
+        session.event(
+            "stopped",
+            Json::object(vec![
+                ("reason", Json::from("exception")),
+                ("description", Json::from(error.message())),
+                ("text", Json::from(error.message())),
+                ("threadId", Json::from(THREAD_ID)),
+            ]),
+        );
+        // the program ends when the client resumes it
+        session.wait(stop);
+    }
+}
+
+/// Sends the output of the program to the client.
+/// The rest of the standard library is the default one, except for the keyboard.
+struct DapStdlib<R, W> {
+    session: Rc<RefCell<Session<R, W>>>,
+    stdlib: DefaultStdlib,
+}
+
+impl<R: BufRead, W: Write> Stdlib for DapStdlib<R, W> {
+    fn print(&mut self, text: &str) {
+        self.session.borrow_mut().output("stdout", text);
+    }
+
+    fn println(&mut self) {
+        self.print("\n");
+    }
+
+    fn lprint(&mut self, text: &str) {
+        self.print(text);
+    }
+
+    fn system(&self) {}
+
+    fn input(&mut self) -> std::io::Result<String> {
+        Err(std::io::ErrorKind::UnexpectedEof.into())
+    }
+
+    fn input_bytes(&mut self, _count: usize) -> std::io::Result<Vec<u8>> {
+        Err(std::io::ErrorKind::UnexpectedEof.into())
+    }
+
+    fn now(&self) -> SystemTime {
+        self.stdlib.now()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.stdlib.utc_offset()
+    }
+
+    fn sleep(&mut self, seconds: u32) {
+        // there is no key to wait for
+        if seconds > 0 {
+            self.stdlib.sleep(seconds);
+        }
+    }
+
+    fn get_env_var(&self, name: &String) -> String {
+        self.stdlib.get_env_var(name)
+    }
+
+    fn set_env_var(&mut self, name: String, value: String) {
+        self.stdlib.set_env_var(name, value)
+    }
+
+    fn read_file(&self, file_name: &str) -> std::io::Result<Vec<u8>> {
+        self.stdlib.read_file(file_name)
+    }
+
+    fn open_file(
+        &mut self,
+        file_number: i32,
+        file_name: &str,
+        append: bool,
+    ) -> std::io::Result<usize> {
+        self.stdlib.open_file(file_number, file_name, append)
+    }
+
+    fn write_file(
+        &mut self,
+        file_number: i32,
+        offset: Option<usize>,
+        contents: &[u8],
+    ) -> std::io::Result<()> {
+        self.stdlib.write_file(file_number, offset, contents)
+    }
+
+    fn close_file(&mut self, file_number: i32) -> std::io::Result<()> {
+        self.stdlib.close_file(file_number)
+    }
+
+    fn kill_file(&mut self, file_name: &str) -> std::io::Result<()> {
+        self.stdlib.kill_file(file_name)
+    }
+
+    fn rename_file(&mut self, old_name: &str, new_name: &str) -> std::io::Result<()> {
+        self.stdlib.rename_file(old_name, new_name)
+    }
+}
+
+/// Serves the Debug Adapter Protocol for one program.
+///
+/// ```no_run
+/// use basic_interpreter_rust::DapServer;
+/// use std::io::{self, BufReader};
+///
+/// DapServer::new(BufReader::new(io::stdin()), io::stdout()).run();
+/// ```
+pub struct DapServer<R, W> {
+    session: Rc<RefCell<Session<R, W>>>,
+}
+
+impl<R: BufRead + 'static, W: Write + 'static> DapServer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            session: Rc::new(RefCell::new(Session {
+                input,
+                output,
+                seq: 0,
+                path: String::new(),
+                outline: vec![],
+                breakpoints: BTreeSet::new(),
+                stop_on_entry: false,
+                started: false,
+                disconnected: false,
+            })),
+        }
+    }
+
+    /// Handles the requests until the client disconnects.
+    pub fn run(&mut self) {
+        let mut source: Option<String> = None;
+        loop {
+            let request = match self.session.borrow_mut().read_request() {
+                Some(request) => request,
+                None => return,
+            };
+            let mut session = self.session.borrow_mut();
+            if session.handle_common(&request) {
+                continue;
+            }
+            match command(&request) {
+                "initialize" => {
+                    let capabilities =
+                        Json::object(vec![("supportsConfigurationDoneRequest", true.into())]);
+                    session.respond(&request, capabilities);
+                    session.event("initialized", Json::object(vec![]));
+                }
+                "launch" => {
+                    let arguments = arguments(&request);
+                    let path = arguments
+                        .get("program")
+                        .and_then(Json::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    match std::fs::read_to_string(&path) {
+                        Ok(s) => {
+                            session.stop_on_entry = arguments
+                                .get("stopOnEntry")
+                                .and_then(Json::as_bool)
+                                .unwrap_or_default();
+                            session.path = path;
+                            source = Some(s);
+                            session.respond(&request, Json::object(vec![]));
+                        }
+                        Err(_) => {
+                            let message = format!("Could not find program {}", path);
+                            session.respond_error(&request, &message);
+                        }
+                    }
+                }
+                "configurationDone" => {
+                    session.respond(&request, Json::object(vec![]));
+                    drop(session);
+                    if let Some(source) = source.take() {
+                        self.run_program(&source);
+                        if self.session.borrow().disconnected {
+                            return;
+                        }
+                    }
+                }
+                "disconnect" => {
+                    session.respond(&request, Json::object(vec![]));
+                    return;
+                }
+                _ => session.respond_error(&request, "Unsupported request"),
+            }
+        }
+    }
+
+    fn run_program(&mut self, source: &str) {
+        let program = Program::parse(source.as_bytes()).and_then(Program::lint);
+        let result = program.and_then(|program| {
+            self.session.borrow_mut().outline = program.outline();
+            let mut interpreter = Interpreter::new(DapStdlib {
+                session: Rc::clone(&self.session),
+                stdlib: DefaultStdlib::default(),
+            });
+            interpreter.set_debugger(Box::new(DapDebugger(Rc::clone(&self.session))));
+            interpreter.run(&program.compile())
+        });
+        let mut session = self.session.borrow_mut();
+        if session.disconnected {
+            // the client has stopped the program
+            return;
+        }
+        let exit_code = match result {
+            Ok(_) => 0,
+            Err(e) => {
+                session.output("stderr", &format!("{}\n", e));
+                1
+            }
+        };
+        session.event(
+            "exited",
+            Json::object(vec![("exitCode", Json::from(exit_code))]),
+        );
+        session.event("terminated", Json::object(vec![]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const PROGRAM: &str = r#"A = 1
+Hello "world"
+A = 2
+SUB Hello(N$)
+    PRINT "Hello "; N$
+END SUB
+"#;
+
+    /// Writes the program to a temporary file.
+    fn program_file(name: &str, program: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, program).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// Plays the requests of a client and returns the messages of the server.
+    fn play(requests: &[Json]) -> Vec<Json> {
+        let mut input: Vec<u8> = vec![];
+        for (i, request) in requests.iter().enumerate() {
+            let mut members = vec![
+                ("seq", Json::from(i as u32 + 1)),
+                ("type", Json::from("request")),
+            ];
+            if let Json::Object(m) = request {
+                members.extend(m.iter().map(|(k, v)| (k.as_str(), v.clone())));
+            }
+            write_message(&mut input, &Json::object(members));
+        }
+        let mut server = DapServer::new(Cursor::new(input), vec![]);
+        server.run();
+        let output = server.session.borrow().output.clone();
+        let mut reader = Cursor::new(output);
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut reader) {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn request(command: &str, arguments: Json) -> Json {
+        Json::object(vec![
+            ("command", Json::from(command)),
+            ("arguments", arguments),
+        ])
+    }
+
+    fn launch(path: &str, stop_on_entry: bool) -> Json {
+        request(
+            "launch",
+            Json::object(vec![
+                ("program", Json::from(path)),
+                ("stopOnEntry", Json::from(stop_on_entry)),
+            ]),
+        )
+    }
+
+    fn set_breakpoints(rows: &[u32]) -> Json {
+        let breakpoints: Vec<Json> = rows
+            .iter()
+            .map(|row| Json::object(vec![("line", Json::from(*row))]))
+            .collect();
+        request(
+            "setBreakpoints",
+            Json::object(vec![("breakpoints", Json::from(breakpoints))]),
+        )
+    }
+
+    fn no_arguments(command: &str) -> Json {
+        request(command, Json::object(vec![]))
+    }
+
+    /// Shows a message briefly, e.g. `response next` or `event stopped step`.
+    fn summary(message: &Json) -> String {
+        let s = |key: &str| message.get(key).and_then(Json::as_str).unwrap_or_default();
+        match s("type") {
+            "response" if message.get("success") == Some(&Json::from(false)) => {
+                format!("error {}", s("command"))
+            }
+            "response" => format!("response {}", s("command")),
+            _ => {
+                let body = message.get("body").unwrap();
+                let detail = ["reason", "output"]
+                    .iter()
+                    .find_map(|key| body.get(key).and_then(Json::as_str))
+                    .unwrap_or_default();
+                if detail.is_empty() {
+                    format!("event {}", s("event"))
+                } else {
+                    format!("event {} {}", s("event"), detail)
+                }
+            }
+        }
+    }
+
+    fn summaries(messages: &[Json]) -> Vec<String> {
+        messages.iter().map(summary).collect()
+    }
+
+    fn response<'a>(messages: &'a [Json], command: &str) -> &'a Json {
+        messages
+            .iter()
+            .find(|m| {
+                m.get("type") == Some(&Json::from("response"))
+                    && m.get("command") == Some(&Json::from(command))
+            })
+            .and_then(|m| m.get("body"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_breakpoint_stack_trace_and_variables() {
+        let path = program_file("dap_test_breakpoint.bas", PROGRAM);
+        let messages = play(&[
+            no_arguments("initialize"),
+            launch(&path, false),
+            set_breakpoints(&[5]),
+            no_arguments("configurationDone"),
+            request(
+                "stackTrace",
+                Json::object(vec![("threadId", Json::from(1))]),
+            ),
+            request("scopes", Json::object(vec![("frameId", Json::from(0))])),
+            request(
+                "variables",
+                Json::object(vec![("variablesReference", Json::from(1))]),
+            ),
+            no_arguments("continue"),
+            no_arguments("disconnect"),
+        ]);
+        assert_eq!(
+            summaries(&messages),
+            vec![
+                "response initialize",
+                "event initialized",
+                "response launch",
+                "response setBreakpoints",
+                "response configurationDone",
+                "event stopped breakpoint",
+                "response stackTrace",
+                "response scopes",
+                "response variables",
+                "response continue",
+                "event output Hello world",
+                "event output \n",
+                "event exited",
+                "event terminated",
+                "response disconnect",
+            ]
+        );
+        assert_eq!(
+            response(&messages, "stackTrace").to_string(),
+            format!(
+                r#"{{"totalFrames":2,"stackFrames":[{{"id":0,"name":"Hello","source":{{"path":"{0}"}},"line":5,"column":5}},{{"id":1,"name":"<main>","source":{{"path":"{0}"}},"line":2,"column":1}}]}}"#,
+                path
+            )
+        );
+        assert_eq!(
+            response(&messages, "variables").to_string(),
+            r#"{"variables":[{"name":"N$","value":"\"world\"","variablesReference":0}]}"#
+        );
+    }
+
+    #[test]
+    fn test_variables_of_caller_frame() {
+        let path = program_file("dap_test_caller_frame.bas", PROGRAM);
+        let messages = play(&[
+            no_arguments("initialize"),
+            launch(&path, false),
+            set_breakpoints(&[5]),
+            no_arguments("configurationDone"),
+            request("scopes", Json::object(vec![("frameId", Json::from(1))])),
+            request(
+                "variables",
+                Json::object(vec![("variablesReference", Json::from(2))]),
+            ),
+            no_arguments("disconnect"),
+        ]);
+        assert_eq!(
+            summaries(&messages),
+            vec![
+                "response initialize",
+                "event initialized",
+                "response launch",
+                "response setBreakpoints",
+                "response configurationDone",
+                "event stopped breakpoint",
+                "error scopes",
+                "error variables",
+                "response disconnect",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stepping() {
+        let path = program_file("dap_test_stepping.bas", PROGRAM);
+        let messages = play(&[
+            no_arguments("initialize"),
+            launch(&path, true),
+            no_arguments("configurationDone"),
+            no_arguments("next"),
+            no_arguments("stepIn"),
+            request("stackTrace", Json::object(vec![])),
+            no_arguments("stepOut"),
+            request("stackTrace", Json::object(vec![])),
+            no_arguments("disconnect"),
+        ]);
+        let stopped: Vec<String> = summaries(&messages)
+            .into_iter()
+            .filter(|s| s.starts_with("event stopped"))
+            .collect();
+        assert_eq!(
+            stopped,
+            vec![
+                "event stopped entry",
+                "event stopped step",
+                "event stopped step",
+                "event stopped step"
+            ]
+        );
+        let lines: Vec<u32> = messages
+            .iter()
+            .filter(|m| m.get("command") == Some(&Json::from("stackTrace")))
+            .map(|m| {
+                m.get("body")
+                    .unwrap()
+                    .get("stackFrames")
+                    .unwrap()
+                    .as_array()
+                    .unwrap()[0]
+                    .get("line")
+                    .and_then(Json::as_u32)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(lines, vec![5, 3]);
+        // disconnecting ends the program
+        assert_eq!(summary(messages.last().unwrap()), "response disconnect");
+    }
+
+    #[test]
+    fn test_runtime_error() {
+        let path = program_file("dap_test_error.bas", "A = 1\nB = A / 0\n");
+        let messages = play(&[
+            no_arguments("initialize"),
+            launch(&path, false),
+            no_arguments("configurationDone"),
+            no_arguments("continue"),
+            no_arguments("disconnect"),
+        ]);
+        assert_eq!(
+            summaries(&messages)[3..],
+            [
+                "response configurationDone",
+                "event stopped exception",
+                "response continue",
+                "event output Division by zero at 2:7\n",
+                "event exited",
+                "event terminated",
+                "response disconnect",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_launch_errors() {
+        let path = program_file("dap_test_lint_error.bas", "A = 1 + \"a\"\n");
+        let messages = play(&[
+            no_arguments("initialize"),
+            launch("does-not-exist.bas", false),
+            launch(&path, false),
+            no_arguments("configurationDone"),
+            no_arguments("evaluate"),
+        ]);
+        assert_eq!(
+            summaries(&messages),
+            vec![
+                "response initialize",
+                "event initialized",
+                "error launch",
+                "response launch",
+                "response configurationDone",
+                "event output Type mismatch at 1:9\n",
+                "event exited",
+                "event terminated",
+                "error evaluate",
+            ]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::common::Location;
+use crate::interpreter::InterpreterError;
+use crate::lexer::LexerError;
+use crate::linter;
+use crate::linter::LinterError;
+use crate::parser::ParserError;
+use std::fmt;
+
+/// An error of any of the steps that run a program.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The program could not be parsed
+    Parser(ParserError),
+    /// The program was parsed, but QBasic would refuse to run it, e.g. because of a type mismatch.
+    /// The location is missing for errors that concern the whole program.
+    Linter(LinterError, Option<Location>),
+    /// An error occurred while running the program and it was not handled with `ON ERROR`
+    Interpreter(InterpreterError),
+}
+
+impl Error {
+    /// The location in the program where the error occurred.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Self::Parser(ParserError::LexerError(LexerError::Internal(_, pos)))
+            | Self::Parser(ParserError::LexerError(LexerError::UnsupportedCharacter(_, pos)))
+            | Self::Parser(ParserError::Internal(_, pos)) => Some(*pos),
+            Self::Parser(ParserError::Unexpected(_, lexeme)) => Some(lexeme.location()),
+            Self::Linter(_, pos) => *pos,
+            Self::Interpreter(e) => e.location(),
+        }
+    }
+
+    /// The message of the error, without its location.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Parser(ParserError::LexerError(LexerError::Internal(msg, _)))
+            | Self::Parser(ParserError::Internal(msg, _))
+            | Self::Parser(ParserError::Unexpected(msg, _)) => msg.clone(),
+            Self::Parser(ParserError::LexerError(LexerError::UnsupportedCharacter(ch, _))) => {
+                format!("Unsupported character {}", ch)
+            }
+            Self::Linter(e, _) => e.to_string(),
+            Self::Interpreter(e) => e.message().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    /// Shows the message and the location, e.g. `Type mismatch at 3:7`,
+    /// and the line number of a line-numbered program, e.g. `Type mismatch in line 20 at 3:7`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location() {
+            Some(pos) => {
+                f.write_str(&self.message())?;
+                if let Some(line_number) = pos.line_number() {
+                    write!(f, " in line {}", line_number)?;
+                }
+                write!(f, " at {}:{}", pos.row(), pos.col())
+            }
+            None => f.write_str(&self.message()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParserError> for Error {
+    fn from(e: ParserError) -> Self {
+        Self::Parser(e)
+    }
+}
+
+impl From<linter::Error> for Error {
+    fn from(e: linter::Error) -> Self {
+        let (e, pos) = e.consume();
+        Self::Linter(e, pos)
+    }
+}
+
+impl From<InterpreterError> for Error {
+    fn from(e: InterpreterError) -> Self {
+        Self::Interpreter(e)
+    }
+}
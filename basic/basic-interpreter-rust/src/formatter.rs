@@ -0,0 +1,479 @@
+//! Formats values with the format strings of `PRINT USING`.
+//!
+//! The format string consists of fields and literal text. The supported fields are:
+//!
+//! - `!` prints the first character of a string
+//! - `&` prints a string as is
+//! - `\  \` prints as many characters of a string as the width of the field
+//! - `#` is a digit position of a number, `.` is the decimal point
+//! - `,` left of the decimal point separates the thousands
+//! - `+` before or after a number prints its sign, `-` after a number prints
+//!   the minus sign of negative numbers
+//! - `$$` prints a dollar sign before a number
+//! - `**` fills the leading space of a number with asterisks
+//! - `^^^^` (or `^^^^^`) prints a number in exponential format
+//!
+//! `_` prints the next character literally. A number that does not fit its field
+//! is printed in full, preceded by `%`.
+
+use crate::variant::Variant;
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Formats the given values with a `PRINT USING` format string.
+///
+/// If there are more values than fields, the format string is used again from
+/// the beginning. After the last value, the literal text up to the next field
+/// (or the end of the format string) is printed as well.
+pub fn format_using(format: &str, values: &[Variant]) -> Result<String> {
+    let items = parse(format);
+    if !items.iter().any(|item| matches!(item, Item::Field(_))) {
+        return Err("Illegal function call".to_string());
+    }
+    let mut result = String::new();
+    let mut i = 0;
+    for value in values {
+        loop {
+            if i == items.len() {
+                i = 0;
+            }
+            i += 1;
+            match &items[i - 1] {
+                Item::Literal(ch) => result.push(*ch),
+                Item::Field(field) => {
+                    result.push_str(&field.format(value)?);
+                    break;
+                }
+            }
+        }
+    }
+    while let Some(Item::Literal(ch)) = items.get(i) {
+        result.push(*ch);
+        i += 1;
+    }
+    Ok(result)
+}
+
+#[derive(Debug, PartialEq)]
+enum Item {
+    Literal(char),
+    Field(Field),
+}
+
+#[derive(Debug, PartialEq)]
+enum Field {
+    /// `!`
+    FirstChar,
+    /// `&`
+    WholeString,
+    /// `\  \`, with the width of the field
+    FixedString(usize),
+    Number(NumberFormat),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Sign {
+    /// Only negative numbers get a (leading) sign, which needs a digit position
+    Default,
+    /// `+##`
+    Leading,
+    /// `##+`
+    TrailingPlus,
+    /// `##-`
+    TrailingMinus,
+}
+
+#[derive(Debug, PartialEq)]
+struct NumberFormat {
+    /// The width of the field
+    width: usize,
+    /// The digit positions left of the decimal point
+    digits: usize,
+    /// The digit positions right of the decimal point, if there is one
+    decimals: Option<usize>,
+    sign: Sign,
+    thousands: bool,
+    dollar: bool,
+    asterisks: bool,
+    /// The digits of the exponent, if the number is printed in exponential format
+    exponent: Option<usize>,
+}
+
+fn parse(format: &str) -> Vec<Item> {
+    let chars: Vec<char> = format.chars().collect();
+    let mut items: Vec<Item> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '_' if i + 1 < chars.len() => {
+                items.push(Item::Literal(chars[i + 1]));
+                i += 2;
+            }
+            '!' => {
+                items.push(Item::Field(Field::FirstChar));
+                i += 1;
+            }
+            '&' => {
+                items.push(Item::Field(Field::WholeString));
+                i += 1;
+            }
+            '\\' => match chars[i + 1..].iter().position(|ch| *ch != ' ') {
+                Some(spaces) if chars[i + 1 + spaces] == '\\' => {
+                    items.push(Item::Field(Field::FixedString(spaces + 2)));
+                    i += spaces + 2;
+                }
+                _ => {
+                    items.push(Item::Literal('\\'));
+                    i += 1;
+                }
+            },
+            ch => match parse_number(&chars, i) {
+                Some(number_format) => {
+                    i += number_format.width;
+                    items.push(Item::Field(Field::Number(number_format)));
+                }
+                None => {
+                    items.push(Item::Literal(ch));
+                    i += 1;
+                }
+            },
+        }
+    }
+    items
+}
+
+fn parse_number(chars: &[char], start: usize) -> Option<NumberFormat> {
+    let at = |i: usize| chars.get(i).copied().unwrap_or_default();
+    let mut result = NumberFormat {
+        width: 0,
+        digits: 0,
+        decimals: None,
+        sign: Sign::Default,
+        thousands: false,
+        dollar: false,
+        asterisks: false,
+        exponent: None,
+    };
+    let mut i = start;
+    if at(i) == '+' {
+        result.sign = Sign::Leading;
+        i += 1;
+    }
+    if at(i) == '*' && at(i + 1) == '*' {
+        result.asterisks = true;
+        result.digits += 2;
+        i += 2;
+        if at(i) == '$' {
+            result.dollar = true;
+            i += 1;
+        }
+    } else if at(i) == '$' && at(i + 1) == '$' {
+        // one of the two positions is taken by the dollar sign
+        result.dollar = true;
+        result.digits += 1;
+        i += 2;
+    } else if at(i) != '#' && !(at(i) == '.' && at(i + 1) == '#') {
+        return None;
+    }
+    while at(i) == '#' || at(i) == ',' {
+        result.thousands |= at(i) == ',';
+        result.digits += 1;
+        i += 1;
+    }
+    if at(i) == '.' {
+        i += 1;
+        let mut decimals = 0;
+        while at(i) == '#' {
+            decimals += 1;
+            i += 1;
+        }
+        result.decimals = Some(decimals);
+    }
+    let carets = chars[i..].iter().take_while(|ch| **ch == '^').count();
+    if carets >= 4 {
+        let carets = carets.min(5);
+        result.exponent = Some(carets - 2);
+        i += carets;
+    }
+    if result.sign == Sign::Default {
+        if at(i) == '-' {
+            result.sign = Sign::TrailingMinus;
+            i += 1;
+        } else if at(i) == '+' {
+            result.sign = Sign::TrailingPlus;
+            i += 1;
+        }
+    }
+    result.width = i - start;
+    Some(result)
+}
+
+impl Field {
+    fn format(&self, value: &Variant) -> Result<String> {
+        match (self, value) {
+            (Self::Number(number_format), _) => Ok(number_format.format(to_double(value)?)),
+            (Self::FirstChar, Variant::VString(s)) => Ok(fixed_width(s, 1)),
+            (Self::WholeString, Variant::VString(s)) => Ok(s.clone()),
+            (Self::FixedString(width), Variant::VString(s)) => Ok(fixed_width(s, *width)),
+            _ => Err("Type mismatch".to_string()),
+        }
+    }
+}
+
+fn to_double(value: &Variant) -> Result<f64> {
+    match value {
+        Variant::VSingle(f) => Ok(*f as f64),
+        Variant::VDouble(d) => Ok(*d),
+        Variant::VInteger(i) => Ok(*i as f64),
+        Variant::VLong(l) => Ok(*l as f64),
+        _ => Err("Type mismatch".to_string()),
+    }
+}
+
+/// Truncates or pads the string with spaces to the given width.
+fn fixed_width(s: &str, width: usize) -> String {
+    format!(
+        "{:width$}",
+        s.chars().take(width).collect::<String>(),
+        width = width
+    )
+}
+
+impl NumberFormat {
+    fn format(&self, value: f64) -> String {
+        let negative = value < 0.0;
+        let (prefix, suffix) = match (self.sign, negative) {
+            (Sign::Default, false) => ("", ""),
+            (Sign::Default, true) | (Sign::Leading, true) => ("-", ""),
+            (Sign::Leading, false) => ("+", ""),
+            (Sign::TrailingPlus, false) => ("", "+"),
+            (Sign::TrailingPlus, true) | (Sign::TrailingMinus, true) => ("", "-"),
+            (Sign::TrailingMinus, false) => ("", " "),
+        };
+        let number = match self.exponent {
+            Some(exponent_digits) => self.format_exponential(value.abs(), exponent_digits),
+            None => self.format_fixed(value.abs()),
+        };
+        let dollar = if self.dollar { "$" } else { "" };
+        let mut text = format!("{}{}{}{}", prefix, dollar, number, suffix);
+        if text.len() > self.width && number.starts_with("0.") {
+            // the leading zero is only printed if there is room for it
+            text = format!("{}{}{}{}", prefix, dollar, &number[1..], suffix);
+        }
+        if text.len() > self.width {
+            format!("%{}", text)
+        } else {
+            let fill = if self.asterisks { "*" } else { " " };
+            format!("{}{}", fill.repeat(self.width - text.len()), text)
+        }
+    }
+
+    fn format_fixed(&self, value: f64) -> String {
+        let s = format!("{:.*}", self.decimals.unwrap_or_default(), value);
+        let (int_part, fraction) = match s.find('.') {
+            Some(idx) => (&s[..idx], &s[idx + 1..]),
+            None => (s.as_str(), ""),
+        };
+        let int_part = if self.thousands {
+            group_thousands(int_part)
+        } else {
+            int_part.to_string()
+        };
+        match self.decimals {
+            Some(_) => format!("{}.{}", int_part, fraction),
+            None => int_part,
+        }
+    }
+
+    fn format_exponential(&self, value: f64, exponent_digits: usize) -> String {
+        // without an explicit sign, one digit position is kept for the minus sign
+        let mut digits = if self.sign == Sign::Default {
+            self.digits.saturating_sub(1)
+        } else {
+            self.digits
+        };
+        let decimals = self.decimals.unwrap_or_default();
+        if digits + decimals == 0 {
+            digits = 1;
+        }
+        // e.g. 1.2345e2, which is .12345 times ten to the power of 3
+        let s = format!("{:.*e}", digits + decimals - 1, value);
+        let (mantissa, exponent) = s.split_at(s.find('e').unwrap());
+        let mantissa: String = mantissa.chars().filter(|ch| *ch != '.').collect();
+        let exponent = if value == 0.0 {
+            0
+        } else {
+            exponent[1..].parse::<i32>().unwrap() + 1 - digits as i32
+        };
+        let mut result = mantissa[..digits].to_string();
+        if self.decimals.is_some() {
+            result.push('.');
+            result.push_str(&mantissa[digits..]);
+        }
+        format!(
+            "{}E{}{:0width$}",
+            result,
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs(),
+            width = exponent_digits
+        )
+    }
+}
+
+/// Inserts a comma between every three digits, e.g. 1234567 becomes 1,234,567.
+fn group_thousands(digits: &str) -> String {
+    let mut result = String::new();
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(ch);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt<V: Into<Variant>>(format: &str, value: V) -> String {
+        format_using(format, &[value.into()]).unwrap()
+    }
+
+    mod numbers {
+        use super::*;
+
+        #[test]
+        fn digits() {
+            assert_eq!(fmt("###", 42), " 42");
+            assert_eq!(fmt("###", 2.7), "  3");
+            assert_eq!(fmt("###", -42), "-42");
+            assert_eq!(fmt("#", 0), "0");
+        }
+
+        #[test]
+        fn decimal_point() {
+            assert_eq!(fmt("##.##", 3.14159), " 3.14");
+            assert_eq!(fmt("##.##", -3.5), "-3.50");
+            assert_eq!(fmt("##.##", 0.5), " 0.50");
+            assert_eq!(fmt("#.##", 0.5), "0.50");
+            assert_eq!(fmt(".##", 0.5), ".50");
+            assert_eq!(fmt("###.", 7), "  7.");
+        }
+
+        #[test]
+        fn thousands() {
+            assert_eq!(fmt("###,###.##", 1234.5), "  1,234.50");
+            assert_eq!(fmt("#,###", 123), "  123");
+            assert_eq!(fmt("##,###,###", 1234567), " 1,234,567");
+        }
+
+        #[test]
+        fn overflow() {
+            assert_eq!(fmt("##", 123), "%123");
+            assert_eq!(fmt("#", -1), "%-1");
+            assert_eq!(fmt("##.#", 99.96), "%100.0");
+        }
+
+        #[test]
+        fn signs() {
+            assert_eq!(fmt("+##", 5), " +5");
+            assert_eq!(fmt("+##", -5), " -5");
+            assert_eq!(fmt("##+", 5), " 5+");
+            assert_eq!(fmt("##+", -5), " 5-");
+            assert_eq!(fmt("##-", 5), " 5 ");
+            assert_eq!(fmt("##-", -5), " 5-");
+        }
+
+        #[test]
+        fn dollar() {
+            assert_eq!(fmt("$$##.##", 3.5), "  $3.50");
+            assert_eq!(fmt("$$##.##", 123.45), "$123.45");
+            assert_eq!(fmt("$$##.##", -3.5), " -$3.50");
+        }
+
+        #[test]
+        fn asterisks() {
+            assert_eq!(fmt("**##.##", 3.5), "***3.50");
+            assert_eq!(fmt("**$##.##", 3.5), "***$3.50");
+            assert_eq!(fmt("**###,###", 1234), "****1,234");
+        }
+
+        #[test]
+        fn exponential() {
+            assert_eq!(fmt("##.##^^^^", 234.56), " 2.35E+02");
+            assert_eq!(fmt("+#.##^^^^", 234.56), "+2.35E+02");
+            assert_eq!(fmt(".####^^^^", 888888), ".8889E+06");
+            assert_eq!(fmt("##.##^^^^", -0.00123), "-1.23E-03");
+            assert_eq!(fmt("##.##^^^^^", 1e100), " 1.00E+100");
+            assert_eq!(fmt("##.##^^^^", 0), " 0.00E+00");
+            assert_eq!(fmt("##.##^^^^", 9.999), " 1.00E+01");
+        }
+
+        #[test]
+        fn string_is_type_mismatch() {
+            assert_eq!(
+                format_using("##", &[Variant::from("a")]).unwrap_err(),
+                "Type mismatch"
+            );
+        }
+    }
+
+    mod strings {
+        use super::*;
+
+        #[test]
+        fn first_char() {
+            assert_eq!(fmt("!", "hello"), "h");
+            assert_eq!(fmt("!", ""), " ");
+        }
+
+        #[test]
+        fn whole_string() {
+            assert_eq!(fmt("[&]", "hello"), "[hello]");
+        }
+
+        #[test]
+        fn fixed_width() {
+            assert_eq!(fmt("\\\\", "hello"), "he");
+            assert_eq!(fmt("\\  \\|", "hello"), "hell|");
+            assert_eq!(fmt("\\  \\|", "hi"), "hi  |");
+        }
+
+        #[test]
+        fn number_is_type_mismatch() {
+            assert_eq!(
+                format_using("&", &[Variant::from(1)]).unwrap_err(),
+                "Type mismatch"
+            );
+        }
+    }
+
+    #[test]
+    fn literal_text() {
+        assert_eq!(fmt("Total: ### EUR", 42), "Total:  42 EUR");
+        assert_eq!(fmt("_#_&: ##", 5), "#&:  5");
+        assert_eq!(fmt("$## * 2", 5), "$ 5 * 2");
+    }
+
+    #[test]
+    fn format_is_reused() {
+        let values = [Variant::from(1), Variant::from(2), Variant::from(3)];
+        assert_eq!(format_using("(#)", &values).unwrap(), "(1)(2)(3)");
+        assert_eq!(format_using("# ", &values).unwrap(), "1 2 3 ");
+    }
+
+    #[test]
+    fn text_after_last_value_stops_at_next_field() {
+        let values = [Variant::from(1)];
+        assert_eq!(format_using("A # B # C", &values).unwrap(), "A 1 B ");
+    }
+
+    #[test]
+    fn without_fields_is_illegal_function_call() {
+        assert_eq!(
+            format_using("abc", &[Variant::from(1)]).unwrap_err(),
+            "Illegal function call"
+        );
+    }
+}
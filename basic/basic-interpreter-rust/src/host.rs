@@ -0,0 +1,143 @@
+use crate::common::CaseInsensitiveString;
+use crate::linter::{BuiltInFunction, BuiltInSub};
+use crate::parser::TypeQualifier;
+use crate::variant::Variant;
+use std::collections::HashMap;
+use std::fmt;
+
+type HostFn<T> = Box<dyn Fn(&mut [Variant]) -> Result<T, String>>;
+
+/// A function that the host offers to programs, e.g. `JSONGET$`.
+pub struct HostFunction {
+    pub params: Vec<TypeQualifier>,
+    pub return_type: TypeQualifier,
+    f: HostFn<Variant>,
+}
+
+impl HostFunction {
+    pub fn call(&self, args: &mut [Variant]) -> Result<Variant, String> {
+        (self.f)(args)
+    }
+}
+
+/// A sub that the host offers to programs.
+pub struct HostSub {
+    pub params: Vec<TypeQualifier>,
+    f: HostFn<()>,
+}
+
+impl HostSub {
+    pub fn call(&self, args: &mut [Variant]) -> Result<(), String> {
+        (self.f)(args)
+    }
+}
+
+/// The functions and subs that the host offers to programs, in addition to the built-in ones.
+///
+/// They are called like user defined functions and subs, so the linter checks
+/// the number and the types of the arguments. The arguments are cast to the
+/// types of the parameters before the call. Arguments that are variables are
+/// passed by reference: the values that the host leaves in them are assigned back.
+/// An error message returned by the host is a runtime error of the program,
+/// which can be handled with `ON ERROR`. Its code is the code of the QBasic error
+/// with the same message, e.g. 53 for `File not found`, or 5 (`Illegal function call`).
+///
+/// ```
+/// use basic_interpreter_rust::{HostRegistry, TypeQualifier, Variant};
+///
+/// let mut host = HostRegistry::new();
+/// host.add_function(
+///     "TWICE",
+///     &[TypeQualifier::PercentInteger],
+///     TypeQualifier::PercentInteger,
+///     |args| match &args[0] {
+///         Variant::VInteger(i) => Ok(Variant::VInteger(i * 2)),
+///         _ => Err("Type mismatch".to_string()),
+///     },
+/// );
+/// ```
+#[derive(Default)]
+pub struct HostRegistry {
+    functions: HashMap<CaseInsensitiveString, HostFunction>,
+    subs: HashMap<CaseInsensitiveString, HostSub>,
+}
+
+impl HostRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a function. The name is without a type qualifier, programs call it
+    /// with the qualifier of the return type, e.g. `JSONGET$(json$, key$)`.
+    ///
+    /// Panics if the name is the name of a built-in function.
+    pub fn add_function<F>(
+        &mut self,
+        name: &str,
+        params: &[TypeQualifier],
+        return_type: TypeQualifier,
+        f: F,
+    ) -> &mut Self
+    where
+        F: Fn(&mut [Variant]) -> Result<Variant, String> + 'static,
+    {
+        let name = CaseInsensitiveString::from(name);
+        let opt_built_in: Option<BuiltInFunction> = (&name).into();
+        assert!(opt_built_in.is_none(), "{} is a built-in function", name);
+        self.functions.insert(
+            name,
+            HostFunction {
+                params: params.to_vec(),
+                return_type,
+                f: Box::new(f),
+            },
+        );
+        self
+    }
+
+    /// Adds a sub.
+    ///
+    /// Panics if the name is the name of a built-in sub.
+    pub fn add_sub<F>(&mut self, name: &str, params: &[TypeQualifier], f: F) -> &mut Self
+    where
+        F: Fn(&mut [Variant]) -> Result<(), String> + 'static,
+    {
+        let name = CaseInsensitiveString::from(name);
+        let opt_built_in: Option<BuiltInSub> = (&name).into();
+        assert!(opt_built_in.is_none(), "{} is a built-in sub", name);
+        self.subs.insert(
+            name,
+            HostSub {
+                params: params.to_vec(),
+                f: Box::new(f),
+            },
+        );
+        self
+    }
+
+    pub fn function(&self, name: &CaseInsensitiveString) -> Option<&HostFunction> {
+        self.functions.get(name)
+    }
+
+    pub fn sub(&self, name: &CaseInsensitiveString) -> Option<&HostSub> {
+        self.subs.get(name)
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = (&CaseInsensitiveString, &HostFunction)> {
+        self.functions.iter()
+    }
+
+    pub fn subs(&self) -> impl Iterator<Item = (&CaseInsensitiveString, &HostSub)> {
+        self.subs.iter()
+    }
+}
+
+impl fmt::Debug for HostRegistry {
+    /// Shows the names, because the closures can't be shown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostRegistry")
+            .field("functions", &self.functions.keys().collect::<Vec<_>>())
+            .field("subs", &self.subs.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
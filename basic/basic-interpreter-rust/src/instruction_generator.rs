@@ -1,9 +1,13 @@
 mod built_in_functions;
 mod built_in_subs;
 mod constant;
+mod dim;
+mod do_loop;
+mod exit;
 mod expression;
 mod for_loop;
 mod function_call;
+mod go_sub;
 mod if_block;
 mod instruction;
 mod instruction_generator;
@@ -1,12 +1,12 @@
 use super::{Instruction, InstructionGenerator};
-use crate::common::Location;
-use crate::linter::{BuiltInSub, ExpressionNode};
+use crate::common::*;
+use crate::linter::{BuiltInSub, Expression, ExpressionNode};
 
 impl InstructionGenerator {
     pub fn generate_built_in_sub_call_instructions(
         &mut self,
         name: BuiltInSub,
-        args: Vec<ExpressionNode>,
+        mut args: Vec<ExpressionNode>,
         pos: Location,
     ) {
         match name {
@@ -14,6 +14,13 @@ impl InstructionGenerator {
                 self.push(Instruction::Halt, pos);
             }
             _ => {
+                if has_optional_file_handle(name) {
+                    // the file number comes first, zero means the screen or the keyboard
+                    match args.first().map(|a| a.as_ref()) {
+                        Some(Expression::FileHandle(_)) => (),
+                        _ => args.insert(0, Expression::IntegerLiteral(0).at(pos)),
+                    }
+                }
                 self.generate_push_unnamed_args_instructions(args, pos);
                 self.push(Instruction::PushStack, pos);
                 self.push(Instruction::BuiltInSub(name), pos);
@@ -22,3 +29,10 @@ impl InstructionGenerator {
         }
     }
 }
+
+fn has_optional_file_handle(name: BuiltInSub) -> bool {
+    matches!(
+        name,
+        BuiltInSub::Input | BuiltInSub::LineInput | BuiltInSub::Print | BuiltInSub::Write
+    )
+}
@@ -0,0 +1,130 @@
+use super::{ArrayElementType, Instruction, InstructionGenerator};
+use crate::common::*;
+use crate::linter::*;
+use crate::variant::{UserDefinedValue, Variant};
+
+impl InstructionGenerator {
+    pub fn generate_dim_instructions(&mut self, dim_names: Vec<DimNameNode>, is_redim: bool) {
+        for dim_name_node in dim_names.into_iter() {
+            let (dim_name, pos) = dim_name_node.consume();
+            if dim_name.dimensions.is_empty() {
+                self.generate_dim_variable_instructions(dim_name, pos);
+                continue;
+            }
+            self.push(Instruction::PreparePush, pos);
+            for d in dim_name.dimensions.into_iter() {
+                self.generate_expression_instructions(d.lbound);
+                self.push(Instruction::PushUnnamedValParam, pos);
+                self.generate_expression_instructions(d.ubound);
+                self.push(Instruction::PushUnnamedValParam, pos);
+            }
+            let element_type = match (&dim_name.user_defined_type, dim_name.fixed_length) {
+                (Some(type_name), _) => {
+                    ArrayElementType::UserDefined(self.default_user_defined_value(type_name))
+                }
+                (None, Some(len)) => ArrayElementType::FixedLengthString(len),
+                (None, None) => ArrayElementType::BuiltIn,
+            };
+            if is_redim {
+                self.push(Instruction::ReDimArray(dim_name.name, element_type), pos);
+            } else if dim_name.is_dynamic {
+                self.push(
+                    Instruction::DimDynamicArray(dim_name.name, element_type),
+                    pos,
+                );
+            } else {
+                self.push(Instruction::DimArray(dim_name.name, element_type), pos);
+            }
+        }
+    }
+
+    fn generate_dim_variable_instructions(&mut self, dim_name: DimName, pos: Location) {
+        if let Some(type_name) = &dim_name.user_defined_type {
+            let value = self.default_user_defined_value(type_name);
+            self.push(Instruction::Load(value), pos);
+            self.push(Instruction::Store(dim_name.name), pos);
+            return;
+        }
+        match dim_name.fixed_length {
+            Some(len) => {
+                self.push(Instruction::DimFixedLengthString(dim_name.name, len), pos);
+            }
+            None => {
+                let q = dim_name.name.qualifier();
+                self.push(Instruction::Load(Variant::default_variant(q)), pos);
+                self.push(Instruction::Store(dim_name.name), pos);
+            }
+        }
+    }
+
+    /// Creates the initial value of a variable of a user defined type.
+    fn default_user_defined_value(&self, type_name: &BareName) -> Variant {
+        let user_defined_type = self
+            .user_defined_types
+            .get(type_name)
+            .expect("The type should have been defined");
+        let members = user_defined_type
+            .elements
+            .iter()
+            .map(|e| {
+                let value = match &e.element_type {
+                    ElementType::BuiltIn(q) => Variant::default_variant(*q),
+                    ElementType::FixedLengthString(len) => {
+                        Variant::VString(String::new()).into_fixed_length(*len)
+                    }
+                    ElementType::UserDefined(t) => self.default_user_defined_value(t),
+                };
+                (e.name.clone(), value)
+            })
+            .collect();
+        Variant::VUserDefined(Box::new(UserDefinedValue::new(type_name.clone(), members)))
+    }
+
+    pub fn generate_array_element_member_assignment_instructions(
+        &mut self,
+        name: QualifiedName,
+        indices: Vec<ExpressionNode>,
+        members: Vec<BareName>,
+        expr: ExpressionNode,
+        pos: Location,
+    ) {
+        self.generate_push_array_indices_instructions(indices, pos);
+        self.generate_expression_instructions(expr);
+        self.push(Instruction::StoreArrayElementMember(name, members), pos);
+    }
+
+    pub fn generate_array_element_assignment_instructions(
+        &mut self,
+        name: QualifiedName,
+        indices: Vec<ExpressionNode>,
+        expr: ExpressionNode,
+        pos: Location,
+    ) {
+        self.generate_push_array_indices_instructions(indices, pos);
+        self.generate_expression_instructions(expr);
+        self.push(Instruction::StoreArrayElement(name), pos);
+    }
+
+    pub fn generate_array_element_instructions(
+        &mut self,
+        name: QualifiedName,
+        indices: Vec<ExpressionNode>,
+        pos: Location,
+    ) {
+        self.generate_push_array_indices_instructions(indices, pos);
+        self.push(Instruction::CopyArrayElementToA(name), pos);
+    }
+
+    fn generate_push_array_indices_instructions(
+        &mut self,
+        indices: Vec<ExpressionNode>,
+        pos: Location,
+    ) {
+        self.push(Instruction::PreparePush, pos);
+        for index in indices.into_iter() {
+            let index_pos = index.location();
+            self.generate_expression_instructions(index);
+            self.push(Instruction::PushUnnamedValParam, index_pos);
+        }
+    }
+}
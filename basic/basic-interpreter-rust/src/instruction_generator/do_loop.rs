@@ -0,0 +1,47 @@
+use super::{Instruction, InstructionGenerator};
+use crate::common::*;
+use crate::linter::{DoLoopConditionKind, DoLoopConditionPosition, DoLoopNode, ExitObject};
+
+impl InstructionGenerator {
+    pub fn generate_do_loop_instructions(&mut self, d: DoLoopNode, pos: Location) {
+        self.begin_loop(ExitObject::Do);
+        let start_idx = self.instructions.len();
+        let (top_condition, bottom_condition) = match d.condition {
+            Some(c) if c.position == DoLoopConditionPosition::Top => (Some(c), None),
+            Some(c) => (None, Some(c)),
+            None => (None, None),
+        };
+        if let Some(c) = top_condition {
+            // evaluate condition into register A
+            self.generate_expression_instructions(c.expr);
+            match c.kind {
+                DoLoopConditionKind::While => {
+                    self.push_loop_exit(Instruction::JumpIfFalse(0), pos);
+                }
+                DoLoopConditionKind::Until => {
+                    // skip the exit jump while the condition is false
+                    let body_idx = self.instructions.len() + 2;
+                    self.push(Instruction::JumpIfFalse(body_idx), pos);
+                    self.push_loop_exit(Instruction::Jump(0), pos);
+                }
+            }
+        }
+        self.generate_block_instructions(d.statements);
+        match bottom_condition {
+            Some(c) => {
+                self.generate_expression_instructions(c.expr);
+                match c.kind {
+                    DoLoopConditionKind::While => {
+                        self.push_loop_exit(Instruction::JumpIfFalse(0), pos);
+                        self.push(Instruction::Jump(start_idx), pos);
+                    }
+                    DoLoopConditionKind::Until => {
+                        self.push(Instruction::JumpIfFalse(start_idx), pos);
+                    }
+                }
+            }
+            None => self.push(Instruction::Jump(start_idx), pos),
+        }
+        self.end_loop();
+    }
+}
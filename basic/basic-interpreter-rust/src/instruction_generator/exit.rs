@@ -0,0 +1,69 @@
+use super::{Instruction, InstructionGenerator};
+use crate::common::*;
+use crate::linter::ExitObject;
+
+/// A loop whose instructions are being generated. Used to resolve `EXIT DO` and `EXIT FOR`.
+#[derive(Debug)]
+pub struct LoopFrame {
+    exit_object: ExitObject,
+    /// The indices of the jumps that exit the loop, patched when the loop ends.
+    exit_jumps: Vec<usize>,
+}
+
+impl InstructionGenerator {
+    pub fn begin_loop(&mut self, exit_object: ExitObject) {
+        self.loops.push(LoopFrame {
+            exit_object,
+            exit_jumps: vec![],
+        });
+    }
+
+    /// Patches the jumps that exit the current loop with the index of the next instruction.
+    pub fn end_loop(&mut self) {
+        let frame = self.loops.pop().expect("Should be inside a loop");
+        let exit_idx = self.instructions.len();
+        for idx in frame.exit_jumps {
+            let pos = self.instructions[idx].location();
+            let instruction = match self.instructions[idx].as_ref() {
+                Instruction::JumpIfFalse(_) => Instruction::JumpIfFalse(exit_idx),
+                _ => Instruction::Jump(exit_idx),
+            };
+            self.instructions[idx] = instruction.at(pos);
+        }
+    }
+
+    /// Adds a jump that will be patched to point after the current loop.
+    /// The given instruction should be a `Jump` or a `JumpIfFalse`.
+    pub fn push_loop_exit(&mut self, instruction: Instruction, pos: Location) {
+        let idx = self.instructions.len();
+        self.push(instruction, pos);
+        self.loops
+            .last_mut()
+            .expect("Should be inside a loop")
+            .exit_jumps
+            .push(idx);
+    }
+
+    pub fn generate_exit_instructions(&mut self, exit_object: ExitObject, pos: Location) {
+        // the body of a FOR loop runs with pushed registers,
+        // they need to be restored for every FOR loop that is exited
+        let mut pop_count = 0;
+        let mut frame_idx = self.loops.len();
+        loop {
+            frame_idx -= 1;
+            let frame_object = self.loops[frame_idx].exit_object;
+            if frame_object == ExitObject::For {
+                pop_count += 1;
+            }
+            if frame_object == exit_object {
+                break;
+            }
+        }
+        for _ in 0..pop_count {
+            self.push(Instruction::PopRegisters, pos);
+        }
+        let idx = self.instructions.len();
+        self.push(Instruction::Jump(0), pos); // will be patched when the loop ends
+        self.loops[frame_idx].exit_jumps.push(idx);
+    }
+}
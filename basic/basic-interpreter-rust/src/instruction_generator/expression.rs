@@ -28,6 +28,19 @@ impl InstructionGenerator {
             Expression::Constant(name) => {
                 self.push(Instruction::CopyVarToA(name), pos);
             }
+            Expression::ArrayElement(name, indices) => {
+                self.generate_array_element_instructions(name, indices, pos);
+            }
+            Expression::ArrayReference(name) | Expression::UserDefinedVariable(name, _) => {
+                self.push(Instruction::CopyVarToA(name), pos);
+            }
+            Expression::UserDefinedArrayElement(name, _, indices) => {
+                self.generate_array_element_instructions(name, indices, pos);
+            }
+            Expression::Member(base, name, _) => {
+                self.generate_expression_instructions(*base);
+                self.push(Instruction::CopyAMemberToA(name), pos);
+            }
             Expression::FunctionCall(n, args) => {
                 let name_node = n.at(pos);
                 self.generate_function_call_instructions(name_node, args);
@@ -44,6 +57,17 @@ impl InstructionGenerator {
                 match op {
                     Operand::Plus => self.push(Instruction::Plus, pos),
                     Operand::Minus => self.push(Instruction::Minus, pos),
+                    Operand::Multiply => self.push(Instruction::Multiply, pos),
+                    Operand::Divide => self.push(Instruction::Divide, pos),
+                    Operand::IntegerDivide => self.push(Instruction::IntegerDivide, pos),
+                    Operand::Modulo => self.push(Instruction::Modulo, pos),
+                    Operand::Power => self.push(Instruction::Power, pos),
+                    Operand::NotEqual => self.push(Instruction::NotEqual, pos),
+                    Operand::And => self.push(Instruction::And, pos),
+                    Operand::Or => self.push(Instruction::Or, pos),
+                    Operand::Xor => self.push(Instruction::Xor, pos),
+                    Operand::Eqv => self.push(Instruction::Eqv, pos),
+                    Operand::Imp => self.push(Instruction::Imp, pos),
                     Operand::Less => self.push(Instruction::Less, pos),
                     Operand::LessOrEqual => self.push(Instruction::LessOrEqual, pos),
                     Operand::Equal => self.push(Instruction::Equal, pos),
@@ -62,6 +86,9 @@ impl InstructionGenerator {
                     self.push(Instruction::NegateA, pos);
                 }
             },
+            Expression::FileHandle(child) => {
+                self.generate_expression_instructions(*child);
+            }
             Expression::Parenthesis(child) => {
                 self.generate_expression_instructions(*child);
             }
@@ -1,6 +1,6 @@
 use super::{Instruction, InstructionGenerator};
 use crate::common::*;
-use crate::linter::{ForLoopNode, QualifiedName, StatementNodes};
+use crate::linter::{ExitObject, ForLoopNode, QualifiedName, StatementNodes};
 use crate::variant::Variant;
 
 impl InstructionGenerator {
@@ -14,6 +14,7 @@ impl InstructionGenerator {
             next_counter: _,
         } = f;
         let counter_var_name = variable_name.strip_location();
+        self.begin_loop(ExitObject::For);
         // lower bound to A
         self.generate_expression_instructions(lower_bound);
         // A to variable
@@ -82,6 +83,7 @@ impl InstructionGenerator {
                 self.label("out-of-for", pos);
             }
         }
+        self.end_loop();
     }
 
     fn generate_for_loop_instructions_positive_or_negative_step(
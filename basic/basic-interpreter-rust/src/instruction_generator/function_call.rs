@@ -12,12 +12,21 @@ impl InstructionGenerator {
         let pos = function_name.location();
 
         let bare_name: &CaseInsensitiveString = function_name.bare_name();
-        let function_parameters = self.function_context.get(bare_name).unwrap().clone();
-        self.generate_push_named_args_instructions(function_parameters, args, pos);
-        self.push(Instruction::PushStack, pos);
-        let idx = self.instructions.len();
-        self.push(Instruction::PushRet(idx + 2), pos);
-        self.jump_to_function(bare_name, pos);
+        match self.function_context.get(bare_name).cloned() {
+            Some(function_parameters) => {
+                self.generate_push_named_args_instructions(function_parameters, args, pos);
+                self.push(Instruction::PushStack, pos);
+                let idx = self.instructions.len();
+                self.push(Instruction::PushRet(idx + 2), pos);
+                self.jump_to_function(bare_name, pos);
+            }
+            None => {
+                // not implemented in the program, so it is a function of the host
+                self.generate_push_unnamed_args_instructions(args, pos);
+                self.push(Instruction::PushStack, pos);
+                self.push(Instruction::HostFunction(bare_name.clone()), pos);
+            }
+        }
 
         self.push(Instruction::PopStack, pos);
         self.push(Instruction::CopyResultToA, pos);
@@ -33,7 +42,9 @@ impl InstructionGenerator {
         for (n, e_node) in param_names.into_iter().zip(expressions.into_iter()) {
             let (e, pos) = e_node.consume();
             match e {
-                Expression::Variable(v_name) => {
+                Expression::Variable(v_name)
+                | Expression::ArrayReference(v_name)
+                | Expression::UserDefinedVariable(v_name, _) => {
                     self.push(
                         Instruction::SetNamedRefParam(NamedRefParam {
                             parameter_name: n,
@@ -59,7 +70,9 @@ impl InstructionGenerator {
         for e_node in expressions.into_iter() {
             let (e, pos) = e_node.consume();
             match e {
-                Expression::Variable(v_name) => {
+                Expression::Variable(v_name)
+                | Expression::ArrayReference(v_name)
+                | Expression::UserDefinedVariable(v_name, _) => {
                     self.push(Instruction::PushUnnamedRefParam(v_name), pos);
                 }
                 _ => {
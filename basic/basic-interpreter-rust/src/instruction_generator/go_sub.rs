@@ -0,0 +1,57 @@
+use super::{Instruction, InstructionGenerator};
+use crate::common::*;
+use crate::linter::ExpressionNode;
+use crate::variant::Variant;
+
+impl InstructionGenerator {
+    pub fn generate_go_sub_instructions(&mut self, label: CaseInsensitiveString, pos: Location) {
+        let idx = self.instructions.len();
+        self.push(Instruction::PushGoSubRet(idx + 2), pos);
+        self.push(Instruction::UnresolvedJump(label), pos);
+    }
+
+    pub fn generate_return_instructions(
+        &mut self,
+        opt_label: Option<CaseInsensitiveString>,
+        pos: Location,
+    ) {
+        match opt_label {
+            Some(label) => {
+                self.push(Instruction::DropGoSubRet, pos);
+                self.push(Instruction::UnresolvedJump(label), pos);
+            }
+            None => self.push(Instruction::PopGoSubRet, pos),
+        }
+    }
+
+    /// Generates `ON expr GOTO` and `ON expr GOSUB`. When the expression does not
+    /// match any label (e.g. it is zero), execution continues with the next statement.
+    /// A negative expression or one greater than 255 is an `Illegal function call`.
+    pub fn generate_on_instructions(
+        &mut self,
+        e: ExpressionNode,
+        labels: Vec<CaseInsensitiveString>,
+        is_go_sub: bool,
+        pos: Location,
+    ) {
+        // evaluate expression into A, round it once, then copy it to B
+        self.generate_expression_instructions(e);
+        self.push(Instruction::CastAToOnSelector, pos);
+        self.push(Instruction::CopyAToB, pos);
+        // every label is a fixed number of instructions,
+        // so the address after the last label is known in advance
+        let label_len = if is_go_sub { 5 } else { 4 };
+        let start_idx = self.instructions.len();
+        let end_idx = start_idx + labels.len() * label_len;
+        for (i, label) in labels.into_iter().enumerate() {
+            let next_idx = start_idx + (i + 1) * label_len;
+            self.push(Instruction::Load(Variant::VInteger(i as i32 + 1)), pos);
+            self.push(Instruction::Equal, pos);
+            self.push(Instruction::JumpIfFalse(next_idx), pos);
+            if is_go_sub {
+                self.push(Instruction::PushGoSubRet(end_idx), pos);
+            }
+            self.push(Instruction::UnresolvedJump(label), pos);
+        }
+    }
+}
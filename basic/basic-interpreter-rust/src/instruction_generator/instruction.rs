@@ -1,5 +1,5 @@
 use crate::common::*;
-use crate::linter::{BuiltInFunction, BuiltInSub, QualifiedName};
+use crate::linter::{BareName, BuiltInFunction, BuiltInSub, QualifiedName};
 use crate::variant::Variant;
 
 #[derive(Debug, PartialEq)]
@@ -20,11 +20,22 @@ pub enum Instruction {
     /// Adds registers A and B and stores the results into register A
     Plus,
     Minus,
+    Multiply,
+    Divide,
+    IntegerDivide,
+    Modulo,
+    Power,
+    And,
+    Or,
+    Xor,
+    Eqv,
+    Imp,
     Less,
     LessOrEqual,
     Equal,
     GreaterOrEqual,
     Greater,
+    NotEqual,
     NegateA,
     NotA,
     Jump(usize),
@@ -35,6 +46,10 @@ pub enum Instruction {
     CopyVarToA(QualifiedName),
     BuiltInSub(BuiltInSub),
     BuiltInFunction(BuiltInFunction),
+    /// Calls a sub of the host. The arguments are popped from the arguments context.
+    HostSub(CaseInsensitiveString),
+    /// Calls a function of the host. The arguments are popped from the arguments context.
+    HostFunction(CaseInsensitiveString),
     Halt,
 
     PushRegisters,
@@ -43,6 +58,16 @@ pub enum Instruction {
     PushRet(usize),
     PopRet,
 
+    /// Pushes the address to return to from a `GOSUB`
+    PushGoSubRet(usize),
+    /// Returns to the address pushed by the last `GOSUB`
+    PopGoSubRet,
+    /// Discards the address pushed by the last `GOSUB`, used by `RETURN label`
+    DropGoSubRet,
+    /// Rounds A to the integer selector of `ON expr GOTO` and `ON expr GOSUB`,
+    /// raising `Illegal function call` when it is not between 0 and 255.
+    CastAToOnSelector,
+
     PreparePush,
     PushStack,
     PopStack,
@@ -61,12 +86,61 @@ pub enum Instruction {
     /// Copies the result of a function to A
     CopyResultToA,
 
+    /// Creates a fixed length string variable, e.g. `DIM A AS STRING * 5`.
+    DimFixedLengthString(QualifiedName, u16),
+    /// Allocates a static array. The bounds are popped from the arguments context.
+    DimArray(QualifiedName, ArrayElementType),
+    /// Allocates a dynamic array. The bounds are popped from the arguments context.
+    DimDynamicArray(QualifiedName, ArrayElementType),
+    /// Re-allocates a dynamic array. The bounds are popped from the arguments context.
+    ReDimArray(QualifiedName, ArrayElementType),
+    /// Copies an array element to A. The indices are popped from the arguments context.
+    CopyArrayElementToA(QualifiedName),
+    /// Stores A into an array element. The indices are popped from the arguments context.
+    StoreArrayElement(QualifiedName),
+    EraseArray(QualifiedName),
+
+    /// Replaces A with one of its members, e.g. `Suit` when A holds a `Card`.
+    CopyAMemberToA(BareName),
+    /// Stores A into a member of a variable of a user defined type,
+    /// e.g. `P.Address.City`.
+    StoreMember(QualifiedName, Vec<BareName>),
+    /// Stores A into a member of an array element of a user defined type.
+    /// The indices are popped from the arguments context.
+    StoreArrayElementMember(QualifiedName, Vec<BareName>),
+
     SetUnresolvedErrorHandler(CaseInsensitiveString),
     SetErrorHandler(usize),
+    /// Disables error handling, used by `ON ERROR GOTO 0`.
+    ClearErrorHandler,
+    /// Raises the runtime error whose code is in register A, used by `ERROR n`.
+    RaiseError,
+    /// Returns from the error handler to the statement that caused the error.
+    Resume,
+    /// Returns from the error handler to the statement after the one that caused the error.
+    ResumeNext,
+    UnresolvedResumeLabel(CaseInsensitiveString),
+    /// Returns from the error handler to the given address.
+    ResumeLabel(usize),
+
+    /// Marks the start of a statement and holds the address right after its end.
+    /// `RESUME` uses it to find the statement that caused an error.
+    StatementStart(usize),
 }
 
 pub type InstructionNode = Locatable<Instruction>;
 
+/// The type of the elements of an array that is being allocated.
+#[derive(Debug, PartialEq)]
+pub enum ArrayElementType {
+    /// A built-in type, which comes from the name of the array.
+    BuiltIn,
+    /// A fixed length string, e.g. `DIM A(10) AS STRING * 5`.
+    FixedLengthString(u16),
+    /// A user defined type. Holds the initial value of the elements.
+    UserDefined(Variant),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct NamedRefParam {
     pub parameter_name: QualifiedName,
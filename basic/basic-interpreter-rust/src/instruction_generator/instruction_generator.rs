@@ -1,3 +1,4 @@
+use super::exit::LoopFrame;
 use super::instruction::*;
 use crate::common::*;
 use crate::linter::*;
@@ -41,6 +42,8 @@ pub struct InstructionGenerator {
     pub instructions: Vec<InstructionNode>,
     pub function_context: ParamMap,
     pub sub_context: ParamMap,
+    pub user_defined_types: HashMap<CaseInsensitiveString, UserDefinedType>,
+    pub loops: Vec<LoopFrame>,
 }
 
 pub fn generate_instructions(program: ProgramNode) -> Vec<InstructionNode> {
@@ -67,6 +70,8 @@ impl InstructionGenerator {
             instructions: vec![],
             function_context,
             sub_context,
+            user_defined_types: HashMap::new(),
+            loops: vec![],
         }
     }
 
@@ -82,6 +87,9 @@ impl InstructionGenerator {
                 }
                 TopLevelToken::FunctionImplementation(f) => functions.push((f, pos)),
                 TopLevelToken::SubImplementation(s) => subs.push((s, pos)),
+                TopLevelToken::UserDefinedType(u) => {
+                    self.user_defined_types.insert(u.name.clone(), u);
+                }
             }
         }
 
@@ -128,6 +136,8 @@ impl InstructionGenerator {
                 *instruction_node = Instruction::JumpIfFalse(*labels.get(x).unwrap()).at(pos);
             } else if let Instruction::SetUnresolvedErrorHandler(x) = instruction {
                 *instruction_node = Instruction::SetErrorHandler(*labels.get(x).unwrap()).at(pos);
+            } else if let Instruction::UnresolvedResumeLabel(x) = instruction {
+                *instruction_node = Instruction::ResumeLabel(*labels.get(x).unwrap()).at(pos);
             }
         }
     }
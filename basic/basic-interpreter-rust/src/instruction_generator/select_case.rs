@@ -42,6 +42,7 @@ impl InstructionGenerator {
                         Operand::Equal => self.push(Instruction::Equal, pos),
                         Operand::GreaterOrEqual => self.push(Instruction::GreaterOrEqual, pos),
                         Operand::Greater => self.push(Instruction::Greater, pos),
+                        Operand::NotEqual => self.push(Instruction::NotEqual, pos),
                         _ => panic!("Unexpected CASE IS operator {:?}", op),
                     }
                     self.jump_if_false(next_case_label(case_blocks_len, has_else, idx), pos);
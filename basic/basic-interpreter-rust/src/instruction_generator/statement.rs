@@ -1,6 +1,6 @@
 use super::{Instruction, InstructionGenerator};
 use crate::common::*;
-use crate::linter::{Statement, StatementNode, StatementNodes};
+use crate::linter::{ResumeOption, Statement, StatementNode, StatementNodes};
 
 impl InstructionGenerator {
     pub fn generate_block_instructions(&mut self, block: StatementNodes) {
@@ -11,10 +11,40 @@ impl InstructionGenerator {
 
     pub fn generate_statement_node_instructions(&mut self, statement_node: StatementNode) {
         let (statement, pos) = statement_node.consume();
+        if let Statement::Comment(_) = statement {
+            // comments don't run, so the debugger never stops at them
+            return;
+        }
+        // the end of the statement is patched once its instructions are generated
+        let start = self.instructions.len();
+        self.push(Instruction::StatementStart(start), pos);
+        self.generate_statement_instructions(statement, pos);
+        let end = self.instructions.len();
+        self.instructions[start] = Instruction::StatementStart(end).at(pos);
+    }
+
+    fn generate_statement_instructions(&mut self, statement: Statement, pos: Location) {
         match statement {
             Statement::Assignment(left_side, right_side) => {
                 self.generate_assignment_instructions(left_side.at(pos), right_side)
             }
+            Statement::ArrayElementAssignment(n, indices, e) => {
+                self.generate_array_element_assignment_instructions(n, indices, e, pos)
+            }
+            Statement::MemberAssignment(n, members, e) => {
+                self.generate_expression_instructions(e);
+                self.push(Instruction::StoreMember(n, members), pos);
+            }
+            Statement::ArrayElementMemberAssignment(n, indices, members, e) => self
+                .generate_array_element_member_assignment_instructions(n, indices, members, e, pos),
+            Statement::Dim(dim_names) => self.generate_dim_instructions(dim_names, false),
+            Statement::ReDim(dim_names) => self.generate_dim_instructions(dim_names, true),
+            Statement::Erase(names) => {
+                for name_node in names.into_iter() {
+                    let (name, pos) = name_node.consume();
+                    self.push(Instruction::EraseArray(name), pos);
+                }
+            }
             Statement::Const(n, e) => self.generate_const_instructions(n, e),
             Statement::SubCall(n, args) => self.generate_sub_call_instructions(n.at(pos), args),
             Statement::BuiltInSubCall(n, args) => {
@@ -24,19 +54,38 @@ impl InstructionGenerator {
             Statement::SelectCase(s) => self.generate_select_case_instructions(s, pos),
             Statement::ForLoop(f) => self.generate_for_loop_instructions(f, pos),
             Statement::While(w) => self.generate_while_instructions(w, pos),
-            Statement::ErrorHandler(label) => {
+            Statement::DoLoop(d) => self.generate_do_loop_instructions(d, pos),
+            Statement::Exit(e) => self.generate_exit_instructions(e, pos),
+            Statement::ErrorHandler(Some(label)) => {
                 self.push(Instruction::SetUnresolvedErrorHandler(label), pos);
             }
+            Statement::ErrorHandler(None) => {
+                self.push(Instruction::ClearErrorHandler, pos);
+            }
+            Statement::Error(e) => {
+                self.generate_expression_instructions(e);
+                self.push(Instruction::RaiseError, pos);
+            }
+            Statement::Resume(ResumeOption::Bare) => self.push(Instruction::Resume, pos),
+            Statement::Resume(ResumeOption::Next) => self.push(Instruction::ResumeNext, pos),
+            Statement::Resume(ResumeOption::Label(label)) => {
+                self.push(Instruction::UnresolvedResumeLabel(label), pos);
+            }
             Statement::Label(name) => {
                 self.push(Instruction::Label(name.clone()), pos);
             }
             Statement::GoTo(name) => {
                 self.push(Instruction::UnresolvedJump(name.clone()), pos);
             }
+            Statement::GoSub(name) => self.generate_go_sub_instructions(name, pos),
+            Statement::Return(opt_label) => self.generate_return_instructions(opt_label, pos),
+            Statement::OnGoTo(e, labels) => self.generate_on_instructions(e, labels, false, pos),
+            Statement::OnGoSub(e, labels) => self.generate_on_instructions(e, labels, true, pos),
             Statement::SetReturnValue(e) => {
                 self.generate_expression_instructions(e);
                 self.push(Instruction::StoreAToResult, pos);
             }
+            Statement::Comment(_) => {}
         }
     }
 }
@@ -8,12 +8,21 @@ impl InstructionGenerator {
         args: Vec<ExpressionNode>,
     ) {
         let (name, pos) = name_node.consume();
-        let sub_impl_parameters = self.sub_context.get(&name).unwrap().clone();
-        self.generate_push_named_args_instructions(sub_impl_parameters, args, pos);
-        self.push(Instruction::PushStack, pos);
-        let idx = self.instructions.len();
-        self.push(Instruction::PushRet(idx + 2), pos);
-        self.jump_to_sub(name, pos);
+        match self.sub_context.get(&name).cloned() {
+            Some(sub_impl_parameters) => {
+                self.generate_push_named_args_instructions(sub_impl_parameters, args, pos);
+                self.push(Instruction::PushStack, pos);
+                let idx = self.instructions.len();
+                self.push(Instruction::PushRet(idx + 2), pos);
+                self.jump_to_sub(name, pos);
+            }
+            None => {
+                // not implemented in the program, so it is a sub of the host
+                self.generate_push_unnamed_args_instructions(args, pos);
+                self.push(Instruction::PushStack, pos);
+                self.push(Instruction::HostSub(name), pos);
+            }
+        }
         self.push(Instruction::PopStack, pos);
     }
 }
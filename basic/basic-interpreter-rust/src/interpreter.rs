@@ -1,24 +1,37 @@
 mod assignment;
 mod built_in_functions;
 mod built_in_subs;
+mod clock;
 mod constant;
 mod context;
 mod context_owner;
+mod debugger;
+mod dim;
+mod do_loop;
 mod expression;
+mod file;
 mod for_loop;
 mod function_call;
+mod go_sub;
 mod go_to;
+mod host;
 mod if_block;
+mod input;
 mod interpreter;
 mod interpreter_error;
+mod print;
+mod random;
 mod select_case;
 mod stdlib;
 mod sub_call;
+mod user_defined_type;
 mod while_wend;
 
 #[cfg(test)]
-mod test_utils;
+pub mod test_utils;
 
+pub use self::debugger::{Debugger, Resume, Stop};
+pub use self::file::io_error_message;
 pub use self::interpreter::Interpreter;
 pub use self::interpreter_error::*;
 pub use self::stdlib::*;
@@ -1,11 +1,15 @@
+use crate::casting::cast;
 use crate::common::*;
 use crate::interpreter::context_owner::ContextOwner;
 use crate::interpreter::{Interpreter, InterpreterError, Result, Stdlib};
-use crate::linter::BuiltInFunction;
+use crate::linter::{BuiltInFunction, TypeQualifier};
 use crate::variant;
-use crate::variant::Variant;
+use crate::variant::{VArray, Variant};
+use std::cmp::Ordering;
 use std::convert::TryInto;
 
+const ILLEGAL_FUNCTION_CALL: &str = "Illegal function call";
+
 impl<S: Stdlib> Interpreter<S> {
     pub fn run_built_in_function(
         &mut self,
@@ -13,9 +17,196 @@ impl<S: Stdlib> Interpreter<S> {
         pos: Location,
     ) -> Result<()> {
         match function_name {
+            BuiltInFunction::Abs => self
+                .run_abs()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Asc => self
+                .run_asc()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Atn => self
+                .run_float_function(f64::atan)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::CDbl => self
+                .run_conversion(TypeQualifier::HashDouble)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Chr => self
+                .run_chr()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::CInt => self
+                .run_conversion(TypeQualifier::PercentInteger)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::CLng => self
+                .run_conversion(TypeQualifier::AmpersandLong)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Cos => self
+                .run_float_function(f64::cos)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::CSng => self
+                .run_conversion(TypeQualifier::BangSingle)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Cvd => self
+                .run_cv(TypeQualifier::HashDouble)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Cvi => self
+                .run_cv(TypeQualifier::PercentInteger)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Cvl => self
+                .run_cv(TypeQualifier::AmpersandLong)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Cvs => self
+                .run_cv(TypeQualifier::BangSingle)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Date => {
+                self.function_result = self.date();
+                Ok(())
+            }
             BuiltInFunction::Environ => self.run_environ(),
+            BuiltInFunction::Eof => {
+                self.function_result = self
+                    .eof()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::Erl => {
+                self.function_result = Variant::VLong(i64::from(self.erl()));
+                Ok(())
+            }
+            BuiltInFunction::Err => {
+                self.function_result = Variant::VInteger(self.err());
+                Ok(())
+            }
+            BuiltInFunction::Exp => self
+                .run_float_function(f64::exp)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Fix => self
+                .run_rounding(f32::trunc, f64::trunc)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::FreeFile => {
+                self.function_result = self
+                    .free_file()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::Hex => self
+                .run_hex_oct(16)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Input => {
+                self.function_result = self
+                    .input_chars()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::InStr => self
+                .run_instr()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Int => self
+                .run_rounding(f32::floor, f64::floor)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::LBound => self
+                .run_bound(|a, d| a.lbound(d))
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::LCase => {
+                let s = self.pop_string();
+                self.function_result = Variant::VString(s.to_ascii_lowercase());
+                Ok(())
+            }
+            BuiltInFunction::Left => self
+                .run_left_right(false)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
             BuiltInFunction::Len => self.run_len(),
+            BuiltInFunction::Loc => {
+                self.function_result = self
+                    .loc()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::Lof => {
+                self.function_result = self
+                    .lof()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::Log => self
+                .run_log()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::LTrim => {
+                let s = self.pop_string();
+                self.function_result = Variant::VString(s.trim_start_matches(' ').to_string());
+                Ok(())
+            }
+            BuiltInFunction::Mid => self
+                .run_mid()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Mkd => self
+                .run_mk(TypeQualifier::HashDouble)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Mki => self
+                .run_mk(TypeQualifier::PercentInteger)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Mkl => self
+                .run_mk(TypeQualifier::AmpersandLong)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Mks => self
+                .run_mk(TypeQualifier::BangSingle)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Oct => self
+                .run_hex_oct(8)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Right => self
+                .run_left_right(true)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Rnd => {
+                self.function_result = self
+                    .rnd()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::RTrim => {
+                let s = self.pop_string();
+                self.function_result = Variant::VString(s.trim_end_matches(' ').to_string());
+                Ok(())
+            }
+            BuiltInFunction::Seek => {
+                self.function_result = self
+                    .seek_position()
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                Ok(())
+            }
+            BuiltInFunction::Sgn => self
+                .run_sgn()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Sin => self
+                .run_float_function(f64::sin)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Space => self
+                .run_space()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Sqr => self
+                .run_sqr()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
             BuiltInFunction::Str => self.run_str(),
+            BuiltInFunction::String => self
+                .run_string()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Tan => self
+                .run_float_function(f64::tan)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::Time => {
+                self.function_result = self.time();
+                Ok(())
+            }
+            BuiltInFunction::Timer => {
+                self.function_result = self.timer();
+                Ok(())
+            }
+            BuiltInFunction::UBound => self
+                .run_bound(|a, d| a.ubound(d))
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInFunction::UCase => {
+                let s = self.pop_string();
+                self.function_result = Variant::VString(s.to_ascii_uppercase());
+                Ok(())
+            }
             BuiltInFunction::Val => self
                 .run_val()
                 .map_err(|e| InterpreterError::new_with_pos(e, pos)),
@@ -34,14 +225,284 @@ impl<S: Stdlib> Interpreter<S> {
         }
     }
 
+    fn run_bound(
+        &mut self,
+        f: fn(&VArray, usize) -> std::result::Result<i32, String>,
+    ) -> std::result::Result<(), String> {
+        let sub_context = self.context_mut().demand_sub();
+        let v = sub_context.pop_front_unnamed();
+        let dimension: usize = match sub_context.try_pop_front_unnamed() {
+            Some(d) => match cast(d, TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l) if l >= 1 => l as usize,
+                _ => return Err("Subscript out of range".to_string()),
+            },
+            None => 1,
+        };
+        self.function_result = match v {
+            Variant::VArray(a) => Variant::VInteger(f(&a, dimension)?),
+            _ => panic!("unexpected arg to LBOUND/UBOUND"),
+        };
+        Ok(())
+    }
+
+    fn pop_string(&mut self) -> String {
+        match self.context_mut().demand_sub().pop_front_unnamed() {
+            Variant::VString(s) => s,
+            _ => panic!("Expected string argument (linter should have caught this)"),
+        }
+    }
+
+    /// Pops a numeric argument that must not be negative, e.g. the length of `LEFT$`.
+    fn pop_non_negative(&mut self) -> std::result::Result<usize, String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        to_non_negative(v)
+    }
+
+    /// Pops a numeric argument that must be positive, e.g. the start of `MID$`.
+    fn pop_positive(&mut self) -> std::result::Result<usize, String> {
+        match self.pop_non_negative()? {
+            0 => Err(ILLEGAL_FUNCTION_CALL.to_string()),
+            i => Ok(i),
+        }
+    }
+
+    fn run_asc(&mut self) -> std::result::Result<(), String> {
+        let s = self.pop_string();
+        match s.chars().next() {
+            Some(ch) => {
+                self.function_result = Variant::VInteger(ch as i32);
+                Ok(())
+            }
+            None => Err(ILLEGAL_FUNCTION_CALL.to_string()),
+        }
+    }
+
+    fn run_chr(&mut self) -> std::result::Result<(), String> {
+        let code = self.pop_non_negative()?;
+        if code > 255 {
+            return Err(ILLEGAL_FUNCTION_CALL.to_string());
+        }
+        self.function_result = Variant::VString((code as u8 as char).to_string());
+        Ok(())
+    }
+
+    /// Implements `INSTR([start,] haystack, needle)`, which returns the position of
+    /// the needle (one based), or zero if it is not found.
+    fn run_instr(&mut self) -> std::result::Result<(), String> {
+        let mut args: Vec<Variant> = vec![];
+        while let Some(v) = self.context_mut().demand_sub().try_pop_front_unnamed() {
+            args.push(v);
+        }
+        let start = if args.len() == 3 {
+            match to_non_negative(args.remove(0))? {
+                0 => return Err(ILLEGAL_FUNCTION_CALL.to_string()),
+                i => i,
+            }
+        } else {
+            1
+        };
+        let haystack: Vec<char> = args[0].to_string().chars().collect();
+        let needle: Vec<char> = args[1].to_string().chars().collect();
+        let found = if start > haystack.len() {
+            None
+        } else if needle.is_empty() {
+            Some(start)
+        } else {
+            haystack[start - 1..]
+                .windows(needle.len())
+                .position(|w| w == needle.as_slice())
+                .map(|i| i + start)
+        };
+        self.function_result = Variant::VInteger(found.unwrap_or_default() as i32);
+        Ok(())
+    }
+
+    /// Implements `LEFT$` (`from_right` is false) and `RIGHT$` (`from_right` is true).
+    fn run_left_right(&mut self, from_right: bool) -> std::result::Result<(), String> {
+        let s = self.pop_string();
+        let len = self.pop_non_negative()?;
+        let char_count = s.chars().count();
+        let result: String = if from_right {
+            s.chars().skip(char_count.saturating_sub(len)).collect()
+        } else {
+            s.chars().take(len).collect()
+        };
+        self.function_result = Variant::VString(result);
+        Ok(())
+    }
+
+    /// Implements `MID$(s, start[, length])`.
+    fn run_mid(&mut self) -> std::result::Result<(), String> {
+        let s = self.pop_string();
+        let start = self.pop_positive()?;
+        let result: String = match self.context_mut().demand_sub().try_pop_front_unnamed() {
+            Some(len) => s
+                .chars()
+                .skip(start - 1)
+                .take(to_non_negative(len)?)
+                .collect(),
+            None => s.chars().skip(start - 1).collect(),
+        };
+        self.function_result = Variant::VString(result);
+        Ok(())
+    }
+
+    fn run_space(&mut self) -> std::result::Result<(), String> {
+        let len = self.pop_non_negative()?;
+        self.function_result = Variant::VString(" ".repeat(len));
+        Ok(())
+    }
+
+    /// Implements `STRING$(count, code)` and `STRING$(count, s)`, which repeat
+    /// the character of the given code, or the first character of the given string.
+    fn run_string(&mut self) -> std::result::Result<(), String> {
+        let len = self.pop_non_negative()?;
+        let ch: char = match self.context_mut().demand_sub().pop_front_unnamed() {
+            Variant::VString(s) => s
+                .chars()
+                .next()
+                .ok_or_else(|| ILLEGAL_FUNCTION_CALL.to_string())?,
+            v => match cast(v, TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l) if (0..=255).contains(&l) => l as u8 as char,
+                _ => return Err(ILLEGAL_FUNCTION_CALL.to_string()),
+            },
+        };
+        self.function_result = Variant::VString(std::iter::repeat_n(ch, len).collect());
+        Ok(())
+    }
+
+    /// Implements `CVI`, `CVL`, `CVS` and `CVD`, which convert the bytes of a string,
+    /// as created by `MKI$`, `MKL$`, `MKS$` and `MKD$`, back into a number.
+    fn run_cv(&mut self, q: TypeQualifier) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let bytes = variant::string_to_bytes(&v.to_string());
+        let template = Variant::default_variant(q);
+        if bytes.len() < template.byte_len()? {
+            return Err("Illegal function call".to_string());
+        }
+        self.function_result = template.read_bytes(&bytes)?;
+        Ok(())
+    }
+
+    /// Implements `MKI$`, `MKL$`, `MKS$` and `MKD$`, which convert a number into
+    /// a string holding its bytes, as stored in a random access or binary file.
+    fn run_mk(&mut self, q: TypeQualifier) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let bytes = cast(v, q)?.to_bytes()?;
+        self.function_result = Variant::VString(variant::bytes_to_string(&bytes));
+        Ok(())
+    }
+
+    fn run_abs(&mut self) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        self.function_result = match v {
+            Variant::VSingle(f) => Variant::VSingle(f.abs()),
+            Variant::VDouble(d) => Variant::VDouble(d.abs()),
+            Variant::VInteger(i) if i < 0 => v.negate()?,
+            Variant::VLong(l) if l < 0 => v.negate()?,
+            _ => v,
+        };
+        Ok(())
+    }
+
+    fn run_sgn(&mut self) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let sign = match v.cmp(&Variant::VInteger(0))? {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        };
+        self.function_result = Variant::VInteger(sign);
+        Ok(())
+    }
+
+    /// Implements `INT` and `FIX`, which keep integers and longs as they are.
+    fn run_rounding(
+        &mut self,
+        single_fn: fn(f32) -> f32,
+        double_fn: fn(f64) -> f64,
+    ) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        self.function_result = match v {
+            Variant::VSingle(f) => Variant::VSingle(single_fn(f)),
+            Variant::VDouble(d) => Variant::VDouble(double_fn(d)),
+            _ => v,
+        };
+        Ok(())
+    }
+
+    /// Implements `CINT`, `CLNG`, `CSNG` and `CDBL`.
+    fn run_conversion(&mut self, q: TypeQualifier) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        self.function_result = cast(v, q)?;
+        Ok(())
+    }
+
+    /// Implements the floating point functions like `SIN` and `EXP`. The result is
+    /// a double if the argument is a double, otherwise it is a single.
+    fn run_float_function(&mut self, f: fn(f64) -> f64) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        self.function_result = float_result(&v, f(to_double(v.clone())?))?;
+        Ok(())
+    }
+
+    fn run_sqr(&mut self) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let d = to_double(v.clone())?;
+        if d < 0.0 {
+            return Err(ILLEGAL_FUNCTION_CALL.to_string());
+        }
+        self.function_result = float_result(&v, d.sqrt())?;
+        Ok(())
+    }
+
+    fn run_log(&mut self) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let d = to_double(v.clone())?;
+        if d <= 0.0 {
+            return Err(ILLEGAL_FUNCTION_CALL.to_string());
+        }
+        self.function_result = float_result(&v, d.ln())?;
+        Ok(())
+    }
+
+    /// Implements `HEX$` (radix 16) and `OCT$` (radix 8). Negative numbers are
+    /// shown in two's complement, using 16 bits for integers and 32 bits for longs.
+    /// Floating point numbers are rounded to an integer or a long first.
+    fn run_hex_oct(&mut self, radix: u32) -> std::result::Result<(), String> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        let bits: u32 = match v {
+            Variant::VInteger(i) => i as i16 as u16 as u32,
+            Variant::VLong(l) => l as i32 as u32,
+            _ => match cast(v, TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l)
+                    if l >= variant::MIN_INTEGER as i64 && l <= variant::MAX_INTEGER as i64 =>
+                {
+                    l as i16 as u16 as u32
+                }
+                Variant::VLong(l) => l as i32 as u32,
+                _ => panic!("Expected long"),
+            },
+        };
+        let result = if radix == 16 {
+            format!("{:X}", bits)
+        } else {
+            format!("{:o}", bits)
+        };
+        self.function_result = Variant::VString(result);
+        Ok(())
+    }
+
     fn run_len(&mut self) -> Result<()> {
         let v = self.context_mut().demand_sub().pop_front_unnamed();
         self.function_result = match v {
             Variant::VSingle(_) => Variant::VInteger(4),
             Variant::VDouble(_) => Variant::VInteger(8),
-            Variant::VString(v) => Variant::VInteger(v.len().try_into().unwrap()),
+            Variant::VString(v) => Variant::VInteger(v.chars().count().try_into().unwrap()),
             Variant::VInteger(_) => Variant::VInteger(2),
             Variant::VLong(_) => Variant::VInteger(4),
+            // the length of a user defined type is calculated by the linter
+            Variant::VArray(_) | Variant::VUserDefined(_) => panic!("unexpected arg to LEN"),
         };
         Ok(())
     }
@@ -54,6 +515,7 @@ impl<S: Stdlib> Interpreter<S> {
             Variant::VString(_) => panic!("unexpected arg to STR$"),
             Variant::VInteger(f) => Variant::VString(format!("{}", f)),
             Variant::VLong(f) => Variant::VString(format!("{}", f)),
+            Variant::VArray(_) | Variant::VUserDefined(_) => panic!("unexpected arg to STR$"),
         };
         Ok(())
     }
@@ -68,6 +530,37 @@ impl<S: Stdlib> Interpreter<S> {
     }
 }
 
+fn to_double(v: Variant) -> std::result::Result<f64, String> {
+    match cast(v, TypeQualifier::HashDouble)? {
+        Variant::VDouble(d) => Ok(d),
+        _ => panic!("Expected double"),
+    }
+}
+
+/// Creates the result of a floating point function, which is a double if the
+/// argument was a double, otherwise a single.
+fn float_result(arg: &Variant, result: f64) -> std::result::Result<Variant, String> {
+    if !result.is_finite() {
+        Err("Overflow".to_string())
+    } else if let Variant::VDouble(_) = arg {
+        Ok(Variant::VDouble(result))
+    } else {
+        let f = result as f32;
+        if f.is_finite() {
+            Ok(Variant::VSingle(f))
+        } else {
+            Err("Overflow".to_string())
+        }
+    }
+}
+
+fn to_non_negative(v: Variant) -> std::result::Result<usize, String> {
+    match cast(v, TypeQualifier::AmpersandLong)? {
+        Variant::VLong(l) if l >= 0 => Ok(l as usize),
+        _ => Err(ILLEGAL_FUNCTION_CALL.to_string()),
+    }
+}
+
 fn val(s: String) -> std::result::Result<Variant, String> {
     let mut is_positive = true;
     let mut value: f64 = 0.0;
@@ -211,7 +704,7 @@ mod tests {
         fn test_len_string() {
             let program = r#"PRINT LEN("hello")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["5"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 5 "]);
         }
 
         #[test]
@@ -221,7 +714,7 @@ mod tests {
             PRINT LEN(A)
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["4"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 4 "]);
         }
 
         #[test]
@@ -231,7 +724,7 @@ mod tests {
             PRINT LEN(A#)
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["8"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 8 "]);
         }
 
         #[test]
@@ -241,7 +734,7 @@ mod tests {
             PRINT LEN(A%)
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["2"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 2 "]);
         }
 
         #[test]
@@ -251,7 +744,7 @@ mod tests {
             PRINT LEN(A&)
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["4"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 4 "]);
         }
 
         #[test]
@@ -306,45 +799,314 @@ mod tests {
         fn test_val_float() {
             let program = r#"PRINT VAL("3.14")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
 
         #[test]
         fn test_val_integer() {
             let program = r#"PRINT VAL("42")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
         }
 
         #[test]
         fn test_val_invalid_string_gives_zero() {
             let program = r#"PRINT VAL("oops")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["0"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 0 "]);
         }
 
         #[test]
         fn test_val_partial_parse() {
             let program = r#"PRINT VAL("3.14oops")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
 
         #[test]
         fn test_val_partial_parse_ignores_spaces() {
             let program = r#"PRINT VAL("  -    4   . 2   ")"#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["-4.2"]);
+            assert_eq!(interpreter.stdlib.output, vec!["-4.2 "]);
         }
 
         #[test]
         fn test_val_no_overflow() {
             let program = r#"PRINT VAL("1234567890123456789012345678901234567890")"#;
             let interpreter = interpret(program);
+            assert_eq!(interpreter.stdlib.output, vec![" 1.234567890123457D+39 "]);
+        }
+    }
+
+    mod string_functions {
+        use super::*;
+
+        #[test]
+        fn test_left_and_right() {
+            let program = r#"
+            PRINT LEFT$("hello", 2)
+            PRINT RIGHT$("hello", 3)
+            PRINT LEFT$("hi", 10)
+            PRINT RIGHT$("hi", 0)
+            "#;
             assert_eq!(
-                interpreter.stdlib.output,
-                vec!["1234567890123456800000000000000000000000"]
+                interpret(program).stdlib.output,
+                vec!["he", "llo", "hi", ""]
             );
         }
+
+        #[test]
+        fn test_mid() {
+            let program = r#"
+            PRINT MID$("hello world", 7)
+            PRINT MID$("hello world", 2, 3)
+            PRINT MID$("hello", 10)
+            "#;
+            assert_eq!(interpret(program).stdlib.output, vec!["world", "ell", ""]);
+        }
+
+        #[test]
+        fn test_instr() {
+            let program = r#"
+            PRINT INSTR("a=1&b=2", "&")
+            PRINT INSTR(4, "a=1&b=2", "=")
+            PRINT INSTR("abc", "x")
+            PRINT INSTR("abc", "")
+            PRINT INSTR(5, "abc", "c")
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec![" 4 ", " 6 ", " 0 ", " 1 ", " 0 "]
+            );
+        }
+
+        #[test]
+        fn test_chr_and_asc() {
+            let program = r#"
+            PRINT CHR$(34) + "quoted" + CHR$(34)
+            PRINT ASC("A")
+            PRINT ASC(CHR$(200))
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec!["\"quoted\"", " 65 ", " 200 "]
+            );
+        }
+
+        #[test]
+        fn test_case_conversion() {
+            let program = r#"
+            PRINT UCASE$("Hello")
+            PRINT LCASE$("Hello")
+            "#;
+            assert_eq!(interpret(program).stdlib.output, vec!["HELLO", "hello"]);
+        }
+
+        #[test]
+        fn test_trim() {
+            let program = r#"
+            PRINT "(" + LTRIM$("  hi  ") + ")"
+            PRINT "(" + RTRIM$("  hi  ") + ")"
+            "#;
+            assert_eq!(interpret(program).stdlib.output, vec!["(hi  )", "(  hi)"]);
+        }
+
+        #[test]
+        fn test_space_and_string() {
+            let program = r#"
+            PRINT "(" + SPACE$(3) + ")"
+            PRINT STRING$(3, "*")
+            PRINT STRING$(2, 65)
+            PRINT STRING$(0, "x")
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec!["(   )", "***", "AA", ""]
+            );
+        }
+
+        #[test]
+        fn test_illegal_function_call() {
+            assert_eq!(interpret_err("PRINT LEFT$(\"a\", -1)").code(), 5);
+            assert_eq!(interpret_err("PRINT MID$(\"a\", 0)").code(), 5);
+            assert_eq!(interpret_err("PRINT CHR$(256)").code(), 5);
+            assert_eq!(interpret_err("PRINT ASC(\"\")").code(), 5);
+            assert_eq!(interpret_err("PRINT SPACE$(-1)").code(), 5);
+            assert_eq!(interpret_err("PRINT STRING$(2, \"\")").code(), 5);
+            assert_eq!(interpret_err("PRINT INSTR(0, \"a\", \"a\")").code(), 5);
+        }
+
+        #[test]
+        fn test_string_functions_must_be_qualified() {
+            assert_linter_err!("PRINT LEFT(\"a\", 1)", LinterError::SyntaxError, 1, 7);
+            assert_linter_err!("PRINT CHR%(65)", LinterError::TypeMismatch, 1, 7);
+        }
+
+        #[test]
+        fn test_asc_and_instr_must_be_unqualified() {
+            assert_linter_err!("PRINT ASC$(\"a\")", LinterError::SyntaxError, 1, 7);
+            assert_linter_err!("PRINT INSTR%(\"a\", \"b\")", LinterError::SyntaxError, 1, 7);
+        }
+
+        #[test]
+        fn test_argument_type_mismatch() {
+            assert_linter_err!(
+                "PRINT LEFT$(1, 1)",
+                LinterError::ArgumentTypeMismatch,
+                1,
+                13
+            );
+            assert_linter_err!(
+                "PRINT MID$(\"a\", \"b\")",
+                LinterError::ArgumentTypeMismatch,
+                1,
+                17
+            );
+            assert_linter_err!(
+                "PRINT INSTR(\"a\", 1)",
+                LinterError::ArgumentTypeMismatch,
+                1,
+                18
+            );
+        }
+
+        #[test]
+        fn test_argument_count_mismatch() {
+            assert_linter_err!(
+                "PRINT LEFT$(\"a\")",
+                LinterError::ArgumentCountMismatch,
+                1,
+                7
+            );
+            assert_linter_err!(
+                "PRINT INSTR(\"a\")",
+                LinterError::ArgumentCountMismatch,
+                1,
+                7
+            );
+        }
+    }
+
+    mod numeric_functions {
+        use super::*;
+
+        #[test]
+        fn test_abs_and_sgn() {
+            let program = r#"
+            PRINT ABS(-5)
+            PRINT ABS(2.5)
+            PRINT SGN(-3.2)
+            PRINT SGN(0)
+            PRINT SGN(7)
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec![" 5 ", " 2.5 ", "-1 ", " 0 ", " 1 "]
+            );
+        }
+
+        #[test]
+        fn test_int_and_fix() {
+            let program = r#"
+            PRINT INT(2.7)
+            PRINT INT(-2.5)
+            PRINT FIX(-2.7)
+            PRINT INT(5)
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec![" 2 ", "-3 ", "-2 ", " 5 "]
+            );
+        }
+
+        #[test]
+        fn test_conversions_round_half_to_even() {
+            let program = r#"
+            PRINT CINT(2.5)
+            PRINT CINT(3.5)
+            PRINT CINT(-2.5)
+            PRINT CLNG(100000.5)
+            PRINT CSNG(1)
+            PRINT CDBL(1.5)
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec![" 2 ", " 4 ", "-2 ", " 100000 ", " 1 ", " 1.5 "]
+            );
+        }
+
+        #[test]
+        fn test_float_functions() {
+            let program = r#"
+            PRINT SQR(16)
+            PRINT SQR(2)
+            D# = 2
+            PRINT SQR(D#)
+            PRINT SIN(0)
+            PRINT COS(0)
+            PRINT TAN(0)
+            PRINT ATN(0)
+            PRINT EXP(0)
+            PRINT LOG(1)
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec![
+                    " 4 ",
+                    " 1.414214 ",
+                    " 1.414213562373095 ",
+                    " 0 ",
+                    " 1 ",
+                    " 0 ",
+                    " 0 ",
+                    " 1 ",
+                    " 0 "
+                ]
+            );
+        }
+
+        #[test]
+        fn test_hex_and_oct() {
+            let program = r#"
+            PRINT HEX$(255)
+            PRINT HEX$(-1)
+            A& = -1
+            PRINT HEX$(A&)
+            PRINT HEX$(70000)
+            PRINT OCT$(8)
+            PRINT OCT$(-1)
+            "#;
+            assert_eq!(
+                interpret(program).stdlib.output,
+                vec!["FF", "FFFF", "FFFFFFFF", "11170", "10", "177777"]
+            );
+        }
+
+        #[test]
+        fn test_illegal_function_call() {
+            assert_eq!(interpret_err("PRINT SQR(-1)").code(), 5);
+            assert_eq!(interpret_err("PRINT LOG(0)").code(), 5);
+        }
+
+        #[test]
+        fn test_overflow() {
+            assert_eq!(interpret_err("PRINT CINT(40000)").code(), 6);
+            assert_eq!(interpret_err("PRINT EXP(1000)").code(), 6);
+            assert_eq!(interpret_err("A% = -32768\nPRINT ABS(A%)").code(), 6);
+        }
+
+        #[test]
+        fn test_string_argument_is_type_mismatch() {
+            assert_linter_err!("PRINT ABS(\"a\")", LinterError::TypeMismatch, 1, 11);
+            assert_linter_err!("PRINT CINT(\"a\")", LinterError::TypeMismatch, 1, 12);
+            assert_linter_err!("PRINT HEX$(\"a\")", LinterError::TypeMismatch, 1, 12);
+        }
+
+        #[test]
+        fn test_qualifiers() {
+            assert_linter_err!("PRINT ABS!(1)", LinterError::SyntaxError, 1, 7);
+            assert_linter_err!("PRINT HEX(1)", LinterError::SyntaxError, 1, 7);
+            assert_linter_err!("PRINT OCT%(1)", LinterError::TypeMismatch, 1, 7);
+        }
     }
 }
@@ -1,32 +1,120 @@
+use crate::casting::cast;
 use crate::common::*;
-use crate::interpreter::context::Argument;
 use crate::interpreter::context_owner::ContextOwner;
 use crate::interpreter::{err, Interpreter, InterpreterError, Result, Stdlib};
-use crate::linter::{BuiltInSub, HasQualifier, QualifiedName, TypeQualifier};
+use crate::linter::{BuiltInSub, TypeQualifier};
 use crate::variant::Variant;
 
 impl<S: Stdlib> Interpreter<S> {
     pub fn run_built_in_sub(&mut self, name: &BuiltInSub, pos: Location) -> Result<()> {
         match name {
-            BuiltInSub::Print => {
-                let mut print_args: Vec<String> = vec![];
-                loop {
-                    match self.context_mut().demand_sub().try_pop_front_unnamed() {
-                        Some(v) => print_args.push(v.to_string()),
-                        None => {
-                            break;
-                        }
-                    }
-                }
-                self.stdlib.print(print_args);
-                Ok(())
-            }
+            BuiltInSub::Close => self
+                .close_files()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
             BuiltInSub::Environ => self.do_environ_sub(pos),
-            BuiltInSub::Input => self.do_input(pos),
+            BuiltInSub::Field => self
+                .field()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Get => self
+                .get_record()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Input => self
+                .input()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Kill => self
+                .kill_file()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::LineInput => self
+                .line_input()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::LPrint => self
+                .lprint()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::LSet => self
+                .set_aligned(false)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Mid => self
+                .do_mid()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Name => self
+                .rename_file()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Open => self
+                .open_file()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Print => self
+                .print()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Put => self
+                .put_record()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Randomize => self
+                .randomize()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::RSet => self
+                .set_aligned(true)
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Seek => self
+                .seek()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
+            BuiltInSub::Sleep => self
+                .sleep()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
             BuiltInSub::System => panic!("Should have been handled at the IG level"),
+            BuiltInSub::Write => self
+                .do_write()
+                .map_err(|e| InterpreterError::new_with_pos(e, pos)),
         }
     }
 
+    /// Implements the `MID$` statement, which replaces characters of a string variable.
+    /// The length of the variable does not change.
+    fn do_mid(&mut self) -> std::result::Result<(), String> {
+        let sub_context = self.context_mut().demand_sub();
+        let arg = sub_context
+            .pop_front_unnamed_arg()
+            .expect("MID$ should have a variable");
+        let value: Vec<char> = sub_context
+            .pop_front_unnamed()
+            .to_string()
+            .chars()
+            .collect();
+        let start = sub_context.pop_front_unnamed();
+        let opt_len = sub_context.try_pop_front_unnamed();
+        let mut target: Vec<char> = sub_context
+            .evaluate_argument(&arg)
+            .map(|v| v.to_string())
+            .unwrap_or_default()
+            .chars()
+            .collect();
+        let start = match cast(start, TypeQualifier::AmpersandLong)? {
+            Variant::VLong(l) if l >= 1 && l as usize <= target.len() => l as usize - 1,
+            _ => return Err("Illegal function call".to_string()),
+        };
+        let mut len = value.len().min(target.len() - start);
+        if let Some(v) = opt_len {
+            match cast(v, TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l) if l >= 0 => len = len.min(l as usize),
+                _ => return Err("Illegal function call".to_string()),
+            }
+        }
+        target[start..start + len].copy_from_slice(&value[..len]);
+        let sub_context = self.context_mut().demand_sub();
+        sub_context.set_value_to_popped_arg(&arg, Variant::VString(target.into_iter().collect()))
+    }
+
+    fn do_write(&mut self) -> std::result::Result<(), String> {
+        let file_number = self.pop_file_number()?;
+        let mut write_args: Vec<String> = vec![];
+        while let Some(v) = self.context_mut().demand_sub().try_pop_front_unnamed() {
+            write_args.push(match v {
+                Variant::VString(s) => format!("\"{}\"", s),
+                _ => v.to_string(),
+            });
+        }
+        self.write_text(file_number, &format!("{}\n", write_args.join(",")))
+    }
+
     fn do_environ_sub(&mut self, pos: Location) -> Result<()> {
         match self.context_mut().demand_sub().pop_front_unnamed() {
             Variant::VString(arg_string_value) => {
@@ -42,66 +130,6 @@ impl<S: Stdlib> Interpreter<S> {
             _ => panic!("Type mismatch"),
         }
     }
-
-    fn do_input(&mut self, pos: Location) -> Result<()> {
-        loop {
-            match &self.context_mut().demand_sub().pop_front_unnamed_arg() {
-                Some(a) => match a {
-                    Argument::ByRef(n) => {
-                        self.do_input_one_var(a, n, pos)?;
-                    }
-                    _ => {
-                        panic!("Expected variable (linter should have caught this)");
-                    }
-                },
-                None => {
-                    break;
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn do_input_one_var(&mut self, a: &Argument, n: &QualifiedName, pos: Location) -> Result<()> {
-        let raw_input: String = self
-            .stdlib
-            .input()
-            .map_err(|e| InterpreterError::new_with_pos(e.to_string(), pos))?;
-        let q: TypeQualifier = n.qualifier();
-        let variable_value = match q {
-            TypeQualifier::BangSingle => Variant::from(
-                parse_single_input(raw_input)
-                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?,
-            ),
-            TypeQualifier::DollarString => Variant::from(raw_input),
-            TypeQualifier::PercentInteger => Variant::from(
-                parse_int_input(raw_input).map_err(|e| InterpreterError::new_with_pos(e, pos))?,
-            ),
-            _ => unimplemented!(),
-        };
-        self.context_mut()
-            .demand_sub()
-            .set_value_to_popped_arg(a, variable_value)
-            .map_err(|e| InterpreterError::new_with_pos(e, pos))
-    }
-}
-
-fn parse_single_input(s: String) -> std::result::Result<f32, String> {
-    if s.is_empty() {
-        Ok(0.0)
-    } else {
-        s.parse::<f32>()
-            .map_err(|e| format!("Could not parse {} as float: {}", s, e))
-    }
-}
-
-fn parse_int_input(s: String) -> std::result::Result<i32, String> {
-    if s.is_empty() {
-        Ok(0)
-    } else {
-        s.parse::<i32>()
-            .map_err(|e| format!("Could not parse {} as int: {}", s, e))
-    }
 }
 
 #[cfg(test)]
@@ -114,4 +142,35 @@ mod tests {
     fn test_sub_call_system_no_args_allowed() {
         assert_linter_err!("SYSTEM 42", LinterError::ArgumentCountMismatch, 1, 1);
     }
+
+    #[test]
+    fn test_mid_statement() {
+        let program = r#"
+        A$ = "hello world"
+        MID$(A$, 7) = "there"
+        PRINT A$
+        MID$(A$, 1, 2) = "JE"
+        PRINT A$
+        MID$(A$, 10) = "abcdef"
+        PRINT A$
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec!["hello there", "JEllo there", "JEllo theab"]
+        );
+    }
+
+    #[test]
+    fn test_mid_statement_start_out_of_range() {
+        let program = r#"
+        A$ = "hello"
+        MID$(A$, 6) = "x"
+        "#;
+        assert_eq!(interpret_err(program).code(), 5);
+    }
+
+    #[test]
+    fn test_mid_statement_requires_string_variable() {
+        assert_linter_err!("MID$(A%, 1) = \"x\"", LinterError::TypeMismatch, 1, 6);
+    }
 }
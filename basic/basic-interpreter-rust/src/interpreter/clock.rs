@@ -0,0 +1,152 @@
+use crate::casting::cast;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::TypeQualifier;
+use crate::variant::Variant;
+use std::time::SystemTime;
+
+type Result<T> = std::result::Result<T, String>;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Implements `TIMER`, the number of seconds since local midnight,
+    /// with hundredths of a second.
+    pub fn timer(&self) -> Variant {
+        let hundredths = self.local_millis() / 10 % (SECONDS_PER_DAY * 100);
+        Variant::VSingle(hundredths as f32 / 100.0)
+    }
+
+    /// Implements `DATE$`, e.g. `03-15-2020`.
+    pub fn date(&self) -> Variant {
+        let days = self.local_millis() / 1000 / SECONDS_PER_DAY;
+        let (year, month, day) = civil_from_days(days);
+        Variant::VString(format!("{:02}-{:02}-{:04}", month, day, year))
+    }
+
+    /// Implements `TIME$`, e.g. `13:45:30`.
+    pub fn time(&self) -> Variant {
+        let seconds = self.local_millis() / 1000 % SECONDS_PER_DAY;
+        Variant::VString(format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ))
+    }
+
+    /// Implements `SLEEP`. Without seconds, or with zero or less, it waits for a key.
+    pub fn sleep(&mut self) -> Result<()> {
+        let seconds = match self.context_mut().demand_sub().try_pop_front_unnamed() {
+            Some(v) => match cast(v, TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l) => l.max(0) as u32,
+                _ => panic!("SLEEP argument should be a long"),
+            },
+            None => 0,
+        };
+        self.stdlib.sleep(seconds);
+        Ok(())
+    }
+
+    /// The milliseconds since 1970-01-01 in local time.
+    fn local_millis(&self) -> u64 {
+        let utc = self
+            .stdlib
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let local = utc + i64::from(self.stdlib.utc_offset()) * 1000;
+        local.max(0) as u64
+    }
+}
+
+/// Converts the days since 1970-01-01 to year, month and day.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // count from 0000-03-01, so that the leap day is the last day of the year
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::linter::LinterError;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_timer_date_and_time() {
+        let program = r#"
+        PRINT TIMER
+        PRINT DATE$
+        PRINT TIME$
+        "#;
+        // the mock clock is at 2020-03-15 13:45:30.25 in a time zone without offset
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" 49530.25 ", "03-15-2020", "13:45:30"]
+        );
+    }
+
+    #[test]
+    fn test_date_of_leap_day() {
+        let mut stdlib = MockStdlib::new();
+        // 2024-02-29 23:59:59
+        stdlib.now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_251_199);
+        let interpreter = interpret_with_stdlib("PRINT DATE$; \" \"; TIME$", stdlib);
+        assert_eq!(interpreter.stdlib.output, vec!["02-29-2024 23:59:59"]);
+    }
+
+    #[test]
+    fn test_timer_date_and_time_in_local_time() {
+        let program = r#"
+        PRINT TIMER
+        PRINT DATE$
+        PRINT TIME$
+        "#;
+        let mut stdlib = MockStdlib::new();
+        // UTC+11, already the next day
+        stdlib.utc_offset = 11 * 3600;
+        assert_eq!(
+            interpret_with_stdlib(program, stdlib).stdlib.output,
+            vec![" 2730.25 ", "03-16-2020", "00:45:30"]
+        );
+        let mut stdlib = MockStdlib::new();
+        // UTC-5
+        stdlib.utc_offset = -5 * 3600;
+        assert_eq!(
+            interpret_with_stdlib(program, stdlib).stdlib.output,
+            vec![" 31530.25 ", "03-15-2020", "08:45:30"]
+        );
+    }
+
+    #[test]
+    fn test_sleep() {
+        let program = r#"
+        T = TIMER
+        SLEEP 2
+        SLEEP
+        PRINT TIMER - T
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec![" 2 "]);
+    }
+
+    #[test]
+    fn test_clock_linter_errors() {
+        assert_linter_err!("PRINT DATE", LinterError::SyntaxError, 1, 7);
+        assert_linter_err!("PRINT TIMER$", LinterError::SyntaxError, 1, 7);
+        assert_linter_err!("SLEEP \"a\"", LinterError::TypeMismatch, 1, 7);
+        assert_linter_err!("SLEEP 1, 2", LinterError::ArgumentCountMismatch, 1, 1);
+    }
+}
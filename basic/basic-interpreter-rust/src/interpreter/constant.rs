@@ -14,7 +14,7 @@ mod tests {
             PRINT X
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
         }
 
         #[test]
@@ -24,7 +24,7 @@ mod tests {
             PRINT X%
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
         }
 
         #[test]
@@ -76,7 +76,7 @@ mod tests {
             PRINT X
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
 
         #[test]
@@ -86,7 +86,7 @@ mod tests {
             PRINT X!
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
 
         #[test]
@@ -109,7 +109,7 @@ mod tests {
             PRINT X
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
     }
 
@@ -137,7 +137,7 @@ mod tests {
             PRINT X!
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
         }
 
         #[test]
@@ -147,7 +147,7 @@ mod tests {
             PRINT X!
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3.14"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3.14 "]);
         }
 
         #[test]
@@ -184,7 +184,7 @@ mod tests {
             PRINT Y
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3 "]);
         }
 
         #[test]
@@ -195,7 +195,7 @@ mod tests {
             PRINT Y
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["1"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 1 "]);
         }
 
         #[test]
@@ -206,7 +206,7 @@ mod tests {
             PRINT Y
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["-3"]);
+            assert_eq!(interpreter.stdlib.output, vec!["-3 "]);
         }
 
         #[test]
@@ -217,7 +217,7 @@ mod tests {
             PRINT FALSE
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["0"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 0 "]);
         }
 
         #[test]
@@ -257,7 +257,7 @@ mod tests {
             "#;
 
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
         }
 
         #[test]
@@ -273,7 +273,7 @@ mod tests {
             END SUB
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["5"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 5 "]);
         }
 
         #[test]
@@ -292,7 +292,7 @@ mod tests {
             END SUB
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["42", "100", "42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 42 ", " 100 ", " 42 "]);
         }
 
         #[test]
@@ -312,7 +312,7 @@ mod tests {
             END SUB
             ";
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["3", "42"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 3 ", " 42 "]);
         }
     }
 }
@@ -98,6 +98,14 @@ impl<T: std::fmt::Debug + Sized + Cast> NameMap<T> {
             None => None,
         }
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (QualifiedName, &T)> {
+        self.0.iter().flat_map(|(bare_name, inner_map)| {
+            inner_map
+                .iter()
+                .map(move |(q, v)| (QualifiedName::new(bare_name.clone(), *q), v))
+        })
+    }
 }
 
 //
@@ -136,10 +144,26 @@ impl ConstantMap {
         }
         Ok(())
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (QualifiedName, &Variant)> {
+        self.0
+            .iter()
+            .map(|(bare_name, v)| (QualifiedName::new(bare_name.clone(), v.qualifier()), v))
+    }
 }
 
 type VariableMap = NameMap<Variant>;
 
+/// Holds the length of the fixed length strings, e.g. `DIM A AS STRING * 5`.
+type FixedLengthMap = HashMap<QualifiedName, u16>;
+
+fn fix_length(fixed_lengths: &FixedLengthMap, name: &QualifiedName, value: Variant) -> Variant {
+    match fixed_lengths.get(name) {
+        Some(len) => value.into_fixed_length(*len),
+        None => value,
+    }
+}
+
 #[derive(Debug)]
 struct ArgumentMap {
     named: NameMap<Argument>,
@@ -191,13 +215,23 @@ impl ArgumentMap {
 pub struct RootContext {
     variables: VariableMap,
     constants: ConstantMap,
+    fixed_lengths: FixedLengthMap,
 }
 
 impl RootContext {
+    fn variables(&self) -> Vec<(QualifiedName, Variant)> {
+        self.constants
+            .iter()
+            .chain(self.variables.iter())
+            .map(|(name, v)| (name, v.clone()))
+            .collect()
+    }
+
     pub fn new() -> Self {
         Self {
             variables: NameMap::new(),
             constants: ConstantMap::new(),
+            fixed_lengths: HashMap::new(),
         }
     }
 
@@ -245,8 +279,22 @@ impl RootContext {
 
     pub fn set_variable(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
         // Arguments do not exist at root level. Create/Update a variable.
+        let value = fix_length(&self.fixed_lengths, &name, value);
         self.variables.insert(name, value)
     }
+
+    pub fn dim_fixed_length_string(&mut self, name: QualifiedName, len: u16) -> Result<(), String> {
+        self.fixed_lengths.insert(name.clone(), len);
+        self.set_variable(name, Variant::VString(String::new()))
+    }
+
+    pub fn get_variable_ref(&self, name: &QualifiedName) -> Option<&Variant> {
+        self.variables.get(name)
+    }
+
+    pub fn get_variable_mut(&mut self, name: &QualifiedName) -> Option<&mut Variant> {
+        self.variables.get_mut(name)
+    }
 }
 
 //
@@ -305,22 +353,39 @@ pub struct SubContext {
     parent: Box<Context>,
     variables: ArgumentMap,
     constants: ConstantMap,
+    fixed_lengths: FixedLengthMap,
 }
 
 impl SubContext {
+    fn variables(&self) -> Vec<(QualifiedName, Variant)> {
+        let constants = self.constants.iter().map(|(name, v)| (name, v.clone()));
+        let variables = self
+            .variables
+            .named
+            .iter()
+            .filter_map(|(name, arg)| self.evaluate_argument(arg).map(|v| (name, v)));
+        constants.chain(variables).collect()
+    }
+
     fn set_variable_parent(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
         self.parent.set_variable(name, value)
     }
 
     fn do_insert_variable(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
+        let value = fix_length(&self.fixed_lengths, &name, value);
         self.variables.insert(name, Argument::ByVal(value))
     }
 
+    pub fn dim_fixed_length_string(&mut self, name: QualifiedName, len: u16) -> Result<(), String> {
+        self.fixed_lengths.insert(name.clone(), len);
+        self.do_insert_variable(name, Variant::VString(String::new()))
+    }
+
     fn get_argument_mut(&mut self, name: &QualifiedName) -> Option<&mut Argument> {
         self.variables.get_mut(name)
     }
 
-    fn evaluate_argument(&self, arg: &Argument) -> Option<Variant> {
+    pub fn evaluate_argument(&self, arg: &Argument) -> Option<Variant> {
         match arg {
             Argument::ByVal(v) => Some(v.clone()),
             Argument::ByRef(n) => self.parent.get_r_value(n),
@@ -331,6 +396,22 @@ impl SubContext {
         self.variables.get(name)
     }
 
+    pub fn get_variable_ref(&self, name: &QualifiedName) -> Option<&Variant> {
+        match self.variables.get(name) {
+            Some(Argument::ByVal(v)) => Some(v),
+            Some(Argument::ByRef(n)) => self.parent.get_variable_ref(n),
+            None => None,
+        }
+    }
+
+    pub fn get_variable_mut(&mut self, name: &QualifiedName) -> Option<&mut Variant> {
+        match self.variables.get_mut(name) {
+            Some(Argument::ByVal(v)) => Some(v),
+            Some(Argument::ByRef(n)) => self.parent.get_variable_mut(n),
+            None => None,
+        }
+    }
+
     pub fn set_constant(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
         self.constants.insert(name, value)
     }
@@ -395,11 +476,17 @@ impl SubContext {
 
     pub fn set_variable(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
         // if a parameter exists, set it (might be a ref)
+        let fixed_length = self.fixed_lengths.get(&name).copied();
         match self.get_argument_mut(&name) {
             Some(a) => {
                 match a {
                     Argument::ByVal(_old_value) => {
-                        *a = Argument::ByVal(value.cast(name.qualifier())?);
+                        let value = value.cast(name.qualifier())?;
+                        let value = match fixed_length {
+                            Some(len) => value.into_fixed_length(len),
+                            None => value,
+                        };
+                        *a = Argument::ByVal(value);
                         Ok(())
                     }
                     Argument::ByRef(n) => {
@@ -466,6 +553,7 @@ impl Context {
                 parent: a.parent,
                 variables: a.args,
                 constants: ConstantMap::new(),
+                fixed_lengths: HashMap::new(),
             }),
             _ => panic!("Not in an args context"),
         }
@@ -479,6 +567,33 @@ impl Context {
         }
     }
 
+    /// The constants and the variables of the main program or of the current sub or function,
+    /// sorted by name.
+    pub fn variables(&self) -> Vec<(QualifiedName, Variant)> {
+        let mut result = match self {
+            Self::Root(r) => r.variables(),
+            Self::Sub(s) => s.variables(),
+            Self::Args(a) => a.parent.variables(),
+        };
+        result.sort_by(|(a, _), (b, _)| {
+            a.bare_name()
+                .as_ref()
+                .to_uppercase()
+                .cmp(&b.bare_name().as_ref().to_uppercase())
+                .then_with(|| a.qualifier().to_string().cmp(&b.qualifier().to_string()))
+        });
+        result
+    }
+
+    /// Pops all the contexts up to the root context.
+    pub fn into_root(self) -> Self {
+        match self {
+            Self::Root(_) => self,
+            Self::Sub(s) => s.parent.into_root(),
+            Self::Args(a) => a.parent.into_root(),
+        }
+    }
+
     pub fn set_constant(&mut self, name: QualifiedName, value: Variant) -> Result<(), String> {
         match self {
             Self::Root(r) => r.set_constant(name, value),
@@ -487,6 +602,28 @@ impl Context {
         }
     }
 
+    /// Pops the arguments context, returning the parent context and the
+    /// values of the arguments. Used for array indices and bounds.
+    pub fn pop_args(self) -> (Self, Vec<Variant>) {
+        match self {
+            Self::Args(mut a) => {
+                let mut values: Vec<Variant> = vec![];
+                while let Some(arg) = a.args.pop_front() {
+                    match arg {
+                        Argument::ByVal(v) => values.push(v),
+                        Argument::ByRef(n) => values.push(
+                            a.parent
+                                .get_r_value(&n)
+                                .expect("Reference should point to a variable"),
+                        ),
+                    }
+                }
+                (*a.parent, values)
+            }
+            _ => panic!("Not in an args context"),
+        }
+    }
+
     pub fn demand_args(&mut self) -> &mut ArgsContext {
         match self {
             Self::Args(a) => a,
@@ -525,6 +662,15 @@ impl Context {
         }
     }
 
+    /// Creates a fixed length string variable, initialized with spaces.
+    pub fn dim_fixed_length_string(&mut self, name: QualifiedName, len: u16) -> Result<(), String> {
+        match self {
+            Self::Root(r) => r.dim_fixed_length_string(name, len),
+            Self::Sub(s) => s.dim_fixed_length_string(name, len),
+            Self::Args(a) => a.parent.dim_fixed_length_string(name, len),
+        }
+    }
+
     pub fn get_r_value(&self, name: &QualifiedName) -> Option<Variant> {
         match self {
             Self::Root(r) => r.get_r_value(name),
@@ -532,4 +678,21 @@ impl Context {
             Self::Sub(s) => s.get_r_value(name),
         }
     }
+
+    /// Gets a reference to a variable, following references to the parent context.
+    pub fn get_variable_ref(&self, name: &QualifiedName) -> Option<&Variant> {
+        match self {
+            Self::Root(r) => r.get_variable_ref(name),
+            Self::Args(a) => a.parent.get_variable_ref(name),
+            Self::Sub(s) => s.get_variable_ref(name),
+        }
+    }
+
+    pub fn get_variable_mut(&mut self, name: &QualifiedName) -> Option<&mut Variant> {
+        match self {
+            Self::Root(r) => r.get_variable_mut(name),
+            Self::Args(a) => a.parent.get_variable_mut(name),
+            Self::Sub(s) => s.get_variable_mut(name),
+        }
+    }
 }
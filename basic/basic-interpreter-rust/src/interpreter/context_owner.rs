@@ -1,5 +1,6 @@
 use super::{Interpreter, Stdlib};
 use crate::interpreter::context::Context;
+use crate::variant::Variant;
 
 /// Represents the owner of a variable context.
 pub trait ContextOwner {
@@ -11,6 +12,9 @@ pub trait ContextOwner {
     /// Pops a context.
     fn pop(&mut self);
 
+    /// Pops the arguments context, returning the values of the arguments.
+    fn pop_args(&mut self) -> Vec<Variant>;
+
     fn context_ref(&self) -> &Context;
     fn context_mut(&mut self) -> &mut Context;
 }
@@ -28,6 +32,12 @@ impl<S: Stdlib> ContextOwner for Interpreter<S> {
         self.context = self.context.take().map(|x| x.pop());
     }
 
+    fn pop_args(&mut self) -> Vec<Variant> {
+        let (parent, values) = self.context.take().expect("stack underflow").pop_args();
+        self.context = Some(parent);
+        values
+    }
+
     fn context_ref(&self) -> &Context {
         match &self.context {
             Some(x) => x,
@@ -0,0 +1,301 @@
+use crate::common::*;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, InterpreterError, Stdlib};
+use crate::linter::{HasQualifier, NameTrait};
+use crate::variant::Variant;
+use std::fmt;
+
+/// A front end that controls a program while it runs, e.g. to step through it.
+///
+/// The program stops before a statement when it is on a line with a breakpoint,
+/// or when the last `Resume` says so.
+pub trait Debugger {
+    /// Whether there is a breakpoint on the given row of the program.
+    fn has_breakpoint(&self, row: u32) -> bool;
+
+    /// Called when the program stops. Returns how the program should continue.
+    fn on_stop(&mut self, stop: &Stop) -> Resume;
+
+    /// Called when the program fails with an error that it doesn't handle,
+    /// before the variables are gone.
+    fn on_error(&mut self, stop: &Stop, error: &InterpreterError);
+}
+
+/// How the program continues after it stops.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resume {
+    /// Runs until a breakpoint
+    Continue,
+    /// Stops at the next statement, which can be in a sub or function that is called
+    StepInto,
+    /// Stops at the next statement, after the subs and functions that are called return
+    StepOver,
+    /// Stops at the next statement after the current sub or function returns
+    StepOut,
+    /// Ends the program
+    Quit,
+}
+
+/// The state of a program that stopped.
+#[derive(Debug)]
+pub struct Stop {
+    /// The statement that runs next, or the one that failed
+    pub location: Location,
+    /// The locations of the calls to the subs and functions that are running,
+    /// the most recent first
+    pub call_stack: Vec<Location>,
+    /// The constants and the variables of the current sub or function, e.g. `A$`
+    pub variables: Vec<(String, Variant)>,
+}
+
+/// The debugger of an interpreter and how the program continues after the last stop.
+pub struct DebugSession {
+    debugger: Box<dyn Debugger>,
+    resume: Resume,
+    /// The depth of the call stack at the last stop
+    depth: usize,
+}
+
+impl fmt::Debug for DebugSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugSession")
+            .field("resume", &self.resume)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Runs the next programs under the control of the given debugger.
+    /// They stop before their first statement.
+    pub fn set_debugger(&mut self, debugger: Box<dyn Debugger>) {
+        self.debug_session = Some(DebugSession {
+            debugger,
+            resume: Resume::StepInto,
+            depth: 0,
+        });
+    }
+
+    /// Called before a statement runs. Returns false if the program should end.
+    pub fn debug_statement(&mut self, pos: Location) -> bool {
+        let depth = self.stacktrace.len();
+        let should_stop = match &self.debug_session {
+            Some(session) => {
+                session.debugger.has_breakpoint(pos.row())
+                    || match session.resume {
+                        Resume::Continue | Resume::Quit => false,
+                        Resume::StepInto => true,
+                        Resume::StepOver => depth <= session.depth,
+                        Resume::StepOut => depth < session.depth,
+                    }
+            }
+            None => false,
+        };
+        if !should_stop {
+            return true;
+        }
+        let stop = self.stop(pos);
+        match &mut self.debug_session {
+            Some(session) => {
+                session.resume = session.debugger.on_stop(&stop);
+                session.depth = depth;
+                session.resume != Resume::Quit
+            }
+            None => true,
+        }
+    }
+
+    /// Called when the program fails with an error that it doesn't handle.
+    pub fn debug_error(&mut self, error: &InterpreterError, pos: Location) {
+        if self.debug_session.is_some() {
+            let stop = self.stop(pos);
+            if let Some(session) = &mut self.debug_session {
+                session.debugger.on_error(&stop, error);
+            }
+        }
+    }
+
+    fn stop(&self, pos: Location) -> Stop {
+        Stop {
+            location: pos,
+            call_stack: self.stacktrace.clone(),
+            variables: self
+                .context_ref()
+                .variables()
+                .into_iter()
+                .map(|(name, v)| (format!("{}{}", name.bare_name(), name.qualifier()), v))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction_generator;
+    use crate::interpreter::test_utils::MockStdlib;
+    use crate::linter;
+    use crate::parser::Parser;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Records the stops and answers with the given commands.
+    struct MockDebugger {
+        breakpoints: Vec<u32>,
+        commands: Vec<Resume>,
+        stops: Rc<RefCell<Vec<Stop>>>,
+        errors: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Debugger for MockDebugger {
+        fn has_breakpoint(&self, row: u32) -> bool {
+            self.breakpoints.contains(&row)
+        }
+
+        fn on_stop(&mut self, stop: &Stop) -> Resume {
+            self.stops.borrow_mut().push(Stop {
+                location: stop.location,
+                call_stack: stop.call_stack.clone(),
+                variables: stop.variables.clone(),
+            });
+            if self.commands.is_empty() {
+                Resume::Continue
+            } else {
+                self.commands.remove(0)
+            }
+        }
+
+        fn on_error(&mut self, stop: &Stop, error: &InterpreterError) {
+            self.errors
+                .borrow_mut()
+                .push(format!("{} {}", error.message(), stop.location.row()));
+        }
+    }
+
+    const PROGRAM: &str = r#"A = 1
+Hello "world"
+A = 2
+PRINT Twice(A)
+SUB Hello(N$)
+    PRINT "Hello "; N$
+    B = 3
+END SUB
+FUNCTION Twice(X)
+    Twice = X * 2
+END FUNCTION
+"#;
+
+    /// Runs the program with the given breakpoints and commands.
+    /// Returns the rows of the stops.
+    fn debug(breakpoints: &[u32], commands: &[Resume]) -> (Vec<Stop>, Vec<String>, Vec<String>) {
+        debug_program(PROGRAM, breakpoints, commands)
+    }
+
+    fn debug_program(
+        program: &str,
+        breakpoints: &[u32],
+        commands: &[Resume],
+    ) -> (Vec<Stop>, Vec<String>, Vec<String>) {
+        let stops = Rc::new(RefCell::new(vec![]));
+        let errors = Rc::new(RefCell::new(vec![]));
+        let program = Parser::from(program).parse().unwrap();
+        let instructions =
+            instruction_generator::generate_instructions(linter::lint(program).unwrap());
+        let mut interpreter = Interpreter::new(MockStdlib::new());
+        interpreter.set_debugger(Box::new(MockDebugger {
+            breakpoints: breakpoints.to_vec(),
+            commands: commands.to_vec(),
+            stops: Rc::clone(&stops),
+            errors: Rc::clone(&errors),
+        }));
+        // an error is reported to the debugger as well
+        interpreter.interpret(&instructions).unwrap_or_default();
+        let stops = stops.replace(vec![]);
+        let errors = errors.replace(vec![]);
+        (stops, errors, interpreter.stdlib.output)
+    }
+
+    fn rows(stops: &[Stop]) -> Vec<u32> {
+        stops.iter().map(|s| s.location.row()).collect()
+    }
+
+    #[test]
+    fn test_step_into() {
+        let (stops, _, _) = debug(&[], &[Resume::StepInto; 20]);
+        assert_eq!(rows(&stops), vec![1, 2, 6, 7, 3, 4, 10]);
+        // inside Hello, which was called on row 2
+        assert_eq!(stops[2].call_stack, vec![Location::new(2, 1)]);
+    }
+
+    #[test]
+    fn test_step_over() {
+        let (stops, _, output) = debug(&[], &[Resume::StepOver; 20]);
+        assert_eq!(rows(&stops), vec![1, 2, 3, 4]);
+        assert_eq!(output, vec!["Hello world", " 4 "]);
+    }
+
+    #[test]
+    fn test_step_out() {
+        let (stops, _, _) = debug(
+            &[],
+            &[
+                Resume::StepInto,
+                Resume::StepInto,
+                Resume::StepOut,
+                Resume::Continue,
+            ],
+        );
+        assert_eq!(rows(&stops), vec![1, 2, 6, 3]);
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let (stops, _, _) = debug(&[7, 10], &[Resume::Continue; 3]);
+        assert_eq!(rows(&stops), vec![1, 7, 10]);
+        assert_eq!(stops[2].call_stack, vec![Location::new(4, 7)]);
+    }
+
+    #[test]
+    fn test_variables() {
+        let (stops, _, _) = debug(&[3, 7, 10], &[Resume::Continue; 4]);
+        assert_eq!(
+            stops[1]
+                .variables
+                .iter()
+                .map(|(n, v)| format!("{}={}", n, v))
+                .collect::<Vec<_>>(),
+            vec!["N$=world"]
+        );
+        assert_eq!(
+            stops[2]
+                .variables
+                .iter()
+                .map(|(n, v)| format!("{}={}", n, v))
+                .collect::<Vec<_>>(),
+            vec!["A!=1"]
+        );
+        assert_eq!(
+            stops[3]
+                .variables
+                .iter()
+                .map(|(n, v)| format!("{}={}", n, v))
+                .collect::<Vec<_>>(),
+            vec!["X!=2"]
+        );
+    }
+
+    #[test]
+    fn test_quit() {
+        let (stops, _, output) = debug(&[], &[Resume::StepOver, Resume::Quit]);
+        assert_eq!(rows(&stops), vec![1, 2]);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_error_stops_with_variables() {
+        let (stops, errors, _) =
+            debug_program("A = 5\nB = A / 0\nPRINT B\n", &[], &[Resume::Continue]);
+        assert_eq!(rows(&stops), vec![1]);
+        assert_eq!(errors, vec!["Division by zero 2"]);
+    }
+}
@@ -0,0 +1,455 @@
+use crate::casting::cast;
+use crate::instruction_generator::ArrayElementType;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::{HasQualifier, QualifiedName, TypeQualifier};
+use crate::variant::{VArray, Variant};
+
+type Result<T> = std::result::Result<T, String>;
+
+pub(super) const SUBSCRIPT_OUT_OF_RANGE: &str = "Subscript out of range";
+
+impl<S: Stdlib> Interpreter<S> {
+    pub fn dim_array(
+        &mut self,
+        name: &QualifiedName,
+        dynamic: bool,
+        element_type: &ArrayElementType,
+    ) -> Result<()> {
+        let dimensions = self.pop_array_dimensions()?;
+        match self.context_ref().get_variable_ref(name) {
+            Some(Variant::VArray(a)) if a.lbound(1).is_ok() => {
+                return Err("Array already dimensioned".to_string());
+            }
+            _ => (),
+        }
+        let array = new_array(name, dimensions, dynamic, element_type)?;
+        self.context_mut()
+            .set_variable(name.clone(), Variant::VArray(Box::new(array)))
+    }
+
+    pub fn redim_array(
+        &mut self,
+        name: &QualifiedName,
+        element_type: &ArrayElementType,
+    ) -> Result<()> {
+        let dimensions = self.pop_array_dimensions()?;
+        let array = new_array(name, dimensions, true, element_type)?;
+        self.context_mut()
+            .set_variable(name.clone(), Variant::VArray(Box::new(array)))
+    }
+
+    pub fn copy_array_element_to_a(&mut self, name: &QualifiedName) -> Result<()> {
+        let indices = self.pop_args();
+        let v = match self.context_ref().get_variable_ref(name) {
+            Some(Variant::VArray(a)) => a.get_element(&indices)?.clone(),
+            _ => return Err(SUBSCRIPT_OUT_OF_RANGE.to_string()),
+        };
+        self.set_a(v);
+        Ok(())
+    }
+
+    pub fn store_array_element(&mut self, name: &QualifiedName) -> Result<()> {
+        let v = self.get_a();
+        let indices = self.pop_args();
+        match self.context_mut().get_variable_mut(name) {
+            Some(Variant::VArray(a)) => a.set_element(&indices, v),
+            _ => Err(SUBSCRIPT_OUT_OF_RANGE.to_string()),
+        }
+    }
+
+    pub fn erase_array(&mut self, name: &QualifiedName) -> Result<()> {
+        match self.context_mut().get_variable_mut(name) {
+            Some(Variant::VArray(a)) => {
+                a.erase();
+                Ok(())
+            }
+            // erasing an array that was never dimensioned is a no-op
+            _ => Ok(()),
+        }
+    }
+
+    fn pop_array_dimensions(&mut self) -> Result<Vec<(i32, i32)>> {
+        let bounds = self.pop_args();
+        let mut dimensions: Vec<(i32, i32)> = vec![];
+        for pair in bounds.chunks(2) {
+            dimensions.push((to_bound(&pair[0])?, to_bound(&pair[1])?));
+        }
+        Ok(dimensions)
+    }
+}
+
+fn new_array(
+    name: &QualifiedName,
+    dimensions: Vec<(i32, i32)>,
+    dynamic: bool,
+    element_type: &ArrayElementType,
+) -> Result<VArray> {
+    match element_type {
+        ArrayElementType::BuiltIn => VArray::new(name.qualifier(), dimensions, dynamic, None),
+        ArrayElementType::FixedLengthString(len) => {
+            VArray::new(name.qualifier(), dimensions, dynamic, Some(*len))
+        }
+        ArrayElementType::UserDefined(default_element) => VArray::new_user_defined(
+            name.qualifier(),
+            dimensions,
+            dynamic,
+            default_element.clone(),
+        ),
+    }
+}
+
+fn to_bound(v: &Variant) -> Result<i32> {
+    match cast(v.clone(), TypeQualifier::PercentInteger)? {
+        Variant::VInteger(i) => Ok(i),
+        _ => Err("Type mismatch".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_err;
+    use crate::assert_linter_err;
+    use crate::common::Location;
+    use crate::interpreter::InterpreterError;
+    use crate::linter::LinterError;
+
+    #[test]
+    fn test_dim_and_assign_elements() {
+        let input = "
+        DIM A(3)
+        FOR I = 0 TO 3
+            A(I) = I * 2
+        NEXT
+        PRINT A(0), A(1), A(2), A(3)
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 0             2             4             6 "]
+        );
+    }
+
+    #[test]
+    fn test_elements_have_default_value() {
+        let input = "
+        DIM A$(2)
+        DIM B%(2)
+        PRINT A$(1)
+        PRINT B%(1)
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["", " 0 "]);
+    }
+
+    #[test]
+    fn test_two_dimensions_with_lbound() {
+        let input = "
+        DIM M(1 TO 3, 2 TO 4) AS INTEGER
+        M(3, 4) = 5
+        M(1, 2) = 1.6
+        PRINT M(3, 4), M(1, 2), M(2, 3)
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 5             2             0 "]
+        );
+    }
+
+    #[test]
+    fn test_option_base() {
+        let input = "
+        OPTION BASE 1
+        DIM A(3)
+        PRINT LBOUND(A), UBOUND(A)
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1             3 "]);
+    }
+
+    #[test]
+    fn test_lbound_ubound_second_dimension() {
+        let input = "
+        DIM A(1 TO 2, -3 TO 5)
+        PRINT LBOUND(A, 2), UBOUND(A, 2)
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["-3             5 "]);
+    }
+
+    #[test]
+    fn test_subscript_out_of_range() {
+        let input = "
+        DIM A(3)
+        A(4) = 1
+        ";
+        assert_err!(input, "Subscript out of range", 3, 9);
+    }
+
+    #[test]
+    fn test_ubound_of_missing_dimension() {
+        let input = "
+        DIM A(3)
+        PRINT UBOUND(A, 2)
+        ";
+        assert_eq!(
+            interpret_err(input),
+            InterpreterError::new(
+                "Subscript out of range",
+                vec![Location::new(3, 15), Location::new(3, 15)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_dynamic_array() {
+        let input = "
+        N = 4
+        DIM A(N)
+        A(4) = 42
+        PRINT A(4)
+        REDIM A(1 TO 2)
+        PRINT LBOUND(A), UBOUND(A), A(2)
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 42 ", " 1             2             0 "]
+        );
+    }
+
+    #[test]
+    fn test_redim_static_array_is_error() {
+        let input = "
+        DIM A(3)
+        REDIM A(4)
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 3, 15);
+    }
+
+    #[test]
+    fn test_dim_twice_is_error() {
+        let input = "
+        DIM A(3)
+        DIM A(4)
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 3, 13);
+    }
+
+    #[test]
+    fn test_dim_dynamic_array_twice_is_runtime_error() {
+        let input = "
+        N = 3
+        FOR I = 1 TO 2
+            REDIM A(N)
+            DIM B(N)
+        NEXT
+        ";
+        assert_err!(input, "Array already dimensioned", 5, 17);
+    }
+
+    #[test]
+    fn test_erase_static_array_resets_elements() {
+        let input = "
+        DIM A(3)
+        A(1) = 5
+        ERASE A
+        PRINT A(1)
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 0 "]);
+    }
+
+    #[test]
+    fn test_erase_dynamic_array_deallocates() {
+        let input = "
+        REDIM A(3)
+        ERASE A
+        PRINT A(1)
+        ";
+        assert_err!(input, "Subscript out of range", 4, 15);
+    }
+
+    #[test]
+    fn test_array_not_defined() {
+        assert_linter_err!("ERASE A", LinterError::ArrayNotDefined, 1, 7);
+    }
+
+    #[test]
+    fn test_array_element_type_mismatch() {
+        let input = "
+        DIM A(3)
+        A(1) = \"hello\"
+        ";
+        assert_linter_err!(input, LinterError::TypeMismatch, 3, 16);
+    }
+
+    #[test]
+    fn test_string_index_is_type_mismatch() {
+        let input = "
+        DIM A(3)
+        PRINT A(\"1\")
+        ";
+        assert_linter_err!(input, LinterError::TypeMismatch, 3, 17);
+    }
+
+    #[test]
+    fn test_pass_array_to_sub() {
+        let input = "
+        DECLARE SUB Fill(A(), N)
+        DIM B(1 TO 3)
+        Fill B(), 7
+        PRINT B(1), B(2), B(3)
+
+        SUB Fill(A(), N)
+            FOR I = LBOUND(A) TO UBOUND(A)
+                A(I) = N
+            NEXT
+        END SUB
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 7             7             7 "]
+        );
+    }
+
+    #[test]
+    fn test_pass_array_to_function() {
+        let input = "
+        DECLARE FUNCTION Sum(A())
+        DIM B(2)
+        B(0) = 1
+        B(1) = 2
+        B(2) = 3
+        PRINT Sum(B())
+
+        FUNCTION Sum(A())
+            S = 0
+            FOR I = LBOUND(A) TO UBOUND(A)
+                S = S + A(I)
+            NEXT
+            Sum = S
+        END FUNCTION
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 6 "]);
+    }
+
+    #[test]
+    fn test_pass_array_of_wrong_type_to_sub() {
+        let input = "
+        DECLARE SUB Fill(A(), N)
+        DIM B$(3)
+        Fill B$(), 7
+        SUB Fill(A(), N)
+        END SUB
+        ";
+        assert_linter_err!(input, LinterError::ArgumentTypeMismatch, 4, 14);
+    }
+
+    #[test]
+    fn test_lbound_of_variable_is_error() {
+        let input = "
+        A = 1
+        PRINT LBOUND(A)
+        ";
+        assert_linter_err!(input, LinterError::ArgumentTypeMismatch, 3, 22);
+    }
+
+    #[test]
+    fn test_dim_variable_as_integer() {
+        let input = "
+        DIM X AS INTEGER
+        X = 1.6
+        PRINT X, X%
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 2             2 "]);
+    }
+
+    #[test]
+    fn test_dim_variable_initializes_default_value() {
+        let input = "
+        X$ = \"hello\"
+        DIM Y AS STRING
+        PRINT \"*\" + Y + \"*\"
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["**"]);
+    }
+
+    #[test]
+    fn test_dim_variable_as_integer_then_other_qualifier_is_error() {
+        let input = "
+        DIM X AS INTEGER
+        X! = 1
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 3, 9);
+    }
+
+    #[test]
+    fn test_dim_variable_after_use_is_error() {
+        let input = "
+        X = 1
+        DIM X AS STRING
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 3, 13);
+    }
+
+    #[test]
+    fn test_fixed_length_string_pads_and_truncates() {
+        let input = "
+        DIM A AS STRING * 5
+        PRINT \"*\" + A + \"*\"
+        A = \"hi\"
+        PRINT \"*\" + A + \"*\"
+        A = \"hello, world\"
+        PRINT \"*\" + A + \"*\"
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["*     *", "*hi   *", "*hello*"]
+        );
+    }
+
+    #[test]
+    fn test_fixed_length_string_array() {
+        let input = "
+        DIM A(1) AS STRING * 3
+        A(0) = \"abcdef\"
+        PRINT \"*\" + A(0) + \"*\"
+        PRINT \"*\" + A(1) + \"*\"
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["*abc*", "*   *"]);
+    }
+
+    #[test]
+    fn test_fixed_length_string_passed_by_ref() {
+        let input = "
+        DECLARE SUB Hello(S$)
+        DIM A AS STRING * 4
+        Hello A
+        PRINT \"*\" + A + \"*\"
+        SUB Hello(S$)
+            S$ = \"abcdef\"
+        END SUB
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["*abcd*"]);
+    }
+
+    #[test]
+    fn test_param_as_integer() {
+        let input = "
+        DECLARE SUB Hello(N AS INTEGER)
+        Hello 41
+        SUB Hello(N AS INTEGER)
+            N = N + 0.6
+            PRINT N, N%
+        END SUB
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 42            42 "]);
+    }
+
+    #[test]
+    fn test_param_as_integer_then_other_qualifier_is_error() {
+        let input = "
+        DECLARE SUB Hello(N AS INTEGER)
+        SUB Hello(N AS INTEGER)
+            PRINT N!
+        END SUB
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 4, 19);
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::linter::LinterError;
+
+    #[test]
+    fn test_do_while_loop() {
+        let input = "
+        A = 1
+        DO WHILE A < 4
+            PRINT A
+            A = A + 1
+        LOOP
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 ", " 2 ", " 3 "]);
+    }
+
+    #[test]
+    fn test_do_until_loop() {
+        let input = "
+        A = 1
+        DO UNTIL A >= 4
+            PRINT A
+            A = A + 1
+        LOOP
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 ", " 2 ", " 3 "]);
+    }
+
+    #[test]
+    fn test_top_condition_is_checked_before_first_iteration() {
+        let input = "
+        A = 5
+        DO WHILE A < 4
+            PRINT A
+        LOOP
+        DO UNTIL A = 5
+            PRINT A
+        LOOP
+        PRINT \"done\"
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec!["done"]);
+    }
+
+    #[test]
+    fn test_loop_while() {
+        let input = "
+        A = 5
+        DO
+            PRINT A
+            A = A + 1
+        LOOP WHILE A < 7
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 5 ", " 6 "]);
+    }
+
+    #[test]
+    fn test_loop_until_runs_at_least_once() {
+        let input = "
+        A = 5
+        DO
+            PRINT A
+        LOOP UNTIL A = 5
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 5 "]);
+    }
+
+    #[test]
+    fn test_exit_do() {
+        let input = "
+        A = 1
+        DO
+            IF A = 3 THEN EXIT DO
+            PRINT A
+            A = A + 1
+        LOOP
+        PRINT \"done\"
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 ", " 2 ", "done"]);
+    }
+
+    #[test]
+    fn test_exit_do_from_nested_for_loop() {
+        let input = "
+        DO
+            FOR I = 1 TO 3
+                FOR J = 1 TO 3
+                    IF I * J = 4 THEN EXIT DO
+                    PRINT I, J
+                NEXT
+            NEXT
+        LOOP
+        FOR K = 1 TO 2
+            PRINT K
+        NEXT
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![
+                " 1             1 ",
+                " 1             2 ",
+                " 1             3 ",
+                " 2             1 ",
+                " 1 ",
+                " 2 "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exit_for_from_do_loop() {
+        let input = "
+        FOR I = 1 TO 3
+            DO
+                EXIT FOR
+            LOOP
+        NEXT
+        PRINT I
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 "]);
+    }
+
+    #[test]
+    fn test_exit_do_outside_do_loop() {
+        let input = "
+        FOR I = 1 TO 3
+            EXIT DO
+        NEXT
+        ";
+        assert_linter_err!(input, LinterError::ExitDoNotWithinDoLoop, 3, 13);
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::assert_err;
     use crate::assert_has_variable;
     use crate::assert_linter_err;
     use crate::interpreter::test_utils::*;
@@ -72,7 +73,7 @@ mod tests {
             END FUNCTION
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["4"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 4 "]);
         }
 
         #[test]
@@ -87,7 +88,7 @@ mod tests {
             END FUNCTION
             "#;
             let interpreter = interpret(program);
-            assert_eq!(interpreter.stdlib.output, vec!["4"]);
+            assert_eq!(interpreter.stdlib.output, vec![" 4 "]);
         }
     }
 
@@ -137,6 +138,123 @@ mod tests {
         }
     }
 
+    mod binary_multiply {
+        use super::*;
+
+        #[test]
+        fn test_left_float() {
+            assert_has_variable!(interpret("X = 1.5 * 2.0"), "X!", 3.0_f32);
+            assert_has_variable!(interpret("X = 1.5 * 2"), "X!", 3.0_f32);
+            assert_linter_err!("X = 1.1 * \"hello\"", LinterError::TypeMismatch, 1, 11);
+        }
+
+        #[test]
+        fn test_left_double() {
+            assert_has_variable!(interpret("X# = 1.5# * 2"), "X#", 3.0_f64);
+        }
+
+        #[test]
+        fn test_left_string() {
+            assert_linter_err!("X$ = \"hello\" * \"hi\"", LinterError::TypeMismatch, 1, 16);
+        }
+
+        #[test]
+        fn test_left_integer() {
+            assert_has_variable!(interpret("X% = 6 * 7"), "X%", 42);
+            assert_has_variable!(interpret("X% = 2 * 2.4"), "X%", 5);
+            assert_err!("X% = 200 * 200", "Overflow", 1, 10);
+        }
+
+        #[test]
+        fn test_left_long() {
+            assert_has_variable!(interpret("A& = 200\nX& = A& * 200"), "X&", 40000_i64);
+            assert_err!("A& = 200000\nX& = A& * A&", "Overflow", 2, 9);
+        }
+    }
+
+    mod binary_divide {
+        use super::*;
+
+        #[test]
+        fn test_divide() {
+            assert_has_variable!(interpret("X = 5 / 2"), "X!", 2.5_f32);
+            assert_has_variable!(interpret("X# = 1 / 4.0#"), "X#", 0.25_f64);
+            assert_has_variable!(interpret("X% = 7 / 2"), "X%", 4);
+            assert_linter_err!("X = \"hello\" / 2", LinterError::TypeMismatch, 1, 15);
+        }
+
+        #[test]
+        fn test_division_by_zero() {
+            assert_err!("X = 1 / 0", "Division by zero", 1, 7);
+            assert_err!("X# = 1.5# / 0", "Division by zero", 1, 11);
+        }
+    }
+
+    mod binary_integer_divide {
+        use super::*;
+
+        #[test]
+        fn test_integer_divide() {
+            assert_has_variable!(interpret("X% = 7 \\ 2"), "X%", 3);
+            assert_has_variable!(interpret("X% = -7 \\ 2"), "X%", -3);
+            assert_has_variable!(interpret("X% = 7.6 \\ 2.4"), "X%", 4);
+            assert_has_variable!(interpret("X& = 100000 \\ 3"), "X&", 33333_i64);
+        }
+
+        #[test]
+        fn test_division_by_zero() {
+            assert_err!("X% = 1 \\ 0.4", "Division by zero", 1, 8);
+        }
+    }
+
+    mod binary_modulo {
+        use super::*;
+
+        #[test]
+        fn test_modulo() {
+            assert_has_variable!(interpret("X% = 7 MOD 3"), "X%", 1);
+            assert_has_variable!(interpret("X% = -7 MOD 3"), "X%", -1);
+            assert_has_variable!(interpret("X% = 19 MOD 6.7"), "X%", 5);
+        }
+
+        #[test]
+        fn test_division_by_zero() {
+            assert_err!("X% = 7 MOD 0", "Division by zero", 1, 8);
+        }
+    }
+
+    mod binary_power {
+        use super::*;
+
+        #[test]
+        fn test_power() {
+            assert_has_variable!(interpret("X = 2 ^ 10"), "X!", 1024.0_f32);
+            assert_has_variable!(interpret("X = 4 ^ 0.5"), "X!", 2.0_f32);
+            assert_has_variable!(interpret("X# = 2.0# ^ -1"), "X#", 0.5_f64);
+            assert_has_variable!(interpret("X = -2 ^ 2"), "X!", -4.0_f32);
+        }
+
+        #[test]
+        fn test_errors() {
+            assert_err!("X = 0 ^ -1", "Division by zero", 1, 7);
+            assert_err!("X = (-8) ^ 0.5", "Illegal function call", 1, 10);
+        }
+    }
+
+    mod priority {
+        use super::*;
+
+        #[test]
+        fn test_arithmetic_priority() {
+            assert_has_variable!(interpret("X% = 2 + 3 * 4"), "X%", 14);
+            assert_has_variable!(interpret("X% = 10 - 4 - 3"), "X%", 3);
+            assert_has_variable!(interpret("X% = 2 * 3 ^ 2"), "X%", 18);
+            assert_has_variable!(interpret("X% = 10 - 7 MOD 4"), "X%", 7);
+            assert_has_variable!(interpret("X% = 17 \\ 2 * 3"), "X%", 2);
+            assert_has_variable!(interpret("X% = 100 / 10 / 5"), "X%", 2);
+        }
+    }
+
     mod unary_minus {
         use super::*;
 
@@ -367,4 +485,102 @@ mod tests {
             assert_condition!("9 <= 19.1#");
         }
     }
+
+    mod relational {
+        use super::*;
+
+        #[test]
+        fn test_greater() {
+            assert_condition!("9.1 > 2");
+            assert_condition_false!("2 > 2");
+            assert_condition!("\"def\" > \"abc\"");
+            assert_condition_err!("9 > \"hello\"", 20);
+        }
+
+        #[test]
+        fn test_greater_or_equal() {
+            assert_condition!("2 >= 2");
+            assert_condition_false!("1.9 >= 2");
+        }
+
+        #[test]
+        fn test_equal() {
+            assert_condition!("2 = 2.0");
+            assert_condition_false!("\"abc\" = \"def\"");
+        }
+
+        #[test]
+        fn test_not_equal() {
+            assert_condition!("2 <> 3");
+            assert_condition_false!("\"abc\" <> \"abc\"");
+            assert_condition_err!("\"abc\" <> 3", 25);
+        }
+    }
+
+    mod logical {
+        use super::*;
+
+        #[test]
+        fn test_and() {
+            assert_has_variable!(interpret("X% = 12 AND 10"), "X%", 8);
+            assert_has_variable!(interpret("X% = -1 AND 0"), "X%", 0);
+            assert_has_variable!(interpret("X% = 5.6 AND 6"), "X%", 6);
+        }
+
+        #[test]
+        fn test_or() {
+            assert_has_variable!(interpret("X% = 12 OR 10"), "X%", 14);
+            assert_has_variable!(interpret("X% = 0 OR 0"), "X%", 0);
+        }
+
+        #[test]
+        fn test_xor() {
+            assert_has_variable!(interpret("X% = 12 XOR 10"), "X%", 6);
+            assert_has_variable!(interpret("X% = -1 XOR -1"), "X%", 0);
+        }
+
+        #[test]
+        fn test_eqv() {
+            assert_has_variable!(interpret("X% = -1 EQV -1"), "X%", -1);
+            assert_has_variable!(interpret("X% = 0 EQV -1"), "X%", 0);
+            assert_has_variable!(interpret("X% = 12 EQV 10"), "X%", -7);
+        }
+
+        #[test]
+        fn test_imp() {
+            assert_has_variable!(interpret("X% = -1 IMP 0"), "X%", 0);
+            assert_has_variable!(interpret("X% = 0 IMP 0"), "X%", -1);
+            assert_has_variable!(interpret("X% = 12 IMP 10"), "X%", -5);
+        }
+
+        #[test]
+        fn test_long() {
+            assert_has_variable!(interpret("X& = 100000 AND 65535"), "X&", 34464_i64);
+        }
+
+        #[test]
+        fn test_string() {
+            assert_linter_err!("X = \"a\" AND 1", LinterError::TypeMismatch, 1, 13);
+            assert_linter_err!("X = 1 OR \"a\"", LinterError::TypeMismatch, 1, 10);
+            assert_linter_err!("X = \"a\" XOR \"b\"", LinterError::TypeMismatch, 1, 13);
+        }
+
+        #[test]
+        fn test_conditions() {
+            assert_condition!("2 > 0 AND 3 > 0");
+            assert_condition_false!("2 > 0 AND 3 < 0");
+            assert_condition!("2 < 0 OR 3 > 0");
+            assert_condition!("NOT 2 < 0 AND 3 > 0");
+            assert_condition_false!("1 = 1 XOR 2 = 2");
+            assert_condition!("1 = 2 IMP 3 = 4");
+        }
+
+        #[test]
+        fn test_priority() {
+            assert_has_variable!(interpret("X% = 1 OR 2 AND 3"), "X%", 3);
+            assert_has_variable!(interpret("X% = NOT 1 AND 3"), "X%", 2);
+            assert_has_variable!(interpret("X% = 1 + 2 AND 3"), "X%", 3);
+            assert_has_variable!(interpret("X% = 6 XOR 3 OR 1"), "X%", 5);
+        }
+    }
 }
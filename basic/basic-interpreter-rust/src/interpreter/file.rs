@@ -0,0 +1,1097 @@
+use crate::casting::cast;
+use crate::common::*;
+use crate::interpreter::context::Argument;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::input::parse_input_value;
+use crate::interpreter::print::next_column;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::{HasQualifier, QualifiedName, TypeQualifier};
+use crate::variant::{bytes_to_string, string_to_bytes, Variant};
+
+type Result<T> = std::result::Result<T, String>;
+
+pub(super) const BAD_FILE_NAME_OR_NUMBER: &str = "Bad file name or number";
+pub(super) const BAD_FILE_MODE: &str = "Bad file mode";
+pub(super) const BAD_RECORD_LENGTH: &str = "Bad record length";
+pub(super) const BAD_RECORD_NUMBER: &str = "Bad record number";
+
+/// The highest file number that can be used with `OPEN`.
+const MAX_FILE_NUMBER: i32 = 255;
+
+/// The record length of a random access file that is opened without `LEN`.
+const DEFAULT_RECORD_LEN: usize = 128;
+
+/// A file that has been opened with `OPEN`.
+#[derive(Debug)]
+pub struct OpenFile {
+    mode: FileMode,
+    record_len: usize,
+    /// The contents of a file that is open for input, random access or binary
+    contents: Vec<u8>,
+    /// The position of the next byte to read or write
+    position: usize,
+    /// The variables of the record buffer, defined with `FIELD`, and their widths
+    fields: Vec<(usize, QualifiedName)>,
+    /// The column (zero based) of the current line, used by `PRINT #`
+    column: usize,
+}
+
+impl OpenFile {
+    fn is_eof(&self) -> bool {
+        self.position >= self.contents.len()
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        if self.is_eof() {
+            return Err("Input past end of file".to_string());
+        }
+        let remaining = &self.contents[self.position..];
+        let (line, consumed) = match remaining.iter().position(|b| *b == b'\n') {
+            Some(idx) => (&remaining[..idx], idx + 1),
+            None => (remaining, remaining.len()),
+        };
+        let line = bytes_to_string(line).trim_end_matches('\r').to_string();
+        self.position += consumed;
+        Ok(line)
+    }
+
+    /// Reads the next field for `INPUT #`. The fields are separated by commas or
+    /// new lines, and they can be quoted like the ones written by `WRITE #`.
+    fn read_field(&mut self) -> Result<String> {
+        while matches!(self.peek(), Some(b' ' | b'\r' | b'\n')) {
+            self.position += 1;
+        }
+        if self.is_eof() {
+            return Err("Input past end of file".to_string());
+        }
+        let field = if self.peek() == Some(b'"') {
+            self.position += 1;
+            let field = self.read_until(|b| b == b'"');
+            // the closing quote
+            self.position = (self.position + 1).min(self.contents.len());
+            while self.peek() == Some(b' ') {
+                self.position += 1;
+            }
+            field
+        } else {
+            let field = self.read_until(|b| b == b',' || b == b'\r' || b == b'\n');
+            field.trim_end_matches(' ').to_string()
+        };
+        // the separator after the field
+        match self.peek() {
+            Some(b',') | Some(b'\n') => self.position += 1,
+            Some(b'\r') => {
+                self.position += 1;
+                if self.peek() == Some(b'\n') {
+                    self.position += 1;
+                }
+            }
+            _ => (),
+        }
+        Ok(field)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.contents.get(self.position).copied()
+    }
+
+    /// Reads up to the first byte that matches the predicate, which is not read.
+    fn read_until<F: Fn(u8) -> bool>(&mut self, predicate: F) -> String {
+        let start = self.position;
+        while matches!(self.peek(), Some(b) if !predicate(b)) {
+            self.position += 1;
+        }
+        bytes_to_string(&self.contents[start..self.position])
+    }
+
+    /// The unit of `LOC` and `SEEK`, which count records in random access files
+    /// and bytes in binary files. For sequential files, `LOC` counts 128 byte blocks.
+    fn position_unit(&self) -> usize {
+        match self.mode {
+            FileMode::Random => self.record_len,
+            FileMode::Binary => 1,
+            _ => 128,
+        }
+    }
+
+    /// Finds the offset of the given record (one based) of a random access file,
+    /// or of the given byte (one based) of any other file. No record means the current position.
+    fn record_offset(&self, record: Option<i64>) -> Result<usize> {
+        match record {
+            None => Ok(self.position),
+            Some(r) if r < 1 => Err(BAD_RECORD_NUMBER.to_string()),
+            Some(r) if self.mode == FileMode::Random => Ok((r as usize - 1) * self.record_len),
+            Some(r) => Ok(r as usize - 1),
+        }
+    }
+
+    /// Reads bytes at the given offset. Bytes past the end of the file are read as zeros.
+    fn read_bytes(&mut self, offset: usize, len: usize) -> Vec<u8> {
+        let result: Vec<u8> = (offset..offset + len)
+            .map(|i| self.contents.get(i).copied().unwrap_or_default())
+            .collect();
+        self.position = offset + len;
+        result
+    }
+
+    /// Writes bytes at the given offset, growing the file if needed.
+    fn write_bytes(&mut self, offset: usize, bytes: &[u8]) {
+        if self.contents.len() < offset + bytes.len() {
+            self.contents.resize(offset + bytes.len(), 0);
+        }
+        self.contents[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self.position = offset + bytes.len();
+    }
+}
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Implements `KILL`, which deletes a file.
+    pub fn kill_file(&mut self) -> Result<()> {
+        let file_name = self
+            .context_mut()
+            .demand_sub()
+            .pop_front_unnamed()
+            .to_string();
+        self.stdlib.kill_file(&file_name).map_err(io_error_message)
+    }
+
+    /// Implements `NAME`, which renames a file. The new name must not exist.
+    pub fn rename_file(&mut self) -> Result<()> {
+        let sub_context = self.context_mut().demand_sub();
+        let old_name = sub_context.pop_front_unnamed().to_string();
+        let new_name = sub_context.pop_front_unnamed().to_string();
+        self.stdlib
+            .rename_file(&old_name, &new_name)
+            .map_err(io_error_message)
+    }
+
+    pub fn open_file(&mut self) -> Result<()> {
+        let sub_context = self.context_mut().demand_sub();
+        let file_name = sub_context.pop_front_unnamed().to_string();
+        let mode: FileMode = to_file_number(sub_context.pop_front_unnamed())?.into();
+        // the access is parsed but not enforced
+        sub_context.pop_front_unnamed();
+        let file_number = to_file_number(sub_context.pop_front_unnamed())?;
+        let record_len = match cast(
+            sub_context.pop_front_unnamed(),
+            TypeQualifier::PercentInteger,
+        ) {
+            Ok(Variant::VInteger(0)) => DEFAULT_RECORD_LEN,
+            Ok(Variant::VInteger(i)) if i > 0 => i as usize,
+            _ => return Err(BAD_RECORD_LENGTH.to_string()),
+        };
+        validate_file_number(file_number)?;
+        if self.files.contains_key(&file_number) {
+            return Err("File already open".to_string());
+        }
+        // a file that is written stays open until it is closed
+        let (contents, position) = match mode {
+            FileMode::Input => self.stdlib.read_file(&file_name).map(|c| (c, 0)),
+            FileMode::Output => self
+                .stdlib
+                .open_file(file_number, &file_name, false)
+                .map(|_| (vec![], 0)),
+            FileMode::Append => self
+                .stdlib
+                .open_file(file_number, &file_name, true)
+                .map(|len| (vec![], len)),
+            FileMode::Random | FileMode::Binary => {
+                // the file is created if it does not exist
+                self.stdlib
+                    .open_file(file_number, &file_name, true)
+                    .and_then(|_| self.stdlib.read_file(&file_name))
+                    .map(|c| (c, 0))
+            }
+        }
+        .map_err(io_error_message)?;
+        self.files.insert(
+            file_number,
+            OpenFile {
+                mode,
+                record_len,
+                contents,
+                position,
+                column: 0,
+                fields: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    pub fn close_files(&mut self) -> Result<()> {
+        let mut file_numbers: Vec<i32> = vec![];
+        while let Some(v) = self.context_mut().demand_sub().try_pop_front_unnamed() {
+            file_numbers.push(to_file_number(v)?);
+        }
+        if file_numbers.is_empty() {
+            // CLOSE without arguments closes all files
+            return self.close_all_files();
+        }
+        for file_number in file_numbers {
+            // closing a file that is not open is not an error
+            validate_file_number(file_number)?;
+            self.close_file(file_number)?;
+        }
+        Ok(())
+    }
+
+    /// Closes all the open files, writing what has been buffered.
+    pub fn close_all_files(&mut self) -> Result<()> {
+        let file_numbers: Vec<i32> = self.files.keys().copied().collect();
+        for file_number in file_numbers {
+            self.close_file(file_number)?;
+        }
+        Ok(())
+    }
+
+    fn close_file(&mut self, file_number: i32) -> Result<()> {
+        match self.files.remove(&file_number) {
+            Some(file) if file.mode != FileMode::Input => self
+                .stdlib
+                .close_file(file_number)
+                .map_err(io_error_message),
+            _ => Ok(()),
+        }
+    }
+
+    /// Implements `INPUT #`, which reads the next fields of a file into the variables.
+    pub fn file_input(&mut self, file_number: i32) -> Result<()> {
+        while let Some(arg) = self.context_mut().demand_sub().pop_front_unnamed_arg() {
+            let q = match &arg {
+                Argument::ByRef(name) => name.qualifier(),
+                _ => panic!("INPUT should have variables"),
+            };
+            let field = self
+                .demand_file(file_number, &[FileMode::Input])?
+                .read_field()?;
+            let value = parse_input_value(field, q).ok_or_else(|| "Type mismatch".to_string())?;
+            self.context_mut()
+                .demand_sub()
+                .set_value_to_popped_arg(&arg, value)?;
+        }
+        Ok(())
+    }
+
+    /// Implements `LINE INPUT`, reading a line from the keyboard or from a file.
+    pub fn line_input(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let line = if file_number == 0 {
+            let prompt = self.context_mut().demand_sub().pop_front_unnamed();
+            self.write_text(0, &prompt.to_string())?;
+            self.read_keyboard_line()?
+        } else {
+            self.demand_file(file_number, &[FileMode::Input])?
+                .read_line()?
+        };
+        let arg: Argument = self
+            .context_mut()
+            .demand_sub()
+            .pop_front_unnamed_arg()
+            .expect("LINE INPUT should have a variable");
+        self.context_mut()
+            .demand_sub()
+            .set_value_to_popped_arg(&arg, Variant::VString(line))
+    }
+
+    /// Writes text to the screen or to a file, used by `PRINT` and `WRITE`.
+    /// A new line is written as `\r\n` in files.
+    pub fn write_text(&mut self, file_number: i32, text: &str) -> Result<()> {
+        if file_number == 0 {
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.stdlib.println();
+                }
+                if !line.is_empty() {
+                    self.stdlib.print(line);
+                }
+            }
+            self.screen_column = next_column(self.screen_column, text);
+            return Ok(());
+        }
+        let file = self.demand_file(file_number, &[FileMode::Output, FileMode::Append])?;
+        file.column = next_column(file.column, text);
+        let bytes = string_to_bytes(&text.replace('\n', "\r\n"));
+        file.position += bytes.len();
+        self.stdlib
+            .write_file(file_number, None, &bytes)
+            .map_err(io_error_message)
+    }
+
+    /// Gets the column (zero based) of the screen or of a file, used by `PRINT`.
+    pub fn column(&mut self, file_number: i32) -> Result<usize> {
+        if file_number == 0 {
+            Ok(self.screen_column)
+        } else {
+            self.demand_file(file_number, &[FileMode::Output, FileMode::Append])
+                .map(|f| f.column)
+        }
+    }
+
+    /// Implements `FIELD`, which defines the variables of the record buffer
+    /// of a random access file.
+    pub fn field(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let record_len = self
+            .demand_file(file_number, &[FileMode::Random])?
+            .record_len;
+        let mut fields: Vec<(usize, QualifiedName)> = vec![];
+        while let Some(width) = self.context_mut().demand_sub().try_pop_front_unnamed() {
+            let width = match cast(width, TypeQualifier::PercentInteger)? {
+                Variant::VInteger(i) if i >= 0 => i as usize,
+                _ => return Err("Illegal function call".to_string()),
+            };
+            match self.context_mut().demand_sub().pop_front_unnamed_arg() {
+                Some(Argument::ByRef(name)) => fields.push((width, name)),
+                _ => return Err("Type mismatch".to_string()),
+            }
+        }
+        if fields.iter().map(|(width, _)| width).sum::<usize>() > record_len {
+            return Err("FIELD overflow".to_string());
+        }
+        self.demand_file(file_number, &[FileMode::Random])?.fields = fields.clone();
+        for (width, name) in fields {
+            self.set_popped_variable(name, Variant::VString(" ".repeat(width)))?;
+        }
+        Ok(())
+    }
+
+    /// Implements `GET`, which reads a record of a random access file into
+    /// the `FIELD` variables or into a variable, or reads a variable from a binary file.
+    pub fn get_record(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let record = self.pop_optional_record()?;
+        let opt_arg = self.context_mut().demand_sub().pop_front_unnamed_arg();
+        let opt_template = opt_arg
+            .as_ref()
+            .and_then(|arg| self.context_mut().demand_sub().evaluate_argument(arg));
+        let file = self.demand_file(file_number, &[FileMode::Random, FileMode::Binary])?;
+        let offset = file.record_offset(record)?;
+        match (opt_arg, opt_template) {
+            (Some(arg), Some(template)) => {
+                let len = template.byte_len()?;
+                let bytes = if file.mode == FileMode::Random {
+                    if len > file.record_len {
+                        return Err(BAD_RECORD_LENGTH.to_string());
+                    }
+                    let record_len = file.record_len;
+                    file.read_bytes(offset, record_len)
+                } else {
+                    file.read_bytes(offset, len)
+                };
+                let value = template.read_bytes(&bytes)?;
+                self.context_mut()
+                    .demand_sub()
+                    .set_value_to_popped_arg(&arg, value)
+            }
+            _ if file.mode == FileMode::Random => {
+                let record_len = file.record_len;
+                let bytes = file.read_bytes(offset, record_len);
+                let mut field_offset: usize = 0;
+                for (width, name) in file.fields.clone() {
+                    let value = bytes_to_string(&bytes[field_offset..field_offset + width]);
+                    field_offset += width;
+                    self.set_popped_variable(name, Variant::VString(value))?;
+                }
+                Ok(())
+            }
+            _ => Err(BAD_FILE_MODE.to_string()),
+        }
+    }
+
+    /// Implements `PUT`, which writes a record of a random access file from
+    /// the `FIELD` variables or from a variable, or writes a variable to a binary file.
+    pub fn put_record(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let record = self.pop_optional_record()?;
+        let opt_value = self.context_mut().demand_sub().try_pop_front_unnamed();
+        let file = self.demand_file(file_number, &[FileMode::Random, FileMode::Binary])?;
+        let (mode, record_len, fields) = (file.mode, file.record_len, file.fields.clone());
+        let mut bytes: Vec<u8> = match opt_value {
+            Some(value) => value.to_bytes()?,
+            None if mode == FileMode::Random => {
+                let mut bytes: Vec<u8> = vec![];
+                for (width, name) in fields {
+                    let value = self
+                        .context_mut()
+                        .demand_sub()
+                        .evaluate_argument(&Argument::ByRef(name))
+                        .unwrap_or_else(|| Variant::VString(String::new()));
+                    bytes.extend(string_to_bytes(
+                        &value.into_fixed_length(width as u16).to_string(),
+                    ));
+                }
+                bytes
+            }
+            None => return Err(BAD_FILE_MODE.to_string()),
+        };
+        if mode == FileMode::Random {
+            if bytes.len() > record_len {
+                return Err(BAD_RECORD_LENGTH.to_string());
+            }
+            bytes.resize(record_len, 0);
+        }
+        let file = self.demand_file(file_number, &[mode])?;
+        let offset = file.record_offset(record)?;
+        file.write_bytes(offset, &bytes);
+        self.stdlib
+            .write_file(file_number, Some(offset), &bytes)
+            .map_err(io_error_message)
+    }
+
+    /// Implements the `SEEK` statement, which sets the position of the next read or write.
+    pub fn seek(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let position = self.pop_record()?;
+        let file = self.demand_any_file(file_number)?;
+        file.position = file.record_offset(Some(position))?;
+        Ok(())
+    }
+
+    /// Implements `LSET` (`right_align` is false) and `RSET` (`right_align` is true).
+    /// The value is padded or truncated to the current length of the variable.
+    pub fn set_aligned(&mut self, right_align: bool) -> Result<()> {
+        let sub_context = self.context_mut().demand_sub();
+        let arg = sub_context
+            .pop_front_unnamed_arg()
+            .expect("LSET and RSET should have a variable");
+        let value = sub_context.pop_front_unnamed().to_string();
+        let len = sub_context
+            .evaluate_argument(&arg)
+            .map(|v| v.to_string().chars().count())
+            .unwrap_or_default();
+        let value: String = value.chars().take(len).collect();
+        let padding = " ".repeat(len - value.chars().count());
+        let aligned = if right_align {
+            padding + &value
+        } else {
+            value + &padding
+        };
+        sub_context.set_value_to_popped_arg(&arg, Variant::VString(aligned))
+    }
+
+    pub fn pop_file_number(&mut self) -> Result<i32> {
+        to_file_number(self.context_mut().demand_sub().pop_front_unnamed())
+    }
+
+    fn pop_record(&mut self) -> Result<i64> {
+        match cast(
+            self.context_mut().demand_sub().pop_front_unnamed(),
+            TypeQualifier::AmpersandLong,
+        )? {
+            Variant::VLong(l) => Ok(l),
+            _ => Err("Type mismatch".to_string()),
+        }
+    }
+
+    /// Pops the record of `GET` and `PUT`, which follows whether there is one.
+    fn pop_optional_record(&mut self) -> Result<Option<i64>> {
+        match self.context_mut().demand_sub().pop_front_unnamed() {
+            Variant::VInteger(0) => Ok(None),
+            _ => self.pop_record().map(Some),
+        }
+    }
+
+    fn set_popped_variable(&mut self, name: QualifiedName, value: Variant) -> Result<()> {
+        self.context_mut()
+            .demand_sub()
+            .set_value_to_popped_arg(&Argument::ByRef(name), value)
+    }
+
+    pub fn eof(&mut self) -> Result<Variant> {
+        let file_number = self.pop_file_number()?;
+        let file = self.demand_file(
+            file_number,
+            &[FileMode::Input, FileMode::Random, FileMode::Binary],
+        )?;
+        Ok(Variant::from(file.is_eof()))
+    }
+
+    pub fn loc(&mut self) -> Result<Variant> {
+        let file_number = self.pop_file_number()?;
+        let file = self.demand_any_file(file_number)?;
+        Ok(Variant::VLong(
+            (file.position / file.position_unit()) as i64,
+        ))
+    }
+
+    pub fn lof(&mut self) -> Result<Variant> {
+        let file_number = self.pop_file_number()?;
+        let file = self.demand_any_file(file_number)?;
+        let len = match file.mode {
+            // a file that is written sequentially ends where the next byte is written
+            FileMode::Output | FileMode::Append => file.position,
+            _ => file.contents.len(),
+        };
+        Ok(Variant::VLong(len as i64))
+    }
+
+    /// Implements the `SEEK` function, which returns the position of the next read or write.
+    pub fn seek_position(&mut self) -> Result<Variant> {
+        let file_number = self.pop_file_number()?;
+        let file = self.demand_any_file(file_number)?;
+        let record = match file.mode {
+            FileMode::Random => file.position / file.record_len,
+            _ => file.position,
+        };
+        Ok(Variant::VLong(record as i64 + 1))
+    }
+
+    /// Implements `INPUT$`, which reads the given number of characters from the
+    /// keyboard or from a file. New lines are read like any other character.
+    pub fn input_chars(&mut self) -> Result<Variant> {
+        let sub_context = self.context_mut().demand_sub();
+        let count = match cast(
+            sub_context.pop_front_unnamed(),
+            TypeQualifier::PercentInteger,
+        )? {
+            Variant::VInteger(i) if i > 0 => i as usize,
+            _ => return Err("Illegal function call".to_string()),
+        };
+        let bytes = match sub_context.try_pop_front_unnamed() {
+            Some(v) => {
+                let file_number = to_file_number(v)?;
+                let file = self.demand_file(file_number, &[FileMode::Input, FileMode::Binary])?;
+                if file.position + count > file.contents.len() {
+                    return Err("Input past end of file".to_string());
+                }
+                file.read_bytes(file.position, count)
+            }
+            None => self.stdlib.input_bytes(count).map_err(io_error_message)?,
+        };
+        Ok(Variant::VString(bytes_to_string(&bytes)))
+    }
+
+    pub fn free_file(&self) -> Result<Variant> {
+        (1..=MAX_FILE_NUMBER)
+            .find(|n| !self.files.contains_key(n))
+            .map(Variant::VInteger)
+            .ok_or_else(|| "Too many files".to_string())
+    }
+
+    fn demand_any_file(&mut self, file_number: i32) -> Result<&mut OpenFile> {
+        self.files
+            .get_mut(&file_number)
+            .ok_or_else(|| BAD_FILE_NAME_OR_NUMBER.to_string())
+    }
+
+    fn demand_file(&mut self, file_number: i32, modes: &[FileMode]) -> Result<&mut OpenFile> {
+        let file = self.demand_any_file(file_number)?;
+        if modes.contains(&file.mode) {
+            Ok(file)
+        } else {
+            Err(BAD_FILE_MODE.to_string())
+        }
+    }
+}
+
+fn to_file_number(v: Variant) -> Result<i32> {
+    match cast(v, TypeQualifier::PercentInteger)? {
+        Variant::VInteger(i) => Ok(i),
+        _ => Err("Type mismatch".to_string()),
+    }
+}
+
+fn validate_file_number(file_number: i32) -> Result<()> {
+    if (1..=MAX_FILE_NUMBER).contains(&file_number) {
+        Ok(())
+    } else {
+        Err(BAD_FILE_NAME_OR_NUMBER.to_string())
+    }
+}
+
+pub fn io_error_message(e: std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => "Input past end of file".to_string(),
+        std::io::ErrorKind::NotFound => "File not found".to_string(),
+        std::io::ErrorKind::AlreadyExists => "File already exists".to_string(),
+        std::io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
+        _ => "Path/File access error".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::common::*;
+    use crate::interpreter::InterpreterError;
+    use crate::linter::LinterError;
+
+    fn assert_file_err(input: &str, msg: &str, row: u32, col: u32) {
+        let pos = Location::new(row, col);
+        assert_eq!(
+            interpret_err(input),
+            InterpreterError::new(msg, vec![pos, pos])
+        );
+    }
+
+    fn stdlib_with_file(file_name: &str, contents: &[u8]) -> MockStdlib {
+        let mut stdlib = MockStdlib::new();
+        stdlib
+            .files
+            .insert(file_name.to_string(), contents.to_vec());
+        stdlib
+    }
+
+    #[test]
+    fn test_read_lines_until_eof() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        WHILE NOT EOF(1)
+            LINE INPUT #1, A$
+            PRINT A$
+        WEND
+        CLOSE #1
+        "#;
+        let stdlib = stdlib_with_file("TEST.TXT", b"hello\r\nworld\r\n");
+        let interpreter = interpret_with_stdlib(input, stdlib);
+        assert_eq!(interpreter.stdlib.output, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_read_last_line_without_new_line() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS 1
+        LINE INPUT #1, A$
+        LINE INPUT #1, B$
+        PRINT B$
+        PRINT EOF(1)
+        CLOSE
+        "#;
+        let stdlib = stdlib_with_file("TEST.TXT", b"hello\nworld");
+        let interpreter = interpret_with_stdlib(input, stdlib);
+        assert_eq!(interpreter.stdlib.output, vec!["world", "-1 "]);
+    }
+
+    #[test]
+    fn test_write_to_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR OUTPUT AS #1
+        PRINT #1, "hello"
+        PRINT #1, "answer:"; 42
+        CLOSE #1
+        "#;
+        let stdlib = stdlib_with_file("TEST.TXT", b"old contents");
+        let interpreter = interpret_with_stdlib(input, stdlib);
+        assert_eq!(
+            interpreter.stdlib.files["TEST.TXT"],
+            b"hello\r\nanswer: 42 \r\n"
+        );
+        assert_eq!(interpreter.stdlib.output, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_append_to_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR APPEND AS #1
+        PRINT #1, "world"
+        CLOSE #1
+        "#;
+        let stdlib = stdlib_with_file("TEST.TXT", b"hello\r\n");
+        let interpreter = interpret_with_stdlib(input, stdlib);
+        assert_eq!(interpreter.stdlib.files["TEST.TXT"], b"hello\r\nworld\r\n");
+    }
+
+    #[test]
+    fn test_write_quotes_strings() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR OUTPUT AS #1
+        WRITE #1, "hello", 42, "world"
+        CLOSE #1
+        WRITE "screen", 1
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(
+            interpreter.stdlib.files["TEST.TXT"],
+            b"\"hello\",42,\"world\"\r\n"
+        );
+        assert_eq!(interpreter.stdlib.output, vec!["\"screen\",1"]);
+    }
+
+    #[test]
+    fn test_write_then_input() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR OUTPUT AS #1
+        WRITE #1, "Smith, John", 42, -1.5
+        WRITE #1, "  spaces  ", ""
+        PRINT #1, "plain text ,7"
+        CLOSE #1
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        INPUT #1, N$, A%, X!
+        INPUT #1, S$, E$
+        INPUT #1, P$, Q
+        PRINT N$; A%; X!
+        PRINT "("; S$; ")("; E$; ")"
+        PRINT P$; Q
+        PRINT EOF(1)
+        CLOSE #1
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![
+                "Smith, John 42 -1.5 ",
+                "(  spaces  )()",
+                "plain text 7 ",
+                "-1 "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_fields_past_end_of_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        INPUT #1, A, B
+        "#;
+        let err = interpret_err_with_stdlib(input, stdlib_with_file("TEST.TXT", b"1\r\n"));
+        assert_eq!(err.code(), 62);
+    }
+
+    #[test]
+    fn test_input_from_file_type_mismatch() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        INPUT #1, A
+        "#;
+        let err = interpret_err_with_stdlib(input, stdlib_with_file("TEST.TXT", b"abc"));
+        assert_eq!(err.code(), 13);
+    }
+
+    #[test]
+    fn test_lof_of_appended_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR APPEND AS #1
+        PRINT #1, "world"
+        PRINT LOF(1)
+        "#;
+        let interpreter = interpret_with_stdlib(input, stdlib_with_file("TEST.TXT", b"hello\r\n"));
+        assert_eq!(interpreter.stdlib.output, vec![" 14 "]);
+    }
+
+    #[test]
+    fn test_free_file() {
+        let input = r#"
+        PRINT FREEFILE
+        OPEN "A.TXT" FOR OUTPUT AS #1
+        PRINT FREEFILE
+        OPEN "B.TXT" FOR OUTPUT AS #2
+        CLOSE #1
+        PRINT FREEFILE
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 ", " 2 ", " 1 "]);
+    }
+
+    #[test]
+    fn test_file_not_found() {
+        let input = r#"OPEN "MISSING.TXT" FOR INPUT AS #1"#;
+        assert_file_err(input, "File not found", 1, 1);
+        assert_eq!(interpret_err(input).code(), 53);
+    }
+
+    #[test]
+    fn test_file_not_found_handled_by_error_handler() {
+        let input = r#"
+        ON ERROR GOTO ErrTrap
+        OPEN "MISSING.TXT" FOR INPUT AS #1
+        PRINT "done"
+        SYSTEM
+        ErrTrap:
+        PRINT ERR
+        RESUME NEXT
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec![" 53 ", "done"]);
+    }
+
+    #[test]
+    fn test_bad_file_number() {
+        let input = r#"PRINT #2, "hello""#;
+        assert_file_err(input, "Bad file name or number", 1, 1);
+        assert_eq!(interpret_err(input).code(), 52);
+    }
+
+    #[test]
+    fn test_file_already_open() {
+        let input = r#"
+        OPEN "A.TXT" FOR OUTPUT AS #1
+        OPEN "B.TXT" FOR OUTPUT AS #1
+        "#;
+        assert_file_err(input, "File already open", 3, 9);
+    }
+
+    #[test]
+    fn test_bad_file_mode() {
+        let input = r#"
+        OPEN "A.TXT" FOR OUTPUT AS #1
+        LINE INPUT #1, A$
+        "#;
+        assert_file_err(input, "Bad file mode", 3, 9);
+    }
+
+    #[test]
+    fn test_input_chars_from_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        A$ = INPUT$(4, #1)
+        B$ = INPUT$(3, 1)
+        A$ = INPUT$(1, #1)
+        "#;
+        let err = interpret_err_with_stdlib(input, stdlib_with_file("TEST.TXT", b"ab\r\ncde"));
+        assert_eq!(err.code(), 62);
+        let input = r#"
+        OPEN "TEST.TXT" FOR BINARY AS #1
+        A$ = INPUT$(4, #1)
+        PRINT A$ = "ab" + CHR$(13) + CHR$(10); INPUT$(3, 1)
+        "#;
+        let interpreter = interpret_with_stdlib(input, stdlib_with_file("TEST.TXT", b"ab\r\ncde"));
+        assert_eq!(interpreter.stdlib.output, vec!["-1 cde"]);
+    }
+
+    #[test]
+    fn test_input_past_end_of_file() {
+        let input = r#"
+        OPEN "TEST.TXT" FOR INPUT AS #1
+        LINE INPUT #1, A$
+        "#;
+        let err = interpret_err_with_stdlib(input, stdlib_with_file("TEST.TXT", b""));
+        assert_eq!(err.code(), 62);
+    }
+
+    #[test]
+    fn test_random_access_with_field() {
+        let input = r#"
+        OPEN "DATA.DAT" FOR RANDOM AS #1 LEN = 10
+        FIELD #1, 6 AS N$, 4 AS A$
+        LSET N$ = "Ann"
+        RSET A$ = "42"
+        PUT #1, 1
+        LSET N$ = "Bob"
+        RSET A$ = "7"
+        PUT #1
+        GET #1, 1
+        PRINT N$ + A$
+        PRINT LOC(1)
+        PRINT SEEK(1)
+        PRINT LOF(1)
+        CLOSE #1
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec!["Ann     42", " 1 ", " 2 ", " 20 "]
+        );
+        assert_eq!(
+            interpreter.stdlib.files["DATA.DAT"],
+            b"Ann     42Bob      7"
+        );
+    }
+
+    #[test]
+    fn test_random_access_with_user_defined_type() {
+        let input = r#"
+        TYPE Card
+            Value AS INTEGER
+            Suit AS STRING * 5
+        END TYPE
+        DIM C AS Card
+        OPEN "CARDS.DAT" FOR RANDOM AS #1 LEN = LEN(C)
+        C.Value = 3
+        C.Suit = "Heart"
+        PUT #1, 2, C
+        C.Value = 0
+        C.Suit = ""
+        GET #1, 2, C
+        PRINT C.Value
+        PRINT C.Suit
+        CLOSE
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(interpreter.stdlib.output, vec![" 3 ", "Heart"]);
+        assert_eq!(
+            interpreter.stdlib.files["CARDS.DAT"],
+            b"\0\0\0\0\0\0\0\x03\0Heart"
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let input = r#"
+        OPEN "DATA.BIN" FOR BINARY AS #1
+        A% = 258
+        PUT #1, , A%
+        X! = 1.5
+        PUT #1, , X!
+        SEEK #1, 1
+        GET #1, , B%
+        GET #1, 3, Y!
+        PRINT B%
+        PRINT Y!
+        PRINT LOF(1)
+        PRINT SEEK(1)
+        PRINT EOF(1)
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![" 258 ", " 1.5 ", " 6 ", " 7 ", "-1 "]
+        );
+        assert_eq!(
+            interpreter.stdlib.files["DATA.BIN"],
+            b"\x02\x01\x00\x00\x40\x81"
+        );
+    }
+
+    #[test]
+    fn test_binary_reads_existing_file() {
+        let input = r#"
+        OPEN "DOS.DAT" FOR BINARY AS #1
+        GET #1, , A%
+        GET #1, , B&
+        GET #1, , C#
+        PRINT A%
+        PRINT B&
+        PRINT C#
+        "#;
+        let mut bytes: Vec<u8> = vec![0x39, 0x30, 0xa0, 0x86, 0x01, 0x00];
+        // 0.25 in the Microsoft Binary Format
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0x7f]);
+        let interpreter = interpret_with_stdlib(input, stdlib_with_file("DOS.DAT", &bytes));
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![" 12345 ", " 100000 ", " .25 "]
+        );
+    }
+
+    #[test]
+    fn test_mk_and_cv_functions() {
+        let input = r#"
+        PRINT CVI(MKI$(-2))
+        PRINT CVL(MKL$(100000))
+        PRINT CVS(MKS$(1.5))
+        PRINT CVD(MKD$(0.25))
+        PRINT LEN(MKI$(1))
+        PRINT LEN(MKD$(1))
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["-2 ", " 100000 ", " 1.5 ", " .25 ", " 2 ", " 8 "]
+        );
+    }
+
+    #[test]
+    fn test_cv_string_too_short() {
+        assert_file_err("PRINT CVI(\"A\")", "Illegal function call", 1, 7);
+    }
+
+    #[test]
+    fn test_field_overflow() {
+        let input = r#"
+        OPEN "DATA.DAT" FOR RANDOM AS #1 LEN = 10
+        FIELD #1, 6 AS N$, 5 AS A$
+        "#;
+        assert_file_err(input, "FIELD overflow", 3, 9);
+        assert_eq!(interpret_err(input).code(), 50);
+    }
+
+    #[test]
+    fn test_field_of_file_not_open() {
+        assert_file_err("FIELD #1, 6 AS N$", "Bad file name or number", 1, 1);
+    }
+
+    #[test]
+    fn test_record_zero() {
+        let input = r#"
+        OPEN "DATA.DAT" FOR RANDOM AS #1 LEN = 2
+        PUT #1, 0, A%
+        "#;
+        assert_file_err(input, "Bad record number", 3, 9);
+        assert_eq!(interpret_err(input).code(), 63);
+    }
+
+    #[test]
+    fn test_put_keeps_other_records() {
+        let input = r#"
+        OPEN "DATA.DAT" FOR RANDOM AS #1 LEN = 2
+        A% = 258
+        PUT #1, 2, A%
+        "#;
+        let interpreter = interpret_with_stdlib(input, stdlib_with_file("DATA.DAT", b"abcdef"));
+        assert_eq!(interpreter.stdlib.files["DATA.DAT"], b"ab\x02\x01ef");
+    }
+
+    #[test]
+    fn test_get_from_sequential_file() {
+        let input = r#"
+        OPEN "DATA.DAT" FOR OUTPUT AS #1
+        GET #1, 1, A%
+        "#;
+        assert_file_err(input, "Bad file mode", 3, 9);
+    }
+
+    #[test]
+    fn test_lset_requires_string_variable() {
+        assert_linter_err!("LSET A% = \"hello\"", LinterError::TypeMismatch, 1, 6);
+    }
+
+    #[test]
+    fn test_get_requires_variable() {
+        assert_linter_err!("GET #1, 1, 5", LinterError::VariableRequired, 1, 12);
+    }
+
+    #[test]
+    fn test_name_and_kill() {
+        let input = r#"
+        NAME "TODO.DAT" AS "TODO.OLD"
+        KILL "OTHER.DAT"
+        "#;
+        let mut stdlib = stdlib_with_file("TODO.DAT", b"milk");
+        stdlib.files.insert("OTHER.DAT".to_string(), vec![]);
+        let interpreter = interpret_with_stdlib(input, stdlib);
+        let mut names: Vec<&String> = interpreter.stdlib.files.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["TODO.OLD"]);
+        assert_eq!(interpreter.stdlib.files["TODO.OLD"], b"milk");
+    }
+
+    #[test]
+    fn test_name_errors() {
+        assert_file_err("NAME \"A.DAT\" AS \"B.DAT\"", "File not found", 1, 1);
+        let mut stdlib = stdlib_with_file("A.DAT", b"");
+        stdlib.files.insert("B.DAT".to_string(), vec![]);
+        let pos = Location::new(1, 1);
+        assert_eq!(
+            interpret_err_with_stdlib("NAME \"A.DAT\" AS \"B.DAT\"", stdlib),
+            InterpreterError::new("File already exists", vec![pos, pos])
+        );
+    }
+
+    #[test]
+    fn test_kill_file_not_found() {
+        assert_file_err("KILL \"A.DAT\"", "File not found", 1, 1);
+    }
+
+    #[test]
+    fn test_name_and_kill_linter_errors() {
+        assert_linter_err!("KILL 1", LinterError::TypeMismatch, 1, 6);
+        assert_linter_err!("KILL", LinterError::ArgumentCountMismatch, 1, 1);
+        assert_linter_err!("NAME \"A\" AS 1", LinterError::TypeMismatch, 1, 13);
+    }
+
+    #[test]
+    fn test_rest_delete() {
+        let mut stdlib = stdlib_with_file("TODO.DAT", b"buy milk\r\nwalk dog\r\n");
+        stdlib
+            .env
+            .insert("REQUEST_METHOD".to_string(), "DELETE".to_string());
+        stdlib
+            .env
+            .insert("QUERY_STRING".to_string(), "id=1".to_string());
+        // the GW-BASIC program of the REST example, relative to the fixtures
+        let interpreter = interpret_file("../../rest/DELETE.BAS", stdlib).unwrap();
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec!["Content-Type: text/plain", "X-Powered-By: GW-BASIC", ""]
+        );
+        assert_eq!(interpreter.stdlib.files.len(), 1);
+        assert_eq!(interpreter.stdlib.files["TODO.DAT"], b"walk dog\r\n");
+    }
+}
@@ -17,7 +17,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 ", " 4 ", " 5 "]);
     }
 
     #[test]
@@ -29,7 +29,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 ", " 4 ", " 5 "]);
     }
 
     #[test]
@@ -41,7 +41,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 ", " 4 ", " 5 "]);
     }
 
     #[test]
@@ -77,7 +77,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "3", "5", "7"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 3 ", " 5 ", " 7 "]);
     }
 
     #[test]
@@ -89,7 +89,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["7", "4", "1", "-2", "-5"]);
+        assert_eq!(stdlib.output, vec![" 7 ", " 4 ", " 1 ", "-2 ", "-5 "]);
     }
 
     #[test]
@@ -115,7 +115,10 @@ mod tests {
         let interpreter = interpret(input);
         assert_has_variable!(interpreter, "i%", -4);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["3", "2", "1", "0", "-1", "-2", "-3"]);
+        assert_eq!(
+            stdlib.output,
+            vec![" 3 ", " 2 ", " 1 ", " 0 ", "-1 ", "-2 ", "-3 "]
+        );
     }
 
     #[test]
@@ -127,7 +130,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 ", " 4 ", " 5 "]);
     }
 
     #[test]
@@ -139,7 +142,7 @@ mod tests {
         ";
         let interpreter = interpret(input);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 ", " 4 ", " 5 "]);
     }
 
     #[test]
@@ -165,7 +168,7 @@ mod tests {
         assert_has_variable!(interpreter, "I%", 4);
         assert_has_variable!(interpreter, "N%", 0);
         let stdlib = interpreter.stdlib;
-        assert_eq!(stdlib.output, vec!["1", "2", "3"]);
+        assert_eq!(stdlib.output, vec![" 1 ", " 2 ", " 3 "]);
     }
 
     #[test]
@@ -178,6 +181,52 @@ mod tests {
         NEXT
         ";
         let interpreter = interpret(input);
-        assert_eq!(interpreter.stdlib.output, vec!["1 3", "1 4", "2 3", "2 4"]);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![
+                " 1             3 ",
+                " 1             4 ",
+                " 2             3 ",
+                " 2             4 "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exit_for() {
+        let input = "
+        FOR I = 1 TO 10
+            IF I = 3 THEN EXIT FOR
+            PRINT I
+        NEXT
+        PRINT I
+        ";
+        assert_eq!(interpret(input).stdlib.output, vec![" 1 ", " 2 ", " 3 "]);
+    }
+
+    #[test]
+    fn test_exit_for_restores_outer_for_loop() {
+        let input = "
+        FOR I = 1 TO 2
+            FOR J = 5 TO 1 STEP -1
+                IF J = 4 THEN EXIT FOR
+                PRINT I, J
+            NEXT
+        NEXT
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 1             5 ", " 2             5 "]
+        );
+    }
+
+    #[test]
+    fn test_exit_for_outside_for_loop() {
+        let input = "
+        WHILE 1
+            EXIT FOR
+        WEND
+        ";
+        assert_linter_err!(input, LinterError::ExitForNotWithinForNext, 3, 13);
     }
 }
@@ -51,7 +51,7 @@ mod tests {
         ";
         let interpreter = interpret(program);
         assert_has_variable!(interpreter, "X!", 0.0_f32);
-        assert_eq!(interpreter.stdlib.output, vec!["3"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 3 "]);
     }
 
     #[test]
@@ -126,7 +126,7 @@ mod tests {
         END FUNCTION
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["1", "4"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 1 ", " 4 "]);
     }
 
     #[test]
@@ -143,7 +143,7 @@ mod tests {
         END FUNCTION
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["2", "3"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 2 ", " 3 "]);
     }
 
     #[test]
@@ -159,7 +159,7 @@ mod tests {
         END FUNCTION
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["3"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 3 "]);
     }
 
     #[test]
@@ -178,7 +178,21 @@ mod tests {
         END FUNCTION
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["6"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 6 "]);
+    }
+
+    #[test]
+    fn test_function_without_parameters_called_without_parenthesis() {
+        let program = r#"
+        DECLARE FUNCTION GetAction$()
+        A$ = GetAction$
+        PRINT A$; GetAction$
+        FUNCTION GetAction$()
+            GetAction$ = "list"
+        END FUNCTION
+        "#;
+        let interpreter = interpret(program);
+        assert_eq!(interpreter.stdlib.output, vec!["listlist"]);
     }
 
     #[test]
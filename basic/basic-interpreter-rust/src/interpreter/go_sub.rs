@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::common::*;
+    use crate::interpreter::InterpreterError;
+    use crate::linter::LinterError;
+
+    #[test]
+    fn test_go_sub_return() {
+        let input = r#"
+        PRINT "a"
+        GOSUB Alpha
+        PRINT "c"
+        GOSUB Alpha
+        SYSTEM
+        Alpha:
+        PRINT "b"
+        RETURN
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["a", "b", "c", "b"]);
+    }
+
+    #[test]
+    fn test_nested_go_sub() {
+        let input = r#"
+        GOSUB Alpha
+        PRINT "done"
+        SYSTEM
+        Alpha:
+        PRINT "alpha"
+        GOSUB Beta
+        PRINT "alpha again"
+        RETURN
+        Beta:
+        PRINT "beta"
+        RETURN
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["alpha", "beta", "alpha again", "done"]
+        );
+    }
+
+    #[test]
+    fn test_return_to_label() {
+        let input = r#"
+        GOSUB Alpha
+        PRINT "skipped"
+        Beta:
+        PRINT "beta"
+        SYSTEM
+        Alpha:
+        RETURN Beta
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["beta"]);
+    }
+
+    #[test]
+    fn test_go_sub_in_single_line_if() {
+        let input = r#"
+        FOR I = 1 TO 3
+            IF I = 2 THEN GOSUB Alpha
+            PRINT I
+        NEXT
+        SYSTEM
+        Alpha:
+        PRINT "alpha"
+        RETURN
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 1 ", "alpha", " 2 ", " 3 "]
+        );
+    }
+
+    #[test]
+    fn test_return_without_go_sub() {
+        let input = r#"
+        PRINT "a"
+        RETURN
+        "#;
+        assert_eq!(
+            interpret_err(input),
+            InterpreterError::new_with_pos("RETURN without GOSUB", Location::new(3, 9))
+        );
+    }
+
+    #[test]
+    fn test_return_without_go_sub_inside_sub() {
+        let input = r#"
+        DECLARE SUB Hello
+
+        Hello
+
+        SUB Hello
+            RETURN
+        END SUB
+        "#;
+        assert_eq!(
+            interpret_err(input),
+            InterpreterError::new(
+                "RETURN without GOSUB",
+                vec![Location::new(7, 13), Location::new(4, 9)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_go_sub_label_not_defined() {
+        assert_linter_err!("GOSUB Alpha", LinterError::LabelNotDefined, 1, 1);
+    }
+
+    #[test]
+    fn test_on_go_to() {
+        let input = r#"
+        FOR I = 0 TO 3
+            ON I GOTO Alpha, Beta
+            PRINT "none"
+            GOTO NextI
+            Alpha:
+            PRINT "alpha"
+            GOTO NextI
+            Beta:
+            PRINT "beta"
+            NextI:
+        NEXT
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["none", "alpha", "beta", "none"]
+        );
+    }
+
+    #[test]
+    fn test_on_go_sub() {
+        let input = r#"
+        FOR I = 1 TO 3
+            ON I GOSUB Alpha, Beta
+            PRINT I
+        NEXT
+        SYSTEM
+        Alpha:
+        PRINT "alpha"
+        RETURN
+        Beta:
+        PRINT "beta"
+        RETURN
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["alpha", " 1 ", "beta", " 2 ", " 3 "]
+        );
+    }
+
+    #[test]
+    fn test_on_go_to_rounds_fractional_selector() {
+        let input = r#"
+        ON 1.6 GOTO Alpha, Beta
+        PRINT "none"
+        SYSTEM
+        Alpha:
+        PRINT "alpha"
+        SYSTEM
+        Beta:
+        PRINT "beta"
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["beta"]);
+    }
+
+    #[test]
+    fn test_on_go_to_selector_out_of_range() {
+        for selector in &["-1", "256"] {
+            let input = format!(
+                "
+                ON {} GOTO Alpha
+                Alpha:
+                ",
+                selector
+            );
+            assert_eq!(
+                interpret_err(input),
+                InterpreterError::new_with_pos("Illegal function call", Location::new(2, 17))
+            );
+        }
+    }
+
+    #[test]
+    fn test_on_go_to_selector_above_labels_falls_through() {
+        let input = r#"
+        ON 255 GOSUB Alpha
+        PRINT "none"
+        SYSTEM
+        Alpha:
+        PRINT "alpha"
+        RETURN
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["none"]);
+    }
+
+    #[test]
+    fn test_on_go_to_label_not_defined() {
+        assert_linter_err!("ON 1 GOTO Alpha", LinterError::LabelNotDefined, 1, 1);
+    }
+
+    #[test]
+    fn test_on_go_to_string_expression() {
+        let input = r#"
+        ON "1" GOTO Alpha
+        Alpha:
+        "#;
+        assert_linter_err!(input, LinterError::TypeMismatch, 2, 12);
+    }
+}
@@ -2,6 +2,8 @@
 mod tests {
     use super::super::test_utils::*;
     use crate::assert_linter_err;
+    use crate::common::*;
+    use crate::interpreter::InterpreterError;
     use crate::linter::LinterError;
 
     #[test]
@@ -29,7 +31,7 @@ mod tests {
         END IF
         "#;
         let interpreter = interpret(input);
-        assert_eq!(interpreter.stdlib.output, vec!["0", "1"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 0 ", " 1 "]);
     }
 
     #[test]
@@ -42,7 +44,7 @@ mod tests {
         IF X <= 1 THEN GOTO Jump
         "#;
         let interpreter = interpret(input);
-        assert_eq!(interpreter.stdlib.output, vec!["0", "1"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 0 ", " 1 "]);
     }
 
     #[test]
@@ -62,4 +64,72 @@ mod tests {
         ";
         assert_linter_err!(input, LinterError::DuplicateLabel, 4, 9);
     }
+
+    #[test]
+    fn go_to_line_number() {
+        let input = r#"
+        10 X = 0
+        20 PRINT X
+        30 X = X + 1
+        40 IF X <= 1 GOTO 20
+        50 GOSUB 100
+        60 IF X = 3 THEN 90
+        70 PRINT "skipped"
+        90 SYSTEM
+        100 PRINT "sub"
+        110 X = X + 1
+        120 RETURN
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(interpreter.stdlib.output, vec![" 0 ", " 1 ", "sub"]);
+    }
+
+    #[test]
+    fn go_to_line_numbers_mixed_with_labels() {
+        let input = r#"
+        10 GOTO Alpha
+        20 PRINT "skipped"
+        Alpha:
+        PRINT "alpha"
+        GOTO 40
+        30 PRINT "skipped"
+        40 PRINT "done"
+        "#;
+        let interpreter = interpret(input);
+        assert_eq!(interpreter.stdlib.output, vec!["alpha", "done"]);
+    }
+
+    #[test]
+    fn go_to_missing_line_number() {
+        let input = "
+        10 GOTO 20
+        ";
+        let (err, pos) = linter_err(input).consume();
+        assert_eq!(err, LinterError::LabelNotDefined);
+        assert_eq!(pos, Some(Location::new(2, 12).with_line_number(10)));
+    }
+
+    #[test]
+    fn go_to_duplicate_line_number() {
+        let input = "
+        10 PRINT 1
+        10 PRINT 2
+        ";
+        let (err, pos) = linter_err(input).consume();
+        assert_eq!(err, LinterError::DuplicateLabel);
+        assert_eq!(pos, Some(Location::new(3, 9).with_line_number(10)));
+    }
+
+    #[test]
+    fn runtime_error_has_line_number() {
+        let input = r#"
+        10 PRINT "a"
+        20 ENVIRON "oops"
+        "#;
+        let pos = Location::new(3, 12).with_line_number(20);
+        assert_eq!(
+            interpret_err(input),
+            InterpreterError::new("Invalid expression. Must be name=value.", vec![pos, pos])
+        );
+    }
 }
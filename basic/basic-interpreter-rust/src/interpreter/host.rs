@@ -0,0 +1,179 @@
+use crate::casting::cast;
+use crate::common::*;
+use crate::interpreter::context::Argument;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, InterpreterError, Stdlib};
+use crate::linter::{HasQualifier, TypeQualifier};
+use crate::variant::Variant;
+use std::rc::Rc;
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Calls a function of the host. The result is cast to the return type of the function.
+    pub fn run_host_function(
+        &mut self,
+        name: &CaseInsensitiveString,
+        pos: Location,
+    ) -> Result<(), InterpreterError> {
+        let host = Rc::clone(&self.host);
+        let function = host
+            .function(name)
+            .expect("host function should be registered");
+        self.function_result = self
+            .call_host(&function.params, |args| function.call(args))
+            .and_then(|v| cast(v, function.return_type))
+            .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+        Ok(())
+    }
+
+    /// Calls a sub of the host.
+    pub fn run_host_sub(
+        &mut self,
+        name: &CaseInsensitiveString,
+        pos: Location,
+    ) -> Result<(), InterpreterError> {
+        let host = Rc::clone(&self.host);
+        let sub = host.sub(name).expect("host sub should be registered");
+        self.call_host(&sub.params, |args| {
+            sub.call(args).map(|_| Variant::VInteger(0))
+        })
+        .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+        Ok(())
+    }
+
+    /// Pops the arguments, casts them to the types of the parameters and calls the host.
+    /// The arguments that were passed by reference get the values that the host left in them.
+    fn call_host<F>(&mut self, params: &[TypeQualifier], f: F) -> Result<Variant, String>
+    where
+        F: FnOnce(&mut [Variant]) -> Result<Variant, String>,
+    {
+        let sub_context = self.context_mut().demand_sub();
+        let mut args: Vec<Argument> = vec![];
+        while let Some(arg) = sub_context.pop_front_unnamed_arg() {
+            args.push(arg);
+        }
+        let mut values: Vec<Variant> = vec![];
+        for (arg, q) in args.iter().zip(params.iter()) {
+            let v = sub_context
+                .evaluate_argument(arg)
+                .expect("argument should have a value");
+            values.push(cast(v, *q)?);
+        }
+        let result = f(&mut values)?;
+        for (arg, value) in args.iter().zip(values) {
+            if let Argument::ByRef(_) = arg {
+                let value = cast(value, arg.qualifier())?;
+                sub_context.set_value_to_popped_arg(arg, value)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::host::HostRegistry;
+    use crate::linter::{LinterError, TypeQualifier};
+    use crate::variant::Variant;
+    use std::rc::Rc;
+
+    fn host() -> Rc<HostRegistry> {
+        let mut host = HostRegistry::new();
+        host.add_function(
+            "JsonGet",
+            &[TypeQualifier::DollarString, TypeQualifier::DollarString],
+            TypeQualifier::DollarString,
+            |args| match (&args[0], &args[1]) {
+                (Variant::VString(json), Variant::VString(key)) => {
+                    let prefix = format!("{}=", key);
+                    json.split(';')
+                        .find_map(|pair| pair.strip_prefix(&prefix))
+                        .map(|v| Variant::VString(v.to_string()))
+                        .ok_or_else(|| "Key not found".to_string())
+                }
+                _ => panic!("string arguments expected"),
+            },
+        )
+        .add_function(
+            "Half",
+            &[TypeQualifier::BangSingle],
+            TypeQualifier::PercentInteger,
+            |args| match &args[0] {
+                Variant::VSingle(f) => Ok(Variant::VSingle(f / 2.0)),
+                _ => panic!("single argument expected"),
+            },
+        )
+        .add_sub(
+            "Increment",
+            &[TypeQualifier::PercentInteger],
+            |args| match &args[0] {
+                Variant::VInteger(i) => {
+                    args[0] = Variant::VInteger(i + 1);
+                    Ok(())
+                }
+                _ => panic!("integer argument expected"),
+            },
+        );
+        Rc::new(host)
+    }
+
+    #[test]
+    fn test_host_function() {
+        let program = r#"
+        J$ = "name=Alice;city=Athens"
+        PRINT JSONGET$(J$, "city"); Half%(7)
+        "#;
+        let interpreter = interpret_with_host(program, host());
+        assert_eq!(interpreter.stdlib.output, vec!["Athens 4 "]);
+    }
+
+    #[test]
+    fn test_host_sub_by_ref_and_by_val() {
+        let program = r#"
+        A% = 1
+        Increment A%
+        Increment A% + 10
+        PRINT A%
+        "#;
+        let interpreter = interpret_with_host(program, host());
+        assert_eq!(interpreter.stdlib.output, vec![" 2 "]);
+    }
+
+    #[test]
+    fn test_host_error_is_runtime_error() {
+        let program = r#"
+        ON ERROR GOTO ErrTrap
+        PRINT JsonGet$("a=1", "b")
+        SYSTEM
+        ErrTrap:
+        PRINT "Error"; ERR
+        "#;
+        let interpreter = interpret_with_host(program, host());
+        assert_eq!(interpreter.stdlib.output, vec!["Error 5 "]);
+    }
+
+    #[test]
+    fn test_host_linter_errors() {
+        let host = host();
+        assert_eq!(
+            lint_err_with_host("PRINT JsonGet$(1, \"a\")", &host),
+            LinterError::ArgumentTypeMismatch
+        );
+        assert_eq!(
+            lint_err_with_host("PRINT JsonGet$(\"a\")", &host),
+            LinterError::ArgumentCountMismatch
+        );
+        assert_eq!(
+            lint_err_with_host("PRINT JsonGet%(\"a\", \"b\")", &host),
+            LinterError::TypeMismatch
+        );
+        assert_eq!(
+            lint_err_with_host("A = 1\nIncrement A", &host),
+            LinterError::ArgumentTypeMismatch
+        );
+        assert_eq!(
+            lint_err_with_host("SUB Increment(A%)\nEND SUB", &host),
+            LinterError::DuplicateDefinition
+        );
+    }
+}
@@ -190,4 +190,33 @@ mod tests {
         "#;
         assert_eq!(interpret(input).stdlib.output, vec!["before", "after"]);
     }
+
+    #[test]
+    fn test_single_line_if_else() {
+        let input = r#"
+        FOR I = 0 TO 1
+            IF I THEN Z$ = "one" ELSE Z$ = "zero"
+            PRINT Z$
+            IF I THEN PRINT "yes"; ELSE PRINT "no";
+            IF I THEN GOTO Skip ELSE PRINT "!"
+            Skip:
+        NEXT
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["zero", "no!", "one", "yes"]
+        );
+    }
+
+    #[test]
+    fn test_else_on_next_line_belongs_to_block() {
+        let input = r#"
+        IF 0 THEN
+            IF 1 THEN PRINT "inner"
+        ELSE
+            PRINT "outer else"
+        END IF
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["outer else"]);
+    }
 }
@@ -0,0 +1,252 @@
+use crate::casting::cast;
+use crate::common::*;
+use crate::interpreter::context::Argument;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::file::io_error_message;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::{HasQualifier, TypeQualifier};
+use crate::variant::Variant;
+
+type Result<T> = std::result::Result<T, String>;
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Implements `INPUT`, which reads comma separated values from the keyboard
+    /// or from a file.
+    ///
+    /// If the values can't be assigned to the variables, e.g. because there are
+    /// too few values or a number is not valid, it shows `Redo from start`
+    /// and asks again.
+    pub fn input(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        if file_number != 0 {
+            return self.file_input(file_number);
+        }
+        let sub_context = self.context_mut().demand_sub();
+        let kind = match sub_context.pop_front_unnamed() {
+            Variant::VInteger(i) => InputPrompt::from(i),
+            _ => panic!("INPUT should have a prompt kind"),
+        };
+        let prompt = match (kind, sub_context.pop_front_unnamed()) {
+            (InputPrompt::QuestionMark, _) => "? ".to_string(),
+            (InputPrompt::PromptWithQuestionMark, Variant::VString(s)) => format!("{}? ", s),
+            (InputPrompt::Prompt, Variant::VString(s)) => s,
+            _ => panic!("INPUT should have a string prompt"),
+        };
+        let mut variables: Vec<Argument> = vec![];
+        while let Some(arg) = sub_context.pop_front_unnamed_arg() {
+            variables.push(arg);
+        }
+        let qualifiers: Vec<TypeQualifier> = variables
+            .iter()
+            .map(|arg| match arg {
+                Argument::ByRef(name) => name.qualifier(),
+                _ => panic!("INPUT should have variables"),
+            })
+            .collect();
+        let values = loop {
+            self.write_text(0, &prompt)?;
+            let line = self.read_keyboard_line()?;
+            match parse_input_values(&line, &qualifiers) {
+                Some(values) => break values,
+                None => self.write_text(0, "Redo from start\n")?,
+            }
+        };
+        for (arg, value) in variables.iter().zip(values) {
+            self.context_mut()
+                .demand_sub()
+                .set_value_to_popped_arg(arg, value)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a line from the keyboard. The cursor moves to the start of the next line,
+    /// because the user pressed Enter.
+    pub fn read_keyboard_line(&mut self) -> Result<String> {
+        let line = self.stdlib.input().map_err(io_error_message)?;
+        self.screen_column = 0;
+        Ok(line)
+    }
+}
+
+/// Parses the line that was typed for `INPUT` into one value for every variable.
+/// Returns `None` if it needs to be typed again.
+fn parse_input_values(line: &str, qualifiers: &[TypeQualifier]) -> Option<Vec<Variant>> {
+    let fields = split_input_fields(line)?;
+    if fields.len() != qualifiers.len() {
+        return None;
+    }
+    fields
+        .into_iter()
+        .zip(qualifiers)
+        .map(|(field, q)| parse_input_value(field, *q))
+        .collect()
+}
+
+/// Splits the line that was typed for `INPUT` into comma separated fields.
+/// A field can be quoted, so that it can contain commas and leading spaces.
+/// Returns `None` if there is text after the closing quote of a field.
+fn split_input_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields: Vec<String> = vec![];
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            // take_while consumes the closing quote
+            fields.push(chars.by_ref().take_while(|ch| *ch != '"').collect());
+            while chars.peek() == Some(&' ') {
+                chars.next();
+            }
+        } else {
+            let mut field = String::new();
+            while let Some(ch) = chars.next_if(|ch| *ch != ',') {
+                field.push(ch);
+            }
+            fields.push(field.trim_end_matches(' ').to_string());
+        }
+        match chars.next() {
+            Some(',') => (),
+            Some(_) => return None,
+            None => return Some(fields),
+        }
+    }
+}
+
+/// Parses one of the values that were typed for `INPUT`.
+/// Returns `None` if it is not valid for the type of the variable.
+pub fn parse_input_value(field: String, q: TypeQualifier) -> Option<Variant> {
+    if q == TypeQualifier::DollarString {
+        return Some(Variant::VString(field));
+    }
+    if field.is_empty() {
+        return cast(Variant::VInteger(0), q).ok();
+    }
+    let is_number_char = |ch: char| ch.is_ascii_digit() || "+-.eEdD".contains(ch);
+    if !field.chars().all(is_number_char) {
+        return None;
+    }
+    // e.g. 1D+3 is a double precision number
+    let n: f64 = field.replace(['d', 'D'], "E").parse().ok()?;
+    cast(Variant::VDouble(n), q).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_has_variable;
+    use crate::variant::Variant;
+
+    fn stdlib_with_input(lines: &[&str]) -> MockStdlib {
+        let mut stdlib = MockStdlib::new();
+        for line in lines {
+            stdlib.add_next_input(line);
+        }
+        stdlib
+    }
+
+    #[test]
+    fn test_input_prompts() {
+        let program = r#"
+        INPUT "Name"; N$
+        INPUT "Age", A
+        INPUT X
+        "#;
+        let stdlib = stdlib_with_input(&["Ann", "42", "1"]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_eq!(interpreter.stdlib.output, vec!["Name? ", "Age", "? "]);
+        assert_has_variable!(interpreter, "N$", "Ann");
+        assert_has_variable!(interpreter, "A!", 42.0_f32);
+    }
+
+    #[test]
+    fn test_input_comma_separated_values() {
+        let program = r#"
+        INPUT A, B$, C#, D&, E$
+        "#;
+        let stdlib = stdlib_with_input(&[r#" 1.5 , "hello, world" , 2.5D3, 100000,  b "#]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_has_variable!(interpreter, "A!", 1.5_f32);
+        assert_has_variable!(interpreter, "B$", "hello, world");
+        assert_has_variable!(interpreter, "C#", 2500.0);
+        assert_has_variable!(interpreter, "D&", 100000_i64);
+        assert_has_variable!(interpreter, "E$", "b");
+    }
+
+    #[test]
+    fn test_input_empty_values() {
+        let program = r#"
+        INPUT A%, B$
+        "#;
+        let stdlib = stdlib_with_input(&[","]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_has_variable!(interpreter, "A%", 0);
+        assert_has_variable!(interpreter, "B$", "");
+    }
+
+    #[test]
+    fn test_input_redo_from_start() {
+        let program = r#"
+        INPUT "Numbers"; A%, B%
+        PRINT A% + B%
+        "#;
+        let stdlib = stdlib_with_input(&["1", "x, 2", "40000, 1", r#""1" x, 2"#, "1, 2"]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![
+                "Numbers? ",
+                "Redo from start",
+                "Numbers? ",
+                "Redo from start",
+                "Numbers? ",
+                "Redo from start",
+                "Numbers? ",
+                "Redo from start",
+                "Numbers? ",
+                " 3 "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_past_end_of_input() {
+        assert_eq!(interpret_err("INPUT A").code(), 62);
+    }
+
+    #[test]
+    fn test_line_input_with_prompt() {
+        let program = r#"
+        LINE INPUT "Name: "; N$
+        PRINT N$
+        "#;
+        let stdlib = stdlib_with_input(&[r#"  "Ann", Bob "#]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec!["Name: ", r#"  "Ann", Bob "#]
+        );
+    }
+
+    #[test]
+    fn test_input_chars() {
+        let program = r#"
+        A$ = INPUT$(5)
+        B$ = INPUT$(3)
+        PRINT A$; "/"; B$
+        "#;
+        let stdlib = stdlib_with_input(&["hel", "lo world"]);
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_eq!(interpreter.stdlib.output, vec!["hello/ wo"]);
+    }
+
+    #[test]
+    fn test_input_chars_past_end_of_input() {
+        let program = r#"
+        A$ = INPUT$(5)
+        "#;
+        let stdlib = stdlib_with_input(&["abc"]);
+        assert_eq!(interpret_err_with_stdlib(program, stdlib).code(), 62);
+    }
+}
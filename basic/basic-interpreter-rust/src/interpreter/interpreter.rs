@@ -1,14 +1,61 @@
+use crate::casting::cast;
 use crate::common::*;
+use crate::host::HostRegistry;
 use crate::instruction_generator::{Instruction, InstructionNode};
 use crate::interpreter::context::*;
 use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::debugger::DebugSession;
+use crate::interpreter::file::OpenFile;
+use crate::interpreter::random::INITIAL_RND_SEED;
 use crate::interpreter::{InterpreterError, Result, Stdlib};
+use crate::linter::TypeQualifier;
 
 use crate::variant::Variant;
 
 use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
+use std::rc::Rc;
+
+pub(super) const RETURN_WITHOUT_GO_SUB: &str = "RETURN without GOSUB";
+pub(super) const RESUME_WITHOUT_ERROR: &str = "RESUME without error";
+
+/// An error that was caught by the error handler.
+#[derive(Debug)]
+struct TrappedError {
+    /// The error code, returned by `ERR`
+    code: i32,
+    /// The line number where the error occurred, returned by `ERL`
+    line_number: u32,
+    /// The address of the statement that caused the error, used by `RESUME`
+    statement_start: usize,
+    /// The address right after that statement, used by `RESUME NEXT`
+    statement_end: usize,
+}
+
+impl TrappedError {
+    fn new(error: &InterpreterError, instructions: &[InstructionNode], i: usize) -> Self {
+        // the innermost statement that contains the failing instruction
+        let (statement_start, statement_end) = (0..=i)
+            .rev()
+            .find_map(|j| match instructions[j].as_ref() {
+                Instruction::StatementStart(end) if *end > i => Some((j, *end)),
+                _ => None,
+            })
+            .unwrap_or((i, i + 1));
+        // the line number of the failing instruction, or the closest one before it
+        let line_number = (0..=i)
+            .rev()
+            .find_map(|j| instructions[j].location().line_number())
+            .unwrap_or_default();
+        Self {
+            code: error.code(),
+            line_number,
+            statement_start,
+            statement_end,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Registers {
@@ -77,24 +124,80 @@ pub struct Interpreter<S: Stdlib> {
     pub context: Option<Context>,
     register_stack: RegisterStack,
     return_stack: Vec<usize>,
-    stacktrace: Vec<Location>,
+    go_sub_stack: Vec<usize>,
+    /// The files that are currently open, by file number
+    pub files: HashMap<i32, OpenFile>,
+    /// The column (zero based) of the cursor on the screen, used by `PRINT`
+    pub screen_column: usize,
+    /// The column (zero based) of the printer, used by `LPRINT`
+    pub printer_column: usize,
+    /// The state of the random number generator, used by `RND` and `RANDOMIZE`
+    pub rnd_seed: u32,
+    pub stacktrace: Vec<Location>,
+    /// The error that is being handled by the error handler
+    trapped_error: Option<TrappedError>,
+    /// The depth of the register stack at the start of the current statement
+    statement_registers_depth: usize,
+    /// The `statement_registers_depth` of the callers of the current sub or function
+    caller_registers_depths: Vec<usize>,
     pub function_result: Variant,
+    /// The functions and subs of the host
+    pub host: Rc<HostRegistry>,
+    /// The debugger that controls the program, if any
+    pub debug_session: Option<DebugSession>,
 }
 
 impl<TStdlib: Stdlib> Interpreter<TStdlib> {
     pub fn new(stdlib: TStdlib) -> Self {
+        Self::with_host(stdlib, Rc::new(HostRegistry::default()))
+    }
+
+    /// Creates an interpreter that can run programs which call the functions and subs of the host.
+    pub fn with_host(stdlib: TStdlib, host: Rc<HostRegistry>) -> Self {
         let mut result = Interpreter {
             stdlib,
             context: Some(Context::new()),
             return_stack: vec![],
+            go_sub_stack: vec![],
+            files: HashMap::new(),
+            screen_column: 0,
+            printer_column: 0,
+            rnd_seed: INITIAL_RND_SEED,
             register_stack: VecDeque::new(),
             stacktrace: vec![],
+            trapped_error: None,
+            statement_registers_depth: 1,
+            caller_registers_depths: vec![],
             function_result: Variant::VInteger(0),
+            host,
+            debug_session: None,
         };
         result.register_stack.push_back(Registers::new());
         result
     }
 
+    /// Returns to the main program, keeping the variables, after a program
+    /// that stopped inside a sub or a function, e.g. because of an error.
+    pub fn unwind(&mut self) {
+        self.context = self.context.take().map(Context::into_root);
+        self.register_stack.clear();
+        self.register_stack.push_back(Registers::new());
+        self.return_stack.clear();
+        self.go_sub_stack.clear();
+        self.stacktrace.clear();
+        self.trapped_error = None;
+        self.statement_registers_depth = 1;
+        self.caller_registers_depths.clear();
+    }
+
+    /// Forgets the variables and closes the files, so that a program can run from scratch.
+    pub fn clear(&mut self) {
+        self.unwind();
+        self.context = Some(Context::new());
+        // a file that can't be written is forgotten anyway
+        self.close_all_files().unwrap_or_default();
+    }
+
     fn registers_ref(&self) -> &Registers {
         self.register_stack.back().unwrap()
     }
@@ -103,7 +206,7 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
         self.register_stack.back_mut().unwrap()
     }
 
-    fn get_a(&self) -> Variant {
+    pub fn get_a(&self) -> Variant {
         self.registers_ref().get_a()
     }
 
@@ -111,7 +214,7 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
         self.registers_ref().get_b()
     }
 
-    fn set_a(&mut self, v: Variant) {
+    pub fn set_a(&mut self, v: Variant) {
         self.registers_mut().set_a(v);
     }
 
@@ -127,6 +230,31 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
             Instruction::SetErrorHandler(idx) => {
                 *error_handler = Some(*idx);
             }
+            Instruction::ClearErrorHandler => {
+                *error_handler = None;
+            }
+            Instruction::RaiseError => {
+                self.raise_error(pos)?;
+            }
+            Instruction::CastAToOnSelector => {
+                self.cast_a_to_on_selector(pos)?;
+            }
+            Instruction::Resume => {
+                let trapped_error = self.take_trapped_error(pos)?;
+                // the marker of the statement is a no-op, continue right after it
+                *i = trapped_error.statement_start;
+            }
+            Instruction::ResumeNext => {
+                let trapped_error = self.take_trapped_error(pos)?;
+                *i = trapped_error.statement_end - 1;
+            }
+            Instruction::ResumeLabel(idx) => {
+                self.take_trapped_error(pos)?;
+                *i = idx - 1;
+            }
+            Instruction::StatementStart(_) => {
+                self.statement_registers_depth = self.register_stack.len();
+            }
             Instruction::PushRegisters => {
                 self.register_stack.push_back(Registers::new());
             }
@@ -186,6 +314,86 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
                     .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
                 self.set_a(c);
             }
+            Instruction::Multiply => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .multiply(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Divide => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .divide(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::IntegerDivide => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .integer_divide(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Modulo => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .modulo(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Power => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .power(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::And => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .and(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Or => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .or(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Xor => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .xor(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Eqv => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .eqv(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
+            Instruction::Imp => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let c = a
+                    .imp(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                self.set_a(c);
+            }
             Instruction::NegateA => {
                 let a = self.get_a();
                 let c = a
@@ -216,6 +424,15 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
                 let is_true = order == Ordering::Equal;
                 self.set_a(is_true.into());
             }
+            Instruction::NotEqual => {
+                let a = self.get_a();
+                let b = self.get_b();
+                let order = a
+                    .cmp(&b)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+                let is_true = order != Ordering::Equal;
+                self.set_a(is_true.into());
+            }
             Instruction::Less => {
                 let a = self.get_a();
                 let b = self.get_b();
@@ -269,10 +486,15 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
             Instruction::PushStack => {
                 self.swap_args_with_sub_context();
                 self.stacktrace.insert(0, pos);
+                self.caller_registers_depths
+                    .push(self.statement_registers_depth);
             }
             Instruction::PopStack => {
                 self.pop();
                 self.stacktrace.remove(0);
+                if let Some(depth) = self.caller_registers_depths.pop() {
+                    self.statement_registers_depth = depth;
+                }
             }
             Instruction::PushUnnamedRefParam(name) => {
                 self.context_mut()
@@ -308,9 +530,16 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
             Instruction::BuiltInFunction(n) => {
                 self.run_built_in_function(n, pos)?;
             }
+            Instruction::HostSub(n) => {
+                self.run_host_sub(n, pos)?;
+            }
+            Instruction::HostFunction(n) => {
+                self.run_host_function(n, pos)?;
+            }
             Instruction::UnresolvedJump(_)
             | Instruction::UnresolvedJumpIfFalse(_)
-            | Instruction::SetUnresolvedErrorHandler(_) => {
+            | Instruction::SetUnresolvedErrorHandler(_)
+            | Instruction::UnresolvedResumeLabel(_) => {
                 panic!("Unresolved label {:?} at {:?}", instruction, pos)
             }
             Instruction::Label(_) => (), // no-op
@@ -324,6 +553,22 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
                 let addr = self.return_stack.pop().unwrap();
                 *i = addr - 1;
             }
+            Instruction::PushGoSubRet(addr) => {
+                self.go_sub_stack.push(*addr);
+            }
+            Instruction::PopGoSubRet => match self.go_sub_stack.pop() {
+                Some(addr) => {
+                    *i = addr - 1;
+                }
+                None => {
+                    return Err(InterpreterError::new_with_pos(RETURN_WITHOUT_GO_SUB, pos));
+                }
+            },
+            Instruction::DropGoSubRet => {
+                if self.go_sub_stack.pop().is_none() {
+                    return Err(InterpreterError::new_with_pos(RETURN_WITHOUT_GO_SUB, pos));
+                }
+            }
             Instruction::StoreAToResult => {
                 let v = self.get_a();
                 self.function_result = v;
@@ -332,6 +577,47 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
                 let v = self.function_result.clone();
                 self.set_a(v);
             }
+            Instruction::DimFixedLengthString(n, len) => {
+                self.context_mut()
+                    .dim_fixed_length_string(n.clone(), *len)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::DimArray(n, element_type) => {
+                self.dim_array(n, false, element_type)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::DimDynamicArray(n, element_type) => {
+                self.dim_array(n, true, element_type)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::ReDimArray(n, element_type) => {
+                self.redim_array(n, element_type)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::CopyAMemberToA(member) => {
+                self.copy_a_member_to_a(member)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::StoreMember(n, members) => {
+                self.store_member(n, members)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::StoreArrayElementMember(n, members) => {
+                self.store_array_element_member(n, members)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::CopyArrayElementToA(n) => {
+                self.copy_array_element_to_a(n)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::StoreArrayElement(n) => {
+                self.store_array_element(n)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
+            Instruction::EraseArray(n) => {
+                self.erase_array(n)
+                    .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+            }
             Instruction::Throw(msg) => {
                 self.throw(msg, pos)?;
             }
@@ -339,22 +625,31 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
         Ok(())
     }
 
-    pub fn interpret(&mut self, instructions: Vec<InstructionNode>) -> Result<()> {
+    pub fn interpret(&mut self, instructions: &[InstructionNode]) -> Result<()> {
         let mut i: usize = 0;
         let mut error_handler: Option<usize> = None;
         let mut exit: bool = false;
         while i < instructions.len() && !exit {
             let instruction = instructions[i].as_ref();
             let pos = instructions[i].location();
+            if let Instruction::StatementStart(_) = instruction {
+                if self.debug_session.is_some() && !self.debug_statement(pos) {
+                    break;
+                }
+            }
             match self.interpret_one(&mut i, instruction, pos, &mut error_handler, &mut exit) {
                 Ok(_) => {
                     i += 1;
                 }
                 Err(e) => match error_handler {
-                    Some(error_idx) => {
+                    // an error within the error handler can't be handled
+                    Some(error_idx) if self.trapped_error.is_none() => {
+                        self.trapped_error = Some(TrappedError::new(&e, instructions, i));
+                        self.unwind_failed_statement();
                         i = error_idx;
                     }
-                    None => {
+                    _ => {
+                        self.debug_error(&e, pos);
                         return Err(e.with_existing_stacktrace(&self.stacktrace));
                     }
                 },
@@ -366,6 +661,58 @@ impl<TStdlib: Stdlib> Interpreter<TStdlib> {
     fn throw(&mut self, msg: &String, pos: Location) -> Result<()> {
         Err(InterpreterError::new_with_pos(msg, pos))
     }
+
+    fn raise_error(&mut self, pos: Location) -> Result<()> {
+        let v = cast(self.get_a(), TypeQualifier::PercentInteger)
+            .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+        match v {
+            Variant::VInteger(code @ 1..=255) => Err(InterpreterError::new_with_code(code, pos)),
+            _ => Err(InterpreterError::new_with_pos("Illegal function call", pos)),
+        }
+    }
+
+    fn cast_a_to_on_selector(&mut self, pos: Location) -> Result<()> {
+        let v = cast(self.get_a(), TypeQualifier::PercentInteger)
+            .map_err(|e| InterpreterError::new_with_pos(e, pos))?;
+        match v {
+            Variant::VInteger(0..=255) => {
+                self.set_a(v);
+                Ok(())
+            }
+            _ => Err(InterpreterError::new_with_pos("Illegal function call", pos)),
+        }
+    }
+
+    /// Discards the registers and the arguments that the failed statement left behind,
+    /// so that `RESUME` and `RESUME NEXT` start from a clean state.
+    fn unwind_failed_statement(&mut self) {
+        self.register_stack.truncate(self.statement_registers_depth);
+        while let Some(Context::Args(_)) = &self.context {
+            self.pop_args();
+        }
+    }
+
+    fn take_trapped_error(&mut self, pos: Location) -> Result<TrappedError> {
+        self.trapped_error
+            .take()
+            .ok_or_else(|| InterpreterError::new_with_pos(RESUME_WITHOUT_ERROR, pos))
+    }
+
+    /// The code of the error being handled, or zero.
+    pub fn err(&self) -> i32 {
+        self.trapped_error
+            .as_ref()
+            .map(|e| e.code)
+            .unwrap_or_default()
+    }
+
+    /// The line number of the error being handled, or zero.
+    pub fn erl(&self) -> u32 {
+        self.trapped_error
+            .as_ref()
+            .map(|e| e.line_number)
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -381,7 +728,7 @@ mod tests {
     #[test]
     fn test_interpret_print_hello_world_two_args() {
         let input = r#"PRINT "Hello", "world!""#;
-        assert_eq!(interpret(input).stdlib.output, vec!["Hello world!"]);
+        assert_eq!(interpret(input).stdlib.output, vec!["Hello         world!"]);
     }
 
     #[test]
@@ -392,7 +739,7 @@ mod tests {
             Test = N + 1
         END FUNCTION
         "#;
-        assert_eq!(interpret(input).stdlib.output, vec!["Hello 2"]);
+        assert_eq!(interpret(input).stdlib.output, vec!["Hello          2 "]);
     }
 
     #[test]
@@ -435,17 +782,18 @@ mod tests {
             output,
             vec![
                 "Enter the number of fibonacci to calculate",
-                "Fibonacci of 0 is 0",
-                "Fibonacci of 1 is 1",
-                "Fibonacci of 2 is 1",
-                "Fibonacci of 3 is 2",
-                "Fibonacci of 4 is 3",
-                "Fibonacci of 5 is 5",
-                "Fibonacci of 6 is 8",
-                "Fibonacci of 7 is 13",
-                "Fibonacci of 8 is 21",
-                "Fibonacci of 9 is 34",
-                "Fibonacci of 10 is 55"
+                "? ",
+                "Fibonacci of   0            is             0 ",
+                "Fibonacci of   1            is             1 ",
+                "Fibonacci of   2            is             1 ",
+                "Fibonacci of   3            is             2 ",
+                "Fibonacci of   4            is             3 ",
+                "Fibonacci of   5            is             5 ",
+                "Fibonacci of   6            is             8 ",
+                "Fibonacci of   7            is             13 ",
+                "Fibonacci of   8            is             21 ",
+                "Fibonacci of   9            is             34 ",
+                "Fibonacci of   10           is             55 "
             ]
         );
     }
@@ -463,4 +811,64 @@ mod tests {
         stdlib.add_next_input("");
         interpret_file("INPUT.BAS", stdlib).unwrap();
     }
+
+    #[test]
+    fn test_interpreter_fixture_mid() {
+        let stdlib = MockStdlib::new();
+        let interpreter = interpret_file("MID.BAS", stdlib).unwrap();
+        assert_eq!(interpreter.stdlib.output, vec!["Jelly"]);
+    }
+
+    #[test]
+    fn test_interpreter_rest_list() {
+        let mut stdlib = MockStdlib::new();
+        stdlib
+            .env
+            .insert("REQUEST_METHOD".to_string(), "GET".to_string());
+        stdlib.files.insert(
+            "TODO.DAT".to_string(),
+            b"buy milk\r\nwalk {the} [dog]?\r\n".to_vec(),
+        );
+        // the GW-BASIC program of the REST example, relative to the fixtures
+        let interpreter = interpret_file("../../rest/LIST.BAS", stdlib).unwrap();
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![
+                "Content-Type: application/json",
+                "X-Powered-By: GW-BASIC",
+                "",
+                "[",
+                "\"buy milk\"",
+                ",",
+                "\"walk {the} [dog]?\"",
+                "]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interpreter_rest_qb_todo_read() {
+        let mut stdlib = MockStdlib::new();
+        stdlib
+            .env
+            .insert("REQUEST_METHOD".to_string(), "GET".to_string());
+        stdlib
+            .env
+            .insert("QUERY_STRING".to_string(), "action=read&id=2".to_string());
+        stdlib
+            .files
+            .insert("TODO.DAT".to_string(), b"buy milk\r\nwalk dog\r\n".to_vec());
+        // the QBasic program of the REST example, which calls GetAction$ without parenthesis
+        let interpreter = interpret_file("../../rest-qb/TODO.BAS", stdlib).unwrap();
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![
+                "Status: 200 OK",
+                "Content-Type: text/plain",
+                "X-Powered-By: QBASIC",
+                "",
+                "{\"item\": \"walk dog\"}"
+            ]
+        );
+    }
 }
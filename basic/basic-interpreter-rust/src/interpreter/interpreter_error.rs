@@ -7,17 +7,71 @@ pub type Stacktrace = Vec<Location>;
 #[derive(Debug, PartialEq)]
 pub struct InterpreterError {
     message: String,
+    code: i32,
     stacktrace: Stacktrace,
 }
 
-// TODO add runtime errors like Overflow with the correct error code
+/// The QBasic runtime error codes and their messages.
+const ERROR_MESSAGES: [(i32, &str); 46] = [
+    (1, "NEXT without FOR"),
+    (2, "Syntax error"),
+    (3, "RETURN without GOSUB"),
+    (4, "Out of DATA"),
+    (5, "Illegal function call"),
+    (6, "Overflow"),
+    (7, "Out of memory"),
+    (8, "Label not defined"),
+    (9, "Subscript out of range"),
+    (10, "Duplicate definition"),
+    (11, "Division by zero"),
+    (12, "Illegal in direct mode"),
+    (13, "Type mismatch"),
+    (14, "Out of string space"),
+    (16, "String formula too complex"),
+    (17, "Cannot continue"),
+    (18, "Function not defined"),
+    (19, "No RESUME"),
+    (20, "RESUME without error"),
+    (24, "Device timeout"),
+    (25, "Device fault"),
+    (26, "FOR without NEXT"),
+    (27, "Out of paper"),
+    (29, "WHILE without WEND"),
+    (30, "WEND without WHILE"),
+    (33, "Duplicate label"),
+    (35, "Subprogram not defined"),
+    (37, "Argument-count mismatch"),
+    (38, "Array not defined"),
+    (40, "Variable required"),
+    (50, "FIELD overflow"),
+    (51, "Internal error"),
+    (52, "Bad file name or number"),
+    (53, "File not found"),
+    (54, "Bad file mode"),
+    (55, "File already open"),
+    (57, "Device I/O error"),
+    (58, "File already exists"),
+    (59, "Bad record length"),
+    (61, "Disk full"),
+    (62, "Input past end of file"),
+    (63, "Bad record number"),
+    (64, "Bad file name"),
+    (67, "Too many files"),
+    (70, "Permission denied"),
+    (75, "Path/File access error"),
+];
+
+/// The code of errors whose message is not a QBasic one.
+const ILLEGAL_FUNCTION_CALL: i32 = 5;
 
 pub type Result<T> = std::result::Result<T, InterpreterError>;
 
 impl InterpreterError {
     pub fn new<S: AsRef<str>>(msg: S, stacktrace: Stacktrace) -> InterpreterError {
+        let code = error_code(msg.as_ref());
         InterpreterError {
             message: msg.as_ref().to_string(),
+            code,
             stacktrace,
         }
     }
@@ -26,6 +80,34 @@ impl InterpreterError {
         InterpreterError::new(msg, vec![pos])
     }
 
+    /// Creates an error from a QBasic error code, e.g. for `ERROR 53`.
+    pub fn new_with_code(code: i32, pos: Location) -> InterpreterError {
+        let message = ERROR_MESSAGES
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, m)| *m)
+            .unwrap_or("Unprintable error");
+        InterpreterError {
+            message: message.to_string(),
+            code,
+            stacktrace: vec![pos],
+        }
+    }
+
+    /// The QBasic error code, as returned by `ERR`.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The location where the error occurred, which is the first entry of the stacktrace.
+    pub fn location(&self) -> Option<Location> {
+        self.stacktrace.first().copied()
+    }
+
     pub fn with_existing_stacktrace(self, stacktrace: &Stacktrace) -> InterpreterError {
         let mut new_vec = vec![];
         for x in self.stacktrace {
@@ -34,10 +116,29 @@ impl InterpreterError {
         for x in stacktrace.iter() {
             new_vec.push(*x);
         }
-        InterpreterError::new(self.message, new_vec)
+        InterpreterError {
+            message: self.message,
+            code: self.code,
+            stacktrace: new_vec,
+        }
     }
 }
 
+/// Finds the QBasic error code of the given message.
+/// "Array already dimensioned" is reported by QBasic as "Duplicate definition".
+fn error_code(msg: &str) -> i32 {
+    let msg = if msg == "Array already dimensioned" {
+        "Duplicate definition"
+    } else {
+        msg
+    };
+    ERROR_MESSAGES
+        .iter()
+        .find(|(_, m)| *m == msg)
+        .map(|(c, _)| *c)
+        .unwrap_or(ILLEGAL_FUNCTION_CALL)
+}
+
 pub fn err<T, S: AsRef<str>>(msg: S, pos: Location) -> Result<T> {
     Err(InterpreterError::new_with_pos(msg, pos))
 }
@@ -45,8 +146,34 @@ pub fn err<T, S: AsRef<str>>(msg: S, pos: Location) -> Result<T> {
 #[cfg(test)]
 mod tests {
     use super::super::test_utils::*;
+    use super::super::{dim, file, interpreter};
+    use super::{error_code, ILLEGAL_FUNCTION_CALL};
+    use crate::assert_err;
     use crate::assert_linter_err;
     use crate::linter::LinterError;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn raised_messages_have_error_codes() {
+        let messages = vec![
+            file::BAD_FILE_NAME_OR_NUMBER.to_string(),
+            file::BAD_FILE_MODE.to_string(),
+            file::BAD_RECORD_LENGTH.to_string(),
+            file::BAD_RECORD_NUMBER.to_string(),
+            dim::SUBSCRIPT_OUT_OF_RANGE.to_string(),
+            interpreter::RETURN_WITHOUT_GO_SUB.to_string(),
+            interpreter::RESUME_WITHOUT_ERROR.to_string(),
+            crate::variant::SUBSCRIPT_OUT_OF_RANGE.to_string(),
+            file::io_error_message(ErrorKind::UnexpectedEof.into()),
+            file::io_error_message(ErrorKind::NotFound.into()),
+            file::io_error_message(ErrorKind::AlreadyExists.into()),
+            file::io_error_message(ErrorKind::PermissionDenied.into()),
+            file::io_error_message(ErrorKind::Other.into()),
+        ];
+        for msg in messages {
+            assert_ne!(error_code(&msg), ILLEGAL_FUNCTION_CALL, "{}", msg);
+        }
+    }
 
     #[test]
     fn on_error_go_to_label() {
@@ -69,4 +196,153 @@ mod tests {
         "#;
         assert_linter_err!(input, LinterError::LabelNotDefined, 2, 9);
     }
+
+    #[test]
+    fn resume_next() {
+        let input = r#"
+        ON ERROR GOTO ErrTrap
+        X = 1 / 0
+        PRINT "After"
+        PRINT ERR
+        SYSTEM
+        ErrTrap:
+            PRINT ERR
+            RESUME NEXT
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec![" 11 ", "After", " 0 "]);
+    }
+
+    #[test]
+    fn resume_retries_the_statement() {
+        let input = r#"
+        ON ERROR GOTO Fix
+        D = 0
+        PRINT 10 / D
+        SYSTEM
+        Fix:
+            D = 2
+            RESUME
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec![" 5 "]);
+    }
+
+    #[test]
+    fn resume_next_within_loop() {
+        let input = r#"
+        ON ERROR GOTO ErrTrap
+        FOR I = 1 TO 3
+            PRINT 6 / (I - 2)
+        NEXT
+        SYSTEM
+        ErrTrap:
+            PRINT "oops"
+            RESUME NEXT
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["-6 ", "oops", " 6 "]);
+    }
+
+    #[test]
+    fn resume_next_within_sub() {
+        let input = r#"
+        DECLARE SUB Hello
+        ON ERROR GOTO ErrTrap
+        Hello
+        PRINT "Bye"
+        SYSTEM
+        ErrTrap:
+            PRINT "oops"
+            RESUME NEXT
+        SUB Hello
+            ERROR 5
+            PRINT "Hello"
+        END SUB
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["oops", "Hello", "Bye"]);
+    }
+
+    #[test]
+    fn resume_line_number() {
+        let input = r#"
+        10 ON ERROR GOTO 100
+        20 ERROR 53
+        30 PRINT "skipped"
+        40 PRINT "resumed"
+        50 SYSTEM
+        100 IF ERR = 53 THEN RESUME 40
+        110 PRINT "unexpected"
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["resumed"]);
+    }
+
+    #[test]
+    fn err_and_erl() {
+        let input = r#"
+        10 ON ERROR GOTO 100
+        20 PRINT "A"
+        30 X = 1 / 0
+        40 SYSTEM
+        100 PRINT ERR, ERL
+        110 RESUME NEXT
+        "#;
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec!["A", " 11            30 "]
+        );
+    }
+
+    #[test]
+    fn err_and_erl_without_error() {
+        assert_eq!(
+            interpret("PRINT ERR, ERL").stdlib.output,
+            vec![" 0             0 "]
+        );
+    }
+
+    #[test]
+    fn err_with_arguments() {
+        assert_linter_err!("PRINT ERR(1)", LinterError::ArgumentCountMismatch, 1, 7);
+    }
+
+    #[test]
+    fn on_error_go_to_zero() {
+        let input = r#"
+        ON ERROR GOTO ErrTrap
+        ON ERROR GOTO 0
+        ERROR 6
+        SYSTEM
+        ErrTrap:
+            PRINT "unexpected"
+        "#;
+        assert_err!(input, "Overflow", 4, 9);
+    }
+
+    #[test]
+    fn error_within_error_handler() {
+        let input = r#"
+        ON ERROR GOTO ErrTrap
+        ERROR 11
+        SYSTEM
+        ErrTrap:
+            ERROR 13
+        "#;
+        assert_err!(input, "Type mismatch", 6, 13);
+    }
+
+    #[test]
+    fn resume_without_error() {
+        assert_err!("RESUME", "RESUME without error", 1, 1);
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(interpret_err("ERROR 53").code(), 53);
+        assert_eq!(interpret_err("X = 1 / 0").code(), 11);
+        assert_eq!(interpret_err("ERROR 200").code(), 200);
+        assert_err!("ERROR 0", "Illegal function call", 1, 1);
+    }
+
+    #[test]
+    fn error_with_string_is_type_mismatch() {
+        assert_linter_err!("ERROR \"oops\"", LinterError::TypeMismatch, 1, 7);
+    }
 }
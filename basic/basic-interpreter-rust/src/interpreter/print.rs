@@ -0,0 +1,340 @@
+use crate::casting::cast;
+use crate::common::*;
+use crate::formatter::format_using;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::TypeQualifier;
+use crate::variant::Variant;
+
+type Result<T> = std::result::Result<T, String>;
+
+/// The width of a print zone, which the `,` separator of `PRINT` moves to.
+const PRINT_ZONE_WIDTH: usize = 14;
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Implements `PRINT`, on the screen or in a file.
+    pub fn print(&mut self) -> Result<()> {
+        let file_number = self.pop_file_number()?;
+        let column = self.column(file_number)?;
+        let text = self.print_text(column)?;
+        self.write_text(file_number, &text)
+    }
+
+    /// Implements `LPRINT`, which prints on the printer.
+    pub fn lprint(&mut self) -> Result<()> {
+        let text = self.print_text(self.printer_column)?;
+        self.printer_column = next_column(self.printer_column, &text);
+        self.stdlib.lprint(&text);
+        Ok(())
+    }
+
+    /// Lays out the text of `PRINT`, starting at the given column.
+    ///
+    /// Numbers are printed with a leading space (or a minus sign) and a trailing space.
+    /// With `USING`, the values are formatted with the format string instead.
+    /// The text ends with a new line, unless the last argument is `;` or `,`.
+    fn print_text(&mut self, column: usize) -> Result<String> {
+        let mut buffer = PrintBuffer::new(column);
+        let mut format: Option<String> = None;
+        let mut values: Vec<Variant> = vec![];
+        let mut new_line = true;
+        while let Some(kind) = self.context_mut().demand_sub().try_pop_front_unnamed() {
+            let kind = match kind {
+                Variant::VInteger(i) => PrintArg::from(i),
+                _ => panic!("PRINT argument kind should be an integer"),
+            };
+            new_line = true;
+            match kind {
+                PrintArg::Using => match self.context_mut().demand_sub().pop_front_unnamed() {
+                    Variant::VString(s) => format = Some(s),
+                    _ => panic!("USING should have a string"),
+                },
+                PrintArg::Expression => {
+                    let v = self.context_mut().demand_sub().pop_front_unnamed();
+                    if format.is_some() {
+                        values.push(v);
+                    } else {
+                        buffer.push_str(&format_print_value(v));
+                    }
+                }
+                PrintArg::Comma => {
+                    if format.is_none() {
+                        let next_zone = (buffer.column / PRINT_ZONE_WIDTH + 1) * PRINT_ZONE_WIDTH;
+                        buffer.move_to(next_zone);
+                    }
+                    new_line = false;
+                }
+                PrintArg::Semicolon => {
+                    new_line = false;
+                }
+                PrintArg::Tab => {
+                    // TAB is one based, moving backwards starts a new line
+                    let column = self.pop_count()?.max(1) - 1;
+                    if buffer.column > column {
+                        buffer.push_str("\n");
+                    }
+                    buffer.move_to(column);
+                }
+                PrintArg::Spc => {
+                    let count = self.pop_count()?;
+                    buffer.push_str(&" ".repeat(count));
+                }
+            }
+        }
+        if let Some(format) = format {
+            buffer.push_str(&format_using(&format, &values)?);
+        }
+        if new_line {
+            buffer.push_str("\n");
+        }
+        Ok(buffer.text)
+    }
+
+    /// Pops the argument of `TAB` or `SPC`. Negative numbers count as zero.
+    fn pop_count(&mut self) -> Result<usize> {
+        let v = self.context_mut().demand_sub().pop_front_unnamed();
+        match cast(v, TypeQualifier::AmpersandLong)? {
+            Variant::VLong(l) => Ok(l.max(0) as usize),
+            _ => Err("Type mismatch".to_string()),
+        }
+    }
+}
+
+/// Formats a value of `PRINT`. Numbers get a leading space instead of a plus sign
+/// and a trailing space.
+fn format_print_value(v: Variant) -> String {
+    match v {
+        Variant::VString(s) => s,
+        _ => {
+            let s = v.to_string();
+            if s.starts_with('-') {
+                format!("{} ", s)
+            } else {
+                format!(" {} ", s)
+            }
+        }
+    }
+}
+
+/// Finds the column (zero based) after printing the given text at the given column.
+pub fn next_column(column: usize, text: &str) -> usize {
+    match text.rfind('\n') {
+        Some(idx) => text[idx + 1..].chars().count(),
+        None => column + text.chars().count(),
+    }
+}
+
+/// The text of a `PRINT` statement, which keeps track of the current column.
+struct PrintBuffer {
+    text: String,
+    column: usize,
+}
+
+impl PrintBuffer {
+    fn new(column: usize) -> Self {
+        Self {
+            text: String::new(),
+            column,
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.text.push_str(s);
+        self.column = next_column(self.column, s);
+    }
+
+    /// Pads with spaces up to the given column.
+    fn move_to(&mut self, column: usize) {
+        if column > self.column {
+            let padding = " ".repeat(column - self.column);
+            self.push_str(&padding);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::linter::LinterError;
+
+    #[test]
+    fn test_print_numbers() {
+        let program = r#"
+        PRINT 42
+        PRINT -42
+        PRINT 1.5; -0.5
+        PRINT "A"; 1; "B"
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" 42 ", "-42 ", " 1.5 -.5 ", "A 1 B"]
+        );
+    }
+
+    #[test]
+    fn test_print_numbers_with_exponent() {
+        let program = r#"
+        PRINT 1.5E-10
+        PRINT 1E+07
+        E = 5
+        PRINT 2 E
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" 1.5E-10 ", " 1E+07 ", " 2  5 "]
+        );
+    }
+
+    #[test]
+    fn test_print_zones() {
+        let program = r#"
+        PRINT "A", "B", "C"
+        PRINT 1, 2
+        PRINT "12345678901234", "X"
+        PRINT ,"X"
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![
+                "A             B             C",
+                " 1             2 ",
+                "12345678901234              X",
+                "              X"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_semicolon() {
+        let program = r#"
+        PRINT "A";
+        PRINT "B"
+        PRINT "C"; "D";
+        PRINT
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec!["AB", "CD"]);
+    }
+
+    #[test]
+    fn test_print_trailing_comma() {
+        let program = r#"
+        PRINT "A",
+        PRINT "B"
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec!["A             B"]);
+    }
+
+    #[test]
+    fn test_print_without_separator() {
+        let program = r#"
+        A = 3
+        PRINT "A="A
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec!["A= 3 "]);
+    }
+
+    #[test]
+    fn test_print_empty_line() {
+        let program = r#"
+        PRINT
+        PRINT "A"
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec!["", "A"]);
+    }
+
+    #[test]
+    fn test_tab_and_spc() {
+        let program = r#"
+        PRINT "A"; TAB(5); "B"
+        PRINT "A"; SPC(3); "B"
+        PRINT "ABCDEF"; TAB(3); "X"
+        PRINT TAB(0); "Y"
+        PRINT "A"; SPC(-1); "B"
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec!["A   B", "A   B", "ABCDEF", "  X", "Y", "AB"]
+        );
+    }
+
+    #[test]
+    fn test_tab_continues_column_of_previous_print() {
+        let program = r#"
+        PRINT "AB";
+        PRINT TAB(5); "C"
+        "#;
+        assert_eq!(interpret(program).stdlib.output, vec!["AB  C"]);
+    }
+
+    #[test]
+    fn test_print_to_file_uses_zones_and_number_spacing() {
+        let program = r#"
+        OPEN "out.txt" FOR OUTPUT AS #1
+        PRINT #1, "A", 1
+        PRINT #1, "B";
+        PRINT #1, .5
+        CLOSE #1
+        "#;
+        let interpreter = interpret(program);
+        assert_eq!(
+            interpreter.stdlib.files["out.txt"],
+            b"A              1 \r\nB .5 \r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_print_using() {
+        let program = r#"
+        PRINT USING "Total: ###,###.##"; 1234.5
+        PRINT USING "Item: \  \ $$##.##"; "Apples"; 3.5; "Pears"; 12
+        PRINT USING "(##)"; 1; 2;
+        PRINT USING "(##)"; 3, 4
+        PRINT USING "<##>"; 123
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![
+                "Total:   1,234.50",
+                "Item: Appl   $3.50Item: Pear  $12.00",
+                "( 1)( 2)( 3)( 4)",
+                "<%123>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_using_to_file() {
+        let program = r#"
+        OPEN "out.txt" FOR OUTPUT AS #1
+        PRINT #1, USING "**#.#"; 2.25
+        CLOSE #1
+        "#;
+        let interpreter = interpret(program);
+        assert_eq!(interpreter.stdlib.files["out.txt"], b"**2.2\r\n".to_vec());
+    }
+
+    #[test]
+    fn test_print_using_type_mismatch() {
+        assert_eq!(interpret_err("PRINT USING \"(##)\"; \"a\"").code(), 13);
+        assert_eq!(interpret_err("PRINT USING \"abc\"; 1").code(), 5);
+        assert_linter_err!("PRINT USING 1; 1", LinterError::TypeMismatch, 1, 13);
+    }
+
+    #[test]
+    fn test_lprint() {
+        let program = r#"
+        LPRINT "A"; 1,
+        LPRINT "B"
+        LPRINT USING "<#.#>"; 1.25
+        "#;
+        let interpreter = interpret(program);
+        assert_eq!(interpreter.stdlib.printer, "A 1           B\n<1.2>\n");
+        assert_eq!(interpreter.stdlib.output, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_tab_requires_number() {
+        assert_linter_err!("PRINT TAB(\"a\")", LinterError::TypeMismatch, 1, 11);
+    }
+}
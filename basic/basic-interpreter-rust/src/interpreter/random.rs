@@ -0,0 +1,149 @@
+use crate::casting::cast;
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::input::parse_input_value;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::TypeQualifier;
+use crate::variant::Variant;
+
+type Result<T> = std::result::Result<T, String>;
+
+/// The seed of the random number generator of QBasic, before `RANDOMIZE` changes it
+pub const INITIAL_RND_SEED: u32 = 0x50000;
+
+impl<S: Stdlib> Interpreter<S> {
+    /// Implements `RND`, with the linear congruential generator of QBasic,
+    /// so that a program gets the same numbers as in QBasic.
+    pub fn rnd(&mut self) -> Result<Variant> {
+        let n = match self.context_mut().demand_sub().try_pop_front_unnamed() {
+            Some(v) => match cast(v, TypeQualifier::BangSingle)? {
+                Variant::VSingle(f) => f,
+                _ => panic!("RND argument should be a single"),
+            },
+            None => 1.0,
+        };
+        if n != 0.0 {
+            if n < 0.0 {
+                // a negative number always starts the same sequence
+                let bits = n.to_bits();
+                self.rnd_seed = (bits & 0xFF_FFFF) + (bits >> 24);
+            }
+            self.rnd_seed = self
+                .rnd_seed
+                .wrapping_mul(16_598_013)
+                .wrapping_add(12_820_163)
+                & 0xFF_FFFF;
+        }
+        Ok(Variant::VSingle(self.rnd_seed as f32 / 16_777_216.0))
+    }
+
+    /// Implements `RANDOMIZE`, which seeds the random number generator.
+    /// Without a seed, it asks for one.
+    pub fn randomize(&mut self) -> Result<()> {
+        let seed = match self.context_mut().demand_sub().try_pop_front_unnamed() {
+            Some(v) => match cast(v, TypeQualifier::HashDouble)? {
+                Variant::VDouble(d) => d,
+                _ => panic!("RANDOMIZE argument should be a double"),
+            },
+            None => self.ask_random_seed()?,
+        };
+        // like QBasic, only the high bits of the seed matter
+        let high = (seed.to_bits() >> 32) as u32;
+        let m = high ^ (high >> 16);
+        self.rnd_seed = ((m & 0xFFFF) << 8) | (self.rnd_seed & 0xFF);
+        Ok(())
+    }
+
+    fn ask_random_seed(&mut self) -> Result<f64> {
+        loop {
+            self.write_text(0, "Random-number seed (-32768 to 32767)? ")?;
+            let line = self.read_keyboard_line()?;
+            match parse_input_value(line.trim().to_string(), TypeQualifier::PercentInteger) {
+                Some(Variant::VInteger(i)) => return Ok(f64::from(i)),
+                _ => self.write_text(0, "Redo from start\n")?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::linter::LinterError;
+
+    #[test]
+    fn test_rnd_without_randomize_matches_qbasic() {
+        let program = r#"
+        PRINT RND; RND(1); RND
+        PRINT RND(0)
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" .7055475  .533424  .5795186 ", " .5795186 "]
+        );
+    }
+
+    #[test]
+    fn test_rnd_negative_argument_reseeds() {
+        let program = r#"
+        PRINT RND(-1)
+        A = RND
+        PRINT RND(-1)
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" .224007 ", " .224007 "]
+        );
+    }
+
+    #[test]
+    fn test_randomize() {
+        let program = r#"
+        RANDOMIZE 1
+        PRINT RND; RND
+        "#;
+        assert_eq!(
+            interpret(program).stdlib.output,
+            vec![" .7648737  .1054455 "]
+        );
+    }
+
+    #[test]
+    fn test_randomize_timer_uses_the_clock() {
+        let program = r#"
+        RANDOMIZE TIMER
+        PRINT RND
+        "#;
+        // the mock clock is at 13:45:30.25
+        assert_eq!(interpret(program).stdlib.output, vec![" .9560358 "]);
+    }
+
+    #[test]
+    fn test_randomize_asks_for_seed() {
+        let program = r#"
+        RANDOMIZE
+        PRINT RND
+        "#;
+        let mut stdlib = MockStdlib::new();
+        stdlib.add_next_input("x");
+        stdlib.add_next_input("1");
+        let interpreter = interpret_with_stdlib(program, stdlib);
+        assert_eq!(
+            interpreter.stdlib.output,
+            vec![
+                "Random-number seed (-32768 to 32767)? ",
+                "Redo from start",
+                "Random-number seed (-32768 to 32767)? ",
+                " .7648737 "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rnd_linter_errors() {
+        assert_linter_err!("PRINT RND(\"a\")", LinterError::TypeMismatch, 1, 11);
+        assert_linter_err!("PRINT RND(1, 2)", LinterError::ArgumentCountMismatch, 1, 7);
+        assert_linter_err!("PRINT RND!", LinterError::SyntaxError, 1, 7);
+        assert_linter_err!("RANDOMIZE \"a\"", LinterError::TypeMismatch, 1, 11);
+    }
+}
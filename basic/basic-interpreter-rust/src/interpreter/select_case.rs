@@ -102,6 +102,19 @@ mod tests {
         assert_eq!(interpret(input).stdlib.output, Vec::<String>::new());
     }
 
+    #[test]
+    fn test_select_is_not_equal() {
+        let input = r#"
+        SELECT CASE 4
+            CASE IS <> 4
+                PRINT "not four"
+            CASE IS <> 5
+                PRINT "not five"
+        END SELECT
+        "#;
+        assert_eq!(interpret(input).stdlib.output, vec!["not five"]);
+    }
+
     #[test]
     fn test_select_range_within_range() {
         let input = r#"
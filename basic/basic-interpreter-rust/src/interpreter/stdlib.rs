@@ -1,8 +1,23 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::time::{Duration, SystemTime};
+
 /// The standard functions that QBasic offers
 pub trait Stdlib {
-    /// Implementation of PRINT x[, y, z]
+    /// Implementation of PRINT, prints the given text without ending the line.
+    /// The interpreter has already laid out the text (print zones, TAB, etc).
+    /// Mutable because of the test implementation
+    fn print(&mut self, text: &str);
+
+    /// Ends the current line, used by PRINT unless it ends with `;` or `,`.
     /// Mutable because of the test implementation
-    fn print(&mut self, args: Vec<String>);
+    fn println(&mut self);
+
+    /// Implementation of LPRINT, prints the given text on the printer.
+    /// The text is laid out like the text of PRINT, including the new lines.
+    /// Mutable because of the test implementation
+    fn lprint(&mut self, text: &str);
 
     /// Implementation of SYSTEM
     fn system(&self);
@@ -11,36 +26,125 @@ pub trait Stdlib {
     /// Mutable because of the test implementation
     fn input(&mut self) -> std::io::Result<String>;
 
+    /// Implementation of INPUT$, reads the given number of bytes of the standard input.
+    /// Mutable because of the test implementation
+    fn input_bytes(&mut self, count: usize) -> std::io::Result<Vec<u8>>;
+
+    /// Gets the current date and time (used by TIMER, DATE$, TIME$ and RANDOMIZE TIMER).
+    fn now(&self) -> SystemTime;
+
+    /// Gets the offset of the local time zone from UTC in seconds, e.g. 3600 for UTC+1.
+    /// The interpreter shows the current date and time in local time, like QBasic.
+    fn utc_offset(&self) -> i32;
+
+    /// Implementation of SLEEP, waits for the given number of seconds.
+    /// Zero means waiting until a key is pressed.
+    /// Mutable because of the test implementation
+    fn sleep(&mut self, seconds: u32);
+
     /// Gets an environment variable (used by built-in function ENVIRON$)
     fn get_env_var(&self, name: &String) -> String;
 
     /// Sets an environment variable (used by built-in sub ENVIRON)
     fn set_env_var(&mut self, name: String, value: String);
+
+    /// Reads the contents of a file (used by OPEN)
+    fn read_file(&self, file_name: &str) -> std::io::Result<Vec<u8>>;
+
+    /// Opens a file for writing under the given file number, creating it if it does not
+    /// exist (used by OPEN). Unless `append` is true, the existing contents are removed.
+    /// The file stays open until `close_file`. Returns the length of the file.
+    fn open_file(
+        &mut self,
+        file_number: i32,
+        file_name: &str,
+        append: bool,
+    ) -> std::io::Result<usize>;
+
+    /// Writes to a file opened with `open_file` (used by PRINT #, WRITE # and PUT).
+    /// The contents are written at the given offset, or after the previous ones.
+    /// The file grows if they end after its end. Writing may be buffered.
+    fn write_file(
+        &mut self,
+        file_number: i32,
+        offset: Option<usize>,
+        contents: &[u8],
+    ) -> std::io::Result<()>;
+
+    /// Closes a file opened with `open_file`, writing what has been buffered (used by CLOSE).
+    fn close_file(&mut self, file_number: i32) -> std::io::Result<()>;
+
+    /// Deletes a file (used by KILL).
+    fn kill_file(&mut self, file_name: &str) -> std::io::Result<()>;
+
+    /// Renames a file (used by NAME). Fails with `AlreadyExists` if the new name is taken.
+    fn rename_file(&mut self, old_name: &str, new_name: &str) -> std::io::Result<()>;
 }
 
-pub struct DefaultStdlib {}
+/// The standard library of the operating system.
+/// The files that are open for writing are flushed when they are closed or dropped.
+#[derive(Default)]
+pub struct DefaultStdlib {
+    files: HashMap<i32, BufWriter<File>>,
+}
 
 impl Stdlib for DefaultStdlib {
-    fn print(&mut self, args: Vec<String>) {
-        for a in args {
-            print!("{}", a)
-        }
+    fn print(&mut self, text: &str) {
+        print!("{}", text)
+    }
 
-        println!("")
+    fn println(&mut self) {
+        println!()
+    }
+
+    fn lprint(&mut self, text: &str) {
+        // there is no printer, the text goes to the standard output like PRINT
+        print!("{}", text)
     }
 
     fn system(&self) {
+        // a line that ended with `;` has not been flushed yet
+        std::io::stdout().flush().unwrap_or_default();
         std::process::exit(0)
     }
 
     fn input(&mut self) -> std::io::Result<String> {
+        // show a prompt that ended with `;`
+        std::io::stdout().flush()?;
         let mut line = String::new();
         match std::io::stdin().read_line(&mut line) {
+            Ok(0) => Err(std::io::ErrorKind::UnexpectedEof.into()),
             Ok(_) => Ok(line.trim_end().to_string()),
             Err(x) => Err(x),
         }
     }
 
+    fn input_bytes(&mut self, count: usize) -> std::io::Result<Vec<u8>> {
+        std::io::stdout().flush()?;
+        let mut buf = vec![0; count];
+        std::io::stdin().read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        chrono::Local::now().offset().local_minus_utc()
+    }
+
+    fn sleep(&mut self, seconds: u32) {
+        std::io::stdout().flush().unwrap_or_default();
+        if seconds == 0 {
+            // a terminal only sends the key when Enter is pressed
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).unwrap_or_default();
+        } else {
+            std::thread::sleep(Duration::from_secs(u64::from(seconds)));
+        }
+    }
+
     fn get_env_var(&self, name: &String) -> String {
         match std::env::var(name) {
             Ok(x) => x,
@@ -51,4 +155,60 @@ impl Stdlib for DefaultStdlib {
     fn set_env_var(&mut self, name: String, value: String) {
         std::env::set_var(name, value);
     }
+
+    fn read_file(&self, file_name: &str) -> std::io::Result<Vec<u8>> {
+        std::fs::read(file_name)
+    }
+
+    fn open_file(
+        &mut self,
+        file_number: i32,
+        file_name: &str,
+        append: bool,
+    ) -> std::io::Result<usize> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(!append)
+            .open(file_name)?;
+        let len = file.seek(SeekFrom::End(0))?;
+        self.files.insert(file_number, BufWriter::new(file));
+        Ok(len as usize)
+    }
+
+    fn write_file(
+        &mut self,
+        file_number: i32,
+        offset: Option<usize>,
+        contents: &[u8],
+    ) -> std::io::Result<()> {
+        let file = self
+            .files
+            .get_mut(&file_number)
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+        if let Some(offset) = offset {
+            file.seek(SeekFrom::Start(offset as u64))?;
+        }
+        file.write_all(contents)
+    }
+
+    fn close_file(&mut self, file_number: i32) -> std::io::Result<()> {
+        match self.files.remove(&file_number) {
+            Some(mut file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn kill_file(&mut self, file_name: &str) -> std::io::Result<()> {
+        std::fs::remove_file(file_name)
+    }
+
+    fn rename_file(&mut self, old_name: &str, new_name: &str) -> std::io::Result<()> {
+        // the file system would replace an existing file
+        std::fs::metadata(old_name)?;
+        if std::path::Path::new(new_name).exists() {
+            return Err(std::io::ErrorKind::AlreadyExists.into());
+        }
+        std::fs::rename(old_name, new_name)
+    }
 }
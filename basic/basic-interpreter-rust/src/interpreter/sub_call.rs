@@ -107,7 +107,7 @@ mod tests {
         END SUB
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["1"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 1 "]);
     }
 
     #[test]
@@ -122,7 +122,7 @@ mod tests {
         END SUB
         "#;
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["42"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
     }
 
     #[test]
@@ -137,7 +137,7 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["1"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 1 "]);
     }
 
     #[test]
@@ -217,7 +217,7 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["42", "43", "42"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 42 ", " 43 ", " 42 "]);
     }
 
     #[test]
@@ -232,7 +232,7 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["3", "4"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 3 ", " 4 "]);
     }
 
     #[test]
@@ -250,7 +250,7 @@ mod tests {
         let mut stdlib = MockStdlib::new();
         stdlib.add_next_input("42");
         let interpreter = interpret_with_stdlib(program, stdlib);
-        assert_eq!(interpreter.stdlib.output, vec!["42", "43"]);
+        assert_eq!(interpreter.stdlib.output, vec!["? ", " 42 ", " 43 "]);
     }
 
     #[test]
@@ -269,7 +269,7 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["42"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
     }
 
     #[test]
@@ -288,7 +288,7 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["42"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 42 "]);
     }
 
     #[test]
@@ -301,6 +301,6 @@ mod tests {
         END SUB
         ";
         let interpreter = interpret(program);
-        assert_eq!(interpreter.stdlib.output, vec!["1"]);
+        assert_eq!(interpreter.stdlib.output, vec![" 1 "]);
     }
 }
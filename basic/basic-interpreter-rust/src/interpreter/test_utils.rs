@@ -1,3 +1,4 @@
+use crate::host::HostRegistry;
 use crate::instruction_generator;
 use crate::interpreter::context_owner::ContextOwner;
 use crate::interpreter::{Interpreter, InterpreterError, Result, Stdlib};
@@ -7,6 +8,8 @@ use crate::variant::Variant;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fs::File;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
 
 pub fn interpret<T>(input: T) -> Interpreter<MockStdlib>
 where
@@ -21,7 +24,7 @@ where
     // }
     let mut interpreter = Interpreter::new(MockStdlib::new());
     interpreter
-        .interpret(instructions)
+        .interpret(&instructions)
         .map(|_| interpreter)
         .unwrap()
 }
@@ -37,11 +40,38 @@ where
     let instructions = instruction_generator::generate_instructions(linted_program);
     let mut interpreter = Interpreter::new(stdlib);
     interpreter
-        .interpret(instructions)
+        .interpret(&instructions)
         .map(|_| interpreter)
         .unwrap()
 }
 
+pub fn interpret_with_host<T>(input: T, host: Rc<HostRegistry>) -> Interpreter<MockStdlib>
+where
+    T: AsRef<[u8]>,
+{
+    let mut parser = Parser::from(input);
+    let program = parser.parse().unwrap();
+    let linted_program = linter::lint_with_host(program, &host).unwrap();
+    let instructions = instruction_generator::generate_instructions(linted_program);
+    let mut interpreter = Interpreter::with_host(MockStdlib::new(), host);
+    interpreter
+        .interpret(&instructions)
+        .map(|_| interpreter)
+        .unwrap()
+}
+
+pub fn lint_err_with_host<T>(input: T, host: &HostRegistry) -> linter::LinterError
+where
+    T: AsRef<[u8]>,
+{
+    let mut parser = Parser::from(input);
+    let program = parser.parse().unwrap();
+    linter::lint_with_host(program, host)
+        .unwrap_err()
+        .consume()
+        .0
+}
+
 pub fn linter_err<T>(input: T) -> linter::Error
 where
     T: AsRef<[u8]>,
@@ -60,7 +90,20 @@ where
     let linted_program = linter::lint(program).unwrap();
     let instructions = instruction_generator::generate_instructions(linted_program);
     let mut interpreter = Interpreter::new(MockStdlib::new());
-    interpreter.interpret(instructions).unwrap_err()
+    interpreter.interpret(&instructions).unwrap_err()
+}
+
+pub fn interpret_err_with_stdlib<T, TStdlib>(input: T, stdlib: TStdlib) -> InterpreterError
+where
+    T: AsRef<[u8]>,
+    TStdlib: Stdlib,
+{
+    let mut parser = Parser::from(input);
+    let program = parser.parse().unwrap();
+    let linted_program = linter::lint(program).unwrap();
+    let instructions = instruction_generator::generate_instructions(linted_program);
+    let mut interpreter = Interpreter::new(stdlib);
+    interpreter.interpret(&instructions).unwrap_err()
 }
 
 pub fn interpret_file<S, TStdlib>(filename: S, stdlib: TStdlib) -> Result<Interpreter<TStdlib>>
@@ -74,14 +117,28 @@ where
     let linted_program = linter::lint(program).unwrap();
     let instructions = instruction_generator::generate_instructions(linted_program);
     let mut interpreter = Interpreter::new(stdlib);
-    interpreter.interpret(instructions).map(|_| interpreter)
+    interpreter.interpret(&instructions).map(|_| interpreter)
 }
 
 #[derive(Debug)]
 pub struct MockStdlib {
+    /// The input, which is read line by line by `INPUT` and character by character by `INPUT$`
     next_input: Vec<String>,
+    /// The printed lines. The last line is incomplete if it was printed with a trailing `;`.
     pub output: Vec<String>,
+    /// Whether the last line of the output is still being printed
+    is_line_open: bool,
+    /// The text printed with `LPRINT`
+    pub printer: String,
     pub env: HashMap<String, String>,
+    /// An in-memory filesystem
+    pub files: HashMap<String, Vec<u8>>,
+    /// The names of the files opened for writing, by file number
+    open_files: HashMap<i32, String>,
+    /// The clock, which only moves with `SLEEP`
+    pub now: SystemTime,
+    /// The offset of the local time zone from UTC in seconds
+    pub utc_offset: i32,
 }
 
 impl MockStdlib {
@@ -89,7 +146,14 @@ impl MockStdlib {
         MockStdlib {
             next_input: vec![],
             output: vec![],
+            is_line_open: false,
+            printer: String::new(),
             env: HashMap::new(),
+            files: HashMap::new(),
+            open_files: HashMap::new(),
+            // 2020-03-15 13:45:30.25 UTC
+            now: SystemTime::UNIX_EPOCH + Duration::from_millis(1_584_279_930_250),
+            utc_offset: 0,
         }
     }
 
@@ -99,20 +163,25 @@ impl MockStdlib {
 }
 
 impl Stdlib for MockStdlib {
-    fn print(&mut self, args: Vec<String>) {
-        let mut is_first = true;
-        let mut buf = String::new();
-        for arg in args {
-            if is_first {
-                is_first = false;
-            } else {
-                buf.push(' ');
-            }
-            buf.push_str(&arg);
+    fn print(&mut self, text: &str) {
+        print!("{}", text);
+        match self.output.last_mut() {
+            Some(last) if self.is_line_open => last.push_str(text),
+            _ => self.output.push(text.to_string()),
         }
+        self.is_line_open = true;
+    }
 
-        println!("{}", buf);
-        self.output.push(buf);
+    fn println(&mut self) {
+        println!();
+        if !self.is_line_open {
+            self.output.push(String::new());
+        }
+        self.is_line_open = false;
+    }
+
+    fn lprint(&mut self, text: &str) {
+        self.printer.push_str(text);
     }
 
     fn system(&self) {
@@ -120,9 +189,42 @@ impl Stdlib for MockStdlib {
     }
 
     fn input(&mut self) -> std::io::Result<String> {
+        if self.next_input.is_empty() {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        // the new line that the user typed ends the line on the screen
+        self.is_line_open = false;
         Ok(self.next_input.remove(0))
     }
 
+    fn input_bytes(&mut self, count: usize) -> std::io::Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![];
+        while result.len() < count {
+            if self.next_input.is_empty() {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            let next = &mut self.next_input[0];
+            let len = next.len().min(count - result.len());
+            result.extend(next.drain(..len).map(|ch| ch as u8));
+            if next.is_empty() {
+                self.next_input.remove(0);
+            }
+        }
+        Ok(result)
+    }
+
+    fn now(&self) -> SystemTime {
+        self.now
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn sleep(&mut self, seconds: u32) {
+        self.now += Duration::from_secs(u64::from(seconds));
+    }
+
     fn get_env_var(&self, name: &String) -> String {
         match self.env.get(name) {
             Some(x) => x.clone(),
@@ -133,6 +235,74 @@ impl Stdlib for MockStdlib {
     fn set_env_var(&mut self, name: String, value: String) {
         self.env.insert(name, value);
     }
+
+    fn read_file(&self, file_name: &str) -> std::io::Result<Vec<u8>> {
+        match self.files.get(file_name) {
+            Some(contents) => Ok(contents.clone()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                file_name.to_string(),
+            )),
+        }
+    }
+
+    fn open_file(
+        &mut self,
+        file_number: i32,
+        file_name: &str,
+        append: bool,
+    ) -> std::io::Result<usize> {
+        let file = self.files.entry(file_name.to_string()).or_default();
+        if !append {
+            file.clear();
+        }
+        self.open_files.insert(file_number, file_name.to_string());
+        Ok(file.len())
+    }
+
+    fn write_file(
+        &mut self,
+        file_number: i32,
+        offset: Option<usize>,
+        contents: &[u8],
+    ) -> std::io::Result<()> {
+        let files = &mut self.files;
+        let file = self
+            .open_files
+            .get(&file_number)
+            .and_then(|file_name| files.get_mut(file_name))
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+        let offset = offset.unwrap_or(file.len());
+        if file.len() < offset + contents.len() {
+            file.resize(offset + contents.len(), 0);
+        }
+        file[offset..offset + contents.len()].copy_from_slice(contents);
+        Ok(())
+    }
+
+    fn close_file(&mut self, file_number: i32) -> std::io::Result<()> {
+        self.open_files.remove(&file_number);
+        Ok(())
+    }
+
+    fn kill_file(&mut self, file_name: &str) -> std::io::Result<()> {
+        match self.files.remove(file_name) {
+            Some(_) => Ok(()),
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn rename_file(&mut self, old_name: &str, new_name: &str) -> std::io::Result<()> {
+        if !self.files.contains_key(old_name) {
+            Err(std::io::ErrorKind::NotFound.into())
+        } else if self.files.contains_key(new_name) {
+            Err(std::io::ErrorKind::AlreadyExists.into())
+        } else {
+            let contents = self.files.remove(old_name).unwrap();
+            self.files.insert(new_name.to_string(), contents);
+            Ok(())
+        }
+    }
 }
 
 impl<S: Stdlib> Interpreter<S> {
@@ -0,0 +1,295 @@
+use crate::interpreter::context_owner::ContextOwner;
+use crate::interpreter::{Interpreter, Stdlib};
+use crate::linter::{BareName, QualifiedName};
+use crate::variant::Variant;
+
+type Result<T> = std::result::Result<T, String>;
+
+impl<S: Stdlib> Interpreter<S> {
+    pub fn copy_a_member_to_a(&mut self, member: &BareName) -> Result<()> {
+        let v = match self.get_a() {
+            Variant::VUserDefined(u) => match u.get_member(member) {
+                Some(m) => m.clone(),
+                None => return Err("Element not defined".to_string()),
+            },
+            _ => return Err("Type mismatch".to_string()),
+        };
+        self.set_a(v);
+        Ok(())
+    }
+
+    pub fn store_member(&mut self, name: &QualifiedName, members: &[BareName]) -> Result<()> {
+        let v = self.get_a();
+        match self.context_mut().get_variable_mut(name) {
+            Some(Variant::VUserDefined(u)) => u.set_member(members, v),
+            _ => Err("Type mismatch".to_string()),
+        }
+    }
+
+    pub fn store_array_element_member(
+        &mut self,
+        name: &QualifiedName,
+        members: &[BareName],
+    ) -> Result<()> {
+        let v = self.get_a();
+        let indices = self.pop_args();
+        match self.context_mut().get_variable_mut(name) {
+            Some(Variant::VArray(a)) => match a.get_element_mut(&indices)? {
+                Variant::VUserDefined(u) => u.set_member(members, v),
+                _ => Err("Type mismatch".to_string()),
+            },
+            _ => Err("Subscript out of range".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::assert_linter_err;
+    use crate::linter::LinterError;
+
+    const CARD: &str = "
+    TYPE Card
+        Value AS INTEGER
+        Suit AS STRING * 9
+    END TYPE
+    ";
+
+    #[test]
+    fn test_assign_and_read_members() {
+        let input = format!(
+            "{}
+            DIM C AS Card
+            C.Value = 3
+            C.Suit = \"Hearts\"
+            PRINT C.Value, C.Suit
+            ",
+            CARD
+        );
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 3            Hearts   "]
+        );
+    }
+
+    #[test]
+    fn test_members_have_default_values() {
+        let input = format!(
+            "{}
+            DIM C AS Card
+            PRINT C.Value
+            PRINT LEN(C.Suit)
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec![" 0 ", " 9 "]);
+    }
+
+    #[test]
+    fn test_fixed_length_member_is_truncated() {
+        let input = format!(
+            "{}
+            DIM C AS Card
+            C.Suit = \"Diamonds and more\"
+            PRINT C.Suit
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec!["Diamonds "]);
+    }
+
+    #[test]
+    fn test_member_is_cast() {
+        let input = format!(
+            "{}
+            DIM C AS Card
+            C.Value = 2.6
+            PRINT C.Value
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec![" 3 "]);
+    }
+
+    #[test]
+    fn test_assignment_copies_record() {
+        let input = format!(
+            "{}
+            DIM A AS Card
+            DIM B AS Card
+            A.Value = 1
+            B = A
+            B.Value = 2
+            PRINT A.Value, B.Value
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec![" 1             2 "]);
+    }
+
+    #[test]
+    fn test_len_of_record() {
+        let input = format!(
+            "{}
+            DIM C AS Card
+            PRINT LEN(C)
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec![" 11 "]);
+    }
+
+    #[test]
+    fn test_nested_type() {
+        let input = "
+        TYPE Address
+            City AS STRING * 5
+        END TYPE
+        TYPE Person
+            Age AS LONG
+            Home AS Address
+        END TYPE
+        DIM P AS Person
+        P.Age = 42
+        P.Home.City = \"Paris\"
+        PRINT P.Age, P.Home.City, LEN(P)
+        ";
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 42           Paris          9 "]
+        );
+    }
+
+    #[test]
+    fn test_array_of_records() {
+        let input = format!(
+            "{}
+            DIM Deck(1 TO 3) AS Card
+            Deck(2).Value = 7
+            Deck(2).Suit = \"Spades\"
+            PRINT Deck(1).Value, Deck(2).Value, Deck(2).Suit, UBOUND(Deck)
+            ",
+            CARD
+        );
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 0             7            Spades         3 "]
+        );
+    }
+
+    #[test]
+    fn test_assign_record_to_array_element() {
+        let input = format!(
+            "{}
+            DIM Deck(2) AS Card
+            DIM C AS Card
+            C.Value = 4
+            C.Suit = \"Clubs\"
+            Deck(1) = C
+            C.Value = 5
+            Deck(1).Value = Deck(1).Value + 1
+            PRINT Deck(1).Value, Deck(1).Suit, C.Value
+            C = Deck(1)
+            PRINT C.Value
+            ",
+            CARD
+        );
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 5            Clubs          5 ", " 5 "]
+        );
+    }
+
+    #[test]
+    fn test_assign_number_to_array_element_of_records() {
+        let input = "
+        TYPE Card
+            Value AS INTEGER
+        END TYPE
+        DIM Deck(2) AS Card
+        Deck(1) = 3
+        ";
+        assert_linter_err!(input, LinterError::TypeMismatch, 6, 19);
+    }
+
+    #[test]
+    fn test_redim_and_erase_array_of_records() {
+        let input = format!(
+            "{}
+            REDIM Deck(1) AS Card
+            Deck(1).Value = 5
+            N = 4
+            REDIM Deck(N) AS Card
+            PRINT Deck(1).Value, UBOUND(Deck)
+            Deck(4).Value = 9
+            ERASE Deck
+            REDIM Deck(2) AS Card
+            PRINT UBOUND(Deck)
+            ",
+            CARD
+        );
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 0             4 ", " 2 "]
+        );
+    }
+
+    #[test]
+    fn test_pass_record_to_sub_by_ref() {
+        let input = format!(
+            "{}
+            DECLARE SUB Flip(C AS Card)
+            DIM X AS Card
+            X.Value = 1
+            Flip X
+            PRINT X.Value
+            SUB Flip(C AS Card)
+                C.Value = C.Value + 10
+            END SUB
+            ",
+            CARD
+        );
+        assert_eq!(interpret(input).stdlib.output, vec![" 11 "]);
+    }
+
+    #[test]
+    fn test_type_not_defined() {
+        assert_linter_err!("DIM C AS Card", LinterError::TypeNotDefined, 1, 10);
+    }
+
+    #[test]
+    fn test_element_not_defined() {
+        let input = "
+        TYPE Card
+            Value AS INTEGER
+        END TYPE
+        DIM C AS Card
+        C.Suit = 1
+        ";
+        assert_linter_err!(input, LinterError::ElementNotDefined, 6, 9);
+    }
+
+    #[test]
+    fn test_duplicate_element() {
+        let input = "
+        TYPE Card
+            Value AS INTEGER
+            Value AS LONG
+        END TYPE
+        ";
+        assert_linter_err!(input, LinterError::DuplicateDefinition, 4, 13);
+    }
+
+    #[test]
+    fn test_print_record_is_type_mismatch() {
+        let input = "
+        TYPE Card
+            Value AS INTEGER
+        END TYPE
+        DIM C AS Card
+        PRINT C
+        ";
+        assert_linter_err!(input, LinterError::TypeMismatch, 6, 15);
+    }
+}
@@ -11,6 +11,9 @@ mod tests {
             A = A + 1
         WEND
         ";
-        assert_eq!(interpret(input).stdlib.output, vec!["1", "2", "3", "4"]);
+        assert_eq!(
+            interpret(input).stdlib.output,
+            vec![" 1 ", " 2 ", " 3 ", " 4 "]
+        );
     }
 }
@@ -0,0 +1,405 @@
+//! A small JSON reader and writer, for the messages of the debug adapter and
+//! the language server. Both protocols send each message with a `Content-Length`
+//! header, see `read_message` and `write_message`.
+//!
+//! Numbers are kept as `f64`, like in JavaScript. The members of an object
+//! keep their order, so that the messages are written predictably.
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+
+type Result<T> = std::result::Result<T, String>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut chars = s.chars().peekable();
+        let value = parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            Some(ch) => Err(format!("Unexpected {}", ch)),
+            None => Ok(value),
+        }
+    }
+
+    /// Creates an object with the given members.
+    pub fn object(members: Vec<(&str, Json)>) -> Self {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    /// Gets the member of an object with the given name.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        self.as_f64()
+            .filter(|n| *n >= 0.0 && *n <= f64::from(u32::MAX) && n.fract() == 0.0)
+            .map(|n| n as u32)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Self {
+        Json::Number(f64::from(n))
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Self {
+        Json::Number(n)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl From<Vec<Json>> for Json {
+    fn from(items: Vec<Json>) -> Self {
+        Json::Array(items)
+    }
+}
+
+impl fmt::Display for Json {
+    /// Writes the value without white space.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => {
+                if n.is_finite() {
+                    write!(f, "{}", n)
+                } else {
+                    write!(f, "null")
+                }
+            }
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, k)?;
+                    write!(f, ":{}", v)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => write!(f, "{}", ch)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while let Some(' ') | Some('\t') | Some('\r') | Some('\n') = chars.peek() {
+        chars.next();
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Json> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('{') => parse_object(chars),
+        Some('[') => parse_array(chars),
+        Some('"') => parse_string(chars).map(Json::String),
+        Some('t') => parse_literal(chars, "true", Json::Bool(true)),
+        Some('f') => parse_literal(chars, "false", Json::Bool(false)),
+        Some('n') => parse_literal(chars, "null", Json::Null),
+        Some(ch) if *ch == '-' || ch.is_ascii_digit() => parse_number(chars),
+        Some(ch) => Err(format!("Unexpected {}", ch)),
+        None => Err("Unexpected end of input".to_string()),
+    }
+}
+
+fn parse_literal(chars: &mut Peekable<Chars>, literal: &str, value: Json) -> Result<Json> {
+    for expected in literal.chars() {
+        if chars.next() != Some(expected) {
+            return Err(format!("Expected {}", literal));
+        }
+    }
+    Ok(value)
+}
+
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<Json> {
+    let mut s = String::new();
+    while let Some(ch) = chars.peek() {
+        if ch.is_ascii_digit() || "+-.eE".contains(*ch) {
+            s.push(*ch);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    s.parse::<f64>()
+        .map(Json::Number)
+        .map_err(|_| format!("Invalid number {}", s))
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String> {
+    chars.next(); // the opening quote
+    let mut s = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(s),
+            Some('\\') => match chars.next() {
+                Some('"') => s.push('"'),
+                Some('\\') => s.push('\\'),
+                Some('/') => s.push('/'),
+                Some('b') => s.push('\u{8}'),
+                Some('f') => s.push('\u{c}'),
+                Some('n') => s.push('\n'),
+                Some('r') => s.push('\r'),
+                Some('t') => s.push('\t'),
+                Some('u') => {
+                    let mut code = parse_hex(chars)?;
+                    if (0xD800..0xDC00).contains(&code) {
+                        // the high half of a surrogate pair, the low half follows
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err("Invalid surrogate pair".to_string());
+                        }
+                        let low = parse_hex(chars)?;
+                        code =
+                            0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                    }
+                    s.push(std::char::from_u32(code).unwrap_or('\u{FFFD}'));
+                }
+                _ => return Err("Invalid escape sequence".to_string()),
+            },
+            Some(ch) => s.push(ch),
+            None => return Err("Unterminated string".to_string()),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Result<u32> {
+    let mut code = 0;
+    for _ in 0..4 {
+        let digit = chars
+            .next()
+            .and_then(|ch| ch.to_digit(16))
+            .ok_or_else(|| "Invalid escape sequence".to_string())?;
+        code = code * 16 + digit;
+    }
+    Ok(code)
+}
+
+fn parse_array(chars: &mut Peekable<Chars>) -> Result<Json> {
+    chars.next(); // [
+    let mut items = vec![];
+    skip_whitespace(chars);
+    if chars.peek() == Some(&']') {
+        chars.next();
+        return Ok(Json::Array(items));
+    }
+    loop {
+        items.push(parse_value(chars)?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some(']') => return Ok(Json::Array(items)),
+            _ => return Err("Expected , or ]".to_string()),
+        }
+    }
+}
+
+fn parse_object(chars: &mut Peekable<Chars>) -> Result<Json> {
+    chars.next(); // {
+    let mut members = vec![];
+    skip_whitespace(chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return Ok(Json::Object(members));
+    }
+    loop {
+        skip_whitespace(chars);
+        if chars.peek() != Some(&'"') {
+            return Err("Expected name".to_string());
+        }
+        let key = parse_string(chars)?;
+        skip_whitespace(chars);
+        if chars.next() != Some(':') {
+            return Err("Expected :".to_string());
+        }
+        members.push((key, parse_value(chars)?));
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => return Ok(Json::Object(members)),
+            _ => return Err("Expected , or }".to_string()),
+        }
+    }
+}
+
+/// Reads a message with a `Content-Length` header.
+/// Returns `None` at the end of the input or when the message can't be read.
+pub(crate) fn read_message<R: BufRead>(input: &mut R) -> Option<Json> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            // tolerate blank lines between the messages
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+    let mut buf = vec![0; length?];
+    input.read_exact(&mut buf).ok()?;
+    Json::parse(&String::from_utf8(buf).ok()?).ok()
+}
+
+/// Writes a message with a `Content-Length` header.
+pub(crate) fn write_message<W: Write>(output: &mut W, message: &Json) {
+    let body = message.to_string();
+    // the client is gone if the output is broken, the next read ends the session
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap_or_default();
+    output.flush().unwrap_or_default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let json = Json::parse(
+            r#" {"seq": 1, "arguments": {"lines": [1, -2.5e1], "ok": true, "x": null},
+            "s": "a\"b\\c\né😀"} "#,
+        )
+        .unwrap();
+        assert_eq!(json.get("seq").and_then(Json::as_u32), Some(1));
+        let arguments = json.get("arguments").unwrap();
+        assert_eq!(
+            arguments.get("lines").and_then(Json::as_array),
+            Some(&[Json::Number(1.0), Json::Number(-25.0)][..])
+        );
+        assert_eq!(arguments.get("ok").and_then(Json::as_bool), Some(true));
+        assert_eq!(arguments.get("x"), Some(&Json::Null));
+        assert_eq!(json.get("s").and_then(Json::as_str), Some("a\"b\\c\né😀"));
+        assert_eq!(json.get("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Json::parse("").is_err());
+        assert!(Json::parse("{\"a\" 1}").is_err());
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("\"abc").is_err());
+        assert!(Json::parse("tru").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let json = Json::object(vec![
+            ("n", Json::from(3)),
+            ("f", Json::from(1.5)),
+            ("s", Json::from("a\"\n\u{1}")),
+            ("a", Json::from(vec![Json::Null, Json::from(false)])),
+            ("o", Json::object(vec![])),
+        ]);
+        let s = json.to_string();
+        assert_eq!(
+            s,
+            r#"{"n":3,"f":1.5,"s":"a\"\n\u0001","a":[null,false],"o":{}}"#
+        );
+        assert_eq!(Json::parse(&s).unwrap(), json);
+    }
+}
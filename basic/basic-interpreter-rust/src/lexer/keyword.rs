@@ -4,8 +4,22 @@ use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Keyword {
+    /// ACCESS
+    Access,
+    /// AND
+    And,
+    /// APPEND
+    Append,
+    /// AS
+    As,
+    /// BASE
+    Base,
+    /// BINARY
+    Binary,
     /// CASE
     Case,
+    /// CLOSE
+    Close,
     /// CONST
     Const,
     /// DECLARE
@@ -20,49 +34,130 @@ pub enum Keyword {
     DefSng,
     /// DEFSTR
     DefStr,
+    /// DIM
+    Dim,
+    /// DO
+    Do,
+    /// DOUBLE
+    Double,
     /// ELSE
     Else,
     /// ELSEIF
     ElseIf,
     /// END
     End,
+    /// EQV
+    Eqv,
+    /// ERASE
+    Erase,
     /// ERROR
     Error,
+    /// EXIT
+    Exit,
+    /// FIELD
+    Field,
     /// FOR
     For,
     /// FUNCTION
     Function,
+    /// GET
+    Get,
+    /// GOSUB
+    GoSub,
     /// GOTO
     GoTo,
     /// IF
     If,
+    /// IMP
+    Imp,
     /// INPUT
     Input,
+    /// INTEGER
+    Integer,
     /// IS
     Is,
+    /// LINE
+    Line,
+    /// LONG
+    Long,
+    /// LOOP
+    Loop,
+    /// LPRINT
+    LPrint,
+    /// LSET
+    LSet,
+    /// MOD
+    Mod,
+    /// NAME
+    Name,
     /// NEXT
     Next,
     /// NOT
     Not,
     /// ON
     On,
+    /// OPEN
+    Open,
+    /// OPTION
+    Option,
+    /// OR
+    Or,
+    /// OUTPUT
+    Output,
+    /// PRINT
+    Print,
+    /// PUT
+    Put,
+    /// RANDOM
+    Random,
+    /// READ
+    Read,
+    /// REDIM
+    ReDim,
+    /// RESUME
+    Resume,
+    /// RETURN
+    Return,
+    /// RSET
+    RSet,
     /// SELECT
     Select,
+    /// SINGLE
+    Single,
     /// STEP
     Step,
+    /// STRING
+    String,
     /// SUB
     Sub,
     /// THEN
     Then,
     /// TO
     To,
+    /// TYPE
+    Type,
+    /// UNTIL
+    Until,
+    /// USING
+    Using,
     /// WEND
     Wend,
     /// WHILE
     While,
+    /// WRITE
+    Write,
+    /// XOR
+    Xor,
 }
 
+const STR_ACCESS: &str = "ACCESS";
+const STR_AND: &str = "AND";
+const STR_APPEND: &str = "APPEND";
+const STR_AS: &str = "AS";
+const STR_BASE: &str = "BASE";
+const STR_BINARY: &str = "BINARY";
 const STR_CASE: &str = "CASE";
+const STR_CLOSE: &str = "CLOSE";
 const STR_CONST: &str = "CONST";
 const STR_DECLARE: &str = "DECLARE";
 const STR_DEFDBL: &str = "DEFDBL";
@@ -70,29 +165,73 @@ const STR_DEFINT: &str = "DEFINT";
 const STR_DEFLNG: &str = "DEFLNG";
 const STR_DEFSNG: &str = "DEFSNG";
 const STR_DEFSTR: &str = "DEFSTR";
+const STR_DIM: &str = "DIM";
+const STR_DO: &str = "DO";
+const STR_DOUBLE: &str = "DOUBLE";
 const STR_ELSE: &str = "ELSE";
 const STR_ELSEIF: &str = "ELSEIF";
 const STR_END: &str = "END";
+const STR_EQV: &str = "EQV";
+const STR_ERASE: &str = "ERASE";
 const STR_ERROR: &str = "ERROR";
+const STR_EXIT: &str = "EXIT";
+const STR_FIELD: &str = "FIELD";
 const STR_FOR: &str = "FOR";
 const STR_FUNCTION: &str = "FUNCTION";
+const STR_GET: &str = "GET";
+const STR_GOSUB: &str = "GOSUB";
 const STR_GOTO: &str = "GOTO";
 const STR_IF: &str = "IF";
+const STR_IMP: &str = "IMP";
 const STR_INPUT: &str = "INPUT";
+const STR_INTEGER: &str = "INTEGER";
 const STR_IS: &str = "IS";
+const STR_LINE: &str = "LINE";
+const STR_LONG: &str = "LONG";
+const STR_LOOP: &str = "LOOP";
+const STR_LPRINT: &str = "LPRINT";
+const STR_LSET: &str = "LSET";
+const STR_MOD: &str = "MOD";
+const STR_NAME: &str = "NAME";
 const STR_NEXT: &str = "NEXT";
 const STR_NOT: &str = "NOT";
 const STR_ON: &str = "ON";
+const STR_OPEN: &str = "OPEN";
+const STR_OPTION: &str = "OPTION";
+const STR_OR: &str = "OR";
+const STR_OUTPUT: &str = "OUTPUT";
+const STR_PRINT: &str = "PRINT";
+const STR_PUT: &str = "PUT";
+const STR_RANDOM: &str = "RANDOM";
+const STR_READ: &str = "READ";
+const STR_REDIM: &str = "REDIM";
+const STR_RESUME: &str = "RESUME";
+const STR_RETURN: &str = "RETURN";
+const STR_RSET: &str = "RSET";
 const STR_SELECT: &str = "SELECT";
+const STR_SINGLE: &str = "SINGLE";
 const STR_STEP: &str = "STEP";
+const STR_STRING: &str = "STRING";
 const STR_SUB: &str = "SUB";
 const STR_THEN: &str = "THEN";
 const STR_TO: &str = "TO";
+const STR_TYPE: &str = "TYPE";
+const STR_UNTIL: &str = "UNTIL";
+const STR_USING: &str = "USING";
 const STR_WEND: &str = "WEND";
 const STR_WHILE: &str = "WHILE";
+const STR_WRITE: &str = "WRITE";
+const STR_XOR: &str = "XOR";
 
-const SORTED_KEYWORDS_STR: [&str; 28] = [
+const SORTED_KEYWORDS_STR: [&str; 72] = [
+    STR_ACCESS,
+    STR_AND,
+    STR_APPEND,
+    STR_AS,
+    STR_BASE,
+    STR_BINARY,
     STR_CASE,
+    STR_CLOSE,
     STR_CONST,
     STR_DECLARE,
     STR_DEFDBL,
@@ -100,30 +239,74 @@ const SORTED_KEYWORDS_STR: [&str; 28] = [
     STR_DEFLNG,
     STR_DEFSNG,
     STR_DEFSTR,
+    STR_DIM,
+    STR_DO,
+    STR_DOUBLE,
     STR_ELSE,
     STR_ELSEIF,
     STR_END,
+    STR_EQV,
+    STR_ERASE,
     STR_ERROR,
+    STR_EXIT,
+    STR_FIELD,
     STR_FOR,
     STR_FUNCTION,
+    STR_GET,
+    STR_GOSUB,
     STR_GOTO,
     STR_IF,
+    STR_IMP,
     STR_INPUT,
+    STR_INTEGER,
     STR_IS,
+    STR_LINE,
+    STR_LONG,
+    STR_LOOP,
+    STR_LPRINT,
+    STR_LSET,
+    STR_MOD,
+    STR_NAME,
     STR_NEXT,
     STR_NOT,
     STR_ON,
+    STR_OPEN,
+    STR_OPTION,
+    STR_OR,
+    STR_OUTPUT,
+    STR_PRINT,
+    STR_PUT,
+    STR_RANDOM,
+    STR_READ,
+    STR_REDIM,
+    STR_RESUME,
+    STR_RETURN,
+    STR_RSET,
     STR_SELECT,
+    STR_SINGLE,
     STR_STEP,
+    STR_STRING,
     STR_SUB,
     STR_THEN,
     STR_TO,
+    STR_TYPE,
+    STR_UNTIL,
+    STR_USING,
     STR_WEND,
     STR_WHILE,
+    STR_WRITE,
+    STR_XOR,
 ];
 
-const SORTED_KEYWORDS: [Keyword; 28] = [
+const SORTED_KEYWORDS: [Keyword; 72] = [
+    Keyword::Access,
+    Keyword::And,
+    Keyword::Append,
+    Keyword::As,
+    Keyword::Base,
+    Keyword::Binary,
     Keyword::Case,
+    Keyword::Close,
     Keyword::Const,
     Keyword::Declare,
     Keyword::DefDbl,
@@ -131,32 +314,76 @@ const SORTED_KEYWORDS: [Keyword; 28] = [
     Keyword::DefLng,
     Keyword::DefSng,
     Keyword::DefStr,
+    Keyword::Dim,
+    Keyword::Do,
+    Keyword::Double,
     Keyword::Else,
     Keyword::ElseIf,
     Keyword::End,
+    Keyword::Eqv,
+    Keyword::Erase,
     Keyword::Error,
+    Keyword::Exit,
+    Keyword::Field,
     Keyword::For,
     Keyword::Function,
+    Keyword::Get,
+    Keyword::GoSub,
     Keyword::GoTo,
     Keyword::If,
+    Keyword::Imp,
     Keyword::Input,
+    Keyword::Integer,
     Keyword::Is,
+    Keyword::Line,
+    Keyword::Long,
+    Keyword::Loop,
+    Keyword::LPrint,
+    Keyword::LSet,
+    Keyword::Mod,
+    Keyword::Name,
     Keyword::Next,
     Keyword::Not,
     Keyword::On,
+    Keyword::Open,
+    Keyword::Option,
+    Keyword::Or,
+    Keyword::Output,
+    Keyword::Print,
+    Keyword::Put,
+    Keyword::Random,
+    Keyword::Read,
+    Keyword::ReDim,
+    Keyword::Resume,
+    Keyword::Return,
+    Keyword::RSet,
     Keyword::Select,
+    Keyword::Single,
     Keyword::Step,
+    Keyword::String,
     Keyword::Sub,
     Keyword::Then,
     Keyword::To,
+    Keyword::Type,
+    Keyword::Until,
+    Keyword::Using,
     Keyword::Wend,
     Keyword::While,
+    Keyword::Write,
+    Keyword::Xor,
 ];
 
 impl Display for Keyword {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
+            Self::Access => STR_ACCESS.fmt(f),
+            Self::And => STR_AND.fmt(f),
+            Self::Append => STR_APPEND.fmt(f),
+            Self::As => STR_AS.fmt(f),
+            Self::Base => STR_BASE.fmt(f),
+            Self::Binary => STR_BINARY.fmt(f),
             Self::Case => STR_CASE.fmt(f),
+            Self::Close => STR_CLOSE.fmt(f),
             Self::Const => STR_CONST.fmt(f),
             Self::Declare => STR_DECLARE.fmt(f),
             Self::DefDbl => STR_DEFDBL.fmt(f),
@@ -164,26 +391,63 @@ impl Display for Keyword {
             Self::DefLng => STR_DEFLNG.fmt(f),
             Self::DefSng => STR_DEFSNG.fmt(f),
             Self::DefStr => STR_DEFSTR.fmt(f),
+            Self::Dim => STR_DIM.fmt(f),
+            Self::Do => STR_DO.fmt(f),
+            Self::Double => STR_DOUBLE.fmt(f),
             Self::Else => STR_ELSE.fmt(f),
             Self::ElseIf => STR_ELSEIF.fmt(f),
             Self::End => STR_END.fmt(f),
+            Self::Eqv => STR_EQV.fmt(f),
+            Self::Erase => STR_ERASE.fmt(f),
             Self::Error => STR_ERROR.fmt(f),
+            Self::Exit => STR_EXIT.fmt(f),
+            Self::Field => STR_FIELD.fmt(f),
             Self::For => STR_FOR.fmt(f),
             Self::Function => STR_FUNCTION.fmt(f),
+            Self::Get => STR_GET.fmt(f),
+            Self::GoSub => STR_GOSUB.fmt(f),
             Self::GoTo => STR_GOTO.fmt(f),
             Self::If => STR_IF.fmt(f),
+            Self::Imp => STR_IMP.fmt(f),
             Self::Input => STR_INPUT.fmt(f),
+            Self::Integer => STR_INTEGER.fmt(f),
             Self::Is => STR_IS.fmt(f),
+            Self::Line => STR_LINE.fmt(f),
+            Self::Long => STR_LONG.fmt(f),
+            Self::Loop => STR_LOOP.fmt(f),
+            Self::LPrint => STR_LPRINT.fmt(f),
+            Self::LSet => STR_LSET.fmt(f),
+            Self::Mod => STR_MOD.fmt(f),
+            Self::Name => STR_NAME.fmt(f),
             Self::Next => STR_NEXT.fmt(f),
             Self::Not => STR_NOT.fmt(f),
             Self::On => STR_ON.fmt(f),
+            Self::Open => STR_OPEN.fmt(f),
+            Self::Option => STR_OPTION.fmt(f),
+            Self::Or => STR_OR.fmt(f),
+            Self::Output => STR_OUTPUT.fmt(f),
+            Self::Print => STR_PRINT.fmt(f),
+            Self::Put => STR_PUT.fmt(f),
+            Self::Random => STR_RANDOM.fmt(f),
+            Self::Read => STR_READ.fmt(f),
+            Self::ReDim => STR_REDIM.fmt(f),
+            Self::Resume => STR_RESUME.fmt(f),
+            Self::Return => STR_RETURN.fmt(f),
+            Self::RSet => STR_RSET.fmt(f),
             Self::Select => STR_SELECT.fmt(f),
+            Self::Single => STR_SINGLE.fmt(f),
             Self::Step => STR_STEP.fmt(f),
+            Self::String => STR_STRING.fmt(f),
             Self::Sub => STR_SUB.fmt(f),
             Self::Then => STR_THEN.fmt(f),
             Self::To => STR_TO.fmt(f),
+            Self::Type => STR_TYPE.fmt(f),
+            Self::Until => STR_UNTIL.fmt(f),
+            Self::Using => STR_USING.fmt(f),
             Self::Wend => STR_WEND.fmt(f),
             Self::While => STR_WHILE.fmt(f),
+            Self::Write => STR_WRITE.fmt(f),
+            Self::Xor => STR_XOR.fmt(f),
         }
     }
 }
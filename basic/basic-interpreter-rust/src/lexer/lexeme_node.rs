@@ -24,6 +24,9 @@ pub enum LexemeNode {
 
     /// An integer number
     Digits(String, Location),
+
+    /// A comment, without the `'` or `REM` that starts it
+    Comment(String, Location),
 }
 
 impl LexemeNode {
@@ -55,6 +58,38 @@ impl LexemeNode {
         }
     }
 
+    pub fn location(&self) -> Location {
+        match self {
+            LexemeNode::EOF(pos)
+            | LexemeNode::EOL(_, pos)
+            | LexemeNode::Keyword(_, _, pos)
+            | LexemeNode::Word(_, pos)
+            | LexemeNode::Whitespace(_, pos)
+            | LexemeNode::Symbol(_, pos)
+            | LexemeNode::Digits(_, pos)
+            | LexemeNode::Comment(_, pos) => *pos,
+        }
+    }
+
+    pub fn with_line_number(self, line_number: u32) -> Self {
+        match self {
+            LexemeNode::EOF(pos) => LexemeNode::EOF(pos.with_line_number(line_number)),
+            LexemeNode::EOL(s, pos) => LexemeNode::EOL(s, pos.with_line_number(line_number)),
+            LexemeNode::Keyword(k, s, pos) => {
+                LexemeNode::Keyword(k, s, pos.with_line_number(line_number))
+            }
+            LexemeNode::Word(s, pos) => LexemeNode::Word(s, pos.with_line_number(line_number)),
+            LexemeNode::Whitespace(s, pos) => {
+                LexemeNode::Whitespace(s, pos.with_line_number(line_number))
+            }
+            LexemeNode::Symbol(c, pos) => LexemeNode::Symbol(c, pos.with_line_number(line_number)),
+            LexemeNode::Digits(s, pos) => LexemeNode::Digits(s, pos.with_line_number(line_number)),
+            LexemeNode::Comment(s, pos) => {
+                LexemeNode::Comment(s, pos.with_line_number(line_number))
+            }
+        }
+    }
+
     pub fn is_whitespace(&self) -> bool {
         match self {
             LexemeNode::Whitespace(_, _) => true,
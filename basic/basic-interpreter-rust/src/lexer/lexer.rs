@@ -11,6 +11,11 @@ use std::str::FromStr;
 pub struct Lexer<T: BufRead> {
     reader: CharOrEofReader<T>,
     pos: Location,
+    /// Whether the last double quote opened a string literal,
+    /// which can contain the characters that start a comment
+    in_string: bool,
+    /// A comment that is read after the end of the statement before it
+    comment: Option<LexemeNode>,
 }
 
 fn _is_letter(ch: char) -> bool {
@@ -25,6 +30,11 @@ fn _is_alphanumeric(ch: char) -> bool {
     _is_letter(ch) || _is_digit(ch)
 }
 
+/// Identifiers can contain periods after the first letter, e.g. `Card.Value`.
+fn _is_identifier_char(ch: char) -> bool {
+    _is_alphanumeric(ch) || ch == '.'
+}
+
 fn _is_whitespace(ch: char) -> bool {
     ch == ' ' || ch == '\t'
 }
@@ -44,6 +54,8 @@ fn _is_symbol(ch: char) -> bool {
         || ch == '-'
         || ch == '*'
         || ch == '/'
+        || ch == '\\'
+        || ch == '^'
         || ch == '('
         || ch == ')'
         || ch == '='
@@ -54,6 +66,8 @@ fn _is_symbol(ch: char) -> bool {
         || ch == '&'
         || ch == '\''
         || ch == ':'
+        || ch == ';'
+        || ch == '_'
 }
 
 impl<T: BufRead> Lexer<T> {
@@ -61,10 +75,15 @@ impl<T: BufRead> Lexer<T> {
         Lexer {
             reader: reader,
             pos: Location::start(),
+            in_string: false,
+            comment: None,
         }
     }
 
     pub fn read(&mut self) -> Result<LexemeNode, LexerError> {
+        if let Some(comment) = self.comment.take() {
+            return Ok(comment);
+        }
         let x = self._read_one()?;
         match x {
             None => Ok(LexemeNode::EOF(self.pos)),
@@ -75,7 +94,10 @@ impl<T: BufRead> Lexer<T> {
     fn _read_char(&mut self, ch: char) -> Result<LexemeNode, LexerError> {
         let pos = self.pos;
         if _is_letter(ch) {
-            let buf = self._read_while(_is_alphanumeric)?;
+            let buf = self._read_while(_is_identifier_char)?;
+            if !self.in_string && buf.eq_ignore_ascii_case("REM") {
+                return self._read_comment(pos);
+            }
             match Keyword::from_str(&buf) {
                 Ok(k) => Ok(LexemeNode::Keyword(k, buf, pos)),
                 Err(_) => Ok(LexemeNode::Word(buf, pos)),
@@ -87,9 +109,18 @@ impl<T: BufRead> Lexer<T> {
             let buf = self._read_while(_is_digit)?;
             Ok(LexemeNode::Digits(buf, pos))
         } else if _is_eol(ch) {
+            self.in_string = false;
             let buf = self._read_while_eol()?;
             Ok(LexemeNode::EOL(buf, pos))
-        } else if _is_symbol(ch) {
+        } else if ch == '\'' && !self.in_string {
+            self._consume();
+            self.pos.inc_col();
+            self._read_comment(pos)
+        } else if _is_symbol(ch) || self.in_string {
+            // a string literal can contain any character up to the closing quote
+            if ch == '"' {
+                self.in_string = !self.in_string;
+            }
             self._consume();
             self.pos.inc_col();
             Ok(LexemeNode::Symbol(ch, pos))
@@ -98,6 +129,15 @@ impl<T: BufRead> Lexer<T> {
         }
     }
 
+    /// Reads the rest of the line as a comment. The statement before the comment
+    /// ends at an empty EOL and the comment follows, so that the parser sees a
+    /// trailing comment like a comment on its own line.
+    fn _read_comment(&mut self, pos: Location) -> Result<LexemeNode, LexerError> {
+        let text = self._read_while(|ch| !_is_eol(ch))?;
+        self.comment = Some(LexemeNode::Comment(text, pos));
+        Ok(LexemeNode::EOL(String::new(), pos))
+    }
+
     fn _read_one(&mut self) -> Result<Option<char>, LexerError> {
         self.reader
             .read()
@@ -197,7 +237,7 @@ mod tests {
         let mut lexer = Lexer::from(input);
         assert_eq!(
             lexer.read().unwrap(),
-            LexemeNode::Word("PRINT".to_string(), Location::new(1, 1))
+            LexemeNode::Keyword(Keyword::Print, "PRINT".to_string(), Location::new(1, 1))
         );
         assert_eq!(
             lexer.read().unwrap(),
@@ -234,6 +274,23 @@ mod tests {
         assert_eq!(lexer.read().unwrap(), LexemeNode::EOF(Location::new(1, 22)));
     }
 
+    #[test]
+    fn test_word_with_period() {
+        let mut lexer = Lexer::from("Card.Value=.5");
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Word("Card.Value".to_string(), Location::new(1, 1))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('=', Location::new(1, 11))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('.', Location::new(1, 12))
+        );
+    }
+
     #[test]
     fn test_cr_lf() {
         let mut lexer = Lexer::from("Hi\r\n123");
@@ -265,4 +322,102 @@ mod tests {
         );
         assert_eq!(lexer.read().unwrap(), LexemeNode::EOF(Location::new(4, 1)));
     }
+
+    #[test]
+    fn test_underscore_is_symbol() {
+        let mut lexer = Lexer::from("A_B");
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Word("A".to_string(), Location::new(1, 1))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('_', Location::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_comment() {
+        let mut lexer = Lexer::from("A ' hi\nREM there");
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Word("A".to_string(), Location::new(1, 1))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Whitespace(" ".to_string(), Location::new(1, 2))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::EOL("".to_string(), Location::new(1, 3))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Comment(" hi".to_string(), Location::new(1, 3))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::EOL("\n".to_string(), Location::new(1, 7))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::EOL("".to_string(), Location::new(2, 1))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Comment(" there".to_string(), Location::new(2, 1))
+        );
+        assert_eq!(lexer.read().unwrap(), LexemeNode::EOF(Location::new(2, 10)));
+    }
+
+    #[test]
+    fn test_comment_marker_in_string() {
+        let mut lexer = Lexer::from("\"'REM\"");
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('"', Location::new(1, 1))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('\'', Location::new(1, 2))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Word("REM".to_string(), Location::new(1, 3))
+        );
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Symbol('"', Location::new(1, 6))
+        );
+    }
+
+    #[test]
+    fn test_any_character_in_string() {
+        let mut lexer = Lexer::from("\"[{?@|}]\"");
+        for (i, ch) in "\"[{?@|}]\"".chars().enumerate() {
+            assert_eq!(
+                lexer.read().unwrap(),
+                LexemeNode::Symbol(ch, Location::new(1, i as u32 + 1))
+            );
+        }
+        assert_eq!(lexer.read().unwrap(), LexemeNode::EOF(Location::new(1, 10)));
+    }
+
+    #[test]
+    fn test_unsupported_character_outside_string() {
+        let mut lexer = Lexer::from("[");
+        assert_eq!(
+            lexer.read().unwrap_err(),
+            LexerError::UnsupportedCharacter('[', Location::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_rem_prefix_is_word() {
+        let mut lexer = Lexer::from("REMARK");
+        assert_eq!(
+            lexer.read().unwrap(),
+            LexemeNode::Word("REMARK".to_string(), Location::new(1, 1))
+        );
+    }
 }
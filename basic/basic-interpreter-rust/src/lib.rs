@@ -0,0 +1,54 @@
+//! An interpreter for QBasic.
+//!
+//! A program is parsed, linted and compiled, before an [`Interpreter`] runs it:
+//!
+//! ```
+//! use basic_interpreter_rust::{DefaultStdlib, Interpreter, Program};
+//!
+//! let program = Program::parse("PRINT \"Hello, world!\"".as_bytes())
+//!     .and_then(Program::lint)
+//!     .map(|p| p.compile())
+//!     .unwrap();
+//! Interpreter::new(DefaultStdlib::default()).run(&program).unwrap();
+//! ```
+mod ast_printer;
+mod casting;
+mod common;
+mod console_debugger;
+mod dap;
+mod error;
+mod formatter;
+mod host;
+mod instruction_generator;
+mod interpreter;
+mod json;
+mod lexer;
+mod linter;
+mod lsp;
+mod parser;
+mod program;
+mod reader;
+mod repl;
+mod variant;
+
+use std::io::Read;
+
+pub use common::Location;
+pub use console_debugger::ConsoleDebugger;
+pub use dap::DapServer;
+pub use error::Error;
+pub use host::{HostFunction, HostRegistry, HostSub};
+pub use interpreter::{Debugger, DefaultStdlib, InterpreterError, Resume, Stdlib, Stop};
+pub use lexer::LexerError;
+pub use linter::LinterError;
+pub use lsp::LspServer;
+pub use parser::{ParserError, TypeQualifier};
+pub use program::{CompiledProgram, Interpreter, LintedProgram, Program};
+pub use repl::Repl;
+pub use variant::Variant;
+
+/// Parses, lints and runs the given program with the given standard library.
+pub fn run<R: Read, S: Stdlib>(program: R, stdlib: S) -> Result<(), Error> {
+    let program = Program::parse(program)?.lint()?.compile();
+    Interpreter::new(stdlib).run(&program)
+}
@@ -1,6 +1,7 @@
 mod built_in_function_linter;
 mod built_in_sub_linter;
 mod error;
+mod exit_linter;
 mod expression_reducer;
 mod for_next_counter_match;
 mod label_linter;
@@ -16,9 +17,10 @@ mod user_defined_sub_linter;
 
 pub use self::error::{Error, LinterError};
 pub use self::linter::*;
+pub use self::subprogram_context::collect_subprograms;
 pub use self::types::*;
 
 pub use crate::parser::{
-    BareName, BareNameNode, HasQualifier, NameTrait, Operand, QualifiedName, TypeQualifier,
-    UnaryOperand,
+    BareName, BareNameNode, DoLoopConditionKind, DoLoopConditionPosition, ExitObject, HasQualifier,
+    NameTrait, Operand, QualifiedName, ResumeOption, TypeQualifier, UnaryOperand,
 };
@@ -13,7 +13,56 @@ impl BuiltInFunctionLinter {
         args: &Vec<ExpressionNode>,
     ) -> Result<(), Error> {
         match name {
+            BuiltInFunction::Abs
+            | BuiltInFunction::Atn
+            | BuiltInFunction::CDbl
+            | BuiltInFunction::CInt
+            | BuiltInFunction::CLng
+            | BuiltInFunction::Cos
+            | BuiltInFunction::CSng
+            | BuiltInFunction::Exp
+            | BuiltInFunction::Fix
+            | BuiltInFunction::Hex
+            | BuiltInFunction::Int
+            | BuiltInFunction::Log
+            | BuiltInFunction::Oct
+            | BuiltInFunction::Sgn
+            | BuiltInFunction::Sin
+            | BuiltInFunction::Sqr
+            | BuiltInFunction::Tan => self.visit_numeric_function(args),
+            BuiltInFunction::Asc
+            | BuiltInFunction::Cvd
+            | BuiltInFunction::Cvi
+            | BuiltInFunction::Cvl
+            | BuiltInFunction::Cvs
+            | BuiltInFunction::LCase
+            | BuiltInFunction::LTrim
+            | BuiltInFunction::RTrim
+            | BuiltInFunction::UCase => self.require_single_string_argument(args),
             BuiltInFunction::Environ => self.visit_environ(args),
+            BuiltInFunction::Input => self.visit_input(args),
+            BuiltInFunction::InStr => self.visit_instr(args),
+            BuiltInFunction::Left | BuiltInFunction::Right => self.visit_left_right(args),
+            BuiltInFunction::Mid => self.visit_mid(args),
+            BuiltInFunction::Rnd => self.visit_rnd(args),
+            BuiltInFunction::String => self.visit_string(args),
+            BuiltInFunction::Chr
+            | BuiltInFunction::Eof
+            | BuiltInFunction::Loc
+            | BuiltInFunction::Lof
+            | BuiltInFunction::Mkd
+            | BuiltInFunction::Mki
+            | BuiltInFunction::Mkl
+            | BuiltInFunction::Mks
+            | BuiltInFunction::Seek
+            | BuiltInFunction::Space => self.require_single_numeric_argument(args),
+            BuiltInFunction::Date
+            | BuiltInFunction::Erl
+            | BuiltInFunction::Err
+            | BuiltInFunction::FreeFile
+            | BuiltInFunction::Time
+            | BuiltInFunction::Timer => self.visit_parameterless(args),
+            BuiltInFunction::LBound | BuiltInFunction::UBound => self.visit_bound(args),
             BuiltInFunction::Len => self.visit_len(args),
             BuiltInFunction::Str => self.visit_str(args),
             BuiltInFunction::Val => self.visit_val(args),
@@ -24,6 +73,112 @@ impl BuiltInFunctionLinter {
         self.require_single_string_argument(args)
     }
 
+    fn require_single_numeric_argument(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        if args.len() != 1 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else if args[0].as_ref().try_qualifier()? == TypeQualifier::DollarString {
+            err_l(LinterError::ArgumentTypeMismatch, &args[0])
+        } else {
+            Ok(())
+        }
+    }
+
+    fn visit_numeric_function(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        // e.g. ABS("hello") is a type mismatch and not an argument type mismatch
+        if args.len() != 1 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else if args[0].as_ref().try_qualifier()? == TypeQualifier::DollarString {
+            err_l(LinterError::TypeMismatch, &args[0])
+        } else {
+            Ok(())
+        }
+    }
+
+    fn visit_input(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        // INPUT$(count[, file number])
+        if args.is_empty() || args.len() > 2 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else {
+            args.iter().try_for_each(require_numeric)
+        }
+    }
+
+    fn visit_rnd(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        // RND or RND(n)
+        if args.is_empty() {
+            Ok(())
+        } else {
+            self.visit_numeric_function(args)
+        }
+    }
+
+    fn visit_instr(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        // INSTR([start,] haystack, needle)
+        match args.len() {
+            2 => {
+                require_string(&args[0])?;
+                require_string(&args[1])
+            }
+            3 => {
+                require_numeric(&args[0])?;
+                require_string(&args[1])?;
+                require_string(&args[2])
+            }
+            _ => err_no_pos(LinterError::ArgumentCountMismatch),
+        }
+    }
+
+    fn visit_left_right(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        if args.len() != 2 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else {
+            require_string(&args[0])?;
+            require_numeric(&args[1])
+        }
+    }
+
+    fn visit_mid(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        if args.len() != 2 && args.len() != 3 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else {
+            require_string(&args[0])?;
+            args[1..].iter().try_for_each(require_numeric)
+        }
+    }
+
+    fn visit_string(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        // STRING$(count, character code or string)
+        if args.len() != 2 {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        } else {
+            require_numeric(&args[0])?;
+            args[1].as_ref().try_qualifier().map(|_| ())
+        }
+    }
+
+    fn visit_parameterless(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        if args.is_empty() {
+            Ok(())
+        } else {
+            err_no_pos(LinterError::ArgumentCountMismatch)
+        }
+    }
+
+    fn visit_bound(&self, args: &[ExpressionNode]) -> Result<(), Error> {
+        if args.is_empty() || args.len() > 2 {
+            return err_no_pos(LinterError::ArgumentCountMismatch);
+        }
+        match args[0].as_ref() {
+            Expression::ArrayReference(_) => (),
+            _ => return err_l(LinterError::ArgumentTypeMismatch, &args[0]),
+        }
+        if args.len() == 2 && args[1].as_ref().try_qualifier()? == TypeQualifier::DollarString {
+            err_l(LinterError::ArgumentTypeMismatch, &args[1])
+        } else {
+            Ok(())
+        }
+    }
+
     fn visit_len(&self, args: &Vec<ExpressionNode>) -> Result<(), Error> {
         if args.len() != 1 {
             err_no_pos(LinterError::ArgumentCountMismatch)
@@ -74,6 +229,22 @@ impl BuiltInFunctionLinter {
     }
 }
 
+fn require_string(arg: &ExpressionNode) -> Result<(), Error> {
+    if arg.as_ref().try_qualifier()? != TypeQualifier::DollarString {
+        err_l(LinterError::ArgumentTypeMismatch, arg)
+    } else {
+        Ok(())
+    }
+}
+
+fn require_numeric(arg: &ExpressionNode) -> Result<(), Error> {
+    if arg.as_ref().try_qualifier()? == TypeQualifier::DollarString {
+        err_l(LinterError::ArgumentTypeMismatch, arg)
+    } else {
+        Ok(())
+    }
+}
+
 impl PostConversionLinter for BuiltInFunctionLinter {
     fn visit_expression(&self, expr_node: &ExpressionNode) -> Result<(), Error> {
         let pos = expr_node.location();
@@ -90,6 +261,14 @@ impl PostConversionLinter for BuiltInFunctionLinter {
                 self.visit_expression(right)
             }
             Expression::UnaryExpression(_, child) => self.visit_expression(child),
+            Expression::ArrayElement(_, indices)
+            | Expression::UserDefinedArrayElement(_, _, indices) => {
+                for x in indices {
+                    self.visit_expression(x)?;
+                }
+                Ok(())
+            }
+            Expression::Member(base, _, _) => self.visit_expression(base),
             _ => Ok(()),
         }
     }
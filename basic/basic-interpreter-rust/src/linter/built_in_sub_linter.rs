@@ -1,7 +1,8 @@
 use super::error::*;
 use super::post_conversion_linter::PostConversionLinter;
 use super::types::*;
-use crate::parser::TypeQualifier;
+use crate::common::PrintArg;
+use crate::parser::{HasQualifier, TypeQualifier};
 
 pub struct BuiltInSubLinter;
 
@@ -29,18 +30,180 @@ impl PostConversionLinter for BuiltInSubLinter {
                 }
             }
             BuiltInSub::Input => {
-                if args.len() == 0 {
+                // the parser has put the file number, or the prompt kind and the prompt,
+                // before the variables
+                let first_variable = match args.first().map(|a| a.as_ref()) {
+                    Some(Expression::FileHandle(_)) => 1,
+                    _ => 2,
+                };
+                if args.len() <= first_variable {
                     err_no_pos(LinterError::ArgumentCountMismatch)
                 } else {
-                    args.iter()
-                        .map(|a| match a.as_ref() {
+                    args[first_variable..]
+                        .iter()
+                        .try_for_each(|a| match a.as_ref() {
                             Expression::Variable(_) => Ok(()),
                             _ => err_l(LinterError::VariableRequired, a),
                         })
-                        .collect()
                 }
             }
-            _ => Ok(()),
+            BuiltInSub::Close => Ok(()),
+            BuiltInSub::Kill | BuiltInSub::Name => {
+                // the file name, or the old and the new file name
+                let count = if *n == BuiltInSub::Kill { 1 } else { 2 };
+                if args.len() != count {
+                    err_no_pos(LinterError::ArgumentCountMismatch)
+                } else {
+                    args.iter().try_for_each(|a| {
+                        if a.as_ref().try_qualifier()? != TypeQualifier::DollarString {
+                            err_l(LinterError::TypeMismatch, a)
+                        } else {
+                            Ok(())
+                        }
+                    })
+                }
+            }
+            BuiltInSub::LineInput => {
+                // the variable follows the file number, or the prompt of the keyboard
+                if args.len() != 2 {
+                    err_no_pos(LinterError::ArgumentCountMismatch)
+                } else {
+                    require_string_variable(&args[1])
+                }
+            }
+            BuiltInSub::Field => {
+                // the parser has created the file number and the width and variable pairs
+                for pair in args[1..].chunks(2) {
+                    require_numeric(&pair[0])?;
+                    require_string_variable(&pair[1])?;
+                }
+                Ok(())
+            }
+            BuiltInSub::Get | BuiltInSub::Put => {
+                // the parser has created the file number and whether there is a record
+                let mut variable_idx = 2;
+                if let Expression::IntegerLiteral(1) = args[1].as_ref() {
+                    require_numeric(&args[2])?;
+                    variable_idx += 1;
+                }
+                match args.get(variable_idx).map(|a| (a, a.as_ref())) {
+                    Some((_, Expression::Variable(_)))
+                    | Some((_, Expression::UserDefinedVariable(_, _)))
+                    | None => Ok(()),
+                    Some((a, _)) => err_l(LinterError::VariableRequired, a),
+                }
+            }
+            BuiltInSub::LSet | BuiltInSub::RSet => {
+                // the parser has created the variable and the value
+                require_string_variable(&args[0])?;
+                if args[1].as_ref().try_qualifier()? != TypeQualifier::DollarString {
+                    err_l(LinterError::TypeMismatch, &args[1])
+                } else {
+                    Ok(())
+                }
+            }
+            BuiltInSub::Mid => {
+                // the parser has moved the value after the variable
+                if args.len() != 3 && args.len() != 4 {
+                    err_no_pos(LinterError::ArgumentCountMismatch)
+                } else {
+                    require_string_variable(&args[0])?;
+                    if args[1].as_ref().try_qualifier()? != TypeQualifier::DollarString {
+                        return err_l(LinterError::TypeMismatch, &args[1]);
+                    }
+                    args[2..].iter().try_for_each(require_numeric)
+                }
+            }
+            BuiltInSub::Open => {
+                // the parser has created the arguments, only the file name
+                // and the record length need checking
+                if args[0].as_ref().try_qualifier()? != TypeQualifier::DollarString {
+                    err_l(LinterError::TypeMismatch, &args[0])
+                } else {
+                    require_numeric(&args[4])
+                }
+            }
+            BuiltInSub::LPrint | BuiltInSub::Print => {
+                // the parser has put the kind of each argument before it
+                let mut i = 0;
+                let args = skip_file_handle(args);
+                while i < args.len() {
+                    let kind = match args[i].as_ref() {
+                        Expression::IntegerLiteral(k) => PrintArg::from(*k),
+                        _ => panic!("PRINT argument kind should be an integer literal"),
+                    };
+                    if kind.has_value() {
+                        let a = &args[i + 1];
+                        match kind {
+                            PrintArg::Expression => require_printable(a)?,
+                            PrintArg::Using => {
+                                if a.as_ref().try_qualifier()? != TypeQualifier::DollarString {
+                                    return err_l(LinterError::TypeMismatch, a);
+                                }
+                            }
+                            _ => require_numeric(a)?,
+                        }
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                Ok(())
+            }
+            BuiltInSub::Write => skip_file_handle(args)
+                .iter()
+                .try_for_each(require_printable),
+            BuiltInSub::Randomize | BuiltInSub::Sleep => {
+                // an optional number
+                match args.len() {
+                    0 => Ok(()),
+                    1 => require_numeric(&args[0]),
+                    _ => err_no_pos(LinterError::ArgumentCountMismatch),
+                }
+            }
+            BuiltInSub::Seek => {
+                if args.len() != 2 {
+                    err_no_pos(LinterError::ArgumentCountMismatch)
+                } else {
+                    require_numeric(&args[0])?;
+                    require_numeric(&args[1])
+                }
+            }
         }
     }
 }
+
+fn require_printable(arg: &ExpressionNode) -> Result<(), Error> {
+    // an array can't be printed, only its elements
+    if let Expression::ArrayReference(_) = arg.as_ref() {
+        return err_l(LinterError::TypeMismatch, arg);
+    }
+    // a user defined type can't be printed, only its members
+    if arg.as_ref().user_defined_type().is_some() {
+        return err_l(LinterError::TypeMismatch, arg);
+    }
+    Ok(())
+}
+
+fn require_numeric(arg: &ExpressionNode) -> Result<(), Error> {
+    if arg.as_ref().try_qualifier()? == TypeQualifier::DollarString {
+        err_l(LinterError::TypeMismatch, arg)
+    } else {
+        Ok(())
+    }
+}
+
+fn require_string_variable(arg: &ExpressionNode) -> Result<(), Error> {
+    match arg.as_ref() {
+        Expression::Variable(name) if name.qualifier() == TypeQualifier::DollarString => Ok(()),
+        Expression::Variable(_) => err_l(LinterError::TypeMismatch, arg),
+        _ => err_l(LinterError::VariableRequired, arg),
+    }
+}
+
+/// Skips the file number of `PRINT #1` and `WRITE #1`.
+fn skip_file_handle(args: &[ExpressionNode]) -> &[ExpressionNode] {
+    match args.first().map(|a| a.as_ref()) {
+        Some(Expression::FileHandle(_)) => &args[1..],
+        _ => args,
+    }
+}
@@ -38,6 +38,42 @@ pub enum LinterError {
 
     // 2
     SyntaxError,
+
+    ArrayNotDefined,
+
+    TypeNotDefined,
+
+    ElementNotDefined,
+
+    ExitDoNotWithinDoLoop,
+
+    ExitForNotWithinForNext,
+}
+
+impl std::fmt::Display for LinterError {
+    /// Shows the message that QBasic shows for the error.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::ArgumentCountMismatch => "Argument-count mismatch",
+            Self::ArgumentTypeMismatch => "Parameter type mismatch",
+            Self::TypeMismatch => "Type mismatch",
+            Self::NextWithoutFor => "NEXT without FOR",
+            Self::DuplicateDefinition => "Duplicate definition",
+            Self::InvalidAssignment => "Invalid assignment",
+            Self::InvalidConstant => "Invalid constant",
+            Self::SubprogramNotDefined => "Subprogram not defined",
+            Self::LabelNotDefined => "Label not defined",
+            Self::DuplicateLabel => "Duplicate label",
+            Self::VariableRequired => "Variable required",
+            Self::SyntaxError => "Syntax error",
+            Self::ArrayNotDefined => "Array not defined",
+            Self::TypeNotDefined => "Type not defined",
+            Self::ElementNotDefined => "Element not defined",
+            Self::ExitDoNotWithinDoLoop => "EXIT DO not within DO...LOOP",
+            Self::ExitForNotWithinForNext => "EXIT FOR not within FOR...NEXT",
+        };
+        f.write_str(message)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -51,7 +87,6 @@ impl Error {
         }
     }
 
-    #[cfg(test)]
     pub fn consume(self) -> (LinterError, Option<Location>) {
         (self.0, self.1)
     }
@@ -0,0 +1,47 @@
+use super::error::*;
+use super::post_conversion_linter::*;
+use super::types::*;
+use crate::parser::ExitObject;
+use std::cell::RefCell;
+
+/// Ensures that `EXIT DO` and `EXIT FOR` are used within the matching loop.
+pub struct ExitLinter {
+    // implemented as RefCell for inner mutability
+    loops: RefCell<Vec<ExitObject>>,
+}
+
+impl ExitLinter {
+    pub fn new() -> Self {
+        Self {
+            loops: RefCell::new(vec![]),
+        }
+    }
+
+    fn visit_loop_body(&self, kind: ExitObject, s: &StatementNodes) -> Result<(), Error> {
+        self.loops.borrow_mut().push(kind);
+        let result = self.visit_statement_nodes(s);
+        self.loops.borrow_mut().pop();
+        result
+    }
+}
+
+impl PostConversionLinter for ExitLinter {
+    fn visit_for_loop(&self, f: &ForLoopNode) -> Result<(), Error> {
+        self.visit_loop_body(ExitObject::For, &f.statements)
+    }
+
+    fn visit_do_loop(&self, d: &DoLoopNode) -> Result<(), Error> {
+        self.visit_loop_body(ExitObject::Do, &d.statements)
+    }
+
+    fn visit_exit(&self, exit_object: ExitObject) -> Result<(), Error> {
+        if self.loops.borrow().contains(&exit_object) {
+            Ok(())
+        } else {
+            match exit_object {
+                ExitObject::Do => err_no_pos(LinterError::ExitDoNotWithinDoLoop),
+                ExitObject::For => err_no_pos(LinterError::ExitForNotWithinForNext),
+            }
+        }
+    }
+}
@@ -28,6 +28,7 @@ pub trait ExpressionReducer {
             TopLevelToken::Statement(s) => {
                 self.visit_statement(s).map(|x| TopLevelToken::Statement(x))
             }
+            TopLevelToken::UserDefinedType(u) => Ok(TopLevelToken::UserDefinedType(u)),
         }
     }
 
@@ -71,6 +72,26 @@ pub trait ExpressionReducer {
                         Statement::Assignment(reduced_left, reduced_right)
                     })
             }
+            Statement::ArrayElementAssignment(left, indices, right) => {
+                Ok(Statement::ArrayElementAssignment(
+                    left,
+                    self.visit_expression_nodes(indices)?,
+                    self.visit_expression_node(right)?,
+                ))
+            }
+            Statement::MemberAssignment(left, members, right) => Ok(Statement::MemberAssignment(
+                left,
+                members,
+                self.visit_expression_node(right)?,
+            )),
+            Statement::ArrayElementMemberAssignment(left, indices, members, right) => {
+                Ok(Statement::ArrayElementMemberAssignment(
+                    left,
+                    self.visit_expression_nodes(indices)?,
+                    members,
+                    self.visit_expression_node(right)?,
+                ))
+            }
             Statement::Const(left, right) => self
                 .visit_const(left, right)
                 .map(|(reduced_left, reduced_right)| Statement::Const(reduced_left, reduced_right)),
@@ -87,13 +108,54 @@ pub trait ExpressionReducer {
             Statement::SelectCase(s) => self.visit_select_case(s).map(|x| Statement::SelectCase(x)),
             Statement::ForLoop(f) => self.visit_for_loop(f).map(|x| Statement::ForLoop(x)),
             Statement::While(w) => self.visit_conditional_block(w).map(|x| Statement::While(x)),
+            Statement::DoLoop(d) => self.visit_do_loop(d).map(Statement::DoLoop),
+            Statement::Exit(e) => Ok(Statement::Exit(e)),
             Statement::ErrorHandler(label) => Ok(Statement::ErrorHandler(label)),
+            Statement::Error(e) => self.visit_expression_node(e).map(Statement::Error),
+            Statement::Resume(r) => Ok(Statement::Resume(r)),
             Statement::Label(label) => Ok(Statement::Label(label)),
             Statement::GoTo(label) => Ok(Statement::GoTo(label)),
+            Statement::GoSub(label) => Ok(Statement::GoSub(label)),
+            Statement::Return(label) => Ok(Statement::Return(label)),
+            Statement::OnGoTo(e, labels) => self
+                .visit_expression_node(e)
+                .map(|x| Statement::OnGoTo(x, labels)),
+            Statement::OnGoSub(e, labels) => self
+                .visit_expression_node(e)
+                .map(|x| Statement::OnGoSub(x, labels)),
+            Statement::Comment(text) => Ok(Statement::Comment(text)),
             Statement::SetReturnValue(expr) => self
                 .visit_expression_node(expr)
                 .map(|x| Statement::SetReturnValue(x)),
+            Statement::Dim(dim_names) => self.visit_dim_names(dim_names).map(Statement::Dim),
+            Statement::ReDim(dim_names) => self.visit_dim_names(dim_names).map(Statement::ReDim),
+            Statement::Erase(names) => Ok(Statement::Erase(names)),
+        }
+    }
+
+    fn visit_dim_names(&self, dim_names: Vec<DimNameNode>) -> Result<Vec<DimNameNode>, Error> {
+        let mut result: Vec<DimNameNode> = vec![];
+        for dim_name_node in dim_names.into_iter() {
+            let (dim_name, pos) = dim_name_node.consume();
+            let mut dimensions: Vec<ArrayDimension> = vec![];
+            for d in dim_name.dimensions.into_iter() {
+                dimensions.push(ArrayDimension {
+                    lbound: self.visit_expression_node(d.lbound)?,
+                    ubound: self.visit_expression_node(d.ubound)?,
+                });
+            }
+            result.push(
+                DimName {
+                    name: dim_name.name,
+                    dimensions,
+                    is_dynamic: dim_name.is_dynamic,
+                    fixed_length: dim_name.fixed_length,
+                    user_defined_type: dim_name.user_defined_type,
+                }
+                .at(pos),
+            );
         }
+        Ok(result)
     }
 
     fn visit_sub_call(
@@ -138,6 +200,22 @@ pub trait ExpressionReducer {
         })
     }
 
+    fn visit_do_loop(&self, d: DoLoopNode) -> Result<DoLoopNode, Error> {
+        let condition = match d.condition {
+            Some(c) => Some(DoLoopCondition {
+                position: c.position,
+                kind: c.kind,
+                expr: self.visit_expression_node(c.expr)?,
+            }),
+            None => None,
+        };
+        let statements = self.visit_statement_nodes(d.statements)?;
+        Ok(DoLoopNode {
+            condition,
+            statements,
+        })
+    }
+
     fn visit_if_block(&self, i: IfBlockNode) -> Result<IfBlockNode, Error> {
         let if_block = self.visit_conditional_block(i.if_block)?;
         let else_if_blocks: Vec<ConditionalBlockNode> = i
@@ -59,4 +59,8 @@ impl PostConversionLinter for LabelLinter {
             err_no_pos(LinterError::LabelNotDefined)
         }
     }
+
+    fn visit_go_sub(&self, label: &CaseInsensitiveString) -> Result<(), Error> {
+        self.visit_go_to(label)
+    }
 }
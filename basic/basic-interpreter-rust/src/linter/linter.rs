@@ -14,10 +14,12 @@ use super::post_conversion_linter::PostConversionLinter;
 use super::subprogram_context::{collect_subprograms, FunctionMap, SubMap};
 use super::types::*;
 use crate::common::*;
+use crate::host::HostRegistry;
 use crate::parser;
 use crate::parser::type_resolver_impl::TypeResolverImpl;
 use crate::parser::{
-    HasQualifier, Name, NameTrait, Operand, QualifiedName, TypeQualifier, TypeResolver,
+    BareName, BareNameNode, HasQualifier, Name, NameTrait, Operand, QualifiedName, TypeQualifier,
+    TypeResolver,
 };
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
@@ -105,6 +107,17 @@ struct LinterContext {
     parent: Option<Box<LinterContext>>,
     constants: HashMap<CaseInsensitiveString, TypeQualifier>,
     variables: VariableSet,
+    arrays: VariableSet,
+    /// Variables declared with an AS clause. A bare name resolves to the declared type.
+    variable_types: HashMap<CaseInsensitiveString, TypeQualifier>,
+    /// Arrays declared with an AS clause. A bare name resolves to the declared type.
+    array_types: HashMap<CaseInsensitiveString, TypeQualifier>,
+    dynamic_arrays: HashSet<QualifiedName>,
+    /// Variables of a user defined type, e.g. `DIM P AS Card`.
+    /// A bare name resolves to the qualified name of the variable and the name of the type.
+    user_defined_variables: HashMap<CaseInsensitiveString, (QualifiedName, BareName)>,
+    /// Arrays of a user defined type, e.g. `DIM A(10) AS Card`.
+    user_defined_arrays: HashMap<CaseInsensitiveString, (QualifiedName, BareName)>,
     function_name: Option<CaseInsensitiveString>,
     sub_name: Option<CaseInsensitiveString>,
 }
@@ -140,6 +153,21 @@ impl LinterContext {
             None => Ok(None),
         }
     }
+
+    /// Checks if the bare name is used by any variable, array or constant.
+    pub fn contains_any(&self, bare_name: &CaseInsensitiveString) -> bool {
+        self.constants.contains_key(bare_name)
+            || self.variables.contains_bare(bare_name)
+            || self.arrays.contains_bare(bare_name)
+            || self.variable_types.contains_key(bare_name)
+            || self.array_types.contains_key(bare_name)
+            || self.contains_user_defined(bare_name)
+    }
+
+    pub fn contains_user_defined(&self, bare_name: &CaseInsensitiveString) -> bool {
+        self.user_defined_variables.contains_key(bare_name)
+            || self.user_defined_arrays.contains_key(bare_name)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -148,6 +176,8 @@ struct Linter {
     context: LinterContext,
     functions: FunctionMap,
     subs: SubMap,
+    option_base: u8,
+    user_defined_types: HashMap<CaseInsensitiveString, UserDefinedType>,
 }
 
 impl Linter {
@@ -176,11 +206,652 @@ impl Linter {
             None => panic!("Stack underflow!"),
         }
     }
+
+    fn resolve_name(&self, n: &Name) -> QualifiedName {
+        match n {
+            Name::Bare(b) => QualifiedName::new(b.clone(), self.resolver.resolve(b)),
+            Name::Qualified(q) => q.clone(),
+        }
+    }
+
+    /// Resolves the name of an array that has been declared in the current context.
+    fn resolve_array_name(&self, n: &Name) -> Result<Option<QualifiedName>, Error> {
+        if let Some((array_name, _)) = self.context.user_defined_arrays.get(n.bare_name()) {
+            return match n {
+                Name::Bare(_) => Ok(Some(array_name.clone())),
+                Name::Qualified(_) => Err(LinterError::DuplicateDefinition.into()),
+            };
+        }
+        match self.context.array_types.get(n.bare_name()) {
+            Some(q) => {
+                if n.bare_or_eq(*q) {
+                    Ok(Some(QualifiedName::new(n.bare_name().clone(), *q)))
+                } else {
+                    Err(LinterError::DuplicateDefinition.into())
+                }
+            }
+            None => {
+                let q_name = self.resolve_name(n);
+                if self.context.arrays.contains_qualified(&q_name) {
+                    Ok(Some(q_name))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn demand_array_name(&self, n: &Name) -> Result<QualifiedName, Error> {
+        match self.resolve_array_name(n)? {
+            Some(q_name) => Ok(q_name),
+            None => Err(LinterError::ArrayNotDefined.into()),
+        }
+    }
+
+    /// Converts the parameters of a function or sub and
+    /// registers them in the current context.
+    fn convert_params(&mut self, params: parser::ParamNodes) -> Result<Vec<QNameNode>, Error> {
+        let mut result: Vec<QNameNode> = vec![];
+        for param_node in params.into_iter() {
+            let (param, pos) = param_node.consume();
+            let q_name =
+                QualifiedName::new(param.bare_name().clone(), self.resolver.resolve(&param));
+            if self.context.contains_user_defined(q_name.bare_name()) {
+                return err(LinterError::DuplicateDefinition, pos);
+            }
+            if let parser::DimType::UserDefined(type_name_node) = param.param_type {
+                if param.is_array {
+                    // arrays of a user defined type can't be passed to subprograms
+                    return err(LinterError::SyntaxError, pos);
+                }
+                let (type_name, type_pos) = type_name_node.consume();
+                if !self.user_defined_types.contains_key(&type_name) {
+                    return err(LinterError::TypeNotDefined, type_pos);
+                }
+                self.context
+                    .user_defined_variables
+                    .insert(q_name.bare_name().clone(), (q_name.clone(), type_name));
+                result.push(q_name.at(pos));
+                continue;
+            }
+            let is_extended = param.param_type != parser::DimType::Bare;
+            if param.is_array {
+                // the array that is passed might be static or dynamic
+                self.context.arrays.insert(q_name.clone());
+                self.context.dynamic_arrays.insert(q_name.clone());
+                if is_extended {
+                    self.context
+                        .array_types
+                        .insert(q_name.bare_name().clone(), q_name.qualifier());
+                }
+            } else {
+                if is_extended {
+                    if self.context.variable_types.contains_key(q_name.bare_name())
+                        || self.context.variables.contains_bare(&q_name)
+                    {
+                        return err(LinterError::DuplicateDefinition, pos);
+                    }
+                    self.context
+                        .variable_types
+                        .insert(q_name.bare_name().clone(), q_name.qualifier());
+                } else if self.context.variable_types.contains_key(q_name.bare_name()) {
+                    return err(LinterError::DuplicateDefinition, pos);
+                }
+                self.context.variables.insert(q_name.clone());
+            }
+            result.push(q_name.at(pos));
+        }
+        Ok(result)
+    }
+
+    fn convert_dim_names(
+        &mut self,
+        dim_names: Vec<parser::DimNameNode>,
+        is_redim: bool,
+    ) -> Result<Vec<DimNameNode>, Error> {
+        let mut result: Vec<DimNameNode> = vec![];
+        for dim_name_node in dim_names.into_iter() {
+            let (dim_name, pos) = dim_name_node.consume();
+            let converted = self
+                .convert_dim_name(dim_name, is_redim)
+                .with_err_pos(pos)?;
+            result.push(converted.at(pos));
+        }
+        Ok(result)
+    }
+
+    fn convert_dim_name(
+        &mut self,
+        dim_name: parser::DimName,
+        is_redim: bool,
+    ) -> Result<DimName, Error> {
+        if let parser::DimType::UserDefined(type_name_node) = &dim_name.dim_type {
+            let type_name_node = type_name_node.clone();
+            return self.convert_user_defined_dim_name(dim_name, type_name_node, is_redim);
+        }
+        let bare_name = dim_name.name.bare_name().clone();
+        let q_name = QualifiedName::new(bare_name.clone(), self.resolver.resolve(&dim_name));
+        let fixed_length = match dim_name.dim_type {
+            parser::DimType::FixedLengthString(len) => Some(len),
+            _ => None,
+        };
+        if self.context.constants.contains_key(&bare_name)
+            || self.context.contains_user_defined(&bare_name)
+        {
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        if dim_name.dimensions.is_empty() {
+            if is_redim {
+                // REDIM needs dimensions
+                return Err(LinterError::SyntaxError.into());
+            }
+            return self.convert_dim_variable(dim_name.dim_type, q_name, fixed_length);
+        }
+        if self.context.variables.contains_qualified(&q_name)
+            || self.context.variables.contains_qualified(&q_name)
+        {
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        match self.context.array_types.get(&bare_name) {
+            // declared with AS, can only be re-dimensioned with the same type
+            Some(q) if *q != q_name.qualifier() => {
+                return Err(LinterError::DuplicateDefinition.into());
+            }
+            _ => (),
+        }
+        let is_extended = dim_name.dim_type != parser::DimType::Bare;
+        if is_extended
+            && self.context.arrays.contains_bare(&bare_name)
+            && !self.context.array_types.contains_key(&bare_name)
+        {
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        if self.context.arrays.contains_qualified(&q_name)
+            && (!is_redim || !self.context.dynamic_arrays.contains(&q_name))
+        {
+            // array already dimensioned
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        let dimensions = self.convert_array_dimensions(dim_name.dimensions)?;
+        let is_dynamic = is_redim || has_dynamic_bounds(&dimensions);
+        if is_extended {
+            self.context
+                .array_types
+                .insert(bare_name, q_name.qualifier());
+        }
+        self.context.arrays.insert(q_name.clone());
+        if is_dynamic {
+            self.context.dynamic_arrays.insert(q_name.clone());
+        }
+        Ok(DimName {
+            name: q_name,
+            dimensions,
+            is_dynamic,
+            fixed_length,
+            user_defined_type: None,
+        })
+    }
+
+    fn convert_array_dimensions(
+        &mut self,
+        array_dimensions: Vec<parser::ArrayDimension>,
+    ) -> Result<Vec<ArrayDimension>, Error> {
+        let mut dimensions: Vec<ArrayDimension> = vec![];
+        for d in array_dimensions.into_iter() {
+            let (lbound, ubound) = match d.lbound {
+                Some(lbound) => (self.convert(lbound)?, self.convert(d.ubound)?),
+                None => {
+                    let ubound: ExpressionNode = self.convert(d.ubound)?;
+                    let lbound =
+                        Expression::IntegerLiteral(self.option_base as i32).at(ubound.location());
+                    (lbound, ubound)
+                }
+            };
+            for bound in [&lbound, &ubound].iter() {
+                if bound.as_ref().try_qualifier()? == TypeQualifier::DollarString {
+                    return err_l(LinterError::TypeMismatch, *bound);
+                }
+            }
+            dimensions.push(ArrayDimension { lbound, ubound });
+        }
+        Ok(dimensions)
+    }
+
+    /// Converts the declaration of a variable or an array of a user defined type,
+    /// e.g. `DIM P AS Card` or `DIM A(1 TO 52) AS Card`.
+    fn convert_user_defined_dim_name(
+        &mut self,
+        dim_name: parser::DimName,
+        type_name_node: BareNameNode,
+        is_redim: bool,
+    ) -> Result<DimName, Error> {
+        let (type_name, type_pos) = type_name_node.consume();
+        if !self.user_defined_types.contains_key(&type_name) {
+            return err(LinterError::TypeNotDefined, type_pos);
+        }
+        let bare_name = dim_name.name.bare_name().clone();
+        if dim_name.dimensions.is_empty() {
+            if is_redim {
+                // REDIM needs dimensions
+                return Err(LinterError::SyntaxError.into());
+            }
+            if self.context.contains_any(&bare_name) {
+                return Err(LinterError::DuplicateDefinition.into());
+            }
+            let q_name = QualifiedName::new(bare_name.clone(), self.resolver.resolve(&dim_name));
+            self.context
+                .user_defined_variables
+                .insert(bare_name, (q_name.clone(), type_name.clone()));
+            return Ok(DimName {
+                name: q_name,
+                dimensions: vec![],
+                is_dynamic: false,
+                fixed_length: None,
+                user_defined_type: Some(type_name),
+            });
+        }
+        let q_name = match self.context.user_defined_arrays.get(&bare_name) {
+            Some((array_name, array_type_name)) => {
+                // only a dynamic array can be re-dimensioned, with the same type
+                if !is_redim
+                    || array_type_name != &type_name
+                    || !self.context.dynamic_arrays.contains(array_name)
+                {
+                    return Err(LinterError::DuplicateDefinition.into());
+                }
+                array_name.clone()
+            }
+            None => {
+                if self.context.contains_any(&bare_name) {
+                    return Err(LinterError::DuplicateDefinition.into());
+                }
+                QualifiedName::new(bare_name.clone(), self.resolver.resolve(&dim_name))
+            }
+        };
+        let dimensions = self.convert_array_dimensions(dim_name.dimensions)?;
+        let is_dynamic = is_redim || has_dynamic_bounds(&dimensions);
+        self.context
+            .user_defined_arrays
+            .insert(bare_name, (q_name.clone(), type_name.clone()));
+        if is_dynamic {
+            self.context.dynamic_arrays.insert(q_name.clone());
+        }
+        Ok(DimName {
+            name: q_name,
+            dimensions,
+            is_dynamic,
+            fixed_length: None,
+            user_defined_type: Some(type_name),
+        })
+    }
+
+    /// Converts a `TYPE` block, registering the new type.
+    fn convert_user_defined_type(
+        &mut self,
+        user_defined_type: parser::UserDefinedType,
+    ) -> Result<UserDefinedType, Error> {
+        let (name, pos) = user_defined_type.name.consume();
+        if self.user_defined_types.contains_key(&name) {
+            return err(LinterError::DuplicateDefinition, pos);
+        }
+        let mut elements: Vec<Element> = vec![];
+        for element_node in user_defined_type.elements.into_iter() {
+            let (element, element_pos) = element_node.consume();
+            if elements.iter().any(|e| e.name == element.name) {
+                return err(LinterError::DuplicateDefinition, element_pos);
+            }
+            let element_type = match element.element_type {
+                parser::ElementType::BuiltIn(q) => ElementType::BuiltIn(q),
+                parser::ElementType::FixedLengthString(len) => ElementType::FixedLengthString(len),
+                parser::ElementType::UserDefined(type_name_node) => {
+                    let (type_name, type_pos) = type_name_node.consume();
+                    // the type must be defined before it is used
+                    if !self.user_defined_types.contains_key(&type_name) {
+                        return err(LinterError::TypeNotDefined, type_pos);
+                    }
+                    ElementType::UserDefined(type_name)
+                }
+            };
+            elements.push(Element {
+                name: element.name,
+                element_type,
+            });
+        }
+        let result = UserDefinedType { name, elements };
+        self.user_defined_types
+            .insert(result.name.clone(), result.clone());
+        Ok(result)
+    }
+
+    /// Resolves the members of a user defined type, e.g. `Address.City`,
+    /// returning the name and the type of each member.
+    /// The qualifier, if any, must match the type of the last member.
+    fn resolve_members<'a, I>(
+        &self,
+        type_name: &BareName,
+        member_names: I,
+        opt_qualifier: Option<TypeQualifier>,
+    ) -> Result<Vec<(BareName, ElementType)>, Error>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut result: Vec<(BareName, ElementType)> = vec![];
+        let mut element_type = ElementType::UserDefined(type_name.clone());
+        for member_name in member_names {
+            let user_defined_type = match &element_type {
+                ElementType::UserDefined(t) => self
+                    .user_defined_types
+                    .get(t)
+                    .expect("The type should have been defined"),
+                _ => return Err(LinterError::ElementNotDefined.into()),
+            };
+            let member_name = BareName::from(member_name);
+            element_type = match user_defined_type.find_element(&member_name) {
+                Some(element) => element.element_type.clone(),
+                None => return Err(LinterError::ElementNotDefined.into()),
+            };
+            result.push((member_name, element_type.clone()));
+        }
+        if result.is_empty() {
+            return Err(LinterError::ElementNotDefined.into());
+        }
+        match (opt_qualifier, &element_type) {
+            (None, _) => Ok(result),
+            (Some(q), ElementType::BuiltIn(e)) if q == *e => Ok(result),
+            (Some(TypeQualifier::DollarString), ElementType::FixedLengthString(_)) => Ok(result),
+            _ => Err(LinterError::DuplicateDefinition.into()),
+        }
+    }
+
+    /// Converts a name that refers to a variable of a user defined type
+    /// or to one of its members, e.g. `P` or `P.Suit`.
+    /// Returns `None` if the name is not a variable of a user defined type.
+    fn convert_user_defined_variable(
+        &self,
+        n: &Name,
+        pos: Location,
+    ) -> Result<Option<Expression>, Error> {
+        let (var_name, type_name) = match self.find_user_defined_variable(n) {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let mut parts = n.bare_name().as_ref().split('.');
+        parts.next();
+        if n.bare_name() == var_name.bare_name() {
+            let variable = Expression::UserDefinedVariable(var_name, type_name);
+            // the variable itself can't be qualified
+            return match n {
+                Name::Bare(_) => Ok(Some(variable)),
+                Name::Qualified(_) => Err(LinterError::DuplicateDefinition.into()),
+            };
+        }
+        let members = self.resolve_members(&type_name, parts, n.opt_qualifier())?;
+        let variable = Expression::UserDefinedVariable(var_name, type_name);
+        Ok(Some(into_member_expression(variable.at(pos), members)))
+    }
+
+    /// Calculates the size of a user defined type in bytes, as returned by `LEN`.
+    fn user_defined_type_len(&self, type_name: &BareName) -> i32 {
+        let user_defined_type = self
+            .user_defined_types
+            .get(type_name)
+            .expect("The type should have been defined");
+        user_defined_type
+            .elements
+            .iter()
+            .map(|e| match &e.element_type {
+                ElementType::BuiltIn(TypeQualifier::PercentInteger) => 2,
+                ElementType::BuiltIn(TypeQualifier::AmpersandLong)
+                | ElementType::BuiltIn(TypeQualifier::BangSingle) => 4,
+                ElementType::BuiltIn(TypeQualifier::HashDouble) => 8,
+                ElementType::BuiltIn(TypeQualifier::DollarString) => {
+                    panic!("Variable length strings are not allowed in a type")
+                }
+                ElementType::FixedLengthString(len) => *len as i32,
+                ElementType::UserDefined(t) => self.user_defined_type_len(t),
+            })
+            .sum()
+    }
+
+    /// Converts the base of a member of an array element, e.g. `A(1)` in `A(1).Suit`.
+    /// Returns the name of the array, the name of its type and the converted indices.
+    fn convert_user_defined_array_element(
+        &mut self,
+        base: parser::Expression,
+    ) -> Result<(QualifiedName, BareName, Vec<ExpressionNode>), Error> {
+        let (n, indices) = match base {
+            parser::Expression::FunctionCall(n, args) => (n, args),
+            _ => return Err(LinterError::SyntaxError.into()),
+        };
+        let (array_name, type_name) = match self.context.user_defined_arrays.get(n.bare_name()) {
+            Some((array_name, type_name)) => (array_name.clone(), type_name.clone()),
+            None => return Err(LinterError::SyntaxError.into()),
+        };
+        if let Name::Qualified(_) = n {
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        if indices.is_empty() {
+            return Err(LinterError::SyntaxError.into());
+        }
+        let converted_indices = self.convert_array_indices(indices)?;
+        Ok((array_name, type_name, converted_indices))
+    }
+
+    /// Checks that the value can be assigned to a member of the given type.
+    fn check_member_assignment(
+        &self,
+        element_type: &ElementType,
+        value: &ExpressionNode,
+    ) -> Result<(), Error> {
+        let is_valid = match element_type {
+            ElementType::BuiltIn(q) => value.as_ref().try_qualifier()?.can_cast_to(*q),
+            ElementType::FixedLengthString(_) => {
+                value.as_ref().try_qualifier()? == TypeQualifier::DollarString
+            }
+            ElementType::UserDefined(type_name) => {
+                value.as_ref().user_defined_type() == Some(type_name)
+            }
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            err_l(LinterError::TypeMismatch, value)
+        }
+    }
+
+    /// Finds the variable of a user defined type that the name refers to,
+    /// e.g. `P` for `P` or `P.Suit`.
+    fn find_user_defined_variable(&self, n: &Name) -> Option<(QualifiedName, BareName)> {
+        let first = n.bare_name().as_ref().split('.').next().unwrap_or_default();
+        self.context
+            .user_defined_variables
+            .get(&BareName::from(first))
+            .cloned()
+    }
+
+    /// Converts the assignment to a variable of a user defined type or to one of its
+    /// members, e.g. `P = Q` or `P.Suit = "Hearts"`.
+    fn convert_user_defined_assignment(
+        &mut self,
+        n: Name,
+        var_name: QualifiedName,
+        type_name: BareName,
+        e: parser::ExpressionNode,
+    ) -> Result<Statement, Error> {
+        let converted_expr: ExpressionNode = self.convert(e)?;
+        let mut parts = n.bare_name().as_ref().split('.');
+        parts.next();
+        if n.bare_name() == var_name.bare_name() {
+            if let Name::Qualified(_) = n {
+                return Err(LinterError::DuplicateDefinition.into());
+            }
+            // copies the whole variable
+            self.check_member_assignment(&ElementType::UserDefined(type_name), &converted_expr)?;
+            return Ok(Statement::Assignment(var_name, converted_expr));
+        }
+        let members = self.resolve_members(&type_name, parts, n.opt_qualifier())?;
+        self.check_member_assignment(&members.last().unwrap().1, &converted_expr)?;
+        let member_names: Vec<BareName> = members.into_iter().map(|(name, _)| name).collect();
+        Ok(Statement::MemberAssignment(
+            var_name,
+            member_names,
+            converted_expr,
+        ))
+    }
+
+    /// Converts the assignment to a member of an array element,
+    /// e.g. `A(1).Suit = "Hearts"`.
+    fn convert_array_element_member_assignment(
+        &mut self,
+        left: parser::ExpressionNode,
+        right: parser::ExpressionNode,
+    ) -> Result<Statement, Error> {
+        let (left, pos) = left.consume();
+        let (base, member_name) = match left {
+            parser::Expression::Member(base, member_name) => (base.consume().0, member_name),
+            _ => panic!("Expected member expression"),
+        };
+        let (array_name, type_name, converted_indices) = self
+            .convert_user_defined_array_element(base)
+            .with_err_pos(pos)?;
+        let members = self
+            .resolve_members(
+                &type_name,
+                member_name.bare_name().as_ref().split('.'),
+                member_name.opt_qualifier(),
+            )
+            .with_err_pos(pos)?;
+        let converted_expr: ExpressionNode = self.convert(right)?;
+        self.check_member_assignment(&members.last().unwrap().1, &converted_expr)?;
+        let member_names: Vec<BareName> = members.into_iter().map(|(name, _)| name).collect();
+        Ok(Statement::ArrayElementMemberAssignment(
+            array_name,
+            converted_indices,
+            member_names,
+            converted_expr,
+        ))
+    }
+
+    /// Converts the declaration of a variable, e.g. `DIM X AS INTEGER`.
+    fn convert_dim_variable(
+        &mut self,
+        dim_type: parser::DimType,
+        q_name: QualifiedName,
+        fixed_length: Option<u16>,
+    ) -> Result<DimName, Error> {
+        let bare_name = q_name.bare_name();
+        if self.context.variable_types.contains_key(bare_name)
+            || self.context.contains_user_defined(bare_name)
+            || self.context.arrays.contains_qualified(&q_name)
+            || self.context.variables.contains_qualified(&q_name)
+        {
+            return Err(LinterError::DuplicateDefinition.into());
+        }
+        if dim_type != parser::DimType::Bare {
+            // the bare name can't be used by any other variable
+            if self.context.variables.contains_bare(&q_name) {
+                return Err(LinterError::DuplicateDefinition.into());
+            }
+            self.context
+                .variable_types
+                .insert(bare_name.clone(), q_name.qualifier());
+        }
+        self.context.variables.insert(q_name.clone());
+        Ok(DimName {
+            name: q_name,
+            dimensions: vec![],
+            is_dynamic: false,
+            fixed_length,
+            user_defined_type: None,
+        })
+    }
+
+    fn convert_array_indices(
+        &mut self,
+        indices: Vec<parser::ExpressionNode>,
+    ) -> Result<Vec<ExpressionNode>, Error> {
+        let converted_indices: Vec<ExpressionNode> = self.convert(indices)?;
+        for index in converted_indices.iter() {
+            if index.as_ref().try_qualifier()? == TypeQualifier::DollarString {
+                return err_l(LinterError::TypeMismatch, index);
+            }
+        }
+        Ok(converted_indices)
+    }
+
+    /// Converts an expression that must be numeric, e.g. in `ON expr GOTO` or `ERROR expr`.
+    fn convert_numeric_expression(
+        &mut self,
+        e: parser::ExpressionNode,
+    ) -> Result<ExpressionNode, Error> {
+        let converted: ExpressionNode = self.convert(e)?;
+        if converted.as_ref().try_qualifier()? == TypeQualifier::DollarString {
+            err_l(LinterError::TypeMismatch, &converted)
+        } else {
+            Ok(converted)
+        }
+    }
+
+    fn convert_built_in_function_args(
+        &mut self,
+        b: BuiltInFunction,
+        args: Vec<parser::ExpressionNode>,
+    ) -> Result<Vec<ExpressionNode>, Error> {
+        match b {
+            BuiltInFunction::LBound | BuiltInFunction::UBound => {
+                // the first argument is the name of an array, e.g. LBOUND(A)
+                let mut converted_args: Vec<ExpressionNode> = vec![];
+                for (i, arg) in args.into_iter().enumerate() {
+                    let opt_array_name = match arg.as_ref() {
+                        parser::Expression::VariableName(n) if i == 0 => {
+                            self.resolve_array_name(n).with_err_pos(arg.location())?
+                        }
+                        _ => None,
+                    };
+                    match opt_array_name {
+                        Some(array_name) => converted_args
+                            .push(Expression::ArrayReference(array_name).at(arg.location())),
+                        None => converted_args.push(self.convert(arg)?),
+                    }
+                }
+                Ok(converted_args)
+            }
+            _ => self.convert(args),
+        }
+    }
+}
+
+/// Checks if the bounds of an array can only be evaluated at runtime.
+fn has_dynamic_bounds(dimensions: &[ArrayDimension]) -> bool {
+    dimensions
+        .iter()
+        .any(|d| !d.lbound.as_ref().is_constant() || !d.ubound.as_ref().is_constant())
+}
+
+/// Wraps the base expression into the given members, e.g. `P` into `P.Address.City`.
+fn into_member_expression(
+    base: ExpressionNode,
+    members: Vec<(BareName, ElementType)>,
+) -> Expression {
+    let pos = base.location();
+    let mut result = base;
+    for (name, element_type) in members.into_iter() {
+        result = Expression::Member(Box::new(result), name, element_type).at(pos);
+    }
+    result.consume().0
 }
 
 pub fn lint(program: parser::ProgramNode) -> Result<ProgramNode, Error> {
+    lint_with_host(program, &HostRegistry::default())
+}
+
+/// Lints a program that can call the functions and subs of the host.
+pub fn lint_with_host(
+    program: parser::ProgramNode,
+    host: &HostRegistry,
+) -> Result<ProgramNode, Error> {
     let mut linter = Linter::default();
-    let (f_c, s_c) = collect_subprograms(&program)?;
+    let (f_c, s_c) = collect_subprograms(&program, host)?;
     linter.functions = f_c;
     linter.subs = s_c;
     linter.convert(program)
@@ -225,6 +896,9 @@ impl Converter<parser::ProgramNode, ProgramNode> for Linter {
         let linter = super::select_case_linter::SelectCaseLinter {};
         linter.visit_program(&result)?;
 
+        let linter = super::exit_linter::ExitLinter::new();
+        linter.visit_program(&result)?;
+
         let mut linter = super::label_linter::LabelLinter::new();
         linter.visit_program(&result)?;
         linter.switch_to_validating_mode();
@@ -241,12 +915,26 @@ impl Converter<parser::ProgramNode, ProgramNode> for Linter {
 
 impl Converter<Name, QualifiedName> for Linter {
     fn convert(&mut self, a: Name) -> Result<QualifiedName, Error> {
-        match a {
-            Name::Bare(b) => {
-                let qualifier = self.resolver.resolve(&b);
-                Ok(QualifiedName::new(b, qualifier))
+        if self.context.contains_user_defined(a.bare_name()) {
+            // e.g. FOR P = 1 TO 10, where P is a user defined type
+            return Err(LinterError::TypeMismatch.into());
+        }
+        match self.context.variable_types.get(a.bare_name()) {
+            // declared with an AS clause, e.g. DIM X AS INTEGER
+            Some(q) => {
+                if a.bare_or_eq(*q) {
+                    Ok(QualifiedName::new(a.bare_name().clone(), *q))
+                } else {
+                    Err(LinterError::DuplicateDefinition.into())
+                }
             }
-            Name::Qualified(q) => Ok(q),
+            None => match a {
+                Name::Bare(b) => {
+                    let qualifier = self.resolver.resolve(&b);
+                    Ok(QualifiedName::new(b, qualifier))
+                }
+                Name::Qualified(q) => Ok(q),
+            },
         }
     }
 }
@@ -259,15 +947,16 @@ impl Converter<parser::TopLevelToken, Option<TopLevelToken>> for Linter {
                 self.resolver.set(&d);
                 Ok(None)
             }
+            parser::TopLevelToken::OptionBase(base) => {
+                self.option_base = base;
+                Ok(None)
+            }
             parser::TopLevelToken::FunctionDeclaration(_, _)
             | parser::TopLevelToken::SubDeclaration(_, _) => Ok(None),
             parser::TopLevelToken::FunctionImplementation(n, params, block) => {
                 let mapped_name = self.convert(n)?;
-                let mapped_params = self.convert(params)?;
                 self.push_function_context(mapped_name.bare_name());
-                for q_n_n in mapped_params.iter() {
-                    self.context.variables.insert(q_n_n.as_ref().clone());
-                }
+                let mapped_params = self.convert_params(params)?;
                 let mapped = TopLevelToken::FunctionImplementation(FunctionImplementation {
                     name: mapped_name,
                     params: mapped_params,
@@ -277,11 +966,8 @@ impl Converter<parser::TopLevelToken, Option<TopLevelToken>> for Linter {
                 Ok(Some(mapped))
             }
             parser::TopLevelToken::SubImplementation(n, params, block) => {
-                let mapped_params = self.convert(params)?;
                 self.push_sub_context(n.bare_name());
-                for q_n_n in mapped_params.iter() {
-                    self.context.variables.insert(q_n_n.as_ref().clone());
-                }
+                let mapped_params = self.convert_params(params)?;
                 let mapped = TopLevelToken::SubImplementation(SubImplementation {
                     name: n,
                     params: mapped_params,
@@ -293,6 +979,10 @@ impl Converter<parser::TopLevelToken, Option<TopLevelToken>> for Linter {
             parser::TopLevelToken::Statement(s) => {
                 Ok(Some(TopLevelToken::Statement(self.convert(s)?)))
             }
+            parser::TopLevelToken::UserDefinedType(u) => {
+                let converted = self.convert_user_defined_type(u)?;
+                Ok(Some(TopLevelToken::UserDefinedType(converted)))
+            }
         }
     }
 }
@@ -335,8 +1025,15 @@ impl Converter<parser::Statement, Statement> for Linter {
                     if self.context.constants.contains_key(n.bare_name()) {
                         // cannot overwrite local constant
                         Err(LinterError::DuplicateDefinition.into())
+                    } else if let Some((var_name, type_name)) = self.find_user_defined_variable(&n)
+                    {
+                        self.convert_user_defined_assignment(n, var_name, type_name, e)
                     } else {
                         let converted_name = self.convert(n)?;
+                        if self.context.arrays.contains_qualified(&converted_name) {
+                            // cannot assign to an array
+                            return Err(LinterError::DuplicateDefinition.into());
+                        }
                         let converted_expr: ExpressionNode = self.convert(e)?;
                         let result_q: TypeQualifier = converted_expr.as_ref().try_qualifier()?;
                         if result_q.can_cast_to(converted_name.qualifier()) {
@@ -348,10 +1045,55 @@ impl Converter<parser::Statement, Statement> for Linter {
                     }
                 }
             }
+            parser::Statement::ArrayElementAssignment(n, indices, e) => {
+                let converted_name = self.demand_array_name(&n)?;
+                let converted_indices = self.convert_array_indices(indices)?;
+                let converted_expr: ExpressionNode = self.convert(e)?;
+                if let Some((_, type_name)) = self.context.user_defined_arrays.get(n.bare_name()) {
+                    // copies the whole record into the element
+                    let element_type = ElementType::UserDefined(type_name.clone());
+                    self.check_member_assignment(&element_type, &converted_expr)?;
+                    return Ok(Statement::ArrayElementAssignment(
+                        converted_name,
+                        converted_indices,
+                        converted_expr,
+                    ));
+                }
+                let result_q: TypeQualifier = converted_expr.as_ref().try_qualifier()?;
+                if result_q.can_cast_to(converted_name.qualifier()) {
+                    Ok(Statement::ArrayElementAssignment(
+                        converted_name,
+                        converted_indices,
+                        converted_expr,
+                    ))
+                } else {
+                    err_l(LinterError::TypeMismatch, &converted_expr)
+                }
+            }
+            parser::Statement::MemberAssignment(left, right) => {
+                self.convert_array_element_member_assignment(left, right)
+            }
+            parser::Statement::Dim(dim_names) => {
+                Ok(Statement::Dim(self.convert_dim_names(dim_names, false)?))
+            }
+            parser::Statement::ReDim(dim_names) => {
+                Ok(Statement::ReDim(self.convert_dim_names(dim_names, true)?))
+            }
+            parser::Statement::Erase(names) => {
+                let mut converted_names: Vec<QNameNode> = vec![];
+                for name_node in names.into_iter() {
+                    let (name, pos) = name_node.consume();
+                    let converted_name = self.demand_array_name(&name).with_err_pos(pos)?;
+                    converted_names.push(converted_name.at(pos));
+                }
+                Ok(Statement::Erase(converted_names))
+            }
             parser::Statement::Const(n, e) => {
                 let (name, pos) = n.consume();
                 if self.context.variables.contains_bare(&name)
+                    || self.context.arrays.contains_bare(&name)
                     || self.context.constants.contains_key(name.bare_name())
+                    || self.context.contains_user_defined(name.bare_name())
                 {
                     // local variable or local constant already present by that name
                     err(LinterError::DuplicateDefinition, pos)
@@ -392,15 +1134,59 @@ impl Converter<parser::Statement, Statement> for Linter {
             parser::Statement::SelectCase(s) => Ok(Statement::SelectCase(self.convert(s)?)),
             parser::Statement::ForLoop(f) => Ok(Statement::ForLoop(self.convert(f)?)),
             parser::Statement::While(c) => Ok(Statement::While(self.convert(c)?)),
+            parser::Statement::DoLoop(d) => Ok(Statement::DoLoop(self.convert(d)?)),
+            parser::Statement::Exit(e) => Ok(Statement::Exit(e)),
             parser::Statement::ErrorHandler(l) => Ok(Statement::ErrorHandler(l)),
+            parser::Statement::Error(e) => {
+                Ok(Statement::Error(self.convert_numeric_expression(e)?))
+            }
+            parser::Statement::Resume(r) => Ok(Statement::Resume(r)),
             parser::Statement::Label(l) => Ok(Statement::Label(l)),
             parser::Statement::GoTo(l) => Ok(Statement::GoTo(l)),
+            parser::Statement::GoSub(l) => Ok(Statement::GoSub(l)),
+            parser::Statement::Return(l) => Ok(Statement::Return(l)),
+            parser::Statement::OnGoTo(e, labels) => Ok(Statement::OnGoTo(
+                self.convert_numeric_expression(e)?,
+                labels,
+            )),
+            parser::Statement::OnGoSub(e, labels) => Ok(Statement::OnGoSub(
+                self.convert_numeric_expression(e)?,
+                labels,
+            )),
+            parser::Statement::Comment(text) => Ok(Statement::Comment(text)),
         }
     }
 }
 
-impl Converter<parser::Expression, Expression> for Linter {
-    fn convert(&mut self, a: parser::Expression) -> Result<Expression, Error> {
+impl Converter<parser::ExpressionNode, ExpressionNode> for Linter {
+    fn convert(&mut self, a: parser::ExpressionNode) -> Result<ExpressionNode, Error> {
+        let (expression, pos) = a.consume();
+        self.convert_expression(expression, pos)
+            .with_pos(pos)
+            .with_err_pos(pos)
+    }
+}
+
+impl Linter {
+    /// Inside the function itself, its name is the return value instead.
+    fn is_parameterless_function_call(&self, n: &Name) -> bool {
+        let is_current_function = self
+            .context
+            .function_name
+            .as_ref()
+            .map(|x| x == n.bare_name())
+            .unwrap_or_default();
+        match self.functions.get(n.bare_name()) {
+            Some((q, params, _)) => !is_current_function && params.is_empty() && n.bare_or_eq(*q),
+            None => false,
+        }
+    }
+
+    fn convert_expression(
+        &mut self,
+        a: parser::Expression,
+        pos: Location,
+    ) -> Result<Expression, Error> {
         match a {
             parser::Expression::SingleLiteral(f) => Ok(Expression::SingleLiteral(f)),
             parser::Expression::DoubleLiteral(f) => Ok(Expression::DoubleLiteral(f)),
@@ -408,6 +1194,22 @@ impl Converter<parser::Expression, Expression> for Linter {
             parser::Expression::IntegerLiteral(f) => Ok(Expression::IntegerLiteral(f)),
             parser::Expression::LongLiteral(f) => Ok(Expression::LongLiteral(f)),
             parser::Expression::VariableName(n) => {
+                // check for a built-in function without parenthesis, e.g. ERR
+                let opt_built_in: Option<BuiltInFunction> = n.bare_name().into();
+                if let Some(b) = opt_built_in.filter(BuiltInFunction::is_parameterless) {
+                    // fails if qualified, e.g. ERR$
+                    let _: Option<BuiltInFunction> = (&n).try_into()?;
+                    return Ok(Expression::BuiltInFunctionCall(b, vec![]));
+                }
+                // check for a user defined function without parameters, e.g. GetAction$
+                if self.is_parameterless_function_call(&n) {
+                    let converted_name = self.convert(n)?;
+                    return Ok(Expression::FunctionCall(converted_name, vec![]));
+                }
+                // check for a variable of a user defined type or one of its members
+                if let Some(converted) = self.convert_user_defined_variable(&n, pos)? {
+                    return Ok(converted);
+                }
                 // check for a local constant
                 match self.context.get_constant_type(&n)? {
                     Some(q) => Ok(Expression::Constant(QualifiedName::new(
@@ -417,6 +1219,10 @@ impl Converter<parser::Expression, Expression> for Linter {
                     None => {
                         // check for an already defined local variable or parameter
                         let converted_name = self.convert(n.clone())?;
+                        if self.context.arrays.contains_qualified(&converted_name) {
+                            // an array can't be used without indices
+                            return Err(LinterError::DuplicateDefinition.into());
+                        }
                         if self.context.variables.contains_qualified(&converted_name) {
                             Ok(Expression::Variable(converted_name))
                         } else {
@@ -436,14 +1242,70 @@ impl Converter<parser::Expression, Expression> for Linter {
                     }
                 }
             }
-            parser::Expression::FunctionCall(n, args) => {
-                let converted_args = self.convert(args)?;
-                let opt_built_in: Option<BuiltInFunction> = (&n).try_into()?;
-                match opt_built_in {
-                    Some(b) => Ok(Expression::BuiltInFunctionCall(b, converted_args)),
-                    None => Ok(Expression::FunctionCall(self.convert(n)?, converted_args)),
+            parser::Expression::FunctionCall(n, args)
+                if self.context.user_defined_arrays.contains_key(n.bare_name()) =>
+            {
+                if args.is_empty() {
+                    // a whole array of a user defined type can't be used
+                    return Err(LinterError::TypeMismatch.into());
                 }
+                let (array_name, type_name, converted_indices) = self
+                    .convert_user_defined_array_element(parser::Expression::FunctionCall(
+                        n, args,
+                    ))?;
+                Ok(Expression::UserDefinedArrayElement(
+                    array_name,
+                    type_name,
+                    converted_indices,
+                ))
             }
+            parser::Expression::Member(base, member_name) => {
+                let (base, base_pos) = base.consume();
+                let (array_name, type_name, converted_indices) = self
+                    .convert_user_defined_array_element(base)
+                    .with_err_pos(base_pos)?;
+                let members = self.resolve_members(
+                    &type_name,
+                    member_name.bare_name().as_ref().split('.'),
+                    member_name.opt_qualifier(),
+                )?;
+                let array_element =
+                    Expression::UserDefinedArrayElement(array_name, type_name, converted_indices);
+                Ok(into_member_expression(array_element.at(base_pos), members))
+            }
+            parser::Expression::FunctionCall(n, args) => match self.resolve_array_name(&n)? {
+                Some(array_name) => {
+                    if args.is_empty() {
+                        // e.g. passing A() to a sub
+                        Ok(Expression::ArrayReference(array_name))
+                    } else {
+                        let converted_indices = self.convert_array_indices(args)?;
+                        Ok(Expression::ArrayElement(array_name, converted_indices))
+                    }
+                }
+                None => {
+                    let opt_built_in: Option<BuiltInFunction> = (&n).try_into()?;
+                    match opt_built_in {
+                        Some(b) => {
+                            let converted_args = self.convert_built_in_function_args(b, args)?;
+                            if b == BuiltInFunction::Len && converted_args.len() == 1 {
+                                // the length of a user defined type is known in advance
+                                if let Some(type_name) =
+                                    converted_args[0].as_ref().user_defined_type()
+                                {
+                                    let len = self.user_defined_type_len(type_name);
+                                    return Ok(Expression::IntegerLiteral(len));
+                                }
+                            }
+                            Ok(Expression::BuiltInFunctionCall(b, converted_args))
+                        }
+                        None => {
+                            let converted_args = self.convert(args)?;
+                            Ok(Expression::FunctionCall(self.convert(n)?, converted_args))
+                        }
+                    }
+                }
+            },
             parser::Expression::BinaryExpression(op, l, r) => {
                 // unbox them
                 let unboxed_left = *l;
@@ -457,10 +1319,11 @@ impl Converter<parser::Expression, Expression> for Linter {
                 // can we cast from right to left?
                 let can_cast = q_right.can_cast_to(q_left);
                 // plus extra checks
-                let is_valid_op = match op {
-                    // you can't do "A" - "B"
-                    Operand::Minus => can_cast && q_left != TypeQualifier::DollarString,
-                    _ => can_cast,
+                let is_valid_op = if op == Operand::Plus || op.is_relational() {
+                    can_cast
+                } else {
+                    // you can do "A" + "B" but not "A" - "B" or "A" AND "B"
+                    can_cast && q_left != TypeQualifier::DollarString
                 };
                 if is_valid_op {
                     Ok(Expression::BinaryExpression(
@@ -488,6 +1351,10 @@ impl Converter<parser::Expression, Expression> for Linter {
                 let converted_child = self.convert(unboxed_child)?;
                 Ok(Expression::Parenthesis(Box::new(converted_child)))
             }
+            parser::Expression::FileHandle(c) => {
+                let converted_child = self.convert_numeric_expression(*c)?;
+                Ok(Expression::FileHandle(Box::new(converted_child)))
+            }
         }
     }
 }
@@ -514,6 +1381,25 @@ impl Converter<parser::ConditionalBlockNode, ConditionalBlockNode> for Linter {
     }
 }
 
+impl Converter<parser::DoLoopNode, DoLoopNode> for Linter {
+    fn convert(&mut self, a: parser::DoLoopNode) -> Result<DoLoopNode, Error> {
+        Ok(DoLoopNode {
+            condition: self.convert(a.condition)?,
+            statements: self.convert(a.statements)?,
+        })
+    }
+}
+
+impl Converter<parser::DoLoopCondition, DoLoopCondition> for Linter {
+    fn convert(&mut self, a: parser::DoLoopCondition) -> Result<DoLoopCondition, Error> {
+        Ok(DoLoopCondition {
+            position: a.position,
+            kind: a.kind,
+            expr: self.convert(a.expr)?,
+        })
+    }
+}
+
 impl Converter<parser::IfBlockNode, IfBlockNode> for Linter {
     fn convert(&mut self, a: parser::IfBlockNode) -> Result<IfBlockNode, Error> {
         Ok(IfBlockNode {
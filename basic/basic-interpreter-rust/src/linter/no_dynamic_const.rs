@@ -10,7 +10,9 @@ impl NoDynamicConst {
         match e {
             Expression::FunctionCall(_, _)
             | Expression::BuiltInFunctionCall(_, _)
-            | Expression::Variable(_) => err_l(LinterError::InvalidConstant, e_node),
+            | Expression::Variable(_)
+            | Expression::ArrayElement(_, _)
+            | Expression::ArrayReference(_) => err_l(LinterError::InvalidConstant, e_node),
             Expression::BinaryExpression(_, left, right) => {
                 let unboxed_left: &ExpressionNode = left;
                 let unboxed_right: &ExpressionNode = right;
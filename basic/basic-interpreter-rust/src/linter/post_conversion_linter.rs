@@ -1,7 +1,7 @@
 use super::error::*;
 use super::types::*;
 use crate::common::*;
-use crate::parser::QualifiedName;
+use crate::parser::{ExitObject, QualifiedName, ResumeOption};
 
 pub trait PostConversionLinter {
     fn visit_program(&self, p: &ProgramNode) -> Result<(), Error> {
@@ -20,6 +20,7 @@ pub trait PostConversionLinter {
             TopLevelToken::FunctionImplementation(f) => self.visit_function_implementation(f),
             TopLevelToken::SubImplementation(s) => self.visit_sub_implementation(s),
             TopLevelToken::Statement(s) => self.visit_statement(s),
+            TopLevelToken::UserDefinedType(_) => Ok(()),
         }
     }
 
@@ -42,6 +43,16 @@ pub trait PostConversionLinter {
     fn visit_statement(&self, s: &Statement) -> Result<(), Error> {
         match s {
             Statement::Assignment(left, right) => self.visit_assignment(left, right),
+            Statement::ArrayElementAssignment(left, indices, right) => {
+                self.visit_array_element_assignment(left, indices, right)
+            }
+            Statement::MemberAssignment(_, _, right) => self.visit_expression(right),
+            Statement::ArrayElementMemberAssignment(_, indices, _, right) => {
+                for index in indices.iter() {
+                    self.visit_expression(index)?;
+                }
+                self.visit_expression(right)
+            }
             Statement::Const(left, right) => self.visit_const(left, right),
             Statement::SubCall(b, e) => self.visit_sub_call(b, e),
             Statement::BuiltInSubCall(b, e) => self.visit_built_in_sub_call(b, e),
@@ -49,13 +60,44 @@ pub trait PostConversionLinter {
             Statement::SelectCase(s) => self.visit_select_case(s),
             Statement::ForLoop(f) => self.visit_for_loop(f),
             Statement::While(w) => self.visit_conditional_block(w),
-            Statement::ErrorHandler(label) => self.visit_error_handler(label),
+            Statement::DoLoop(d) => self.visit_do_loop(d),
+            Statement::Exit(e) => self.visit_exit(*e),
+            Statement::ErrorHandler(Some(label)) => self.visit_error_handler(label),
+            Statement::ErrorHandler(None) => Ok(()),
+            Statement::Error(e) => self.visit_expression(e),
+            // RESUME with a label jumps to that label
+            Statement::Resume(ResumeOption::Label(label)) => self.visit_go_to(label),
+            Statement::Resume(_) => Ok(()),
             Statement::Label(label) => self.visit_label(label),
             Statement::GoTo(label) => self.visit_go_to(label),
+            Statement::GoSub(label) => self.visit_go_sub(label),
+            // RETURN with a label jumps to that label
+            Statement::Return(Some(label)) => self.visit_go_to(label),
+            Statement::Return(None) => Ok(()),
+            Statement::OnGoTo(e, labels) => {
+                self.visit_expression(e)?;
+                labels.iter().try_for_each(|label| self.visit_go_to(label))
+            }
+            Statement::OnGoSub(e, labels) => {
+                self.visit_expression(e)?;
+                labels.iter().try_for_each(|label| self.visit_go_sub(label))
+            }
             Statement::SetReturnValue(expr) => self.visit_expression(expr),
+            Statement::Dim(dim_names) | Statement::ReDim(dim_names) => {
+                dim_names.iter().try_for_each(|d| self.visit_dim_name(d))
+            }
+            Statement::Erase(_) | Statement::Comment(_) => Ok(()),
         }
     }
 
+    fn visit_dim_name(&self, d: &DimNameNode) -> Result<(), Error> {
+        for dimension in d.as_ref().dimensions.iter() {
+            self.visit_expression(&dimension.lbound)?;
+            self.visit_expression(&dimension.ubound)?;
+        }
+        Ok(())
+    }
+
     fn visit_error_handler(&self, _label: &CaseInsensitiveString) -> Result<(), Error> {
         Ok(())
     }
@@ -68,6 +110,10 @@ pub trait PostConversionLinter {
         Ok(())
     }
 
+    fn visit_go_sub(&self, _label: &CaseInsensitiveString) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn visit_sub_call(
         &self,
         _name: &CaseInsensitiveString,
@@ -88,6 +134,18 @@ pub trait PostConversionLinter {
         self.visit_expression(v)
     }
 
+    fn visit_array_element_assignment(
+        &self,
+        _name: &QualifiedName,
+        indices: &[ExpressionNode],
+        v: &ExpressionNode,
+    ) -> Result<(), Error> {
+        for index in indices.iter() {
+            self.visit_expression(index)?;
+        }
+        self.visit_expression(v)
+    }
+
     fn visit_for_loop(&self, f: &ForLoopNode) -> Result<(), Error> {
         self.visit_expression(&f.lower_bound)?;
         self.visit_expression(&f.upper_bound)?;
@@ -98,6 +156,17 @@ pub trait PostConversionLinter {
         self.visit_statement_nodes(&f.statements)
     }
 
+    fn visit_do_loop(&self, d: &DoLoopNode) -> Result<(), Error> {
+        if let Some(c) = &d.condition {
+            self.visit_expression(&c.expr)?;
+        }
+        self.visit_statement_nodes(&d.statements)
+    }
+
+    fn visit_exit(&self, _exit_object: ExitObject) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn visit_if_block(&self, i: &IfBlockNode) -> Result<(), Error> {
         self.visit_conditional_block(&i.if_block)?;
         for else_if_block in i.else_if_blocks.iter() {
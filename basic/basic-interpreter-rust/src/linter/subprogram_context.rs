@@ -1,9 +1,10 @@
 use super::error::*;
-use super::types::{BuiltInFunction, BuiltInSub};
+use super::types::{BuiltInFunction, BuiltInSub, ParamType};
 use crate::common::*;
+use crate::host::HostRegistry;
 use crate::parser;
 use crate::parser::type_resolver_impl::TypeResolverImpl;
-use crate::parser::{NameNode, NameTrait, TypeQualifier, TypeResolver};
+use crate::parser::{NameNode, NameTrait, ParamNodes, TypeQualifier, TypeResolver};
 use std::collections::HashMap;
 
 //
@@ -32,7 +33,23 @@ where
     }
 }
 
-pub type ParamTypes = Vec<TypeQualifier>;
+pub type ParamTypes = Vec<ParamType>;
+
+fn resolve_params(resolver: &TypeResolverImpl, params: &ParamNodes) -> ParamTypes {
+    params
+        .iter()
+        .map(|p| {
+            let q = resolver.resolve(p);
+            match &p.as_ref().param_type {
+                parser::DimType::UserDefined(type_name) if !p.as_ref().is_array => {
+                    ParamType::UserDefined(type_name.bare_name().clone())
+                }
+                _ if p.as_ref().is_array => ParamType::Array(q),
+                _ => ParamType::Variable(q),
+            }
+        })
+        .collect()
+}
 pub type FunctionMap = HashMap<CaseInsensitiveString, (TypeQualifier, ParamTypes, Location)>;
 
 #[derive(Debug, Default)]
@@ -50,13 +67,12 @@ impl FunctionContext {
     pub fn add_declaration(
         &mut self,
         name: &NameNode,
-        params: &Vec<NameNode>,
+        params: &ParamNodes,
         pos: Location,
     ) -> Result<(), Error> {
         // name does not have to be unique (duplicate identical declarations okay)
         // conflicting declarations to previous declaration or implementation not okay
-        let q_params: Vec<TypeQualifier> =
-            params.iter().map(|p| self.resolver.resolve(p)).collect();
+        let q_params: ParamTypes = resolve_params(&self.resolver, params);
         let q_name: TypeQualifier = self.resolver.resolve(name);
         let bare_name = name.bare_name().clone();
         self.check_implementation_type(&bare_name, &q_name, &q_params, pos)?;
@@ -72,15 +88,14 @@ impl FunctionContext {
     pub fn add_implementation(
         &mut self,
         name: &NameNode,
-        params: &Vec<NameNode>,
+        params: &ParamNodes,
         pos: Location,
     ) -> Result<(), Error> {
         // type must match declaration
         // param count must match declaration
         // param types must match declaration
         // name needs to be unique
-        let q_params: Vec<TypeQualifier> =
-            params.iter().map(|p| self.resolver.resolve(p)).collect();
+        let q_params: ParamTypes = resolve_params(&self.resolver, params);
         let q_name: TypeQualifier = self.resolver.resolve(name);
         let bare_name = name.bare_name().clone();
         match self.implementations.get(&bare_name) {
@@ -98,7 +113,7 @@ impl FunctionContext {
         &mut self,
         name: &CaseInsensitiveString,
         q_name: &TypeQualifier,
-        q_params: &Vec<TypeQualifier>,
+        q_params: &ParamTypes,
         pos: Location,
     ) -> Result<(), Error> {
         match self.declarations.get(name) {
@@ -116,7 +131,7 @@ impl FunctionContext {
         &mut self,
         name: &CaseInsensitiveString,
         q_name: &TypeQualifier,
-        q_params: &Vec<TypeQualifier>,
+        q_params: &ParamTypes,
         pos: Location,
     ) -> Result<(), Error> {
         match self.implementations.get(name) {
@@ -187,13 +202,12 @@ impl SubContext {
     pub fn add_declaration(
         &mut self,
         name: &CaseInsensitiveString,
-        params: &Vec<NameNode>,
+        params: &ParamNodes,
         pos: Location,
     ) -> Result<(), Error> {
         // name does not have to be unique (duplicate identical declarations okay)
         // conflicting declarations to previous declaration or implementation not okay
-        let q_params: Vec<TypeQualifier> =
-            params.iter().map(|p| self.resolver.resolve(p)).collect();
+        let q_params: ParamTypes = resolve_params(&self.resolver, params);
         self.check_implementation_type(name, &q_params, pos)?;
         match self.declarations.get(name) {
             Some(_) => self.check_declaration_type(name, &q_params, pos),
@@ -207,14 +221,13 @@ impl SubContext {
     pub fn add_implementation(
         &mut self,
         name: &CaseInsensitiveString,
-        params: &Vec<NameNode>,
+        params: &ParamNodes,
         pos: Location,
     ) -> Result<(), Error> {
         // param count must match declaration
         // param types must match declaration
         // name needs to be unique
-        let q_params: Vec<TypeQualifier> =
-            params.iter().map(|p| self.resolver.resolve(p)).collect();
+        let q_params: ParamTypes = resolve_params(&self.resolver, params);
         match self.implementations.get(name) {
             Some(_) => err(LinterError::DuplicateDefinition, pos),
             None => {
@@ -228,7 +241,7 @@ impl SubContext {
     fn check_declaration_type(
         &mut self,
         name: &CaseInsensitiveString,
-        q_params: &Vec<TypeQualifier>,
+        q_params: &ParamTypes,
         pos: Location,
     ) -> Result<(), Error> {
         match self.declarations.get(name) {
@@ -245,7 +258,7 @@ impl SubContext {
     fn check_implementation_type(
         &mut self,
         name: &CaseInsensitiveString,
-        q_params: &Vec<TypeQualifier>,
+        q_params: &ParamTypes,
         pos: Location,
     ) -> Result<(), Error> {
         match self.implementations.get(name) {
@@ -304,10 +317,42 @@ impl PostVisitor<parser::ProgramNode> for SubContext {
 /// - No duplicate implementations
 /// - No conflicts between declarations and implementations
 /// - Resolves types of parameters and functions
-pub fn collect_subprograms(p: &parser::ProgramNode) -> Result<(FunctionMap, SubMap), Error> {
+/// - No implementations of subprograms of the host
+pub fn collect_subprograms(
+    p: &parser::ProgramNode,
+    host: &HostRegistry,
+) -> Result<(FunctionMap, SubMap), Error> {
     let mut f_c = FunctionContext::new();
     f_c.visit(p)?;
     let mut s_c = SubContext::new();
     s_c.visit(p)?;
-    Ok((f_c.implementations, s_c.implementations))
+    let mut functions = f_c.implementations;
+    for (name, function) in host.functions() {
+        if let Some((_, _, pos)) = functions.get(name) {
+            return err(LinterError::DuplicateDefinition, *pos);
+        }
+        functions.insert(
+            name.clone(),
+            (
+                function.return_type,
+                host_param_types(&function.params),
+                Location::start(),
+            ),
+        );
+    }
+    let mut subs = s_c.implementations;
+    for (name, sub) in host.subs() {
+        if let Some((_, pos)) = subs.get(name) {
+            return err(LinterError::DuplicateDefinition, *pos);
+        }
+        subs.insert(
+            name.clone(),
+            (host_param_types(&sub.params), Location::start()),
+        );
+    }
+    Ok((functions, subs))
+}
+
+fn host_param_types(params: &[TypeQualifier]) -> ParamTypes {
+    params.iter().map(|q| ParamType::Variable(*q)).collect()
 }
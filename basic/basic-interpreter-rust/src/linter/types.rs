@@ -7,29 +7,184 @@ pub type QNameNode = Locatable<QualifiedName>;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum BuiltInFunction {
+    /// ABS
+    Abs,
+    /// ASC
+    Asc,
+    /// ATN
+    Atn,
+    /// CDBL
+    CDbl,
+    /// CHR$
+    Chr,
+    /// CINT
+    CInt,
+    /// CLNG
+    CLng,
+    /// COS
+    Cos,
+    /// CSNG
+    CSng,
+    /// CVD
+    Cvd,
+    /// CVI
+    Cvi,
+    /// CVL
+    Cvl,
+    /// CVS
+    Cvs,
+    /// DATE$, the current date as MM-DD-YYYY
+    Date,
     /// ENVIRON$
     Environ,
+    /// EOF
+    Eof,
+    /// ERL
+    Erl,
+    /// ERR
+    Err,
+    /// EXP
+    Exp,
+    /// FIX
+    Fix,
+    /// FREEFILE
+    FreeFile,
+    /// HEX$
+    Hex,
+    /// INPUT$(n[, [#]file_number]), reads n characters from the keyboard or a file
+    Input,
+    /// INSTR
+    InStr,
+    /// INT
+    Int,
+    /// LBOUND
+    LBound,
+    /// LCASE$
+    LCase,
+    /// LEFT$
+    Left,
     /// LEN
     Len,
+    /// LOC
+    Loc,
+    /// LOF
+    Lof,
+    /// LOG
+    Log,
+    /// LTRIM$
+    LTrim,
+    /// MID$
+    Mid,
+    /// MKD$
+    Mkd,
+    /// MKI$
+    Mki,
+    /// MKL$
+    Mkl,
+    /// MKS$
+    Mks,
+    /// OCT$
+    Oct,
+    /// RIGHT$
+    Right,
+    /// RND[(n)], the next random number. Zero repeats the last number and
+    /// a negative number reseeds the generator.
+    Rnd,
+    /// RTRIM$
+    RTrim,
+    /// SEEK
+    Seek,
+    /// SGN
+    Sgn,
+    /// SIN
+    Sin,
+    /// SPACE$
+    Space,
+    /// SQR
+    Sqr,
     /// STR$
     Str,
+    /// STRING$
+    String,
+    /// TAN
+    Tan,
+    /// TIME$, the current time as HH:MM:SS
+    Time,
+    /// TIMER, the number of seconds since midnight
+    Timer,
+    /// UBOUND
+    UBound,
+    /// UCASE$
+    UCase,
     /// VAL
     Val,
 }
 
+/// The names of the built-in functions, without the `$` of the ones that return a string.
+pub const BUILT_IN_FUNCTIONS: &[(&str, BuiltInFunction)] = &[
+    ("ABS", BuiltInFunction::Abs),
+    ("ASC", BuiltInFunction::Asc),
+    ("ATN", BuiltInFunction::Atn),
+    ("CDBL", BuiltInFunction::CDbl),
+    ("CHR", BuiltInFunction::Chr),
+    ("CINT", BuiltInFunction::CInt),
+    ("CLNG", BuiltInFunction::CLng),
+    ("COS", BuiltInFunction::Cos),
+    ("CSNG", BuiltInFunction::CSng),
+    ("CVD", BuiltInFunction::Cvd),
+    ("CVI", BuiltInFunction::Cvi),
+    ("CVL", BuiltInFunction::Cvl),
+    ("CVS", BuiltInFunction::Cvs),
+    ("DATE", BuiltInFunction::Date),
+    ("ENVIRON", BuiltInFunction::Environ),
+    ("EOF", BuiltInFunction::Eof),
+    ("ERL", BuiltInFunction::Erl),
+    ("ERR", BuiltInFunction::Err),
+    ("EXP", BuiltInFunction::Exp),
+    ("FIX", BuiltInFunction::Fix),
+    ("FREEFILE", BuiltInFunction::FreeFile),
+    ("HEX", BuiltInFunction::Hex),
+    ("INPUT", BuiltInFunction::Input),
+    ("INSTR", BuiltInFunction::InStr),
+    ("INT", BuiltInFunction::Int),
+    ("LBOUND", BuiltInFunction::LBound),
+    ("LCASE", BuiltInFunction::LCase),
+    ("LEFT", BuiltInFunction::Left),
+    ("LEN", BuiltInFunction::Len),
+    ("LOC", BuiltInFunction::Loc),
+    ("LOF", BuiltInFunction::Lof),
+    ("LOG", BuiltInFunction::Log),
+    ("LTRIM", BuiltInFunction::LTrim),
+    ("MID", BuiltInFunction::Mid),
+    ("MKD", BuiltInFunction::Mkd),
+    ("MKI", BuiltInFunction::Mki),
+    ("MKL", BuiltInFunction::Mkl),
+    ("MKS", BuiltInFunction::Mks),
+    ("OCT", BuiltInFunction::Oct),
+    ("RIGHT", BuiltInFunction::Right),
+    ("RND", BuiltInFunction::Rnd),
+    ("RTRIM", BuiltInFunction::RTrim),
+    ("SEEK", BuiltInFunction::Seek),
+    ("SGN", BuiltInFunction::Sgn),
+    ("SIN", BuiltInFunction::Sin),
+    ("SPACE", BuiltInFunction::Space),
+    ("SQR", BuiltInFunction::Sqr),
+    ("STR", BuiltInFunction::Str),
+    ("STRING", BuiltInFunction::String),
+    ("TAN", BuiltInFunction::Tan),
+    ("TIME", BuiltInFunction::Time),
+    ("TIMER", BuiltInFunction::Timer),
+    ("UBOUND", BuiltInFunction::UBound),
+    ("UCASE", BuiltInFunction::UCase),
+    ("VAL", BuiltInFunction::Val),
+];
+
 impl From<&CaseInsensitiveString> for Option<BuiltInFunction> {
     fn from(s: &CaseInsensitiveString) -> Option<BuiltInFunction> {
-        if s == "ENVIRON" {
-            Some(BuiltInFunction::Environ)
-        } else if s == "LEN" {
-            Some(BuiltInFunction::Len)
-        } else if s == "STR" {
-            Some(BuiltInFunction::Str)
-        } else if s == "VAL" {
-            Some(BuiltInFunction::Val)
-        } else {
-            None
-        }
+        BUILT_IN_FUNCTIONS
+            .iter()
+            .find(|(name, _)| s == *name)
+            .map(|(_, x)| *x)
     }
 }
 
@@ -39,8 +194,60 @@ impl TryFrom<&Name> for Option<BuiltInFunction> {
         let opt_built_in: Option<BuiltInFunction> = name.bare_name().into();
         match opt_built_in {
             Some(b) => match b {
-                BuiltInFunction::Environ => {
-                    // ENVIRON$ must be qualified
+                BuiltInFunction::Asc
+                | BuiltInFunction::Cvd
+                | BuiltInFunction::Cvi
+                | BuiltInFunction::Cvl
+                | BuiltInFunction::Cvs
+                | BuiltInFunction::InStr
+                | BuiltInFunction::Loc
+                | BuiltInFunction::Lof
+                | BuiltInFunction::Seek => {
+                    // ASC, CVD, CVI, CVL, CVS, INSTR, LOC, LOF and SEEK must be unqualified
+                    match name {
+                        Name::Bare(_) => Ok(Some(b)),
+                        Name::Qualified(_) => err_no_pos(LinterError::SyntaxError),
+                    }
+                }
+                BuiltInFunction::Abs
+                | BuiltInFunction::Atn
+                | BuiltInFunction::CDbl
+                | BuiltInFunction::CInt
+                | BuiltInFunction::CLng
+                | BuiltInFunction::Cos
+                | BuiltInFunction::CSng
+                | BuiltInFunction::Exp
+                | BuiltInFunction::Fix
+                | BuiltInFunction::Int
+                | BuiltInFunction::Log
+                | BuiltInFunction::Sgn
+                | BuiltInFunction::Sin
+                | BuiltInFunction::Sqr
+                | BuiltInFunction::Tan => {
+                    // numeric functions like ABS and CINT must be unqualified
+                    match name {
+                        Name::Bare(_) => Ok(Some(b)),
+                        Name::Qualified(_) => err_no_pos(LinterError::SyntaxError),
+                    }
+                }
+                BuiltInFunction::Chr
+                | BuiltInFunction::Hex
+                | BuiltInFunction::Input
+                | BuiltInFunction::LCase
+                | BuiltInFunction::Left
+                | BuiltInFunction::LTrim
+                | BuiltInFunction::Mid
+                | BuiltInFunction::Mkd
+                | BuiltInFunction::Mki
+                | BuiltInFunction::Mkl
+                | BuiltInFunction::Mks
+                | BuiltInFunction::Oct
+                | BuiltInFunction::Right
+                | BuiltInFunction::RTrim
+                | BuiltInFunction::Space
+                | BuiltInFunction::String
+                | BuiltInFunction::UCase => {
+                    // string functions like LEFT$ and MKI$ must be qualified
                     match name {
                         Name::Bare(_) => err_no_pos(LinterError::SyntaxError),
                         Name::Qualified(q) => {
@@ -52,6 +259,38 @@ impl TryFrom<&Name> for Option<BuiltInFunction> {
                         }
                     }
                 }
+                BuiltInFunction::Date | BuiltInFunction::Environ | BuiltInFunction::Time => {
+                    // DATE$, ENVIRON$ and TIME$ must be qualified
+                    match name {
+                        Name::Bare(_) => err_no_pos(LinterError::SyntaxError),
+                        Name::Qualified(q) => {
+                            if q.qualifier() == TypeQualifier::DollarString {
+                                Ok(Some(b))
+                            } else {
+                                err_no_pos(LinterError::TypeMismatch)
+                            }
+                        }
+                    }
+                }
+                BuiltInFunction::Eof
+                | BuiltInFunction::Erl
+                | BuiltInFunction::Err
+                | BuiltInFunction::FreeFile
+                | BuiltInFunction::Rnd
+                | BuiltInFunction::Timer => {
+                    // EOF, ERL, ERR, FREEFILE, RND and TIMER must be unqualified
+                    match name {
+                        Name::Bare(_) => Ok(Some(b)),
+                        Name::Qualified(_) => err_no_pos(LinterError::SyntaxError),
+                    }
+                }
+                BuiltInFunction::LBound | BuiltInFunction::UBound => {
+                    // LBOUND and UBOUND must be unqualified
+                    match name {
+                        Name::Bare(_) => Ok(Some(b)),
+                        Name::Qualified(_) => err_no_pos(LinterError::SyntaxError),
+                    }
+                }
                 BuiltInFunction::Len => {
                     // LEN must be unqualified
                     match name {
@@ -86,12 +325,109 @@ impl TryFrom<&Name> for Option<BuiltInFunction> {
     }
 }
 
+impl BuiltInFunction {
+    /// The name of the function, with `$` if it returns a string, e.g. `LEFT$`.
+    pub fn name(&self) -> String {
+        let (name, _) = BUILT_IN_FUNCTIONS.iter().find(|(_, f)| f == self).unwrap();
+        if self.qualifier() == TypeQualifier::DollarString {
+            format!("{}$", name)
+        } else {
+            name.to_string()
+        }
+    }
+    /// Checks if the function can be used without parenthesis, e.g. `ERR`.
+    /// `RND` can also be called with an argument.
+    pub fn is_parameterless(&self) -> bool {
+        matches!(
+            self,
+            Self::Date
+                | Self::Erl
+                | Self::Err
+                | Self::FreeFile
+                | Self::Rnd
+                | Self::Time
+                | Self::Timer
+        )
+    }
+
+    /// Gets the type of the result of calling the function with the given arguments.
+    /// Most functions have a fixed type, but `ABS`, `FIX` and `INT` return the type of
+    /// their argument, while `SQR`, `SIN` etc. return a double only for a double argument.
+    pub fn try_result_qualifier(&self, args: &[ExpressionNode]) -> Result<TypeQualifier, Error> {
+        match (self, args.first()) {
+            (Self::Abs | Self::Fix | Self::Int, Some(arg)) => arg.as_ref().try_qualifier(),
+            (
+                Self::Atn | Self::Cos | Self::Exp | Self::Log | Self::Sin | Self::Sqr | Self::Tan,
+                Some(arg),
+            ) => {
+                if arg.as_ref().try_qualifier()? == TypeQualifier::HashDouble {
+                    Ok(TypeQualifier::HashDouble)
+                } else {
+                    Ok(TypeQualifier::BangSingle)
+                }
+            }
+            _ => Ok(self.qualifier()),
+        }
+    }
+}
+
 impl HasQualifier for BuiltInFunction {
     fn qualifier(&self) -> TypeQualifier {
         match self {
+            Self::Abs => TypeQualifier::BangSingle,
+            Self::Asc => TypeQualifier::PercentInteger,
+            Self::Atn => TypeQualifier::BangSingle,
+            Self::CDbl => TypeQualifier::HashDouble,
+            Self::Chr => TypeQualifier::DollarString,
+            Self::CInt => TypeQualifier::PercentInteger,
+            Self::CLng => TypeQualifier::AmpersandLong,
+            Self::Cos => TypeQualifier::BangSingle,
+            Self::CSng => TypeQualifier::BangSingle,
+            Self::Cvd => TypeQualifier::HashDouble,
+            Self::Cvi => TypeQualifier::PercentInteger,
+            Self::Cvl => TypeQualifier::AmpersandLong,
+            Self::Cvs => TypeQualifier::BangSingle,
+            Self::Date => TypeQualifier::DollarString,
             Self::Environ => TypeQualifier::DollarString,
+            Self::Eof => TypeQualifier::PercentInteger,
+            Self::Erl => TypeQualifier::AmpersandLong,
+            Self::Err => TypeQualifier::PercentInteger,
+            Self::Exp => TypeQualifier::BangSingle,
+            Self::Fix => TypeQualifier::BangSingle,
+            Self::FreeFile => TypeQualifier::PercentInteger,
+            Self::Hex => TypeQualifier::DollarString,
+            Self::Input => TypeQualifier::DollarString,
+            Self::InStr => TypeQualifier::PercentInteger,
+            Self::Int => TypeQualifier::BangSingle,
+            Self::LBound => TypeQualifier::PercentInteger,
+            Self::LCase => TypeQualifier::DollarString,
+            Self::Left => TypeQualifier::DollarString,
             Self::Len => TypeQualifier::PercentInteger,
+            Self::Loc => TypeQualifier::AmpersandLong,
+            Self::Lof => TypeQualifier::AmpersandLong,
+            Self::Log => TypeQualifier::BangSingle,
+            Self::LTrim => TypeQualifier::DollarString,
+            Self::Mid => TypeQualifier::DollarString,
+            Self::Mkd => TypeQualifier::DollarString,
+            Self::Mki => TypeQualifier::DollarString,
+            Self::Mkl => TypeQualifier::DollarString,
+            Self::Mks => TypeQualifier::DollarString,
+            Self::Oct => TypeQualifier::DollarString,
+            Self::Right => TypeQualifier::DollarString,
+            Self::Rnd => TypeQualifier::BangSingle,
+            Self::RTrim => TypeQualifier::DollarString,
+            Self::Seek => TypeQualifier::AmpersandLong,
+            Self::Sgn => TypeQualifier::PercentInteger,
+            Self::Sin => TypeQualifier::BangSingle,
+            Self::Space => TypeQualifier::DollarString,
+            Self::Sqr => TypeQualifier::BangSingle,
             Self::Str => TypeQualifier::DollarString,
+            Self::String => TypeQualifier::DollarString,
+            Self::Tan => TypeQualifier::BangSingle,
+            Self::Time => TypeQualifier::DollarString,
+            Self::Timer => TypeQualifier::BangSingle,
+            Self::UBound => TypeQualifier::PercentInteger,
+            Self::UCase => TypeQualifier::DollarString,
             Self::Val => TypeQualifier::BangSingle,
         }
     }
@@ -99,25 +435,86 @@ impl HasQualifier for BuiltInFunction {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum BuiltInSub {
+    /// `CLOSE [#]n[, ...]`, without arguments it closes all files
+    Close,
     Environ,
+    /// `FIELD #n, width AS A$, ...`. The arguments are the file number, followed by
+    /// the width and variable pairs.
+    Field,
+    /// `GET #n, record, variable`. A record of zero means the next record.
+    Get,
     Input,
+    /// `KILL file_name`, deletes a file
+    Kill,
+    /// `LINE INPUT [#n,] A$`. The first argument is the file number, or zero for the keyboard.
+    LineInput,
+    /// `LPRINT ...`, which has the same arguments as `PRINT` without the file number.
+    LPrint,
+    /// `LSET A$ = value`
+    LSet,
+    /// `MID$(A$, start[, length]) = value`. The arguments are the variable, the value,
+    /// the start and the optional length.
+    Mid,
+    /// `NAME old_file_name AS new_file_name`, renames a file
+    Name,
+    /// `OPEN`. The arguments are the file name, the mode, the access, the file number
+    /// and the record length.
+    Open,
+    /// `PRINT [#n,] ...`. The first argument is the file number, or zero for the screen.
     Print,
+    /// `PUT #n, record, variable`. A record of zero means the next record.
+    Put,
+    /// `RANDOMIZE [seed]`, without a seed it asks for one
+    Randomize,
+    /// `RSET A$ = value`
+    RSet,
+    /// `SEEK #n, position`
+    Seek,
+    /// `SLEEP [seconds]`, without seconds (or zero) it waits for a key
+    Sleep,
     System,
+    /// `WRITE [#n,] ...`. The first argument is the file number, or zero for the screen.
+    Write,
 }
 
+/// The names of the built-in statements that are parsed as calls of subs.
+pub const BUILT_IN_SUBS: &[(&str, BuiltInSub)] = &[
+    ("CLOSE", BuiltInSub::Close),
+    ("ENVIRON", BuiltInSub::Environ),
+    ("FIELD", BuiltInSub::Field),
+    ("GET", BuiltInSub::Get),
+    ("INPUT", BuiltInSub::Input),
+    ("KILL", BuiltInSub::Kill),
+    ("LINE INPUT", BuiltInSub::LineInput),
+    ("LPRINT", BuiltInSub::LPrint),
+    ("LSET", BuiltInSub::LSet),
+    ("MID$", BuiltInSub::Mid),
+    ("NAME", BuiltInSub::Name),
+    ("OPEN", BuiltInSub::Open),
+    ("PRINT", BuiltInSub::Print),
+    ("PUT", BuiltInSub::Put),
+    ("RANDOMIZE", BuiltInSub::Randomize),
+    ("RSET", BuiltInSub::RSet),
+    ("SEEK", BuiltInSub::Seek),
+    ("SLEEP", BuiltInSub::Sleep),
+    ("SYSTEM", BuiltInSub::System),
+    ("WRITE", BuiltInSub::Write),
+];
+
 impl From<&CaseInsensitiveString> for Option<BuiltInSub> {
     fn from(s: &CaseInsensitiveString) -> Option<BuiltInSub> {
-        if s == "ENVIRON" {
-            Some(BuiltInSub::Environ)
-        } else if s == "INPUT" {
-            Some(BuiltInSub::Input)
-        } else if s == "PRINT" {
-            Some(BuiltInSub::Print)
-        } else if s == "SYSTEM" {
-            Some(BuiltInSub::System)
-        } else {
-            None
-        }
+        BUILT_IN_SUBS
+            .iter()
+            .find(|(name, _)| s == *name)
+            .map(|(_, x)| *x)
+    }
+}
+
+impl BuiltInSub {
+    /// The name of the statement, e.g. `LINE INPUT`.
+    pub fn name(&self) -> &'static str {
+        let (name, _) = BUILT_IN_SUBS.iter().find(|(_, s)| s == self).unwrap();
+        name
     }
 }
 
@@ -133,9 +530,22 @@ pub enum Expression {
     Variable(QualifiedName),
     FunctionCall(QualifiedName, Vec<ExpressionNode>),
     BuiltInFunctionCall(BuiltInFunction, Vec<ExpressionNode>),
+    /// An element of an array, e.g. `A(1, 2)`
+    ArrayElement(QualifiedName, Vec<ExpressionNode>),
+    /// A whole array, e.g. `A()` when passed to a sub or `A` in `LBOUND(A)`
+    ArrayReference(QualifiedName),
+    /// A variable of a user defined type, e.g. `P` in `DIM P AS Card`.
+    /// The second field is the name of the type.
+    UserDefinedVariable(QualifiedName, BareName),
+    /// An element of an array of a user defined type, e.g. `A(1)` in `DIM A(10) AS Card`.
+    UserDefinedArrayElement(QualifiedName, BareName, Vec<ExpressionNode>),
+    /// A member of a user defined type, e.g. `P.Suit`
+    Member(Box<ExpressionNode>, BareName, ElementType),
     BinaryExpression(Operand, Box<ExpressionNode>, Box<ExpressionNode>),
     UnaryExpression(UnaryOperand, Box<ExpressionNode>),
     Parenthesis(Box<ExpressionNode>),
+    /// A file number, e.g. `#1` in `PRINT #1, A$`
+    FileHandle(Box<ExpressionNode>),
 }
 
 impl Expression {
@@ -149,18 +559,57 @@ impl Expression {
             Self::Variable(name) | Self::Constant(name) | Self::FunctionCall(name, _) => {
                 Ok(name.qualifier())
             }
-            Self::BuiltInFunctionCall(f, _) => Ok(f.qualifier()),
+            Self::BuiltInFunctionCall(f, args) => f.try_result_qualifier(args),
+            Self::ArrayElement(name, _) => Ok(name.qualifier()),
+            // a whole array can only be used as an argument
+            Self::ArrayReference(_) => err_no_pos(LinterError::TypeMismatch),
+            // a user defined type can only be assigned or used as an argument
+            Self::UserDefinedVariable(_, _) | Self::UserDefinedArrayElement(_, _, _) => {
+                err_no_pos(LinterError::TypeMismatch)
+            }
+            Self::Member(_, _, element_type) => match element_type {
+                ElementType::BuiltIn(q) => Ok(*q),
+                ElementType::FixedLengthString(_) => Ok(TypeQualifier::DollarString),
+                ElementType::UserDefined(_) => err_no_pos(LinterError::TypeMismatch),
+            },
             Self::BinaryExpression(op, l, r) => {
                 let q_left = l.as_ref().as_ref().try_qualifier()?;
                 let q_right = r.as_ref().as_ref().try_qualifier()?;
                 if q_left.can_cast_to(q_right) {
                     match op {
-                        Operand::Plus | Operand::Minus => Ok(q_left),
+                        Operand::Plus | Operand::Minus | Operand::Multiply => {
+                            Ok(q_left.promote(q_right))
+                        }
+                        Operand::Divide | Operand::Power => {
+                            if q_left == TypeQualifier::HashDouble
+                                || q_right == TypeQualifier::HashDouble
+                            {
+                                Ok(TypeQualifier::HashDouble)
+                            } else {
+                                Ok(TypeQualifier::BangSingle)
+                            }
+                        }
+                        Operand::IntegerDivide
+                        | Operand::Modulo
+                        | Operand::And
+                        | Operand::Or
+                        | Operand::Xor
+                        | Operand::Eqv
+                        | Operand::Imp => {
+                            if q_left == TypeQualifier::PercentInteger
+                                && q_right == TypeQualifier::PercentInteger
+                            {
+                                Ok(TypeQualifier::PercentInteger)
+                            } else {
+                                Ok(TypeQualifier::AmpersandLong)
+                            }
+                        }
                         Operand::Less
                         | Operand::LessOrEqual
                         | Operand::Equal
                         | Operand::GreaterOrEqual
-                        | Operand::Greater => Ok(TypeQualifier::PercentInteger),
+                        | Operand::Greater
+                        | Operand::NotEqual => Ok(TypeQualifier::PercentInteger),
                     }
                 } else {
                     err(LinterError::TypeMismatch, r.as_ref().location())
@@ -175,13 +624,106 @@ impl Expression {
                     Ok(q_child)
                 }
             }
-            Self::Parenthesis(c) => c.as_ref().as_ref().try_qualifier(),
+            Self::Parenthesis(c) | Self::FileHandle(c) => c.as_ref().as_ref().try_qualifier(),
+        }
+    }
+
+    /// Gets the name of the user defined type of this expression,
+    /// or `None` if it is not a user defined type.
+    pub fn user_defined_type(&self) -> Option<&BareName> {
+        match self {
+            Self::UserDefinedVariable(_, type_name)
+            | Self::UserDefinedArrayElement(_, type_name, _)
+            | Self::Member(_, _, ElementType::UserDefined(type_name)) => Some(type_name),
+            Self::Parenthesis(c) => c.as_ref().as_ref().user_defined_type(),
+            _ => None,
+        }
+    }
+
+    /// Checks if this expression can be evaluated before running the program,
+    /// i.e. it consists only of literals and constants.
+    pub fn is_constant(&self) -> bool {
+        match self {
+            Self::SingleLiteral(_)
+            | Self::DoubleLiteral(_)
+            | Self::StringLiteral(_)
+            | Self::IntegerLiteral(_)
+            | Self::LongLiteral(_)
+            | Self::Constant(_) => true,
+            Self::BinaryExpression(_, l, r) => {
+                l.as_ref().as_ref().is_constant() && r.as_ref().as_ref().is_constant()
+            }
+            Self::UnaryExpression(_, c) | Self::Parenthesis(c) => c.as_ref().as_ref().is_constant(),
+            _ => false,
         }
     }
 }
 
 pub type ExpressionNode = Locatable<Expression>;
 
+/// The type of a parameter of a function or sub.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamType {
+    /// A variable, e.g. `N$`
+    Variable(TypeQualifier),
+    /// An array, e.g. `A()`
+    Array(TypeQualifier),
+    /// A variable of a user defined type, e.g. `P AS Card`
+    UserDefined(BareName),
+}
+
+/// The type of an element of a user defined type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElementType {
+    /// A numeric element, e.g. `Value AS INTEGER`
+    BuiltIn(TypeQualifier),
+    /// A fixed length string, e.g. `Suit AS STRING * 9`
+    FixedLengthString(u16),
+    /// An element of a user defined type, e.g. `Address AS Address`
+    UserDefined(BareName),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: BareName,
+    pub element_type: ElementType,
+}
+
+/// A user defined type, declared with a `TYPE` block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserDefinedType {
+    pub name: BareName,
+    pub elements: Vec<Element>,
+}
+
+impl UserDefinedType {
+    pub fn find_element(&self, name: &BareName) -> Option<&Element> {
+        self.elements.iter().find(|e| &e.name == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrayDimension {
+    pub lbound: ExpressionNode,
+    pub ubound: ExpressionNode,
+}
+
+/// A variable or an array declared by a `DIM` or `REDIM` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DimName {
+    pub name: QualifiedName,
+    /// The dimensions of an array. It is empty for a variable.
+    pub dimensions: Vec<ArrayDimension>,
+    /// Dynamic arrays can be re-dimensioned and are deallocated by `ERASE`.
+    pub is_dynamic: bool,
+    /// The length of a fixed length string, e.g. `AS STRING * 5`.
+    pub fixed_length: Option<u16>,
+    /// The name of the type of a user defined type, e.g. `AS Card`.
+    pub user_defined_type: Option<BareName>,
+}
+
+pub type DimNameNode = Locatable<DimName>;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ForLoopNode {
     pub variable_name: QNameNode,
@@ -192,6 +734,19 @@ pub struct ForLoopNode {
     pub next_counter: Option<QNameNode>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoLoopNode {
+    pub condition: Option<DoLoopCondition>,
+    pub statements: StatementNodes,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoLoopCondition {
+    pub position: DoLoopConditionPosition,
+    pub kind: DoLoopConditionKind,
+    pub expr: ExpressionNode,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConditionalBlockNode {
     pub condition: ExpressionNode,
@@ -231,7 +786,22 @@ pub enum CaseExpression {
 #[derive(Clone, Debug, PartialEq)]
 pub enum Statement {
     Assignment(QualifiedName, ExpressionNode),
+    ArrayElementAssignment(QualifiedName, Vec<ExpressionNode>, ExpressionNode),
+    /// Assignment to a member of a variable of a user defined type,
+    /// e.g. `P.Suit = "Hearts"`. Nested members are listed in order.
+    MemberAssignment(QualifiedName, Vec<BareName>, ExpressionNode),
+    /// Assignment to a member of an array element of a user defined type,
+    /// e.g. `A(1).Suit = "Hearts"`
+    ArrayElementMemberAssignment(
+        QualifiedName,
+        Vec<ExpressionNode>,
+        Vec<BareName>,
+        ExpressionNode,
+    ),
     Const(QNameNode, ExpressionNode),
+    Dim(Vec<DimNameNode>),
+    ReDim(Vec<DimNameNode>),
+    Erase(Vec<QNameNode>),
     SubCall(BareName, Vec<ExpressionNode>),
     BuiltInSubCall(BuiltInSub, Vec<ExpressionNode>),
 
@@ -240,10 +810,19 @@ pub enum Statement {
 
     ForLoop(ForLoopNode),
     While(ConditionalBlockNode),
+    DoLoop(DoLoopNode),
+    Exit(ExitObject),
 
-    ErrorHandler(CaseInsensitiveString),
+    ErrorHandler(Option<CaseInsensitiveString>),
+    Error(ExpressionNode),
+    Resume(ResumeOption),
     Label(CaseInsensitiveString),
     GoTo(CaseInsensitiveString),
+    GoSub(CaseInsensitiveString),
+    Return(Option<CaseInsensitiveString>),
+    OnGoTo(ExpressionNode, Vec<CaseInsensitiveString>),
+    OnGoSub(ExpressionNode, Vec<CaseInsensitiveString>),
+    Comment(String),
 
     SetReturnValue(ExpressionNode),
 }
@@ -275,6 +854,9 @@ pub enum TopLevelToken {
 
     /// A sub implementation
     SubImplementation(SubImplementation),
+
+    /// A user defined type
+    UserDefinedType(UserDefinedType),
 }
 
 pub type TopLevelTokenNode = Locatable<TopLevelToken>;
@@ -39,6 +39,22 @@ impl<'a> ExpressionReducer for UndefinedFunctionReducer<'a> {
                 name,
                 self.visit_expression_nodes(args)?,
             )),
+            Expression::ArrayElement(name, indices) => Ok(Expression::ArrayElement(
+                name,
+                self.visit_expression_nodes(indices)?,
+            )),
+            Expression::UserDefinedArrayElement(name, type_name, indices) => {
+                Ok(Expression::UserDefinedArrayElement(
+                    name,
+                    type_name,
+                    self.visit_expression_nodes(indices)?,
+                ))
+            }
+            Expression::Member(base, name, element_type) => Ok(Expression::Member(
+                Box::new(self.visit_expression_node(*base)?),
+                name,
+                element_type,
+            )),
             _ => Ok(expression),
         }
     }
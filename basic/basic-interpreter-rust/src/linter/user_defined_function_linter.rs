@@ -1,6 +1,6 @@
 use super::error::*;
 use super::post_conversion_linter::PostConversionLinter;
-use super::subprogram_context::FunctionMap;
+use super::subprogram_context::{FunctionMap, ParamTypes};
 use super::types::*;
 use crate::common::*;
 use crate::parser::{HasQualifier, NameTrait, QualifiedName, TypeQualifier};
@@ -10,16 +10,39 @@ pub struct UserDefinedFunctionLinter<'a> {
 }
 
 type ExpressionNodes = Vec<ExpressionNode>;
-type TypeQualifiers = Vec<TypeQualifier>;
 type Result = std::result::Result<(), Error>;
 
-pub fn lint_call_args(args: &ExpressionNodes, param_types: &TypeQualifiers) -> Result {
+pub fn lint_call_args(args: &ExpressionNodes, param_types: &ParamTypes) -> Result {
     if args.len() != param_types.len() {
         return err_no_pos(LinterError::ArgumentCountMismatch);
     }
 
     for (arg_node, param_type) in args.iter().zip(param_types.iter()) {
         let arg = arg_node.as_ref();
+        let param_type = match param_type {
+            ParamType::Array(q) => {
+                // arrays are passed by ref, the element type needs to match exactly
+                match arg {
+                    Expression::ArrayReference(n) if n.qualifier() == *q => continue,
+                    _ => return err_l(LinterError::ArgumentTypeMismatch, arg_node),
+                }
+            }
+            ParamType::UserDefined(type_name) => {
+                // by ref for a variable, by val otherwise, the type needs to match exactly
+                if arg.user_defined_type() == Some(type_name) {
+                    continue;
+                } else {
+                    return err_l(LinterError::ArgumentTypeMismatch, arg_node);
+                }
+            }
+            ParamType::Variable(q) => q,
+        };
+        if let Expression::ArrayReference(_) = arg {
+            return err_l(LinterError::ArgumentTypeMismatch, arg_node);
+        }
+        if arg.user_defined_type().is_some() {
+            return err_l(LinterError::ArgumentTypeMismatch, arg_node);
+        }
         let arg_q = arg.try_qualifier()?;
         match arg {
             Expression::Variable(_) => {
@@ -85,6 +108,14 @@ impl<'a> PostConversionLinter for UserDefinedFunctionLinter<'a> {
                 self.visit_expression(right)
             }
             Expression::UnaryExpression(_, child) => self.visit_expression(child),
+            Expression::ArrayElement(_, indices)
+            | Expression::UserDefinedArrayElement(_, _, indices) => {
+                for x in indices {
+                    self.visit_expression(x)?;
+                }
+                Ok(())
+            }
+            Expression::Member(base, _, _) => self.visit_expression(base),
             _ => Ok(()),
         }
     }
@@ -15,6 +15,13 @@ impl<'a> PostConversionLinter for UserDefinedSubLinter<'a> {
         name: &CaseInsensitiveString,
         args: &Vec<ExpressionNode>,
     ) -> Result<(), Error> {
+        // a file number can only be passed to a built-in sub, e.g. PRINT #1
+        if let Some(a) = args
+            .iter()
+            .find(|a| matches!(a.as_ref(), Expression::FileHandle(_)))
+        {
+            return err_l(LinterError::ArgumentTypeMismatch, a);
+        }
         match self.subs.get(name) {
             Some((param_types, _)) => lint_call_args(args, param_types),
             None => err_no_pos(LinterError::SubprogramNotDefined),
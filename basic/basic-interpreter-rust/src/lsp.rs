@@ -0,0 +1,740 @@
+//! A server of the Language Server Protocol, so that editors can check programs
+//! while they are written.
+//!
+//! The messages are JSON-RPC objects with a `Content-Length` header, on the standard
+//! input and output. The documents are synchronized in full. The server supports:
+//!
+//! - diagnostics, with the first error of the parser or the linter
+//! - completion of the SUBs and FUNCTIONs of the document and of the built-in ones
+//! - hover, which shows the declaration of a SUB or FUNCTION
+//! - go to definition, for SUBs, FUNCTIONs and labels
+//! - document symbols, for SUBs, FUNCTIONs and labels
+
+use crate::common::*;
+use crate::error::Error;
+use crate::host::HostRegistry;
+use crate::json::{read_message, write_message, Json};
+use crate::linter;
+use crate::linter::{
+    collect_subprograms, BuiltInFunction, BuiltInSub, BUILT_IN_FUNCTIONS, BUILT_IN_SUBS,
+};
+use crate::parser::{Parser, ProgramNode, Statement, TopLevelToken};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+// The kinds of the protocol
+const COMPLETION_FUNCTION: u32 = 3;
+const COMPLETION_KEYWORD: u32 = 14;
+const SYMBOL_FUNCTION: u32 = 12;
+/// There is no kind for labels, the editors show keys with a similar icon
+const SYMBOL_KEY: u32 = 20;
+const SEVERITY_ERROR: u32 = 1;
+
+const METHOD_NOT_FOUND: f64 = -32601.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SymbolKind {
+    Sub,
+    Function,
+    Label,
+}
+
+#[derive(Clone, Debug)]
+struct Symbol {
+    name: CaseInsensitiveString,
+    kind: SymbolKind,
+    /// The location of `SUB` or `FUNCTION`, or of the label
+    pos: Location,
+}
+
+/// An open document.
+#[derive(Debug, Default)]
+struct Document {
+    lines: Vec<String>,
+    /// The symbols of the last version that could be parsed,
+    /// so that they are available while a line is being written
+    symbols: Vec<Symbol>,
+}
+
+impl Document {
+    fn line(&self, row: u32) -> &str {
+        match row {
+            0 => "",
+            _ => self
+                .lines
+                .get(row as usize - 1)
+                .map(String::as_str)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn find(&self, name: &CaseInsensitiveString) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| &s.name == name)
+    }
+
+    /// The symbol that the word at the given position refers to.
+    fn find_at(&self, params: &Json) -> Option<&Symbol> {
+        word_at(self, params).and_then(|word| self.find(&CaseInsensitiveString::from(word)))
+    }
+
+    /// The range from the given location to the end of its line.
+    fn line_range(&self, pos: Location) -> Json {
+        let end = Location::new(pos.row(), self.line(pos.row()).chars().count() as u32 + 1);
+        range(pos, end)
+    }
+}
+
+/// Parses and lints the program. Returns the first error and, if the program
+/// could be parsed, its symbols.
+fn analyze(text: &str) -> (Option<Error>, Option<Vec<Symbol>>) {
+    let program = match Parser::from(text).parse() {
+        Ok(program) => program,
+        Err(e) => return (Some(e.into()), None),
+    };
+    let symbols = symbols(&program);
+    let error = linter::lint(program).err().map(Error::from);
+    (error, Some(symbols))
+}
+
+fn symbols(program: &ProgramNode) -> Vec<Symbol> {
+    // the linter reports the conflicting SUBs and FUNCTIONs
+    let (functions, subs) =
+        collect_subprograms(program, &HostRegistry::default()).unwrap_or_default();
+    let mut symbols: Vec<Symbol> = functions
+        .into_iter()
+        .map(|(name, (_, _, pos))| Symbol {
+            name,
+            kind: SymbolKind::Function,
+            pos,
+        })
+        .chain(subs.into_iter().map(|(name, (_, pos))| Symbol {
+            name,
+            kind: SymbolKind::Sub,
+            pos,
+        }))
+        .collect();
+    let mut add_label = |statement: &Statement, pos: Location| {
+        if let Statement::Label(name) = statement {
+            symbols.push(Symbol {
+                name: name.clone(),
+                kind: SymbolKind::Label,
+                pos,
+            });
+        }
+    };
+    for t in program {
+        match t.as_ref() {
+            TopLevelToken::Statement(s) => add_label(s, t.location()),
+            TopLevelToken::SubImplementation(_, _, body)
+            | TopLevelToken::FunctionImplementation(_, _, body) => {
+                for s in body {
+                    add_label(s.as_ref(), s.location());
+                }
+            }
+            _ => {}
+        }
+    }
+    symbols.sort_by_key(|s| (s.pos.row(), s.pos.col()));
+    symbols
+}
+
+/// The name at the position of a request, without its type qualifier.
+fn word_at<'a>(document: &'a Document, params: &Json) -> Option<&'a str> {
+    let position = params.get("position")?;
+    let row = position.get("line").and_then(Json::as_u32)? + 1;
+    let character = position.get("character").and_then(Json::as_u32)? as usize;
+    let line = document.line(row);
+    let is_name = |ch: char| ch.is_ascii_alphanumeric() || ch == '.';
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    // the cursor can be right after the name
+    let mut start = character.min(chars.len());
+    while start > 0 && is_name(chars[start - 1].1) {
+        start -= 1;
+    }
+    let mut end = start;
+    while end < chars.len() && is_name(chars[end].1) {
+        end += 1;
+    }
+    if start == end {
+        return None;
+    }
+    let byte_end = chars.get(end).map(|(i, _)| *i).unwrap_or(line.len());
+    Some(&line[chars[start].0..byte_end])
+}
+
+fn position(pos: Location) -> Json {
+    Json::object(vec![
+        ("line", Json::from(pos.row().saturating_sub(1))),
+        ("character", Json::from(pos.col().saturating_sub(1))),
+    ])
+}
+
+fn range(start: Location, end: Location) -> Json {
+    Json::object(vec![("start", position(start)), ("end", position(end))])
+}
+
+fn uri(params: &Json) -> String {
+    params
+        .get("textDocument")
+        .and_then(|d| d.get("uri"))
+        .and_then(Json::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Serves the Language Server Protocol.
+///
+/// ```no_run
+/// use basic_interpreter_rust::LspServer;
+/// use std::io::{self, BufReader};
+///
+/// LspServer::new(BufReader::new(io::stdin()), io::stdout()).run();
+/// ```
+#[derive(Debug)]
+pub struct LspServer<R, W> {
+    input: R,
+    output: W,
+    documents: HashMap<String, Document>,
+}
+
+impl<R: BufRead, W: Write> LspServer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Handles the messages until the client sends `exit`.
+    pub fn run(&mut self) {
+        while let Some(message) = read_message(&mut self.input) {
+            let method = message
+                .get("method")
+                .and_then(Json::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if method == "exit" {
+                return;
+            }
+            let params = message.get("params").cloned().unwrap_or(Json::Null);
+            match message.get("id") {
+                Some(id) => {
+                    let response = match self.request(&method, &params) {
+                        Some(result) => ("result", result),
+                        None => (
+                            "error",
+                            Json::object(vec![
+                                ("code", Json::from(METHOD_NOT_FOUND)),
+                                ("message", Json::from("Method not found")),
+                            ]),
+                        ),
+                    };
+                    self.send(vec![("id", id.clone()), response]);
+                }
+                None => self.notification(&method, &params),
+            }
+        }
+    }
+
+    fn send(&mut self, members: Vec<(&str, Json)>) {
+        let mut all = vec![("jsonrpc", Json::from("2.0"))];
+        all.extend(members);
+        write_message(&mut self.output, &Json::object(all));
+    }
+
+    /// Handles a request. Returns `None` if the method is not supported.
+    fn request(&mut self, method: &str, params: &Json) -> Option<Json> {
+        let result = match method {
+            "initialize" => Json::object(vec![
+                (
+                    "capabilities",
+                    Json::object(vec![
+                        ("textDocumentSync", Json::from(1)),
+                        ("hoverProvider", Json::from(true)),
+                        ("completionProvider", Json::object(vec![])),
+                        ("definitionProvider", Json::from(true)),
+                        ("documentSymbolProvider", Json::from(true)),
+                    ]),
+                ),
+                (
+                    "serverInfo",
+                    Json::object(vec![("name", Json::from("basic-lsp"))]),
+                ),
+            ]),
+            "shutdown" => Json::Null,
+            "textDocument/completion" => self.completion(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Handles a notification. The ones that are not supported are ignored.
+    fn notification(&mut self, method: &str, params: &Json) {
+        match method {
+            "textDocument/didOpen" => {
+                let text = params
+                    .get("textDocument")
+                    .and_then(|d| d.get("text"))
+                    .and_then(Json::as_str)
+                    .unwrap_or_default();
+                self.update(uri(params), text);
+            }
+            "textDocument/didChange" => {
+                // the whole document is in the last change
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str)
+                    .unwrap_or_default();
+                self.update(uri(params), text);
+            }
+            "textDocument/didClose" => {
+                let uri = uri(params);
+                self.documents.remove(&uri);
+                self.publish_diagnostics(uri, vec![]);
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, uri: String, text: &str) {
+        let (error, symbols) = analyze(text);
+        let document = self.documents.entry(uri.clone()).or_default();
+        document.lines = text.lines().map(|l| l.to_string()).collect();
+        if let Some(symbols) = symbols {
+            document.symbols = symbols;
+        }
+        let diagnostics: Vec<Json> = error
+            .into_iter()
+            .map(|e| {
+                let start = e.location().unwrap_or_else(|| Location::new(1, 1));
+                let end = Location::new(start.row(), start.col() + 1);
+                Json::object(vec![
+                    ("range", range(start, end)),
+                    ("severity", Json::from(SEVERITY_ERROR)),
+                    ("source", Json::from("basic")),
+                    ("message", Json::from(e.message())),
+                ])
+            })
+            .collect();
+        self.publish_diagnostics(uri, diagnostics);
+    }
+
+    fn publish_diagnostics(&mut self, uri: String, diagnostics: Vec<Json>) {
+        self.send(vec![
+            ("method", Json::from("textDocument/publishDiagnostics")),
+            (
+                "params",
+                Json::object(vec![
+                    ("uri", Json::from(uri)),
+                    ("diagnostics", Json::from(diagnostics)),
+                ]),
+            ),
+        ]);
+    }
+
+    fn document(&self, params: &Json) -> Option<&Document> {
+        self.documents.get(&uri(params))
+    }
+
+    fn completion(&self, params: &Json) -> Json {
+        let item = |label: String, kind: u32, detail: &str| {
+            Json::object(vec![
+                ("label", Json::from(label)),
+                ("kind", Json::from(kind)),
+                ("detail", Json::from(detail)),
+            ])
+        };
+        let mut items: Vec<Json> = vec![];
+        if let Some(document) = self.document(params) {
+            for symbol in &document.symbols {
+                if symbol.kind != SymbolKind::Label {
+                    let detail = document.line(symbol.pos.row()).trim();
+                    items.push(item(symbol.name.to_string(), COMPLETION_FUNCTION, detail));
+                }
+            }
+        }
+        for (_, function) in BUILT_IN_FUNCTIONS {
+            items.push(item(
+                function.name(),
+                COMPLETION_FUNCTION,
+                "built-in function",
+            ));
+        }
+        for (name, _) in BUILT_IN_SUBS {
+            items.push(item(
+                name.to_string(),
+                COMPLETION_KEYWORD,
+                "built-in statement",
+            ));
+        }
+        Json::from(items)
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let document = match self.document(params) {
+            Some(document) => document,
+            None => return Json::Null,
+        };
+        let word = match word_at(document, params) {
+            Some(word) => CaseInsensitiveString::from(word),
+            None => return Json::Null,
+        };
+        let value = match document.find(&word) {
+            Some(symbol) if symbol.kind == SymbolKind::Label => format!("Label `{}`", word),
+            Some(symbol) => format!("```basic\n{}\n```", document.line(symbol.pos.row()).trim()),
+            None => {
+                let function: Option<BuiltInFunction> = (&word).into();
+                let sub: Option<BuiltInSub> = (&word).into();
+                if let Some(function) = function {
+                    format!("Built-in function `{}`", function.name())
+                } else if let Some(sub) = sub {
+                    format!("Built-in statement `{}`", sub.name())
+                } else {
+                    return Json::Null;
+                }
+            }
+        };
+        Json::object(vec![(
+            "contents",
+            Json::object(vec![
+                ("kind", Json::from("markdown")),
+                ("value", Json::from(value)),
+            ]),
+        )])
+    }
+
+    fn definition(&self, params: &Json) -> Json {
+        let document = self.document(params);
+        match document.and_then(|d| d.find_at(params).map(|s| (d, s))) {
+            Some((document, symbol)) => Json::object(vec![
+                ("uri", Json::from(uri(params))),
+                ("range", document.line_range(symbol.pos)),
+            ]),
+            None => Json::Null,
+        }
+    }
+
+    fn document_symbols(&self, params: &Json) -> Json {
+        let document = match self.document(params) {
+            Some(document) => document,
+            None => return Json::from(vec![]),
+        };
+        let symbols: Vec<Json> = document
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let kind = match symbol.kind {
+                    SymbolKind::Sub | SymbolKind::Function => SYMBOL_FUNCTION,
+                    SymbolKind::Label => SYMBOL_KEY,
+                };
+                Json::object(vec![
+                    ("name", Json::from(symbol.name.to_string())),
+                    ("kind", Json::from(kind)),
+                    (
+                        "location",
+                        Json::object(vec![
+                            ("uri", Json::from(uri(params))),
+                            ("range", document.line_range(symbol.pos)),
+                        ]),
+                    ),
+                ])
+            })
+            .collect();
+        Json::from(symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const URI: &str = "file:///test.bas";
+
+    const PROGRAM: &str = r#"ON ERROR GOTO Trap
+Hello "world"
+PRINT Twice(LEN("abc"))
+SYSTEM
+Trap:
+PRINT "Error"
+SUB Hello(N$)
+    PRINT "Hello "; N$
+END SUB
+FUNCTION Twice(X)
+    Twice = X * 2
+END FUNCTION
+"#;
+
+    /// Plays the messages of a client and returns the messages of the server.
+    /// A message with an id is a request, the rest are notifications.
+    fn play(messages: &[Json]) -> Vec<Json> {
+        let mut input: Vec<u8> = vec![];
+        for message in messages {
+            write_message(&mut input, message);
+        }
+        let mut server = LspServer::new(Cursor::new(input), vec![]);
+        server.run();
+        let mut reader = Cursor::new(server.output);
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut reader) {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn request(id: u32, method: &str, params: Json) -> Json {
+        Json::object(vec![
+            ("jsonrpc", Json::from("2.0")),
+            ("id", Json::from(id)),
+            ("method", Json::from(method)),
+            ("params", params),
+        ])
+    }
+
+    fn notification(method: &str, params: Json) -> Json {
+        Json::object(vec![
+            ("jsonrpc", Json::from("2.0")),
+            ("method", Json::from(method)),
+            ("params", params),
+        ])
+    }
+
+    fn did_open(text: &str) -> Json {
+        notification(
+            "textDocument/didOpen",
+            Json::object(vec![(
+                "textDocument",
+                Json::object(vec![
+                    ("uri", Json::from(URI)),
+                    ("languageId", Json::from("basic")),
+                    ("version", Json::from(1)),
+                    ("text", Json::from(text)),
+                ]),
+            )]),
+        )
+    }
+
+    fn did_change(text: &str) -> Json {
+        notification(
+            "textDocument/didChange",
+            Json::object(vec![
+                (
+                    "textDocument",
+                    Json::object(vec![("uri", Json::from(URI)), ("version", Json::from(2))]),
+                ),
+                (
+                    "contentChanges",
+                    Json::from(vec![Json::object(vec![("text", Json::from(text))])]),
+                ),
+            ]),
+        )
+    }
+
+    /// A request about the given position, e.g. `textDocument/hover`.
+    fn at(id: u32, method: &str, line: u32, character: u32) -> Json {
+        request(
+            id,
+            method,
+            Json::object(vec![
+                ("textDocument", Json::object(vec![("uri", Json::from(URI))])),
+                (
+                    "position",
+                    Json::object(vec![
+                        ("line", Json::from(line)),
+                        ("character", Json::from(character)),
+                    ]),
+                ),
+            ]),
+        )
+    }
+
+    fn result(messages: &[Json], id: u32) -> &Json {
+        messages
+            .iter()
+            .find(|m| m.get("id") == Some(&Json::from(id)))
+            .and_then(|m| m.get("result"))
+            .unwrap()
+    }
+
+    /// The diagnostics as `line:character message`.
+    fn diagnostics(messages: &[Json]) -> Vec<Vec<String>> {
+        messages
+            .iter()
+            .filter(|m| m.get("method") == Some(&Json::from("textDocument/publishDiagnostics")))
+            .map(|m| {
+                let diagnostics = m.get("params").unwrap().get("diagnostics").unwrap();
+                diagnostics
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|d| {
+                        let start = d.get("range").unwrap().get("start").unwrap();
+                        format!(
+                            "{}:{} {}",
+                            start.get("line").unwrap(),
+                            start.get("character").unwrap(),
+                            d.get("message").and_then(Json::as_str).unwrap()
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_initialize_and_exit() {
+        let messages = play(&[
+            request(1, "initialize", Json::object(vec![])),
+            notification("initialized", Json::object(vec![])),
+            request(2, "workspace/symbol", Json::object(vec![])),
+            request(3, "shutdown", Json::Null),
+            notification("exit", Json::Null),
+            request(4, "shutdown", Json::Null),
+        ]);
+        assert_eq!(messages.len(), 3);
+        let capabilities = result(&messages, 1).get("capabilities").unwrap();
+        assert_eq!(capabilities.get("textDocumentSync"), Some(&Json::from(1)));
+        assert_eq!(
+            messages[1].to_string(),
+            r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"Method not found"}}"#
+        );
+        assert_eq!(result(&messages, 3), &Json::Null);
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let messages = play(&[
+            did_open("A = 1\nB$ = A + \"x\"\n"),
+            did_change("PRINT (1\n"),
+            did_change("PRINT 1\n"),
+            notification(
+                "textDocument/didClose",
+                Json::object(vec![(
+                    "textDocument",
+                    Json::object(vec![("uri", Json::from(URI))]),
+                )]),
+            ),
+        ]);
+        assert_eq!(
+            diagnostics(&messages),
+            vec![
+                vec!["1:9 Type mismatch".to_string()],
+                vec!["0:8 Expected closing parenthesis".to_string()],
+                vec![],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completion() {
+        let messages = play(&[did_open(PROGRAM), at(1, "textDocument/completion", 2, 6)]);
+        let labels: Vec<&str> = result(&messages, 1)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item.get("label").and_then(Json::as_str).unwrap())
+            .collect();
+        assert_eq!(labels[..2], ["Hello", "Twice"]);
+        assert!(labels.contains(&"LEN"));
+        assert!(labels.contains(&"LEFT$"));
+        assert!(labels.contains(&"LINE INPUT"));
+        assert!(!labels.contains(&"Trap"));
+        let hello = &result(&messages, 1).as_array().unwrap()[0];
+        assert_eq!(hello.get("detail"), Some(&Json::from("SUB Hello(N$)")));
+    }
+
+    #[test]
+    fn test_hover() {
+        let messages = play(&[
+            did_open(PROGRAM),
+            at(1, "textDocument/hover", 1, 2),
+            at(2, "textDocument/hover", 2, 8),
+            at(3, "textDocument/hover", 2, 13),
+            at(4, "textDocument/hover", 0, 16),
+            at(5, "textDocument/hover", 1, 8),
+        ]);
+        let value = |id| {
+            result(&messages, id)
+                .get("contents")
+                .and_then(|c| c.get("value"))
+                .and_then(Json::as_str)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(value(1), "```basic\nSUB Hello(N$)\n```");
+        assert_eq!(value(2), "```basic\nFUNCTION Twice(X)\n```");
+        assert_eq!(value(3), "Built-in function `LEN`");
+        assert_eq!(value(4), "Label `Trap`");
+        // a string
+        assert_eq!(result(&messages, 5), &Json::Null);
+    }
+
+    #[test]
+    fn test_definition() {
+        let messages = play(&[
+            did_open(PROGRAM),
+            at(1, "textDocument/definition", 0, 15),
+            at(2, "textDocument/definition", 1, 0),
+            at(3, "textDocument/definition", 2, 11),
+            at(4, "textDocument/definition", 2, 13),
+        ]);
+        let line = |id| {
+            result(&messages, id)
+                .get("range")
+                .and_then(|r| r.get("start"))
+                .and_then(|s| s.get("line"))
+                .and_then(Json::as_u32)
+                .unwrap()
+        };
+        assert_eq!(line(1), 4);
+        assert_eq!(line(2), 6);
+        // right after the name
+        assert_eq!(line(3), 9);
+        assert_eq!(result(&messages, 4), &Json::Null);
+    }
+
+    #[test]
+    fn test_document_symbols_survive_parser_errors() {
+        let messages = play(&[
+            did_open(PROGRAM),
+            did_change(&format!("{}PRINT (", PROGRAM)),
+            request(
+                1,
+                "textDocument/documentSymbol",
+                Json::object(vec![(
+                    "textDocument",
+                    Json::object(vec![("uri", Json::from(URI))]),
+                )]),
+            ),
+        ]);
+        let symbols: Vec<String> = result(&messages, 1)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| {
+                format!(
+                    "{} {} {}",
+                    s.get("name").and_then(Json::as_str).unwrap(),
+                    s.get("kind").unwrap(),
+                    s.get("location").unwrap().get("range").unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                r#"Trap 20 {"start":{"line":4,"character":0},"end":{"line":4,"character":5}}"#,
+                r#"Hello 12 {"start":{"line":6,"character":0},"end":{"line":6,"character":13}}"#,
+                r#"Twice 12 {"start":{"line":9,"character":0},"end":{"line":9,"character":17}}"#,
+            ]
+        );
+    }
+}
@@ -1,39 +1,45 @@
-mod casting;
-mod common;
-mod instruction_generator;
-mod interpreter;
-mod lexer;
-mod linter;
-mod parser;
-mod reader;
-mod variant;
-
 use std::env;
 use std::fs::File;
+use std::io::{self, BufReader};
 
-use interpreter::{DefaultStdlib, Interpreter};
-use parser::Parser;
+use basic_interpreter_rust::{
+    ConsoleDebugger, DapServer, DefaultStdlib, Interpreter, Program, Repl,
+};
 
 fn main() {
-    let filename = env::args()
-        .skip(1)
-        .take(1)
-        .last()
-        .expect("The first argument should be the program to run");
-    let f = File::open(&filename).expect(format!("Could not find program {}", filename).as_ref());
-    let mut parser = Parser::from(f);
-    match parser.parse() {
-        Ok(program) => match linter::lint(program) {
-            Ok(linted_program) => {
-                let mut interpreter = Interpreter::new(DefaultStdlib {});
-                let instructions = instruction_generator::generate_instructions(linted_program);
-                match interpreter.interpret(instructions) {
-                    Ok(_) => (),
-                    Err(e) => eprintln!("Runtime error. {:?}", e),
-                }
-            }
-            Err(e) => eprintln!("Could not lint program. {:?}", e),
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None | Some("--repl") => Repl::new(DefaultStdlib::default()).run(),
+        Some("--dap") => DapServer::new(BufReader::new(io::stdin()), io::stdout()).run(),
+        Some("--debug") => match args.get(1) {
+            Some(filename) => debug(filename),
+            None => eprintln!("Usage: basic-interpreter-rust --debug program.bas"),
         },
-        Err(e) => eprintln!("Could not parse program. {:?}", e),
+        Some(filename) => {
+            let f = File::open(filename)
+                .unwrap_or_else(|_| panic!("Could not find program {}", filename));
+            if let Err(e) = basic_interpreter_rust::run(f, DefaultStdlib::default()) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+fn debug(filename: &str) {
+    let source = std::fs::read_to_string(filename)
+        .unwrap_or_else(|_| panic!("Could not find program {}", filename));
+    let program = match Program::parse(source.as_bytes()).and_then(Program::lint) {
+        Ok(p) => p.compile(),
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    // reads one byte at a time, so that the INPUT of the program gets the rest
+    let input = BufReader::with_capacity(1, io::stdin());
+    let mut interpreter = Interpreter::new(DefaultStdlib::default());
+    interpreter.set_debugger(Box::new(ConsoleDebugger::new(&source, input, io::stderr())));
+    if let Err(e) = interpreter.run(&program) {
+        eprintln!("{}", e);
     }
 }
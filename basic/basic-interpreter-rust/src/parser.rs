@@ -3,17 +3,25 @@ mod buf_lexer;
 mod constant;
 mod declaration;
 mod def_type;
+mod dim;
+mod do_loop;
 mod error;
 mod expression;
+mod file;
 mod for_loop;
+mod go_sub;
 mod if_block;
+mod input;
 mod name;
 mod parser;
+mod print;
+mod resume;
 mod select_case;
 mod statement;
 mod sub_call;
 pub mod type_resolver_impl;
 mod types;
+mod user_defined_type;
 mod while_wend;
 
 #[cfg(test)]
@@ -1,4 +1,7 @@
-use super::{NameNode, Parser, ParserError, Statement, StatementNode};
+use super::{
+    ArgumentNodes, BareName, HasQualifier, Name, NameNode, NameTrait, Parser, ParserError,
+    Statement, StatementNode, TypeQualifier,
+};
 use crate::common::*;
 use std::io::BufRead;
 
@@ -12,6 +15,34 @@ impl<T: BufRead> Parser<T> {
         let (name, pos) = left_side.consume();
         Ok(Statement::Assignment(name, right_side).at(pos))
     }
+
+    pub fn read_demand_array_element_assignment_skipping_whitespace(
+        &mut self,
+        left_side: NameNode,
+        indices: ArgumentNodes,
+    ) -> Result<StatementNode, ParserError> {
+        let right_side = self.read_demand_expression_skipping_whitespace()?;
+        self.read_demand_eol_or_eof_skipping_whitespace()?;
+        let (name, pos) = left_side.consume();
+        if is_mid_statement(&name) {
+            // MID$(A$, start[, length]) = value, the value moves after the variable
+            let mut args = indices;
+            args.insert(args.len().min(1), right_side);
+            return Ok(Statement::SubCall(BareName::new("MID$".to_string()), args).at(pos));
+        }
+        Ok(Statement::ArrayElementAssignment(name, indices, right_side).at(pos))
+    }
+}
+
+/// Checks if the left side of an array element assignment is `MID$`,
+/// which is the statement that replaces a part of a string.
+fn is_mid_statement(name: &Name) -> bool {
+    match name {
+        Name::Qualified(q) => {
+            name.bare_name() == "MID" && q.qualifier() == TypeQualifier::DollarString
+        }
+        Name::Bare(_) => false,
+    }
 }
 
 #[cfg(test)]
@@ -53,4 +84,20 @@ mod tests {
     fn test_numeric_assignment_to_keyword_plus_number_allowed() {
         assert_top_level_assignment!("FOR42 = 42", "FOR42", 42);
     }
+
+    #[test]
+    fn test_mid_statement() {
+        assert_eq!(
+            parse("MID$(A$, 2, 3) = \"abc\"").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("MID$".to_string()),
+                vec![
+                    "A$".as_var_expr(1, 6),
+                    "abc".as_lit_expr(1, 18),
+                    2.as_lit_expr(1, 10),
+                    3.as_lit_expr(1, 13)
+                ]
+            )
+        );
+    }
 }
@@ -8,6 +8,9 @@ use std::io::{BufRead, BufReader, Cursor};
 pub struct BufLexer<T: BufRead> {
     lexer: Lexer<T>,
     _history: VecDeque<LexemeNode>,
+    /// The row and the line number of the current line of a line-numbered program.
+    /// Lexemes of that row are stamped with the line number.
+    line_number: Option<(u32, u32)>,
 }
 
 impl<T: BufRead> BufLexer<T> {
@@ -15,6 +18,7 @@ impl<T: BufRead> BufLexer<T> {
         BufLexer {
             lexer: lexer,
             _history: VecDeque::new(),
+            line_number: None,
         }
     }
 
@@ -23,12 +27,22 @@ impl<T: BufRead> BufLexer<T> {
     }
 
     pub fn read(&mut self) -> Result<LexemeNode, ParserError> {
-        match self._history.pop_front() {
-            Some(x) => Ok(x),
-            None => self._lexer_read(),
+        let next = match self._history.pop_front() {
+            Some(x) => x,
+            None => self._lexer_read()?,
+        };
+        match self.line_number {
+            Some((row, line_number)) if next.location().row() == row => {
+                Ok(next.with_line_number(line_number))
+            }
+            _ => Ok(next),
         }
     }
 
+    pub fn set_line_number(&mut self, row: u32, line_number: u32) {
+        self.line_number = Some((row, line_number));
+    }
+
     pub fn undo(&mut self, lexeme: LexemeNode) {
         self._history.push_front(lexeme);
     }
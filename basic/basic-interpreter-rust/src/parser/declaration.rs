@@ -1,4 +1,5 @@
-use super::{unexpected, NameNode, Parser, ParserError, TopLevelToken};
+use super::{unexpected, ParamName, ParamNameNode, ParamNodes, Parser, ParserError, TopLevelToken};
+use crate::common::*;
 use crate::lexer::{Keyword, LexemeNode};
 use std::io::BufRead;
 
@@ -31,7 +32,7 @@ impl<T: BufRead> Parser<T> {
         self.read_demand_whitespace("Expected whitespace after FUNCTION keyword")?;
         let name = self.read_demand_name_node("Expected function name")?;
         // function parameters
-        let params: Vec<NameNode> = self.parse_declaration_parameters()?;
+        let params: ParamNodes = self.parse_declaration_parameters()?;
         // function body
         let (block, _) =
             self.parse_statements(|x| x.is_keyword(Keyword::End), "Function without End")?;
@@ -47,7 +48,7 @@ impl<T: BufRead> Parser<T> {
         self.read_demand_whitespace("Expected whitespace after SUB keyword")?;
         let name = self.read_demand_bare_name_node("Expected sub name")?;
         // sub parameters
-        let params: Vec<NameNode> = self.parse_declaration_parameters()?;
+        let params: ParamNodes = self.parse_declaration_parameters()?;
         // body
         let (block, _) =
             self.parse_statements(|x| x.is_keyword(Keyword::End), "Sub without End")?;
@@ -57,8 +58,8 @@ impl<T: BufRead> Parser<T> {
         Ok(TopLevelToken::SubImplementation(name, params, block))
     }
 
-    fn parse_declaration_parameters(&mut self) -> Result<Vec<NameNode>, ParserError> {
-        let mut params: Vec<NameNode> = vec![];
+    fn parse_declaration_parameters(&mut self) -> Result<ParamNodes, ParserError> {
+        let mut params: ParamNodes = vec![];
         let next = self.read_skipping_whitespace()?;
         if next.is_symbol('(') {
             self.parse_inside_parentheses(&mut params)?;
@@ -72,7 +73,7 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
-    fn parse_inside_parentheses(&mut self, params: &mut Vec<NameNode>) -> Result<(), ParserError> {
+    fn parse_inside_parentheses(&mut self, params: &mut ParamNodes) -> Result<(), ParserError> {
         // holds the previous token, which can be one of:
         // '(' -> opening parenthesis (the starting point)
         // 'p' -> parameter
@@ -98,7 +99,7 @@ impl<T: BufRead> Parser<T> {
                 }
                 LexemeNode::Word(_, _) => {
                     if prev == '(' || prev == ',' {
-                        params.push(self.demand_name_node(next, "Expected parameter")?);
+                        params.push(self.demand_param_name_node(next)?);
                         prev = 'p';
                     } else {
                         return unexpected("Unexpected name", next);
@@ -111,6 +112,22 @@ impl<T: BufRead> Parser<T> {
         }
         Ok(())
     }
+
+    fn demand_param_name_node(&mut self, next: LexemeNode) -> Result<ParamNameNode, ParserError> {
+        let (name, pos) = self.demand_name_node(next, "Expected parameter")?.consume();
+        // array parameter e.g. A()
+        let is_array = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('('))?;
+        if is_array {
+            self.read_demand_symbol_skipping_whitespace(')')?;
+        }
+        let param_type = self.try_parse_dim_type(&name, false)?;
+        Ok(ParamName {
+            name,
+            is_array,
+            param_type,
+        }
+        .at(pos))
+    }
 }
 
 #[cfg(test)]
@@ -157,7 +174,7 @@ mod tests {
             result,
             TopLevelToken::FunctionImplementation(
                 "Add".as_name(2, 18),
-                vec!["A".as_name(2, 22), "B".as_name(2, 25)],
+                vec!["A".as_param(2, 22), "B".as_param(2, 25)],
                 vec![Statement::Assignment(
                     "Add".into(),
                     Expression::BinaryExpression(
@@ -185,7 +202,7 @@ mod tests {
             result,
             TopLevelToken::FunctionImplementation(
                 "add".as_name(2, 18),
-                vec!["a".as_name(2, 22), "b".as_name(2, 25)],
+                vec!["a".as_param(2, 22), "b".as_param(2, 25)],
                 vec![Statement::Assignment(
                     "add".into(),
                     Expression::BinaryExpression(
@@ -0,0 +1,461 @@
+use super::{
+    unexpected, ArrayDimension, BareNameNode, DimName, DimNameNode, DimType, Name, NameNode,
+    Parser, ParserError, Statement, TopLevelToken, TypeQualifier,
+};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+const MAX_STRING_LENGTH: u16 = 32767;
+
+impl<T: BufRead> Parser<T> {
+    pub fn demand_dim(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after DIM")?;
+        self.demand_dim_names().map(Statement::Dim)
+    }
+
+    pub fn demand_redim(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after REDIM")?;
+        self.demand_dim_names().map(Statement::ReDim)
+    }
+
+    pub fn demand_erase(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after ERASE")?;
+        let mut names: Vec<NameNode> = vec![];
+        loop {
+            let next = self.read_skipping_whitespace()?;
+            names.push(self.demand_name_node(next, "Expected array name")?);
+            if !self.read_comma_or_demand_eol_or_eof()? {
+                break;
+            }
+        }
+        Ok(Statement::Erase(names))
+    }
+
+    pub fn demand_option_base(&mut self) -> Result<TopLevelToken, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after OPTION")?;
+        self.read_demand_keyword(Keyword::Base)?;
+        self.read_demand_whitespace("Expected whitespace after BASE")?;
+        let next = self.buf_lexer.read()?;
+        let base: u8 = match &next {
+            LexemeNode::Digits(d, _) if d == "0" => 0,
+            LexemeNode::Digits(d, _) if d == "1" => 1,
+            _ => return unexpected("Expected 0 or 1", next),
+        };
+        self.read_demand_eol_or_eof_skipping_whitespace()?;
+        Ok(TopLevelToken::OptionBase(base))
+    }
+
+    fn demand_dim_names(&mut self) -> Result<Vec<DimNameNode>, ParserError> {
+        let mut dim_names: Vec<DimNameNode> = vec![];
+        loop {
+            let next = self.read_skipping_whitespace()?;
+            dim_names.push(self.demand_dim_name(next)?);
+            if !self.read_comma_or_demand_eol_or_eof()? {
+                break;
+            }
+        }
+        Ok(dim_names)
+    }
+
+    fn demand_dim_name(&mut self, next: LexemeNode) -> Result<DimNameNode, ParserError> {
+        let (name, pos) = self
+            .demand_name_node(next, "Expected variable name")?
+            .consume();
+        let dimensions = if self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('('))? {
+            self.demand_array_dimensions()?
+        } else {
+            vec![]
+        };
+        let dim_type = self.try_parse_dim_type(&name, true)?;
+        Ok(DimName {
+            name,
+            dimensions,
+            dim_type,
+        }
+        .at(pos))
+    }
+
+    fn demand_array_dimensions(&mut self) -> Result<Vec<ArrayDimension>, ParserError> {
+        let mut dimensions: Vec<ArrayDimension> = vec![];
+        loop {
+            let first = self.read_demand_expression_skipping_whitespace()?;
+            let mut next = self.read_skipping_whitespace()?;
+            if next.is_keyword(Keyword::To) {
+                self.read_demand_whitespace("Expected whitespace after TO")?;
+                let ubound = self.read_demand_expression()?;
+                dimensions.push(ArrayDimension {
+                    lbound: Some(first),
+                    ubound,
+                });
+                next = self.read_skipping_whitespace()?;
+            } else {
+                dimensions.push(ArrayDimension {
+                    lbound: None,
+                    ubound: first,
+                });
+            }
+            match next {
+                LexemeNode::Symbol(',', _) => (),
+                LexemeNode::Symbol(')', _) => break,
+                _ => return unexpected("Expected comma or )", next),
+            }
+        }
+        Ok(dimensions)
+    }
+
+    /// Parses an optional `AS` clause, e.g. `AS INTEGER` or `AS STRING * 5`.
+    pub fn try_parse_dim_type(
+        &mut self,
+        name: &Name,
+        allow_fixed_length: bool,
+    ) -> Result<DimType, ParserError> {
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        if opt_space.is_some() && next.is_keyword(Keyword::As) {
+            if let Name::Qualified(_) = name {
+                return unexpected("Identifier cannot end with %, &, !, #, or $", next);
+            }
+            self.read_demand_whitespace("Expected whitespace after AS")?;
+            let type_keyword = self.buf_lexer.read()?;
+            let qualifier = match type_keyword {
+                LexemeNode::Keyword(Keyword::Double, _, _) => TypeQualifier::HashDouble,
+                LexemeNode::Keyword(Keyword::Integer, _, _) => TypeQualifier::PercentInteger,
+                LexemeNode::Keyword(Keyword::Long, _, _) => TypeQualifier::AmpersandLong,
+                LexemeNode::Keyword(Keyword::Single, _, _) => TypeQualifier::BangSingle,
+                LexemeNode::Keyword(Keyword::String, _, _) => {
+                    if allow_fixed_length {
+                        if let Some(len) = self.try_parse_string_length()? {
+                            return Ok(DimType::FixedLengthString(len));
+                        }
+                    }
+                    TypeQualifier::DollarString
+                }
+                LexemeNode::Word(w, pos) => {
+                    return Ok(DimType::UserDefined(BareNameNode::new(
+                        CaseInsensitiveString::new(w),
+                        pos,
+                    )));
+                }
+                _ => {
+                    return unexpected(
+                        "Expected INTEGER, LONG, SINGLE, DOUBLE, STRING or type name",
+                        type_keyword,
+                    )
+                }
+            };
+            Ok(DimType::BuiltIn(qualifier))
+        } else {
+            self.buf_lexer.undo(next);
+            if let Some(space) = opt_space {
+                self.buf_lexer.undo(space);
+            }
+            Ok(DimType::Bare)
+        }
+    }
+
+    /// Parses the length of a fixed length string, e.g. `* 5`.
+    pub fn try_parse_string_length(&mut self) -> Result<Option<u16>, ParserError> {
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        if next.is_symbol('*') {
+            let len_lexeme = self.read_skipping_whitespace()?;
+            match &len_lexeme {
+                LexemeNode::Digits(d, _) => match d.parse::<u16>() {
+                    Ok(len) if (1..=MAX_STRING_LENGTH).contains(&len) => Ok(Some(len)),
+                    _ => unexpected("Overflow", len_lexeme),
+                },
+                _ => unexpected("Expected string length", len_lexeme),
+            }
+        } else {
+            self.buf_lexer.undo(next);
+            if let Some(space) = opt_space {
+                self.buf_lexer.undo(space);
+            }
+            Ok(None)
+        }
+    }
+
+    /// Reads a comma, returning true, or the end of the line, returning false.
+    fn read_comma_or_demand_eol_or_eof(&mut self) -> Result<bool, ParserError> {
+        let next = self.read_skipping_whitespace()?;
+        match next {
+            LexemeNode::Symbol(',', _) => Ok(true),
+            LexemeNode::EOL(_, _) | LexemeNode::EOF(_) => Ok(false),
+            _ => unexpected("Expected comma or EOL", next),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::common::*;
+    use crate::lexer::LexemeNode;
+    use crate::parser::{
+        ArrayDimension, DimName, DimType, Name, ParserError, Statement, TopLevelToken,
+        TypeQualifier,
+    };
+
+    #[test]
+    fn test_dim_single_dimension() {
+        let program = parse("DIM A(10)").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![DimName {
+                name: Name::from("A"),
+                dimensions: vec![ArrayDimension {
+                    lbound: None,
+                    ubound: 10.as_lit_expr(1, 7)
+                }],
+                dim_type: DimType::Bare
+            }
+            .at_rc(1, 5)])
+        );
+    }
+
+    #[test]
+    fn test_dim_multiple_dimensions_as_integer() {
+        let program = parse("DIM M(1 TO 5, 0 TO N) AS INTEGER").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![DimName {
+                name: Name::from("M"),
+                dimensions: vec![
+                    ArrayDimension {
+                        lbound: Some(1.as_lit_expr(1, 7)),
+                        ubound: 5.as_lit_expr(1, 12)
+                    },
+                    ArrayDimension {
+                        lbound: Some(0.as_lit_expr(1, 15)),
+                        ubound: "N".as_var_expr(1, 20)
+                    }
+                ],
+                dim_type: DimType::BuiltIn(TypeQualifier::PercentInteger)
+            }
+            .at_rc(1, 5)])
+        );
+    }
+
+    #[test]
+    fn test_dim_two_arrays() {
+        let program = parse("DIM A$(2), B(3) AS DOUBLE").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![
+                DimName {
+                    name: Name::from("A$"),
+                    dimensions: vec![ArrayDimension {
+                        lbound: None,
+                        ubound: 2.as_lit_expr(1, 8)
+                    }],
+                    dim_type: DimType::Bare
+                }
+                .at_rc(1, 5),
+                DimName {
+                    name: Name::from("B"),
+                    dimensions: vec![ArrayDimension {
+                        lbound: None,
+                        ubound: 3.as_lit_expr(1, 14)
+                    }],
+                    dim_type: DimType::BuiltIn(TypeQualifier::HashDouble)
+                }
+                .at_rc(1, 12)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_dim_qualified_name_as_type_is_error() {
+        assert_eq!(
+            parse_err("DIM A$(2) AS STRING"),
+            ParserError::Unexpected(
+                "Identifier cannot end with %, &, !, #, or $".to_string(),
+                LexemeNode::Keyword(
+                    crate::lexer::Keyword::As,
+                    "AS".to_string(),
+                    Location::new(1, 11)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_dim_variable() {
+        let program = parse("DIM X").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![DimName {
+                name: Name::from("X"),
+                dimensions: vec![],
+                dim_type: DimType::Bare
+            }
+            .at_rc(1, 5)])
+        );
+    }
+
+    #[test]
+    fn test_dim_variable_as_string() {
+        let program = parse("DIM X AS STRING").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![DimName {
+                name: Name::from("X"),
+                dimensions: vec![],
+                dim_type: DimType::BuiltIn(TypeQualifier::DollarString)
+            }
+            .at_rc(1, 5)])
+        );
+    }
+
+    #[test]
+    fn test_dim_fixed_length_string() {
+        let program = parse("DIM X AS STRING * 5").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Dim(vec![DimName {
+                name: Name::from("X"),
+                dimensions: vec![],
+                dim_type: DimType::FixedLengthString(5)
+            }
+            .at_rc(1, 5)])
+        );
+    }
+
+    #[test]
+    fn test_dim_fixed_length_string_overflow() {
+        assert_eq!(
+            parse_err("DIM X AS STRING * 32768"),
+            ParserError::Unexpected(
+                "Overflow".to_string(),
+                LexemeNode::Digits("32768".to_string(), Location::new(1, 19))
+            )
+        );
+    }
+
+    #[test]
+    fn test_redim() {
+        let program = parse("REDIM A(N)").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::ReDim(vec![DimName {
+                name: Name::from("A"),
+                dimensions: vec![ArrayDimension {
+                    lbound: None,
+                    ubound: "N".as_var_expr(1, 9)
+                }],
+                dim_type: DimType::Bare
+            }
+            .at_rc(1, 7)])
+        );
+    }
+
+    #[test]
+    fn test_erase() {
+        let program = parse("ERASE A, B$").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::Erase(vec!["A".as_name(1, 7), "B$".as_name(1, 10)])
+        );
+    }
+
+    #[test]
+    fn test_option_base() {
+        let program = parse("OPTION BASE 1").demand_single();
+        assert_eq!(program, TopLevelToken::OptionBase(1).at_rc(1, 1));
+    }
+
+    #[test]
+    fn test_option_base_two_is_error() {
+        assert_eq!(
+            parse_err("OPTION BASE 2"),
+            ParserError::Unexpected(
+                "Expected 0 or 1".to_string(),
+                LexemeNode::Digits("2".to_string(), Location::new(1, 13))
+            )
+        );
+    }
+
+    #[test]
+    fn test_array_element_assignment() {
+        let program = parse("A(1, 2) = 3").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::ArrayElementAssignment(
+                Name::from("A"),
+                vec![1.as_lit_expr(1, 3), 2.as_lit_expr(1, 6)],
+                3.as_lit_expr(1, 11)
+            )
+        );
+    }
+
+    #[test]
+    fn test_qualified_array_element_assignment() {
+        let program = parse("A$(1) = \"hi\"").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::ArrayElementAssignment(
+                Name::from("A$"),
+                vec![1.as_lit_expr(1, 4)],
+                "hi".as_lit_expr(1, 9)
+            )
+        );
+    }
+
+    #[test]
+    fn test_sub_call_with_parenthesis_is_not_assignment() {
+        let program = parse("Log(\"a\") + \"b\"").demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::SubCall(
+                "Log".into(),
+                vec![crate::parser::Expression::BinaryExpression(
+                    crate::parser::Operand::Plus,
+                    Box::new(
+                        crate::parser::Expression::Parenthesis(Box::new("a".as_lit_expr(1, 5)))
+                            .at_rc(1, 4)
+                    ),
+                    Box::new("b".as_lit_expr(1, 12))
+                )
+                .at_rc(1, 10)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_array_param() {
+        let program = parse("DECLARE SUB Sort(A(), N)").demand_single();
+        assert_eq!(
+            program,
+            TopLevelToken::SubDeclaration(
+                "Sort".as_bare_name(1, 13),
+                vec![
+                    crate::parser::ParamName {
+                        name: Name::from("A"),
+                        is_array: true,
+                        param_type: DimType::Bare
+                    }
+                    .at_rc(1, 18),
+                    "N".as_param(1, 23)
+                ]
+            )
+            .at_rc(1, 1)
+        );
+    }
+
+    #[test]
+    fn test_param_as_integer() {
+        let program = parse("DECLARE SUB Hello(N AS INTEGER)").demand_single();
+        assert_eq!(
+            program,
+            TopLevelToken::SubDeclaration(
+                "Hello".as_bare_name(1, 13),
+                vec![crate::parser::ParamName {
+                    name: Name::from("N"),
+                    is_array: false,
+                    param_type: DimType::BuiltIn(TypeQualifier::PercentInteger)
+                }
+                .at_rc(1, 19)]
+            )
+            .at_rc(1, 1)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use super::{
+    unexpected, DoLoopCondition, DoLoopConditionKind, DoLoopConditionPosition, DoLoopNode, Parser,
+    ParserError, Statement,
+};
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    pub fn demand_do_loop(&mut self) -> Result<Statement, ParserError> {
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        let top_condition = match next {
+            LexemeNode::EOL(_, _) => None,
+            LexemeNode::Keyword(Keyword::While, _, _)
+            | LexemeNode::Keyword(Keyword::Until, _, _)
+                if opt_space.is_some() =>
+            {
+                let condition =
+                    self.demand_do_loop_condition(DoLoopConditionPosition::Top, next)?;
+                self.read_demand_eol_skipping_whitespace()?;
+                Some(condition)
+            }
+            _ => return unexpected("Expected WHILE, UNTIL or EOL after DO", next),
+        };
+
+        let (statements, _) =
+            self.parse_statements(|x| x.is_keyword(Keyword::Loop), "DO without LOOP")?;
+
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        let bottom_condition = match next {
+            LexemeNode::EOL(_, _) | LexemeNode::EOF(_) => None,
+            LexemeNode::Keyword(Keyword::While, _, _)
+            | LexemeNode::Keyword(Keyword::Until, _, _)
+                if opt_space.is_some() =>
+            {
+                if top_condition.is_some() {
+                    return unexpected("DO and LOOP cannot both have a condition", next);
+                }
+                let condition =
+                    self.demand_do_loop_condition(DoLoopConditionPosition::Bottom, next)?;
+                self.read_demand_eol_or_eof_skipping_whitespace()?;
+                Some(condition)
+            }
+            _ => return unexpected("Expected WHILE, UNTIL or EOL after LOOP", next),
+        };
+
+        Ok(Statement::DoLoop(DoLoopNode {
+            condition: top_condition.or(bottom_condition),
+            statements,
+        }))
+    }
+
+    fn demand_do_loop_condition(
+        &mut self,
+        position: DoLoopConditionPosition,
+        keyword: LexemeNode,
+    ) -> Result<DoLoopCondition, ParserError> {
+        let kind = if keyword.is_keyword(Keyword::While) {
+            DoLoopConditionKind::While
+        } else {
+            DoLoopConditionKind::Until
+        };
+        self.read_demand_whitespace("Expected whitespace after WHILE or UNTIL")?;
+        let expr = self.read_demand_expression()?;
+        Ok(DoLoopCondition {
+            position,
+            kind,
+            expr,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::lexer::{Keyword, LexemeNode};
+    use crate::parser::test_utils::*;
+    use crate::parser::{
+        BareName, DoLoopCondition, DoLoopConditionKind, DoLoopConditionPosition, DoLoopNode,
+        ExitObject, Expression, Operand, ParserError, Statement,
+    };
+
+    #[test]
+    fn test_do_while_loop() {
+        let input = "
+        DO WHILE A < 5
+            SYSTEM
+        LOOP
+        ";
+        let program = parse(input).demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::DoLoop(DoLoopNode {
+                condition: Some(DoLoopCondition {
+                    position: DoLoopConditionPosition::Top,
+                    kind: DoLoopConditionKind::While,
+                    expr: Expression::BinaryExpression(
+                        Operand::Less,
+                        Box::new("A".as_var_expr(2, 18)),
+                        Box::new(5.as_lit_expr(2, 22))
+                    )
+                    .at_rc(2, 20),
+                }),
+                statements: vec![Statement::SubCall(BareName::from("SYSTEM"), vec![]).at_rc(3, 13)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_do_loop_until() {
+        let input = "
+        DO
+            SYSTEM
+        LOOP UNTIL A
+        ";
+        let program = parse(input).demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::DoLoop(DoLoopNode {
+                condition: Some(DoLoopCondition {
+                    position: DoLoopConditionPosition::Bottom,
+                    kind: DoLoopConditionKind::Until,
+                    expr: "A".as_var_expr(4, 20),
+                }),
+                statements: vec![Statement::SubCall(BareName::from("SYSTEM"), vec![]).at_rc(3, 13)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_do_loop_with_exit_do() {
+        let input = "
+        DO
+            EXIT DO
+        LOOP
+        ";
+        let program = parse(input).demand_single_statement();
+        assert_eq!(
+            program,
+            Statement::DoLoop(DoLoopNode {
+                condition: None,
+                statements: vec![Statement::Exit(ExitObject::Do).at_rc(3, 13)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_both_conditions_is_error() {
+        let input = "
+        DO WHILE A
+        LOOP UNTIL B
+        ";
+        assert_eq!(
+            parse_err(input),
+            ParserError::Unexpected(
+                "DO and LOOP cannot both have a condition".to_string(),
+                LexemeNode::Keyword(Keyword::Until, "UNTIL".to_string(), Location::new(3, 14))
+            )
+        );
+    }
+
+    #[test]
+    fn test_do_without_loop() {
+        let input = "
+        DO
+            SYSTEM
+        ";
+        assert_eq!(
+            parse_err(input),
+            ParserError::Unexpected(
+                "DO without LOOP".to_string(),
+                LexemeNode::EOF(Location::new(4, 9))
+            )
+        );
+    }
+}
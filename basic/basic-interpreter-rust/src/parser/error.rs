@@ -13,6 +13,14 @@ pub enum ParserError {
     Unexpected(String, LexemeNode),
 }
 
+impl ParserError {
+    /// Whether the input ended before a block was terminated, e.g. `FOR` without `NEXT`.
+    /// More input could make it valid.
+    pub fn is_unexpected_eof(&self) -> bool {
+        matches!(self, Self::Unexpected(_, LexemeNode::EOF(_)))
+    }
+}
+
 pub fn unexpected<T, S: AsRef<str>>(msg: S, lexeme: LexemeNode) -> Result<T, ParserError> {
     Err(ParserError::Unexpected(msg.as_ref().to_string(), lexeme))
 }
@@ -24,6 +24,17 @@ impl<T: BufRead> Parser<T> {
         self.try_parse_second_expression(first)
     }
 
+    /// Parses a file number, e.g. `#1`. The `#` is optional, as in `CLOSE 1`.
+    pub fn demand_file_handle(&mut self, next: LexemeNode) -> Result<ExpressionNode, ParserError> {
+        let pos = next.location();
+        let file_number = if next.is_symbol('#') {
+            self.read_demand_expression()?
+        } else {
+            self.demand_expression(next)?
+        };
+        Ok(Expression::FileHandle(Box::new(file_number)).at(pos))
+    }
+
     fn demand_single_expression(
         &mut self,
         next: LexemeNode,
@@ -31,16 +42,37 @@ impl<T: BufRead> Parser<T> {
         match next {
             LexemeNode::Symbol('"', pos) => self.parse_string_literal(pos),
             LexemeNode::Word(word, pos) => self.parse_word(word, pos),
+            LexemeNode::Keyword(k @ (Keyword::Input | Keyword::String), word, pos) => {
+                // INPUT and STRING are keywords, but INPUT$ and STRING$ are functions
+                let next = self.buf_lexer.read()?;
+                let is_function = next.is_symbol('$');
+                self.buf_lexer.undo(next);
+                if !is_function {
+                    unexpected("Expected expression", LexemeNode::Keyword(k, word, pos))
+                } else if k == Keyword::Input {
+                    self.parse_input_function(word, pos)
+                } else {
+                    self.parse_word(word, pos)
+                }
+            }
             LexemeNode::Digits(digits, pos) => self.parse_number_literal(digits, pos),
             LexemeNode::Symbol('.', pos) => self.parse_floating_point_literal("0".to_string(), pos),
             LexemeNode::Symbol('-', minus_pos) => {
                 let child = self.read_demand_expression()?;
-                Ok(Expression::unary_minus(child).at(minus_pos))
+                Ok(Self::apply_unary_priority_order(
+                    child,
+                    UnaryOperand::Minus,
+                    minus_pos,
+                ))
             }
             LexemeNode::Keyword(Keyword::Not, _, not_pos) => {
                 self.read_demand_whitespace("Expected whitespace after NOT")?;
                 let child = self.read_demand_expression()?;
-                Ok(Expression::UnaryExpression(UnaryOperand::Not, Box::new(child)).at(not_pos))
+                Ok(Self::apply_unary_priority_order(
+                    child,
+                    UnaryOperand::Not,
+                    not_pos,
+                ))
             }
             LexemeNode::Symbol('(', pos) => {
                 let inner = self.read_demand_expression_skipping_whitespace()?;
@@ -49,14 +81,14 @@ impl<T: BufRead> Parser<T> {
                     LexemeNode::Symbol(')', _) => {
                         Ok(Expression::Parenthesis(Box::new(inner)).at(pos))
                     }
-                    _ => unexpected("Expected closing parenthesis", closing)
+                    _ => unexpected("Expected closing parenthesis", closing),
                 }
             }
             _ => unexpected("Expected expression", next),
         }
     }
 
-    fn try_parse_second_expression(
+    pub fn try_parse_second_expression(
         &mut self,
         left_side: ExpressionNode,
     ) -> Result<ExpressionNode, ParserError> {
@@ -70,38 +102,77 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
+    /// Combines the left side and the right side of a binary expression,
+    /// respecting the priority of the operands.
+    ///
+    /// The right side has already been parsed recursively, so for an input
+    /// like `A - B * C + D` it will be `(B * C) + D`. If the current operand
+    /// binds at least as tight as the top level operand of the right side,
+    /// the tree is rotated so that the current operand is applied first,
+    /// which also makes operands of equal priority left associative.
     fn apply_priority_order(
         left_side: ExpressionNode,
         right_side: ExpressionNode,
         op: Operand,
         pos: Location,
     ) -> ExpressionNode {
-        match right_side.as_ref() {
-            Expression::BinaryExpression(r_op, r_left, r_right) => {
-                let should_flip = op == Operand::Plus && *r_op == Operand::Less;
-                if should_flip {
-                    Expression::BinaryExpression(
-                        *r_op,
-                        Box::new(
-                            Expression::BinaryExpression(op, Box::new(left_side), r_left.clone())
-                                .at(pos),
-                        ),
-                        r_right.clone(),
-                    )
-                    .at(right_side.location())
-                } else {
-                    Expression::BinaryExpression(op, Box::new(left_side), Box::new(right_side))
-                        .at(pos)
-                }
+        let (right_expr, right_pos) = right_side.consume();
+        match right_expr {
+            Expression::BinaryExpression(r_op, r_left, r_right)
+                if op.priority() >= r_op.priority() =>
+            {
+                Expression::BinaryExpression(
+                    r_op,
+                    Box::new(Self::apply_priority_order(left_side, *r_left, op, pos)),
+                    r_right,
+                )
+                .at(right_pos)
+            }
+            _ => Expression::BinaryExpression(
+                op,
+                Box::new(left_side),
+                Box::new(right_expr.at(right_pos)),
+            )
+            .at(pos),
+        }
+    }
+
+    /// Applies a unary operand to the given child expression, respecting the
+    /// priority of the operands.
+    ///
+    /// The child expression has been parsed greedily, so for an input like
+    /// `-A * B` it will be `A * B`. If the unary operand binds tighter than
+    /// the top level operand of the child, the unary operand is pushed down
+    /// to the left side of the child.
+    fn apply_unary_priority_order(
+        child: ExpressionNode,
+        op: UnaryOperand,
+        pos: Location,
+    ) -> ExpressionNode {
+        let (child_expr, child_pos) = child.consume();
+        match child_expr {
+            Expression::BinaryExpression(r_op, r_left, r_right)
+                if op.priority() > r_op.priority() =>
+            {
+                Expression::BinaryExpression(
+                    r_op,
+                    Box::new(Self::apply_unary_priority_order(*r_left, op, pos)),
+                    r_right,
+                )
+                .at(child_pos)
             }
             _ => {
-                Expression::BinaryExpression(op, Box::new(left_side), Box::new(right_side)).at(pos)
+                let child = child_expr.at(child_pos);
+                match op {
+                    UnaryOperand::Minus => Expression::unary_minus(child).at(pos),
+                    _ => Expression::unary(op, child).at(pos),
+                }
             }
         }
     }
 
     /// Parses a comma separated list of expressions.
-    fn parse_expression_list_with_parentheses(
+    pub fn parse_expression_list_with_parentheses(
         &mut self,
     ) -> Result<Vec<ExpressionNode>, ParserError> {
         let mut args: Vec<ExpressionNode> = vec![];
@@ -150,7 +221,7 @@ impl<T: BufRead> Parser<T> {
             let l = self.buf_lexer.read()?;
             match l {
                 LexemeNode::EOF(_) => return unexpected("EOF while looking for end of string", l),
-                LexemeNode::EOL(_, _) => {
+                LexemeNode::EOL(_, _) | LexemeNode::Comment(_, _) => {
                     return unexpected("Unexpected new line while looking for end of string", l)
                 }
                 LexemeNode::Keyword(_, s, _)
@@ -179,12 +250,54 @@ impl<T: BufRead> Parser<T> {
         let found_decimal_point = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('.'))?;
         if found_decimal_point {
             self.parse_floating_point_literal(digits, pos)
+        } else if let Some((exponent, is_double)) = self.try_parse_exponent()? {
+            // e.g. 1E+07
+            self.floating_point_literal_to_expression_node(
+                format!("{}e{}", digits, exponent),
+                is_double,
+                pos,
+            )
         } else {
             // no decimal point, just integer
             integer_literal_to_expression_node(digits, pos)
         }
     }
 
+    /// Parses the exponent that follows the digits of a number literal,
+    /// e.g. `E-10` of `1.5E-10`. Returns the exponent and whether it
+    /// was written with a `D`, which makes the literal a double.
+    fn try_parse_exponent(&mut self) -> Result<Option<(String, bool)>, ParserError> {
+        let next = self.buf_lexer.read()?;
+        if let LexemeNode::Word(word, _) = &next {
+            let mut chars = word.chars();
+            let letter = chars.next().unwrap_or_default().to_ascii_uppercase();
+            let digits = chars.as_str();
+            if (letter == 'E' || letter == 'D') && digits.chars().all(|c| c.is_ascii_digit()) {
+                let is_double = letter == 'D';
+                if !digits.is_empty() {
+                    // e.g. E10, the lexer reads the digits as part of the word
+                    return Ok(Some((digits.to_string(), is_double)));
+                }
+                let sign = self.buf_lexer.read()?;
+                if sign.is_symbol('+') || sign.is_symbol('-') {
+                    let exponent_digits = self.buf_lexer.read()?;
+                    if let LexemeNode::Digits(d, _) = &exponent_digits {
+                        let exponent = if sign.is_symbol('-') {
+                            format!("-{}", d)
+                        } else {
+                            d.clone()
+                        };
+                        return Ok(Some((exponent, is_double)));
+                    }
+                    self.buf_lexer.undo(exponent_digits);
+                }
+                self.buf_lexer.undo(sign);
+            }
+        }
+        self.buf_lexer.undo(next);
+        Ok(None)
+    }
+
     fn demand_digits(&mut self) -> Result<String, ParserError> {
         let next = self.buf_lexer.read()?;
         match next {
@@ -199,14 +312,29 @@ impl<T: BufRead> Parser<T> {
         pos: Location,
     ) -> Result<ExpressionNode, ParserError> {
         let fraction_digits = self.demand_digits()?;
-        let is_double = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('#'))?;
+        let mut literal = format!("{}.{}", integer_digits, fraction_digits);
+        let mut is_double = false;
+        if let Some((exponent, is_double_exponent)) = self.try_parse_exponent()? {
+            literal.push_str(&format!("e{}", exponent));
+            is_double = is_double_exponent;
+        }
+        self.floating_point_literal_to_expression_node(literal, is_double, pos)
+    }
+
+    fn floating_point_literal_to_expression_node(
+        &mut self,
+        literal: String,
+        is_double: bool,
+        pos: Location,
+    ) -> Result<ExpressionNode, ParserError> {
+        let is_double = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('#'))? || is_double;
         if is_double {
-            match format!("{}.{}", integer_digits, fraction_digits).parse::<f64>() {
+            match literal.parse::<f64>() {
                 Ok(f) => Ok(Expression::DoubleLiteral(f).at(pos)),
                 Err(err) => Err(ParserError::Internal(err.to_string(), pos)),
             }
         } else {
-            match format!("{}.{}", integer_digits, fraction_digits).parse::<f32>() {
+            match literal.parse::<f32>() {
                 Ok(f) => Ok(Expression::SingleLiteral(f).at(pos)),
                 Err(err) => Err(ParserError::Internal(err.to_string(), pos)),
             }
@@ -220,20 +348,98 @@ impl<T: BufRead> Parser<T> {
         let found_opening_parenthesis = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('('))?;
         if found_opening_parenthesis {
             let args = self.parse_expression_list_with_parentheses()?;
-            Ok(Expression::FunctionCall(Name::new(word, qualifier), args).at(pos))
+            let function_call = Expression::FunctionCall(Name::new(word, qualifier), args).at(pos);
+            self.try_parse_member(function_call)
         } else {
             Ok(Expression::VariableName(Name::new(word, qualifier)).at(pos))
         }
     }
 
+    /// Parses `INPUT$(count[, [#]file_number])`. Unlike the file number of other
+    /// functions, e.g. `EOF(1)`, the file number of `INPUT$` can have a `#`.
+    fn parse_input_function(
+        &mut self,
+        word: String,
+        pos: Location,
+    ) -> Result<ExpressionNode, ParserError> {
+        let qualifier = self.try_parse_type_qualifier()?;
+        self.read_demand_symbol_skipping_whitespace('(')?;
+        let mut args = vec![self.read_demand_expression_skipping_whitespace()?];
+        let next = self.read_skipping_whitespace()?;
+        if next.is_symbol(',') {
+            let next = self.read_skipping_whitespace()?;
+            let file_number = if next.is_symbol('#') {
+                self.read_demand_expression()?
+            } else {
+                self.demand_expression(next)?
+            };
+            args.push(file_number);
+            self.read_demand_symbol_skipping_whitespace(')')?;
+        } else if !next.is_symbol(')') {
+            return unexpected("Expected , or )", next);
+        }
+        Ok(Expression::FunctionCall(Name::new(word, qualifier), args).at(pos))
+    }
+
+    /// Parses the member of an array element, e.g. `.Suit` in `A(1).Suit`.
+    pub fn try_parse_member(
+        &mut self,
+        base: ExpressionNode,
+    ) -> Result<ExpressionNode, ParserError> {
+        let found_dot = self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('.'))?;
+        if found_dot {
+            let pos = base.location();
+            let next = self.buf_lexer.read()?;
+            match next {
+                LexemeNode::Word(w, _) => {
+                    let qualifier = self.try_parse_type_qualifier()?;
+                    Ok(Expression::Member(Box::new(base), Name::new(w, qualifier)).at(pos))
+                }
+                _ => unexpected("Expected element name", next),
+            }
+        } else {
+            Ok(base)
+        }
+    }
+
     fn try_parse_operand(&mut self) -> Result<Option<(Operand, Location)>, ParserError> {
         // if we can't find an operand, we need to restore the whitespace as it was,
         // in case there is a next call that will be demanding for it
         let (opt_space, next) = self.read_preserve_whitespace()?;
         match next {
-            LexemeNode::Symbol('<', pos) => Ok(Some((self.less_or_lte()?, pos))),
+            LexemeNode::Symbol('<', pos) => Ok(Some((self.less_or_lte_or_ne()?, pos))),
+            LexemeNode::Symbol('>', pos) => Ok(Some((self.greater_or_gte()?, pos))),
+            LexemeNode::Symbol('=', pos) => Ok(Some((Operand::Equal, pos))),
             LexemeNode::Symbol('+', pos) => Ok(Some((Operand::Plus, pos))),
             LexemeNode::Symbol('-', pos) => Ok(Some((Operand::Minus, pos))),
+            LexemeNode::Symbol('*', pos) => Ok(Some((Operand::Multiply, pos))),
+            LexemeNode::Symbol('/', pos) => Ok(Some((Operand::Divide, pos))),
+            LexemeNode::Symbol('\\', pos) => Ok(Some((Operand::IntegerDivide, pos))),
+            LexemeNode::Symbol('^', pos) => Ok(Some((Operand::Power, pos))),
+            LexemeNode::Keyword(Keyword::Mod, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after MOD")?;
+                Ok(Some((Operand::Modulo, pos)))
+            }
+            LexemeNode::Keyword(Keyword::And, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after AND")?;
+                Ok(Some((Operand::And, pos)))
+            }
+            LexemeNode::Keyword(Keyword::Or, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after OR")?;
+                Ok(Some((Operand::Or, pos)))
+            }
+            LexemeNode::Keyword(Keyword::Xor, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after XOR")?;
+                Ok(Some((Operand::Xor, pos)))
+            }
+            LexemeNode::Keyword(Keyword::Eqv, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after EQV")?;
+                Ok(Some((Operand::Eqv, pos)))
+            }
+            LexemeNode::Keyword(Keyword::Imp, _, pos) => {
+                self.read_demand_whitespace("Expected whitespace after IMP")?;
+                Ok(Some((Operand::Imp, pos)))
+            }
             _ => {
                 self.buf_lexer.undo(next);
                 match opt_space {
@@ -245,14 +451,26 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
-    fn less_or_lte(&mut self) -> Result<Operand, ParserError> {
+    fn less_or_lte_or_ne(&mut self) -> Result<Operand, ParserError> {
+        let next = self.buf_lexer.read()?;
+        match next {
+            LexemeNode::Symbol('=', _) => Ok(Operand::LessOrEqual),
+            LexemeNode::Symbol('>', _) => Ok(Operand::NotEqual),
+            _ => {
+                self.buf_lexer.undo(next);
+                Ok(Operand::Less)
+            }
+        }
+    }
+
+    fn greater_or_gte(&mut self) -> Result<Operand, ParserError> {
         self.buf_lexer
             .skip_if(|lexeme| lexeme.is_symbol('='))
             .map(|found_equal_sign| {
                 if found_equal_sign {
-                    Operand::LessOrEqual
+                    Operand::GreaterOrEqual
                 } else {
-                    Operand::Less
+                    Operand::Greater
                 }
             })
     }
@@ -287,8 +505,9 @@ mod tests {
             let program = parse(&format!("PRINT {}", $left)).demand_single_statement();
             match program {
                 Statement::SubCall(_, args) => {
-                    assert_eq!(1, args.len());
-                    assert_eq!(args[0].clone().strip_location(), $right);
+                    // the first argument is the PrintArg kind
+                    assert_eq!(2, args.len());
+                    assert_eq!(args[1].clone().strip_location(), $right);
                 }
                 _ => panic!("Expected sub-call"),
             }
@@ -318,6 +537,16 @@ mod tests {
         assert_literal_expression!("-42", -42);
     }
 
+    #[test]
+    fn test_parse_literals_with_exponent() {
+        assert_literal_expression!("1.5E-10", 1.5e-10_f32);
+        assert_literal_expression!("1E+07", 1e7_f32);
+        assert_literal_expression!("2.5e3", 2500_f32);
+        assert_literal_expression!("1E10", 1e10_f32);
+        assert_literal_expression!("1.5D-10", 1.5e-10_f64);
+        assert_literal_expression!("3E2#", 300_f64);
+    }
+
     #[test]
     fn test_variable_expression() {
         assert_variable_expression!("A", "A");
@@ -361,6 +590,17 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_string_function_call() {
+            assert_expression!(
+                "STRING$(3, \"*\")",
+                Expression::FunctionCall(
+                    Name::from("STRING$"),
+                    vec![3.as_lit_expr(1, 15), "*".as_lit_expr(1, 18)]
+                )
+            );
+        }
+
         #[test]
         fn test_function_call_in_function_call() {
             assert_expression!(
@@ -488,6 +728,162 @@ mod tests {
                 )
             );
         }
+
+        #[test]
+        fn test_plus_multiply() {
+            assert_expression!(
+                "A + B * C",
+                Expression::BinaryExpression(
+                    Operand::Plus,
+                    Box::new("A".as_var_expr(1, 7)),
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::Multiply,
+                            Box::new("B".as_var_expr(1, 11)),
+                            Box::new("C".as_var_expr(1, 15))
+                        )
+                        .at_rc(1, 13)
+                    )
+                )
+            );
+        }
+
+        #[test]
+        fn test_multiply_plus() {
+            assert_expression!(
+                "A * B + C",
+                Expression::BinaryExpression(
+                    Operand::Plus,
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::Multiply,
+                            Box::new("A".as_var_expr(1, 7)),
+                            Box::new("B".as_var_expr(1, 11))
+                        )
+                        .at_rc(1, 9)
+                    ),
+                    Box::new("C".as_var_expr(1, 15))
+                )
+            );
+        }
+
+        #[test]
+        fn test_minus_is_left_associative() {
+            assert_expression!(
+                "A - B - C",
+                Expression::BinaryExpression(
+                    Operand::Minus,
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::Minus,
+                            Box::new("A".as_var_expr(1, 7)),
+                            Box::new("B".as_var_expr(1, 11))
+                        )
+                        .at_rc(1, 9)
+                    ),
+                    Box::new("C".as_var_expr(1, 15))
+                )
+            );
+        }
+
+        #[test]
+        fn test_integer_division_and_modulo() {
+            assert_expression!(
+                "A MOD B \\ C",
+                Expression::BinaryExpression(
+                    Operand::Modulo,
+                    Box::new("A".as_var_expr(1, 7)),
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::IntegerDivide,
+                            Box::new("B".as_var_expr(1, 13)),
+                            Box::new("C".as_var_expr(1, 17))
+                        )
+                        .at_rc(1, 15)
+                    )
+                )
+            );
+        }
+
+        #[test]
+        fn test_negation_binds_tighter_than_multiply() {
+            assert_expression!(
+                "-A * B",
+                Expression::BinaryExpression(
+                    Operand::Multiply,
+                    Box::new(
+                        Expression::UnaryExpression(
+                            UnaryOperand::Minus,
+                            Box::new("A".as_var_expr(1, 8))
+                        )
+                        .at_rc(1, 7)
+                    ),
+                    Box::new("B".as_var_expr(1, 12))
+                )
+            );
+        }
+
+        #[test]
+        fn test_power_binds_tighter_than_negation() {
+            assert_expression!(
+                "-2 ^ 3",
+                Expression::UnaryExpression(
+                    UnaryOperand::Minus,
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::Power,
+                            Box::new(2.as_lit_expr(1, 8)),
+                            Box::new(3.as_lit_expr(1, 12))
+                        )
+                        .at_rc(1, 10)
+                    )
+                )
+            );
+        }
+
+        #[test]
+        fn test_relational_and_logical() {
+            assert_expression!(
+                "A > 0 AND B <> 1",
+                Expression::BinaryExpression(
+                    Operand::And,
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::Greater,
+                            Box::new("A".as_var_expr(1, 7)),
+                            Box::new(0.as_lit_expr(1, 11))
+                        )
+                        .at_rc(1, 9)
+                    ),
+                    Box::new(
+                        Expression::BinaryExpression(
+                            Operand::NotEqual,
+                            Box::new("B".as_var_expr(1, 17)),
+                            Box::new(1.as_lit_expr(1, 22))
+                        )
+                        .at_rc(1, 19)
+                    )
+                )
+            );
+        }
+
+        #[test]
+        fn test_not_binds_tighter_than_and() {
+            assert_expression!(
+                "NOT A AND B",
+                Expression::BinaryExpression(
+                    Operand::And,
+                    Box::new(
+                        Expression::UnaryExpression(
+                            UnaryOperand::Not,
+                            Box::new("A".as_var_expr(1, 11))
+                        )
+                        .at_rc(1, 7)
+                    ),
+                    Box::new("B".as_var_expr(1, 17))
+                )
+            );
+        }
     }
 
     mod binary_plus {
@@ -511,15 +907,15 @@ mod tests {
                 "N + 1 + 2",
                 Expression::BinaryExpression(
                     Operand::Plus,
-                    Box::new("N".as_var_expr(1, 7)),
                     Box::new(
                         Expression::BinaryExpression(
                             Operand::Plus,
-                            Box::new(1.as_lit_expr(1, 11)),
-                            Box::new(2.as_lit_expr(1, 15))
+                            Box::new("N".as_var_expr(1, 7)),
+                            Box::new(1.as_lit_expr(1, 11))
                         )
-                        .at_rc(1, 13)
-                    )
+                        .at_rc(1, 9)
+                    ),
+                    Box::new(2.as_lit_expr(1, 15))
                 )
             );
         }
@@ -604,4 +1000,21 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_array_element_member() {
+        assert_expression!(
+            "Deck(1).Suit",
+            Expression::Member(
+                Box::new(
+                    Expression::FunctionCall(
+                        Name::from("Deck"),
+                        vec![Expression::IntegerLiteral(1).at_rc(1, 12)],
+                    )
+                    .at_rc(1, 7)
+                ),
+                Name::from("Suit")
+            )
+        );
+    }
 }
@@ -0,0 +1,418 @@
+use super::{
+    unexpected, ArgumentNodes, BareName, Expression, ExpressionNode, Parser, ParserError, Statement,
+};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    /// Parses `OPEN file FOR mode [ACCESS access] AS [#]file_number [LEN = record_length]`.
+    /// It becomes a call to the built-in sub `OPEN` with the file name,
+    /// the mode, the access, the file number and the record length as arguments.
+    /// A missing record length is passed as zero.
+    pub fn demand_open(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after OPEN")?;
+        let file_name = self.read_demand_expression()?;
+        self.read_demand_whitespace("Expected space after file name")?;
+        self.read_demand_keyword(Keyword::For)?;
+        self.read_demand_whitespace("Expected space after FOR")?;
+        let next = self.buf_lexer.read()?;
+        let mode = match next {
+            LexemeNode::Keyword(Keyword::Input, _, _) => FileMode::Input,
+            LexemeNode::Keyword(Keyword::Output, _, _) => FileMode::Output,
+            LexemeNode::Keyword(Keyword::Append, _, _) => FileMode::Append,
+            LexemeNode::Keyword(Keyword::Random, _, _) => FileMode::Random,
+            LexemeNode::Keyword(Keyword::Binary, _, _) => FileMode::Binary,
+            _ => return unexpected("Expected INPUT, OUTPUT, APPEND, RANDOM or BINARY", next),
+        };
+        let mode_pos = next.location();
+        self.read_demand_whitespace("Expected space after file mode")?;
+        let mut next = self.buf_lexer.read()?;
+        let access_pos = next.location();
+        let access = if next.is_keyword(Keyword::Access) {
+            let access = self.demand_file_access()?;
+            self.read_demand_whitespace("Expected space after file access")?;
+            next = self.buf_lexer.read()?;
+            access
+        } else {
+            FileAccess::Unspecified
+        };
+        if !next.is_keyword(Keyword::As) {
+            return unexpected("Expected AS", next);
+        }
+        self.read_demand_whitespace("Expected space after AS")?;
+        let next = self.buf_lexer.read()?;
+        let file_handle = self.demand_file_handle(next)?;
+        let record_len = match self.try_parse_record_len()? {
+            Some(record_len) => record_len,
+            None => Expression::IntegerLiteral(0).at(file_handle.location()),
+        };
+        Ok(Statement::SubCall(
+            BareName::new("OPEN".to_string()),
+            vec![
+                file_name,
+                Expression::IntegerLiteral(mode.into()).at(mode_pos),
+                Expression::IntegerLiteral(access.into()).at(access_pos),
+                file_handle,
+                record_len,
+            ],
+        ))
+    }
+
+    fn try_parse_record_len(&mut self) -> Result<Option<ExpressionNode>, ParserError> {
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        match next {
+            LexemeNode::Word(w, _) if opt_space.is_some() && w.eq_ignore_ascii_case("LEN") => {
+                self.read_demand_symbol_skipping_whitespace('=')?;
+                self.read_demand_expression_skipping_whitespace().map(Some)
+            }
+            _ => {
+                self.buf_lexer.undo(next);
+                if let Some(space) = opt_space {
+                    self.buf_lexer.undo(space);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn demand_file_access(&mut self) -> Result<FileAccess, ParserError> {
+        self.read_demand_whitespace("Expected space after ACCESS")?;
+        let next = self.buf_lexer.read()?;
+        match next {
+            LexemeNode::Keyword(Keyword::Read, _, _) => {
+                // READ or READ WRITE
+                let (opt_space, next) = self.read_preserve_whitespace()?;
+                if opt_space.is_some() && next.is_keyword(Keyword::Write) {
+                    Ok(FileAccess::ReadWrite)
+                } else {
+                    self.buf_lexer.undo(next);
+                    if let Some(space) = opt_space {
+                        self.buf_lexer.undo(space);
+                    }
+                    Ok(FileAccess::Read)
+                }
+            }
+            LexemeNode::Keyword(Keyword::Write, _, _) => Ok(FileAccess::Write),
+            _ => unexpected("Expected READ, WRITE or READ WRITE", next),
+        }
+    }
+
+    /// Parses `CLOSE`, which closes all files, or `CLOSE #1, #2`.
+    pub fn demand_close(&mut self) -> Result<Statement, ParserError> {
+        let mut args: ArgumentNodes = vec![];
+        let next = self.buf_lexer.read()?;
+        if next.is_whitespace() {
+            let mut file_number = self.buf_lexer.read()?;
+            match file_number {
+                LexemeNode::Symbol('#', _) | LexemeNode::Digits(_, _) | LexemeNode::Word(_, _) => {
+                    loop {
+                        args.push(self.demand_file_handle(file_number)?);
+                        let next = self.read_skipping_whitespace()?;
+                        if next.is_symbol(',') {
+                            file_number = self.read_skipping_whitespace()?;
+                        } else {
+                            self.buf_lexer.undo(next);
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    self.buf_lexer.undo(file_number);
+                }
+            }
+        } else {
+            self.buf_lexer.undo(next);
+        }
+        Ok(Statement::SubCall(BareName::new("CLOSE".to_string()), args))
+    }
+
+    /// Parses `FIELD [#]file_number, width AS variable[, width AS variable]`.
+    /// The arguments are the file number, followed by the width and variable pairs.
+    pub fn demand_field(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after FIELD")?;
+        let next = self.buf_lexer.read()?;
+        let mut args: ArgumentNodes = vec![self.demand_file_handle(next)?];
+        self.read_demand_symbol_skipping_whitespace(',')?;
+        loop {
+            args.push(self.read_demand_expression_skipping_whitespace()?);
+            self.read_demand_whitespace("Expected space after field width")?;
+            self.read_demand_keyword(Keyword::As)?;
+            self.read_demand_whitespace("Expected space after AS")?;
+            let name_node = self.read_demand_name_node("Expected field variable")?;
+            let (name, pos) = name_node.consume();
+            args.push(Expression::VariableName(name).at(pos));
+            let next = self.read_skipping_whitespace()?;
+            if !next.is_symbol(',') {
+                self.buf_lexer.undo(next);
+                break;
+            }
+        }
+        Ok(Statement::SubCall(BareName::new("FIELD".to_string()), args))
+    }
+
+    /// Parses `GET [#]file_number[, [record][, variable]]` and the same syntax of `PUT`.
+    /// The arguments are the file number, whether there is a record (1) or not (0),
+    /// the record if there is one and the optional variable.
+    /// A missing record means the next record.
+    pub fn demand_get_or_put(&mut self, name: &str) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace(format!("Expected space after {}", name))?;
+        let next = self.buf_lexer.read()?;
+        let file_handle = self.demand_file_handle(next)?;
+        let mut record: Option<ExpressionNode> = None;
+        let mut args: ArgumentNodes = vec![];
+        let next = self.read_skipping_whitespace()?;
+        if next.is_symbol(',') {
+            let next = self.read_skipping_whitespace()?;
+            let next = if next.is_symbol(',') {
+                next
+            } else {
+                record = Some(self.demand_expression(next)?);
+                self.read_skipping_whitespace()?
+            };
+            if next.is_symbol(',') {
+                args.push(self.read_demand_expression_skipping_whitespace()?);
+            } else {
+                self.buf_lexer.undo(next);
+            }
+        } else {
+            self.buf_lexer.undo(next);
+        }
+        let has_record = Expression::IntegerLiteral(record.is_some() as i32);
+        if let Some(record) = record {
+            args.insert(0, record);
+        }
+        args.insert(0, has_record.at(file_handle.location()));
+        args.insert(0, file_handle);
+        Ok(Statement::SubCall(BareName::new(name.to_string()), args))
+    }
+
+    /// Parses `NAME old_file_name AS new_file_name`, which renames a file.
+    pub fn demand_name(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after NAME")?;
+        let old_name = self.read_demand_expression()?;
+        self.read_demand_whitespace("Expected space after file name")?;
+        self.read_demand_keyword(Keyword::As)?;
+        self.read_demand_whitespace("Expected space after AS")?;
+        let new_name = self.read_demand_expression()?;
+        Ok(Statement::SubCall(
+            BareName::new("NAME".to_string()),
+            vec![old_name, new_name],
+        ))
+    }
+
+    /// Parses `LSET variable = expression` and the same syntax of `RSET`.
+    pub fn demand_lset_or_rset(&mut self, name: &str) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace(format!("Expected space after {}", name))?;
+        let name_node = self.read_demand_name_node("Expected variable")?;
+        let (variable_name, pos) = name_node.consume();
+        self.read_demand_symbol_skipping_whitespace('=')?;
+        let value = self.read_demand_expression_skipping_whitespace()?;
+        Ok(Statement::SubCall(
+            BareName::new(name.to_string()),
+            vec![Expression::VariableName(variable_name).at(pos), value],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::parser::test_utils::*;
+    use crate::parser::{BareName, Expression, Statement};
+
+    fn file_handle(n: i32, row: u32, col: u32) -> crate::parser::ExpressionNode {
+        Expression::FileHandle(Box::new(n.as_lit_expr(row, col + 1))).at_rc(row, col)
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(
+            parse("NAME \"TODO.DAT\" AS \"TODO.OLD\"").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("NAME".to_string()),
+                vec!["TODO.DAT".as_lit_expr(1, 6), "TODO.OLD".as_lit_expr(1, 20)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_for_input() {
+        assert_eq!(
+            parse("OPEN \"TODO.DAT\" FOR INPUT AS #1").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("OPEN".to_string()),
+                vec![
+                    "TODO.DAT".as_lit_expr(1, 6),
+                    Expression::IntegerLiteral(FileMode::Input.into()).at_rc(1, 21),
+                    Expression::IntegerLiteral(FileAccess::Unspecified.into()).at_rc(1, 27),
+                    file_handle(1, 1, 30),
+                    0.as_lit_expr(1, 30)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_with_access() {
+        assert_eq!(
+            parse("OPEN F$ FOR APPEND ACCESS READ WRITE AS 2").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("OPEN".to_string()),
+                vec![
+                    "F$".as_var_expr(1, 6),
+                    Expression::IntegerLiteral(FileMode::Append.into()).at_rc(1, 13),
+                    Expression::IntegerLiteral(FileAccess::ReadWrite.into()).at_rc(1, 20),
+                    Expression::FileHandle(Box::new(2.as_lit_expr(1, 41))).at_rc(1, 41),
+                    0.as_lit_expr(1, 41)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_random_with_record_length() {
+        assert_eq!(
+            parse("OPEN \"DATA.DAT\" FOR RANDOM AS #1 LEN = 64").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("OPEN".to_string()),
+                vec![
+                    "DATA.DAT".as_lit_expr(1, 6),
+                    Expression::IntegerLiteral(FileMode::Random.into()).at_rc(1, 21),
+                    Expression::IntegerLiteral(FileAccess::Unspecified.into()).at_rc(1, 28),
+                    file_handle(1, 1, 31),
+                    64.as_lit_expr(1, 40)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_field() {
+        assert_eq!(
+            parse("FIELD #1, 20 AS N$, 44 AS A$").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("FIELD".to_string()),
+                vec![
+                    file_handle(1, 1, 7),
+                    20.as_lit_expr(1, 11),
+                    "N$".as_var_expr(1, 17),
+                    44.as_lit_expr(1, 21),
+                    "A$".as_var_expr(1, 27)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_get() {
+        assert_eq!(
+            parse("GET #1").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("GET".to_string()),
+                vec![file_handle(1, 1, 5), 0.as_lit_expr(1, 5)]
+            )
+        );
+        assert_eq!(
+            parse("GET #1, R").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("GET".to_string()),
+                vec![
+                    file_handle(1, 1, 5),
+                    1.as_lit_expr(1, 5),
+                    "R".as_var_expr(1, 9)
+                ]
+            )
+        );
+        assert_eq!(
+            parse("GET #1, , A%").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("GET".to_string()),
+                vec![
+                    file_handle(1, 1, 5),
+                    0.as_lit_expr(1, 5),
+                    "A%".as_var_expr(1, 11)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_put() {
+        assert_eq!(
+            parse("PUT #1, 3, A%").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("PUT".to_string()),
+                vec![
+                    file_handle(1, 1, 5),
+                    1.as_lit_expr(1, 5),
+                    3.as_lit_expr(1, 9),
+                    "A%".as_var_expr(1, 12)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_lset() {
+        assert_eq!(
+            parse("LSET N$ = \"hello\"").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("LSET".to_string()),
+                vec!["N$".as_var_expr(1, 6), "hello".as_lit_expr(1, 11)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_seek() {
+        assert_eq!(
+            parse("SEEK #1, 5").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("SEEK".to_string()),
+                vec![file_handle(1, 1, 6), 5.as_lit_expr(1, 10)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_close() {
+        assert_eq!(
+            parse("CLOSE").demand_single_statement(),
+            Statement::SubCall(BareName::new("CLOSE".to_string()), vec![])
+        );
+        assert_eq!(
+            parse("CLOSE #1, #2").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("CLOSE".to_string()),
+                vec![file_handle(1, 1, 7), file_handle(2, 1, 11)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_line_input() {
+        assert_eq!(
+            parse("LINE INPUT #1, A$").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("LINE INPUT".to_string()),
+                vec![file_handle(1, 1, 12), "A$".as_var_expr(1, 16)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_to_file() {
+        assert_eq!(
+            parse("PRINT #1, A$").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("PRINT".to_string()),
+                vec![
+                    file_handle(1, 1, 7),
+                    print_arg_kind(PrintArg::Expression, 1, 11),
+                    "A$".as_var_expr(1, 11)
+                ]
+            )
+        );
+    }
+}
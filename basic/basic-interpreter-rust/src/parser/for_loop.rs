@@ -131,9 +131,14 @@ mod tests {
                 lower_bound: 1.as_lit_expr(1, 9),
                 upper_bound: 10.as_lit_expr(1, 14),
                 step: None,
-                statements: vec![
-                    Statement::SubCall("PRINT".into(), vec!["I".as_var_expr(2, 7)]).at_rc(2, 1)
-                ],
+                statements: vec![Statement::SubCall(
+                    "PRINT".into(),
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 2, 7),
+                        "I".as_var_expr(2, 7)
+                    ]
+                )
+                .at_rc(2, 1)],
                 next_counter: None,
             })
         );
@@ -150,9 +155,14 @@ mod tests {
                 lower_bound: 1.as_lit_expr(1, 9),
                 upper_bound: 10.as_lit_expr(1, 14),
                 step: None,
-                statements: vec![
-                    Statement::SubCall("print".into(), vec!["i".as_var_expr(2, 7)]).at_rc(2, 1)
-                ],
+                statements: vec![Statement::SubCall(
+                    "print".into(),
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 2, 7),
+                        "i".as_var_expr(2, 7)
+                    ]
+                )
+                .at_rc(2, 1)],
                 next_counter: None,
             })
         );
@@ -170,7 +180,13 @@ mod tests {
                 step: None,
                 statements: vec![Statement::SubCall(
                     "PRINT".into(),
-                    vec!["Hello".as_lit_expr(2, 11), "I".as_var_expr(2, 20)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 2, 11),
+                        "Hello".as_lit_expr(2, 11),
+                        print_arg_kind(PrintArg::Comma, 2, 18),
+                        print_arg_kind(PrintArg::Expression, 2, 20),
+                        "I".as_var_expr(2, 20)
+                    ]
                 )
                 .at_rc(2, 5)],
                 next_counter: None,
@@ -186,7 +202,10 @@ mod tests {
             vec![
                 TopLevelToken::Statement(Statement::SubCall(
                     "PRINT".into(),
-                    vec!["Before the outer loop".as_lit_expr(1, 7)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 1, 7),
+                        "Before the outer loop".as_lit_expr(1, 7)
+                    ]
                 )),
                 TopLevelToken::Statement(Statement::ForLoop(ForLoopNode {
                     variable_name: "I".as_name(2, 5),
@@ -197,7 +216,10 @@ mod tests {
                         Statement::SubCall(
                             "PRINT".into(),
                             vec![
+                                print_arg_kind(PrintArg::Expression, 3, 11),
                                 "Before the inner loop".as_lit_expr(3, 11),
+                                print_arg_kind(PrintArg::Comma, 3, 34),
+                                print_arg_kind(PrintArg::Expression, 3, 36),
                                 "I".as_var_expr(3, 36)
                             ]
                         )
@@ -210,8 +232,13 @@ mod tests {
                             statements: vec![Statement::SubCall(
                                 "PRINT".into(),
                                 vec![
+                                    print_arg_kind(PrintArg::Expression, 5, 15),
                                     "Inner loop".as_lit_expr(5, 15),
+                                    print_arg_kind(PrintArg::Comma, 5, 27),
+                                    print_arg_kind(PrintArg::Expression, 5, 29),
                                     "I".as_var_expr(5, 29),
+                                    print_arg_kind(PrintArg::Comma, 5, 30),
+                                    print_arg_kind(PrintArg::Expression, 5, 32),
                                     "J".as_var_expr(5, 32)
                                 ]
                             )
@@ -222,7 +249,10 @@ mod tests {
                         Statement::SubCall(
                             "PRINT".into(),
                             vec![
+                                print_arg_kind(PrintArg::Expression, 7, 11),
                                 "After the inner loop".as_lit_expr(7, 11),
+                                print_arg_kind(PrintArg::Comma, 7, 33),
+                                print_arg_kind(PrintArg::Expression, 7, 35),
                                 "I".as_var_expr(7, 35)
                             ]
                         )
@@ -232,7 +262,10 @@ mod tests {
                 })),
                 TopLevelToken::Statement(Statement::SubCall(
                     BareName::from("PRINT"),
-                    vec!["After the outer loop".as_lit_expr(9, 7)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 9, 7),
+                        "After the outer loop".as_lit_expr(9, 7)
+                    ]
                 )),
             ]
         );
@@ -0,0 +1,122 @@
+use super::{unexpected, ExpressionNode, Parser, ParserError, Statement};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    pub fn demand_go_sub(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after GOSUB")?;
+        let name = self.read_demand_label()?;
+        Ok(Statement::GoSub(name))
+    }
+
+    pub fn demand_return(&mut self) -> Result<Statement, ParserError> {
+        // RETURN can be followed by an optional label, e.g. RETURN Again or RETURN 100
+        let next = self.buf_lexer.read()?;
+        if next.is_whitespace() {
+            let label = self.buf_lexer.read()?;
+            match label {
+                LexemeNode::Word(w, _) => {
+                    return Ok(Statement::Return(Some(CaseInsensitiveString::new(w))));
+                }
+                LexemeNode::Digits(_, _) => {
+                    return Ok(Statement::Return(Some(self.demand_line_number(label)?)));
+                }
+                _ => {
+                    self.buf_lexer.undo(label);
+                }
+            }
+        }
+        self.buf_lexer.undo(next);
+        Ok(Statement::Return(None))
+    }
+
+    /// Parses `ON expr GOTO label1, label2` and `ON expr GOSUB label1, label2`.
+    /// The `ON` keyword, the whitespace after it and the expression have been parsed.
+    pub fn demand_on_go_to_or_go_sub(
+        &mut self,
+        expr: ExpressionNode,
+    ) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after expression")?;
+        let next = self.buf_lexer.read()?;
+        let is_go_sub = match next {
+            LexemeNode::Keyword(Keyword::GoTo, _, _) => false,
+            LexemeNode::Keyword(Keyword::GoSub, _, _) => true,
+            _ => return unexpected("Expected GOTO or GOSUB", next),
+        };
+        self.read_demand_whitespace("Expected space after GOTO or GOSUB")?;
+        let mut labels: Vec<CaseInsensitiveString> = vec![];
+        loop {
+            labels.push(self.read_demand_label()?);
+            let next = self.read_skipping_whitespace()?;
+            if next.is_symbol(',') {
+                self.buf_lexer.skip_if(|x| x.is_whitespace())?;
+            } else {
+                self.buf_lexer.undo(next);
+                break;
+            }
+        }
+        if is_go_sub {
+            Ok(Statement::OnGoSub(expr, labels))
+        } else {
+            Ok(Statement::OnGoTo(expr, labels))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::parser::test_utils::*;
+    use crate::parser::Statement;
+
+    #[test]
+    fn test_go_sub() {
+        assert_eq!(
+            parse("GOSUB Alpha").demand_single_statement(),
+            Statement::GoSub(CaseInsensitiveString::from("Alpha"))
+        );
+    }
+
+    #[test]
+    fn test_return_without_label() {
+        assert_eq!(
+            parse("RETURN").demand_single_statement(),
+            Statement::Return(None)
+        );
+    }
+
+    #[test]
+    fn test_return_with_label() {
+        assert_eq!(
+            parse("RETURN Alpha").demand_single_statement(),
+            Statement::Return(Some(CaseInsensitiveString::from("Alpha")))
+        );
+    }
+
+    #[test]
+    fn test_on_go_to() {
+        assert_eq!(
+            parse("ON N GOTO Alpha, Beta,Gamma").demand_single_statement(),
+            Statement::OnGoTo(
+                "N".as_var_expr(1, 4),
+                vec![
+                    CaseInsensitiveString::from("Alpha"),
+                    CaseInsensitiveString::from("Beta"),
+                    CaseInsensitiveString::from("Gamma")
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_on_go_sub() {
+        assert_eq!(
+            parse("ON 2 GOSUB Alpha").demand_single_statement(),
+            Statement::OnGoSub(
+                2.as_lit_expr(1, 4),
+                vec![CaseInsensitiveString::from("Alpha")]
+            )
+        );
+    }
+}
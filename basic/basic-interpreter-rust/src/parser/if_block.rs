@@ -10,6 +10,12 @@ impl<T: BufRead> Parser<T> {
         self.read_demand_whitespace("Expected whitespace after IF keyword")?;
         let if_condition = self.read_demand_expression()?;
         self.read_demand_whitespace("Expected whitespace before THEN keyword")?;
+        let next = self.buf_lexer.read()?;
+        if next.is_keyword(Keyword::GoTo) {
+            // IF X GOTO 100 is the same as IF X THEN GOTO 100
+            return self.consume_if_block_single_line(if_condition, next);
+        }
+        self.buf_lexer.undo(next);
         self.read_demand_keyword(Keyword::Then)?;
         let (opt_space, next) = self.read_preserve_whitespace()?;
         match next {
@@ -26,19 +32,33 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
+    /// Parses the statement after `THEN` and the optional `ELSE` statement,
+    /// which must be on the same line.
     fn consume_if_block_single_line(
         &mut self,
         if_condition: ExpressionNode,
         next: LexemeNode,
     ) -> Result<Statement, ParserError> {
+        let row = next.location().row();
         let if_block = ConditionalBlockNode {
             condition: if_condition,
             statements: vec![self.demand_single_line_then_statement(next)?],
         };
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        let else_block = if next.is_keyword(Keyword::Else) && next.location().row() == row {
+            let next = self.read_skipping_whitespace()?;
+            Some(vec![self.demand_single_line_then_statement(next)?])
+        } else {
+            self.buf_lexer.undo(next);
+            if let Some(space) = opt_space {
+                self.buf_lexer.undo(space);
+            }
+            None
+        };
         Ok(Statement::IfBlock(IfBlockNode {
             if_block: if_block,
             else_if_blocks: vec![],
-            else_block: None,
+            else_block,
         }))
     }
 
@@ -145,7 +165,10 @@ mod tests {
                     condition: "X".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["X".as_var_expr(2, 7)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 7),
+                            "X".as_var_expr(2, 7)
+                        ]
                     )
                     .at_rc(2, 1)]
                 },
@@ -170,7 +193,10 @@ mod tests {
                         condition: "X".as_var_expr(2, 12),
                         statements: vec![Statement::SubCall(
                             "PRINT".into(),
-                            vec!["X".as_var_expr(2, 25)]
+                            vec![
+                                print_arg_kind(PrintArg::Expression, 2, 25),
+                                "X".as_var_expr(2, 25)
+                            ]
                         )
                         .at_rc(2, 19)]
                     },
@@ -197,14 +223,20 @@ END IF"#;
                     condition: "X".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["X".as_var_expr(2, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 11),
+                            "X".as_var_expr(2, 11)
+                        ]
                     )
                     .at_rc(2, 5)],
                 },
                 else_if_blocks: vec![],
                 else_block: Some(vec![Statement::SubCall(
                     "PRINT".into(),
-                    vec!["Y".as_var_expr(4, 11)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 4, 11),
+                        "Y".as_var_expr(4, 11)
+                    ]
                 )
                 .at_rc(4, 5)]),
             }),
@@ -226,7 +258,10 @@ END IF"#;
                     condition: "X".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["X".as_var_expr(2, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 11),
+                            "X".as_var_expr(2, 11)
+                        ]
                     )
                     .at_rc(2, 5)],
                 },
@@ -234,7 +269,10 @@ END IF"#;
                     condition: "Y".as_var_expr(3, 8),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["Y".as_var_expr(4, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 4, 11),
+                            "Y".as_var_expr(4, 11)
+                        ]
                     )
                     .at_rc(4, 5)],
                 }],
@@ -260,7 +298,10 @@ END IF"#;
                     condition: "X".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["X".as_var_expr(2, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 11),
+                            "X".as_var_expr(2, 11)
+                        ]
                     )
                     .at_rc(2, 5)],
                 },
@@ -269,7 +310,10 @@ END IF"#;
                         condition: "Y".as_var_expr(3, 8),
                         statements: vec![Statement::SubCall(
                             "PRINT".into(),
-                            vec!["Y".as_var_expr(4, 11)]
+                            vec![
+                                print_arg_kind(PrintArg::Expression, 4, 11),
+                                "Y".as_var_expr(4, 11)
+                            ]
                         )
                         .at_rc(4, 5)],
                     },
@@ -277,7 +321,10 @@ END IF"#;
                         condition: "Z".as_var_expr(5, 8),
                         statements: vec![Statement::SubCall(
                             "PRINT".into(),
-                            vec!["Z".as_var_expr(6, 11)]
+                            vec![
+                                print_arg_kind(PrintArg::Expression, 6, 11),
+                                "Z".as_var_expr(6, 11)
+                            ]
                         )
                         .at_rc(6, 5)],
                     },
@@ -304,7 +351,10 @@ END IF"#;
                     condition: "X".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["X".as_var_expr(2, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 11),
+                            "X".as_var_expr(2, 11)
+                        ]
                     )
                     .at_rc(2, 5)],
                 },
@@ -312,13 +362,19 @@ END IF"#;
                     condition: "Y".as_var_expr(3, 8),
                     statements: vec![Statement::SubCall(
                         "PRINT".into(),
-                        vec!["Y".as_var_expr(4, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 4, 11),
+                            "Y".as_var_expr(4, 11)
+                        ]
                     )
                     .at_rc(4, 5)],
                 }],
                 else_block: Some(vec![Statement::SubCall(
                     "PRINT".into(),
-                    vec!["Z".as_var_expr(6, 11)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 6, 11),
+                        "Z".as_var_expr(6, 11)
+                    ]
                 )
                 .at_rc(6, 5)]),
             })
@@ -342,7 +398,10 @@ end if"#;
                     condition: "x".as_var_expr(1, 4),
                     statements: vec![Statement::SubCall(
                         "print".into(),
-                        vec!["x".as_var_expr(2, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 2, 11),
+                            "x".as_var_expr(2, 11)
+                        ]
                     )
                     .at_rc(2, 5)],
                 },
@@ -350,16 +409,44 @@ end if"#;
                     condition: "y".as_var_expr(3, 8),
                     statements: vec![Statement::SubCall(
                         "print".into(),
-                        vec!["y".as_var_expr(4, 11)]
+                        vec![
+                            print_arg_kind(PrintArg::Expression, 4, 11),
+                            "y".as_var_expr(4, 11)
+                        ]
                     )
                     .at_rc(4, 5)],
                 }],
                 else_block: Some(vec![Statement::SubCall(
                     "print".into(),
-                    vec!["z".as_var_expr(6, 11)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 6, 11),
+                        "z".as_var_expr(6, 11)
+                    ]
                 )
                 .at_rc(6, 5)]),
             })
         );
     }
+
+    #[test]
+    fn test_if_single_line_else() {
+        let input = "IF X THEN Y = 1 ELSE Y = 2";
+        assert_eq!(
+            parse(input).demand_single_statement(),
+            Statement::IfBlock(IfBlockNode {
+                if_block: ConditionalBlockNode {
+                    condition: "X".as_var_expr(1, 4),
+                    statements: vec![
+                        Statement::Assignment("Y".into(), 1.as_lit_expr(1, 15)).at_rc(1, 11)
+                    ]
+                },
+                else_if_blocks: vec![],
+                else_block: Some(vec![Statement::Assignment(
+                    "Y".into(),
+                    2.as_lit_expr(1, 26)
+                )
+                .at_rc(1, 22)])
+            })
+        );
+    }
 }
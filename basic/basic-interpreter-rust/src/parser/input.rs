@@ -0,0 +1,165 @@
+use super::{
+    unexpected, ArgumentNodes, BareName, Expression, Parser, ParserError, Statement, StatementNode,
+};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    /// Parses `INPUT #file_number, variable[, variable]...` and
+    /// `INPUT [;] ["prompt" {; | ,}] variable[, variable]...`.
+    ///
+    /// Reading from the keyboard, the arguments are the `InputPrompt` kind, the prompt
+    /// (empty if there is none) and the variables. The `;` after `INPUT` is accepted
+    /// but has no effect, because the terminal moves to the next line when the user
+    /// presses Enter.
+    pub fn demand_input(
+        &mut self,
+        raw_name: String,
+        pos: Location,
+    ) -> Result<StatementNode, ParserError> {
+        let mut next = self.read_skipping_whitespace()?;
+        if next.is_symbol(';') {
+            next = self.read_skipping_whitespace()?;
+        }
+        let mut args: ArgumentNodes = vec![];
+        if next.is_symbol('#') {
+            args.push(self.demand_file_handle(next)?);
+            self.read_demand_symbol_skipping_whitespace(',')?;
+            next = self.read_skipping_whitespace()?;
+        } else if next.is_symbol('"') {
+            let prompt = self.demand_expression(next)?;
+            let separator = self.read_skipping_whitespace()?;
+            let kind = match separator {
+                LexemeNode::Symbol(';', _) => InputPrompt::PromptWithQuestionMark,
+                LexemeNode::Symbol(',', _) => InputPrompt::Prompt,
+                _ => return unexpected("Expected ; or , after prompt", separator),
+            };
+            args.push(Expression::IntegerLiteral(kind.into()).at(pos));
+            args.push(prompt);
+            next = self.read_skipping_whitespace()?;
+        } else {
+            args.push(Expression::IntegerLiteral(InputPrompt::QuestionMark.into()).at(pos));
+            args.push(Expression::StringLiteral(String::new()).at(pos));
+        }
+        loop {
+            args.push(self.demand_expression(next)?);
+            let separator = self.read_skipping_whitespace()?;
+            if separator.is_symbol(',') {
+                next = self.read_skipping_whitespace()?;
+            } else {
+                self.buf_lexer.undo(separator);
+                break;
+            }
+        }
+        Ok(Statement::SubCall(CaseInsensitiveString::new(raw_name), args).at(pos))
+    }
+
+    /// Parses `LINE INPUT #file_number, variable` and `LINE INPUT [;] ["prompt";] variable`.
+    ///
+    /// Reading from the keyboard, the arguments are the prompt (empty if there is none)
+    /// and the variable. Unlike `INPUT`, no question mark is shown.
+    pub fn demand_line_input(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after LINE")?;
+        self.read_demand_keyword(Keyword::Input)?;
+        let mut args: ArgumentNodes = vec![];
+        let mut next = self.read_skipping_whitespace()?;
+        if next.is_symbol('#') {
+            args.push(self.demand_file_handle(next)?);
+            self.read_demand_symbol_skipping_whitespace(',')?;
+            next = self.read_skipping_whitespace()?;
+        } else {
+            if next.is_symbol(';') {
+                next = self.read_skipping_whitespace()?;
+            }
+            if next.is_symbol('"') {
+                args.push(self.demand_expression(next)?);
+                self.read_demand_symbol_skipping_whitespace(';')?;
+                next = self.read_skipping_whitespace()?;
+            } else {
+                args.push(Expression::StringLiteral(String::new()).at(next.location()));
+            }
+        }
+        args.push(self.demand_expression(next)?);
+        Ok(Statement::SubCall(
+            BareName::new("LINE INPUT".to_string()),
+            args,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::parser::test_utils::*;
+    use crate::parser::{BareName, Expression, Statement};
+
+    #[test]
+    fn test_input_without_prompt() {
+        assert_eq!(
+            parse("INPUT A, B$").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("INPUT".to_string()),
+                vec![
+                    input_prompt_kind(InputPrompt::QuestionMark, 1, 1),
+                    "".as_lit_expr(1, 1),
+                    "A".as_var_expr(1, 7),
+                    "B$".as_var_expr(1, 10)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_input_with_prompt() {
+        assert_eq!(
+            parse(r#"INPUT "Name"; N$"#).demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("INPUT".to_string()),
+                vec![
+                    input_prompt_kind(InputPrompt::PromptWithQuestionMark, 1, 1),
+                    "Name".as_lit_expr(1, 7),
+                    "N$".as_var_expr(1, 15)
+                ]
+            )
+        );
+        assert_eq!(
+            parse(r#"INPUT; "x", A, B"#).demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("INPUT".to_string()),
+                vec![
+                    input_prompt_kind(InputPrompt::Prompt, 1, 1),
+                    "x".as_lit_expr(1, 8),
+                    "A".as_var_expr(1, 13),
+                    "B".as_var_expr(1, 16)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_input_from_file() {
+        assert_eq!(
+            parse("INPUT #1, A, B$").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("INPUT".to_string()),
+                vec![
+                    Expression::FileHandle(Box::new(1.as_lit_expr(1, 8))).at_rc(1, 7),
+                    "A".as_var_expr(1, 11),
+                    "B$".as_var_expr(1, 14)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_line_input_with_prompt() {
+        assert_eq!(
+            parse(r#"LINE INPUT "Name: "; N$"#).demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("LINE INPUT".to_string()),
+                vec!["Name: ".as_lit_expr(1, 12), "N$".as_var_expr(1, 22)]
+            )
+        );
+    }
+}
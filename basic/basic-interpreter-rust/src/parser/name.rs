@@ -48,6 +48,37 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
+    /// Reads the target of a jump, which is either a label or a line number,
+    /// e.g. `GOTO Alpha` or `GOTO 100`.
+    pub fn read_demand_label(&mut self) -> Result<CaseInsensitiveString, ParserError> {
+        let next = self.buf_lexer.read()?;
+        match next {
+            LexemeNode::Word(word, _) => Ok(CaseInsensitiveString::new(word)),
+            LexemeNode::Digits(_, _) => self.demand_line_number(next),
+            _ => unexpected("Expected label name", next),
+        }
+    }
+
+    /// Parses a line number as a label, normalizing it so that e.g. `010` and `10`
+    /// are the same label.
+    pub fn demand_line_number(
+        &mut self,
+        next: LexemeNode,
+    ) -> Result<CaseInsensitiveString, ParserError> {
+        self.parse_line_number(next)
+            .map(|n| CaseInsensitiveString::new(n.to_string()))
+    }
+
+    pub fn parse_line_number(&mut self, next: LexemeNode) -> Result<u32, ParserError> {
+        match &next {
+            LexemeNode::Digits(digits, _) => match digits.parse::<u32>() {
+                Ok(n) => Ok(n),
+                Err(_) => unexpected("Invalid line number", next),
+            },
+            _ => unexpected("Expected line number", next),
+        }
+    }
+
     pub fn try_parse_type_qualifier(&mut self) -> Result<Option<TypeQualifier>, ParserError> {
         self.buf_lexer.try_read(|next| match next {
             LexemeNode::Symbol(ch, _) => TypeQualifier::try_from(*ch),
@@ -1,8 +1,9 @@
 use crate::common::*;
-use crate::lexer::{Keyword, LexemeNode};
+use crate::lexer::{Keyword, LexemeNode, Lexer};
 use crate::parser::buf_lexer::BufLexer;
 use crate::parser::error::*;
 use crate::parser::types::*;
+use crate::reader::CharOrEofReader;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Cursor};
 
@@ -16,6 +17,11 @@ impl<T: BufRead> Parser<T> {
         Parser { buf_lexer }
     }
 
+    /// Creates a parser that reads the source code from the given reader.
+    pub fn from_reader(reader: T) -> Parser<T> {
+        Parser::new(BufLexer::new(Lexer::new(CharOrEofReader::new(reader))))
+    }
+
     pub fn parse(&mut self) -> Result<ProgramNode, ParserError> {
         let mut v: Vec<TopLevelTokenNode> = vec![];
         let mut next = self.read_skipping_whitespace_and_eol()?;
@@ -39,15 +45,38 @@ impl<T: BufRead> Parser<T> {
                 | Keyword::DefSng
                 | Keyword::DefStr => self.demand_def_type(k).map(|x| x.at(pos)),
                 Keyword::Function => self.demand_function_implementation().map(|x| x.at(pos)),
+                Keyword::Option => self.demand_option_base().map(|x| x.at(pos)),
                 Keyword::Sub => self.demand_sub_implementation().map(|x| x.at(pos)),
-                Keyword::Const
+                Keyword::Type => self.demand_user_defined_type().map(|x| x.at(pos)),
+                Keyword::Close
+                | Keyword::Const
+                | Keyword::Dim
+                | Keyword::Do
+                | Keyword::Erase
+                | Keyword::Error
+                | Keyword::Exit
+                | Keyword::Field
                 | Keyword::For
+                | Keyword::Get
+                | Keyword::GoSub
                 | Keyword::GoTo
                 | Keyword::If
                 | Keyword::Input
+                | Keyword::Line
+                | Keyword::LPrint
+                | Keyword::LSet
+                | Keyword::Name
                 | Keyword::On
+                | Keyword::Open
+                | Keyword::Print
+                | Keyword::Put
+                | Keyword::ReDim
+                | Keyword::Resume
+                | Keyword::Return
+                | Keyword::RSet
                 | Keyword::Select
-                | Keyword::While => self
+                | Keyword::While
+                | Keyword::Write => self
                     .demand_statement(next)
                     .map(|s| s.consume())
                     .map(|(s, p)| TopLevelToken::from(s).at(p)),
@@ -124,6 +153,11 @@ impl<T: BufRead> Parser<T> {
         let next = self.read_skipping_whitespace()?;
         match next {
             LexemeNode::EOL(_, _) | LexemeNode::EOF(_) => Ok(()),
+            LexemeNode::Keyword(Keyword::Else, _, _) => {
+                // the statement after THEN of a single line IF ends at ELSE
+                self.buf_lexer.undo(next);
+                Ok(())
+            }
             _ => unexpected("Expected EOL or EOF", next),
         }
     }
@@ -183,17 +217,24 @@ mod tests {
                 // DECLARE FUNCTION Fib! (N!)
                 TopLevelToken::FunctionDeclaration(
                     "Fib!".as_name(1, 18),
-                    vec!["N!".as_name(1, 24)],
+                    vec!["N!".as_param(1, 24)],
                 ),
                 // PRINT "Enter the number of fibonacci to calculate"
                 TopLevelToken::Statement(Statement::SubCall(
                     BareName::from("PRINT"),
-                    vec!["Enter the number of fibonacci to calculate".as_lit_expr(2, 7)],
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 2, 7),
+                        "Enter the number of fibonacci to calculate".as_lit_expr(2, 7)
+                    ],
                 )),
                 // INPUT N
                 TopLevelToken::Statement(Statement::SubCall(
                     BareName::from("INPUT"),
-                    vec!["N".as_var_expr(3, 7)]
+                    vec![
+                        input_prompt_kind(InputPrompt::QuestionMark, 3, 1),
+                        "".as_lit_expr(3, 1),
+                        "N".as_var_expr(3, 7)
+                    ]
                 )),
                 // FOR I = 0 TO N
                 TopLevelToken::Statement(Statement::ForLoop(ForLoopNode {
@@ -206,9 +247,16 @@ mod tests {
                         Statement::SubCall(
                             BareName::from("PRINT"),
                             vec![
+                                print_arg_kind(PrintArg::Expression, 5, 11),
                                 "Fibonacci of".as_lit_expr(5, 11),
+                                print_arg_kind(PrintArg::Comma, 5, 25),
+                                print_arg_kind(PrintArg::Expression, 5, 27),
                                 "I".as_var_expr(5, 27),
+                                print_arg_kind(PrintArg::Comma, 5, 28),
+                                print_arg_kind(PrintArg::Expression, 5, 30),
                                 "is".as_lit_expr(5, 30),
+                                print_arg_kind(PrintArg::Comma, 5, 34),
+                                print_arg_kind(PrintArg::Expression, 5, 36),
                                 Expression::FunctionCall(
                                     Name::from("Fib"),
                                     vec!["I".as_var_expr(5, 40)],
@@ -223,7 +271,7 @@ mod tests {
                 // FUNCTION Fib (N)
                 TopLevelToken::FunctionImplementation(
                     Name::from("Fib").at_rc(8, 10),
-                    vec!["N".as_name(8, 15)],
+                    vec!["N".as_param(8, 15)],
                     vec![
                         // IF N <= 1 THEN
                         Statement::IfBlock(IfBlockNode {
@@ -287,4 +335,89 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_line_numbers() {
+        let input = r#"
+        10 PRINT "a"
+        20 GOTO 010
+        "#;
+        let program = parse(input);
+        assert_eq!(
+            program,
+            vec![
+                TopLevelToken::Statement(Statement::Label("10".into()))
+                    .at(Location::new(2, 9).with_line_number(10)),
+                TopLevelToken::Statement(Statement::SubCall(
+                    "PRINT".into(),
+                    vec![
+                        Expression::IntegerLiteral(PrintArg::Expression.into())
+                            .at(Location::new(2, 18).with_line_number(10)),
+                        Expression::StringLiteral("a".to_string())
+                            .at(Location::new(2, 18).with_line_number(10))
+                    ]
+                ))
+                .at(Location::new(2, 12).with_line_number(10)),
+                TopLevelToken::Statement(Statement::Label("20".into()))
+                    .at(Location::new(3, 9).with_line_number(20)),
+                TopLevelToken::Statement(Statement::GoTo("10".into()))
+                    .at(Location::new(3, 12).with_line_number(20)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = r#"
+        ' hello
+        X = 1 REM set X
+        SELECT CASE X ' select
+        CASE 1
+        END SELECT
+        "#;
+        let program = parse(input);
+        assert_eq!(
+            program,
+            vec![
+                TopLevelToken::Statement(Statement::Comment(" hello".to_string())).at_rc(2, 9),
+                TopLevelToken::Statement(Statement::Assignment("X".into(), 1.as_lit_expr(3, 13)))
+                    .at_rc(3, 9),
+                TopLevelToken::Statement(Statement::Comment(" set X".to_string())).at_rc(3, 15),
+                TopLevelToken::Statement(Statement::SelectCase(SelectCaseNode {
+                    expr: "X".as_var_expr(4, 21),
+                    comments: vec![Statement::Comment(" select".to_string()).at_rc(4, 23)],
+                    case_blocks: vec![CaseBlockNode {
+                        expr: CaseExpression::Simple(1.as_lit_expr(5, 14)),
+                        statements: vec![],
+                    }],
+                    else_block: None,
+                }))
+                .at_rc(4, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_if_goto_line_number() {
+        let inputs = [
+            ("IF X GOTO 100", 6),
+            ("IF X THEN 100", 11),
+            ("IF X THEN GOTO 100", 11),
+        ];
+        for (input, go_to_col) in inputs.iter() {
+            assert_eq!(
+                parse(input).demand_single_statement(),
+                Statement::IfBlock(IfBlockNode {
+                    if_block: ConditionalBlockNode {
+                        condition: "X".as_var_expr(1, 4),
+                        statements: vec![Statement::GoTo("100".into()).at_rc(1, *go_to_col)],
+                    },
+                    else_if_blocks: vec![],
+                    else_block: None,
+                }),
+                "{}",
+                input
+            );
+        }
+    }
 }
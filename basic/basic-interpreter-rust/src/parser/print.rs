@@ -0,0 +1,169 @@
+use super::{
+    ArgumentNodes, Expression, ExpressionNode, Parser, ParserError, Statement, StatementNode,
+};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    /// Parses `PRINT [#file_number,] [USING format$;] [expression] [{; | ,} [expression]]...`.
+    ///
+    /// Every argument is preceded by an integer literal with its `PrintArg` kind,
+    /// because the separators and `TAB`/`SPC` affect how the values are printed.
+    /// Two expressions without a separator between them, as in `PRINT "A=" A`,
+    /// are printed as if they were separated by a semicolon.
+    pub fn demand_print(
+        &mut self,
+        raw_name: String,
+        pos: Location,
+    ) -> Result<StatementNode, ParserError> {
+        let mut args: ArgumentNodes = vec![];
+        let mut next = self.read_skipping_whitespace()?;
+        if next.is_symbol('#') {
+            args.push(self.demand_file_handle(next)?);
+            self.read_demand_symbol_skipping_whitespace(',')?;
+            next = self.read_skipping_whitespace()?;
+        }
+        self.demand_print_args(next, &mut args)?;
+        Ok(Statement::SubCall(CaseInsensitiveString::new(raw_name), args).at(pos))
+    }
+
+    /// Parses `LPRINT`, which has the same arguments as `PRINT` without the file number.
+    pub fn demand_lprint(
+        &mut self,
+        raw_name: String,
+        pos: Location,
+    ) -> Result<StatementNode, ParserError> {
+        let mut args: ArgumentNodes = vec![];
+        let next = self.read_skipping_whitespace()?;
+        self.demand_print_args(next, &mut args)?;
+        Ok(Statement::SubCall(CaseInsensitiveString::new(raw_name), args).at(pos))
+    }
+
+    fn demand_print_args(
+        &mut self,
+        mut next: LexemeNode,
+        args: &mut ArgumentNodes,
+    ) -> Result<(), ParserError> {
+        // with USING, TAB and SPC are not supported and the separators
+        // only matter at the end of the line
+        let is_using = next.is_keyword(Keyword::Using);
+        if is_using {
+            args.push(print_arg(PrintArg::Using, next.location()));
+            args.push(self.read_demand_expression_skipping_whitespace()?);
+            self.read_demand_symbol_skipping_whitespace(';')?;
+            next = self.read_skipping_whitespace()?;
+        }
+        loop {
+            let arg_pos = next.location();
+            match next {
+                LexemeNode::EOF(_) | LexemeNode::EOL(_, _) => break,
+                LexemeNode::Keyword(Keyword::Else, _, _) => {
+                    // the statement after THEN of a single line IF ends at ELSE
+                    self.buf_lexer.undo(next);
+                    break;
+                }
+                LexemeNode::Symbol(',', _) => {
+                    args.push(print_arg(PrintArg::Comma, arg_pos));
+                }
+                LexemeNode::Symbol(';', _) => {
+                    args.push(print_arg(PrintArg::Semicolon, arg_pos));
+                }
+                LexemeNode::Word(ref w, _)
+                    if !is_using
+                        && (w.eq_ignore_ascii_case("TAB") || w.eq_ignore_ascii_case("SPC")) =>
+                {
+                    let kind = if w.eq_ignore_ascii_case("TAB") {
+                        PrintArg::Tab
+                    } else {
+                        PrintArg::Spc
+                    };
+                    let after_word = self.buf_lexer.read()?;
+                    if after_word.is_symbol('(') {
+                        args.push(print_arg(kind, arg_pos));
+                        args.push(self.read_demand_expression_skipping_whitespace()?);
+                        self.read_demand_symbol_skipping_whitespace(')')?;
+                    } else {
+                        // e.g. a variable named TAB
+                        self.buf_lexer.undo(after_word);
+                        args.push(print_arg(PrintArg::Expression, arg_pos));
+                        args.push(self.demand_expression(next)?);
+                    }
+                }
+                _ => {
+                    args.push(print_arg(PrintArg::Expression, arg_pos));
+                    args.push(self.demand_expression(next)?);
+                }
+            }
+            next = self.read_skipping_whitespace()?;
+        }
+        Ok(())
+    }
+}
+
+fn print_arg(kind: PrintArg, pos: Location) -> ExpressionNode {
+    Expression::IntegerLiteral(kind.into()).at(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::parser::test_utils::*;
+    use crate::parser::{BareName, Statement};
+
+    #[test]
+    fn test_print_separators_and_tab() {
+        assert_eq!(
+            parse("PRINT A; TAB(10); B,").demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("PRINT".to_string()),
+                vec![
+                    print_arg_kind(PrintArg::Expression, 1, 7),
+                    "A".as_var_expr(1, 7),
+                    print_arg_kind(PrintArg::Semicolon, 1, 8),
+                    print_arg_kind(PrintArg::Tab, 1, 10),
+                    10.as_lit_expr(1, 14),
+                    print_arg_kind(PrintArg::Semicolon, 1, 17),
+                    print_arg_kind(PrintArg::Expression, 1, 19),
+                    "B".as_var_expr(1, 19),
+                    print_arg_kind(PrintArg::Comma, 1, 20),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_using() {
+        assert_eq!(
+            parse(r###"PRINT USING "##.##"; A; B"###).demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("PRINT".to_string()),
+                vec![
+                    print_arg_kind(PrintArg::Using, 1, 7),
+                    "##.##".as_lit_expr(1, 13),
+                    print_arg_kind(PrintArg::Expression, 1, 22),
+                    "A".as_var_expr(1, 22),
+                    print_arg_kind(PrintArg::Semicolon, 1, 23),
+                    print_arg_kind(PrintArg::Expression, 1, 25),
+                    "B".as_var_expr(1, 25),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_lprint_using() {
+        assert_eq!(
+            parse(r#"LPRINT USING "&"; A$"#).demand_single_statement(),
+            Statement::SubCall(
+                BareName::new("LPRINT".to_string()),
+                vec![
+                    print_arg_kind(PrintArg::Using, 1, 8),
+                    "&".as_lit_expr(1, 14),
+                    print_arg_kind(PrintArg::Expression, 1, 19),
+                    "A$".as_var_expr(1, 19),
+                ]
+            )
+        );
+    }
+}
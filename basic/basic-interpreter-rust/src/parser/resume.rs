@@ -0,0 +1,81 @@
+use super::{Parser, ParserError, ResumeOption, Statement};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    pub fn demand_resume(&mut self) -> Result<Statement, ParserError> {
+        // RESUME can be followed by NEXT, a label or a line number, e.g. RESUME 150
+        let next = self.buf_lexer.read()?;
+        if next.is_whitespace() {
+            let target = self.buf_lexer.read()?;
+            match target {
+                LexemeNode::Keyword(Keyword::Next, _, _) => {
+                    return Ok(Statement::Resume(ResumeOption::Next));
+                }
+                LexemeNode::Word(w, _) => {
+                    return Ok(Statement::Resume(ResumeOption::Label(
+                        CaseInsensitiveString::new(w),
+                    )));
+                }
+                LexemeNode::Digits(_, _) => {
+                    // RESUME 0 is the same as RESUME
+                    return match self.parse_line_number(target)? {
+                        0 => Ok(Statement::Resume(ResumeOption::Bare)),
+                        n => Ok(Statement::Resume(ResumeOption::Label(
+                            CaseInsensitiveString::new(n.to_string()),
+                        ))),
+                    };
+                }
+                _ => {
+                    self.buf_lexer.undo(target);
+                }
+            }
+        }
+        self.buf_lexer.undo(next);
+        Ok(Statement::Resume(ResumeOption::Bare))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::*;
+    use crate::parser::test_utils::*;
+    use crate::parser::{ResumeOption, Statement};
+
+    #[test]
+    fn test_resume() {
+        assert_eq!(
+            parse("RESUME").demand_single_statement(),
+            Statement::Resume(ResumeOption::Bare)
+        );
+        assert_eq!(
+            parse("RESUME 0").demand_single_statement(),
+            Statement::Resume(ResumeOption::Bare)
+        );
+    }
+
+    #[test]
+    fn test_resume_next() {
+        assert_eq!(
+            parse("RESUME NEXT").demand_single_statement(),
+            Statement::Resume(ResumeOption::Next)
+        );
+    }
+
+    #[test]
+    fn test_resume_label() {
+        assert_eq!(
+            parse("RESUME Retry").demand_single_statement(),
+            Statement::Resume(ResumeOption::Label(CaseInsensitiveString::new(
+                "Retry".to_string()
+            )))
+        );
+        assert_eq!(
+            parse("RESUME 0150").demand_single_statement(),
+            Statement::Resume(ResumeOption::Label(CaseInsensitiveString::new(
+                "150".to_string()
+            )))
+        );
+    }
+}
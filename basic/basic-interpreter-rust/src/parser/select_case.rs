@@ -2,6 +2,7 @@ use super::{
     unexpected, CaseBlockNode, CaseExpression, ExpressionNode, Operand, Parser, ParserError,
     SelectCaseNode, Statement, StatementNodes,
 };
+use crate::common::AtLocation;
 use crate::lexer::{Keyword, LexemeNode};
 use std::io::BufRead;
 
@@ -18,6 +19,7 @@ impl<T: BufRead> Parser<T> {
         let mut next = self.read_skipping_whitespace_and_eol()?;
         let mut has_more = true;
         let mut else_block: Option<StatementNodes> = None;
+        let mut comments: StatementNodes = vec![];
         while has_more {
             match next {
                 LexemeNode::Keyword(Keyword::End, _, _) => {
@@ -31,12 +33,17 @@ impl<T: BufRead> Parser<T> {
                     // CASE something
                     next = self.read_after_case(&mut case_blocks, &mut else_block)?;
                 }
+                LexemeNode::Comment(text, pos) if case_blocks.is_empty() => {
+                    comments.push(Statement::Comment(text).at(pos));
+                    next = self.read_skipping_whitespace_and_eol()?;
+                }
                 _ => return unexpected("Expected CASE or END", next),
             }
         }
 
         Ok(Statement::SelectCase(SelectCaseNode {
             expr,
+            comments,
             case_blocks,
             else_block,
         }))
@@ -73,6 +80,8 @@ impl<T: BufRead> Parser<T> {
             LexemeNode::Symbol('<', _) => {
                 op = if self.try_read_equals()? {
                     Operand::LessOrEqual
+                } else if self.buf_lexer.skip_if(|lexeme| lexeme.is_symbol('>'))? {
+                    Operand::NotEqual
                 } else {
                     Operand::Less
                 }
@@ -85,7 +94,7 @@ impl<T: BufRead> Parser<T> {
                 }
             }
             _ => {
-                return unexpected("Expected =, < or > after IS", first);
+                return unexpected("Expected =, <, >, <=, >= or <> after IS", first);
             }
         }
         let expr = self.read_demand_expression_skipping_whitespace()?;
@@ -8,18 +8,51 @@ use std::io::BufRead;
 impl<T: BufRead> Parser<T> {
     pub fn demand_statement(&mut self, next: LexemeNode) -> Result<StatementNode, ParserError> {
         match next {
+            LexemeNode::Keyword(Keyword::Close, _, pos) => self.demand_close().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::Const, _, pos) => self.demand_const().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Dim, _, pos) => self.demand_dim().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Do, _, pos) => self.demand_do_loop().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Erase, _, pos) => self.demand_erase().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Error, _, pos) => self.demand_error().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Exit, _, pos) => self.demand_exit().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Field, _, pos) => self.demand_field().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::For, _, pos) => self.demand_for_loop().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Get, _, pos) => {
+                self.demand_get_or_put("GET").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::GoSub, _, pos) => self.demand_go_sub().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::GoTo, _, pos) => self.demand_go_to().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::If, _, pos) => self.demand_if_block().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::Input, w, pos) => self.demand_input(w, pos),
+            LexemeNode::Keyword(Keyword::Line, _, pos) => {
+                self.demand_line_input().map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::LPrint, w, pos) => self.demand_lprint(w, pos),
+            LexemeNode::Keyword(Keyword::LSet, _, pos) => {
+                self.demand_lset_or_rset("LSET").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::Name, _, pos) => self.demand_name().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::On, _, pos) => self.demand_on().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Open, _, pos) => self.demand_open().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Print, w, pos) => self.demand_print(w, pos),
+            LexemeNode::Keyword(Keyword::Put, _, pos) => {
+                self.demand_get_or_put("PUT").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::ReDim, _, pos) => self.demand_redim().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Resume, _, pos) => self.demand_resume().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Return, _, pos) => self.demand_return().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::RSet, _, pos) => {
+                self.demand_lset_or_rset("RSET").map(|x| x.at(pos))
+            }
             LexemeNode::Keyword(Keyword::Select, _, pos) => {
                 self.demand_select_case().map(|x| x.at(pos))
             }
             LexemeNode::Keyword(Keyword::While, _, pos) => {
                 self.demand_while_block().map(|x| x.at(pos))
             }
+            LexemeNode::Keyword(Keyword::Write, w, pos) => self.demand_write(w, pos),
+            LexemeNode::Digits(_, pos) => self.demand_line_number_label(next, pos),
+            LexemeNode::Comment(text, pos) => Ok(Statement::Comment(text).at(pos)),
             _ => self.demand_assignment_or_sub_call_or_label(next, true),
         }
     }
@@ -35,8 +68,39 @@ impl<T: BufRead> Parser<T> {
                 p,
                 false,
             ),
+            LexemeNode::Keyword(Keyword::Close, _, pos) => self.demand_close().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Error, _, pos) => self.demand_error().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Exit, _, pos) => self.demand_exit().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Field, _, pos) => self.demand_field().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Get, _, pos) => {
+                self.demand_get_or_put("GET").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::GoSub, _, pos) => self.demand_go_sub().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::GoTo, _, pos) => self.demand_go_to().map(|x| x.at(pos)),
             LexemeNode::Keyword(Keyword::Input, w, pos) => self.demand_input(w, pos),
+            LexemeNode::Keyword(Keyword::Line, _, pos) => {
+                self.demand_line_input().map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::LPrint, w, pos) => self.demand_lprint(w, pos),
+            LexemeNode::Keyword(Keyword::LSet, _, pos) => {
+                self.demand_lset_or_rset("LSET").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::Name, _, pos) => self.demand_name().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Open, _, pos) => self.demand_open().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Print, w, pos) => self.demand_print(w, pos),
+            LexemeNode::Keyword(Keyword::Put, _, pos) => {
+                self.demand_get_or_put("PUT").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::Resume, _, pos) => self.demand_resume().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::Return, _, pos) => self.demand_return().map(|x| x.at(pos)),
+            LexemeNode::Keyword(Keyword::RSet, _, pos) => {
+                self.demand_lset_or_rset("RSET").map(|x| x.at(pos))
+            }
+            LexemeNode::Keyword(Keyword::Write, w, pos) => self.demand_write(w, pos),
+            // IF X THEN 100 is the same as IF X THEN GOTO 100
+            LexemeNode::Digits(_, pos) => {
+                Ok(Statement::GoTo(self.demand_line_number(next)?).at(pos))
+            }
             _ => unexpected("Expected assignment, sub-call or GOTO after THEN", next),
         }
     }
@@ -91,9 +155,13 @@ impl<T: BufRead> Parser<T> {
                     unexpected("Expected type qualifier", next)
                 }
             }
-            LexemeNode::Symbol('(', _) => {
-                // parenthesis e.g. Log("message")
-                self.demand_sub_call(BareNameNode::new(bare_name, bare_name_pos), next)
+            LexemeNode::Symbol('(', paren_pos) => {
+                // array element assignment e.g. A(1) = 2
+                // or parenthesis e.g. Log("message")
+                self.demand_array_element_assignment_or_sub_call(
+                    BareNameNode::new(bare_name, bare_name_pos),
+                    paren_pos,
+                )
             }
             LexemeNode::Symbol(ch, _) => match TypeQualifier::try_from(ch) {
                 Ok(q) => self._demand_assignment_or_sub_call_with_qualified_name(
@@ -130,10 +198,54 @@ impl<T: BufRead> Parser<T> {
             LexemeNode::Symbol('=', _) => {
                 self.read_demand_assignment_skipping_whitespace(name_node)
             }
+            LexemeNode::Symbol('(', _) => {
+                let indices = self.parse_expression_list_with_parentheses()?;
+                self.read_demand_symbol_skipping_whitespace('=')?;
+                self.read_demand_array_element_assignment_skipping_whitespace(name_node, indices)
+            }
             _ => unexpected("Syntax error", next),
         }
     }
 
+    fn demand_array_element_assignment_or_sub_call(
+        &mut self,
+        name_node: BareNameNode,
+        paren_pos: Location,
+    ) -> Result<StatementNode, ParserError> {
+        let mut args = self.parse_expression_list_with_parentheses()?;
+        let (opt_space, next) = self.read_preserve_whitespace()?;
+        if opt_space.is_none() && next.is_symbol('.') {
+            // assignment to a member of an array element, e.g. A(1).Suit = "Hearts"
+            self.buf_lexer.undo(next);
+            let (bare_name, pos) = name_node.consume();
+            let array_element = Expression::FunctionCall(Name::new_bare(bare_name), args).at(pos);
+            let left_side = self.try_parse_member(array_element)?;
+            self.read_demand_symbol_skipping_whitespace('=')?;
+            let right_side = self.read_demand_expression_skipping_whitespace()?;
+            self.read_demand_eol_or_eof_skipping_whitespace()?;
+            Ok(Statement::MemberAssignment(left_side, right_side).at(pos))
+        } else if next.is_symbol('=') {
+            let (bare_name, pos) = name_node.consume();
+            self.read_demand_array_element_assignment_skipping_whitespace(
+                Name::new_bare(bare_name).at(pos),
+                args,
+            )
+        } else if args.len() == 1 {
+            // sub call whose first argument starts with a parenthesis
+            self.buf_lexer.undo(next);
+            if let Some(space) = opt_space {
+                self.buf_lexer.undo(space);
+            }
+            let inner = args.pop().unwrap();
+            let first_arg = self.try_parse_second_expression(
+                Expression::Parenthesis(Box::new(inner)).at(paren_pos),
+            )?;
+            self.demand_sub_call_after_first_arg(name_node, first_arg)
+        } else {
+            unexpected("Syntax error", next)
+        }
+    }
+
     pub fn parse_statements<F, S: AsRef<str>>(
         &mut self,
         exit_predicate: F,
@@ -157,31 +269,67 @@ impl<T: BufRead> Parser<T> {
         }
     }
 
+    /// A line number at the start of a line, e.g. `10 PRINT "hi"`, acts as a label.
+    /// The rest of the line is parsed as the next statement.
+    fn demand_line_number_label(
+        &mut self,
+        next: LexemeNode,
+        pos: Location,
+    ) -> Result<StatementNode, ParserError> {
+        let line_number = self.parse_line_number(next)?;
+        self.buf_lexer.set_line_number(pos.row(), line_number);
+        let label = CaseInsensitiveString::new(line_number.to_string());
+        Ok(Statement::Label(label).at(pos.with_line_number(line_number)))
+    }
+
     fn demand_on(&mut self) -> Result<Statement, ParserError> {
         self.read_demand_whitespace("Expected space after ON")?;
-        self.read_demand_keyword(Keyword::Error)?;
+        let next = self.buf_lexer.read()?;
+        if !next.is_keyword(Keyword::Error) {
+            let expr = self.demand_expression(next)?;
+            return self.demand_on_go_to_or_go_sub(expr);
+        }
         self.read_demand_whitespace("Expected space after ERROR")?;
         self.read_demand_keyword(Keyword::GoTo)?;
         self.read_demand_whitespace("Expected space after GOTO")?;
-        let name_node = self.read_demand_bare_name_node("Expected label name")?;
-        let (name, _) = name_node.consume();
-        Ok(Statement::ErrorHandler(name))
+        let name = self.read_demand_label()?;
+        if &name == "0" {
+            // ON ERROR GOTO 0 disables error handling
+            Ok(Statement::ErrorHandler(None))
+        } else {
+            Ok(Statement::ErrorHandler(Some(name)))
+        }
+    }
+
+    fn demand_error(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after ERROR")?;
+        let expr = self.read_demand_expression()?;
+        Ok(Statement::Error(expr))
+    }
+
+    fn demand_exit(&mut self) -> Result<Statement, ParserError> {
+        self.read_demand_whitespace("Expected space after EXIT")?;
+        let next = self.buf_lexer.read()?;
+        match next {
+            LexemeNode::Keyword(Keyword::Do, _, _) => Ok(Statement::Exit(ExitObject::Do)),
+            LexemeNode::Keyword(Keyword::For, _, _) => Ok(Statement::Exit(ExitObject::For)),
+            _ => unexpected("Expected DO or FOR after EXIT", next),
+        }
     }
 
     fn demand_go_to(&mut self) -> Result<Statement, ParserError> {
         self.read_demand_whitespace("Expected space after GOTO")?;
-        let name_node = self.read_demand_bare_name_node("Expected label name")?;
-        let (name, _) = name_node.consume();
+        let name = self.read_demand_label()?;
         Ok(Statement::GoTo(name))
     }
 
-    fn demand_input(
+    fn demand_write(
         &mut self,
         raw_name: String,
         bare_name_pos: Location,
     ) -> Result<StatementNode, ParserError> {
-        self.read_demand_whitespace("Expected space after INPUT")?;
-        let next = self.buf_lexer.read()?;
+        // WRITE without arguments writes an empty line
+        let next = self.read_skipping_whitespace()?;
         self.demand_sub_call(
             BareNameNode::new(CaseInsensitiveString::new(raw_name), bare_name_pos),
             next,
@@ -1,8 +1,9 @@
 use super::{
-    unexpected, BareNameNode, ExpressionNode, Parser, ParserError, Statement, StatementNode,
+    unexpected, ArgumentNodes, BareNameNode, ExpressionNode, Parser, ParserError, Statement,
+    StatementNode,
 };
 use crate::common::*;
-use crate::lexer::LexemeNode;
+use crate::lexer::{Keyword, LexemeNode};
 use std::io::BufRead;
 
 impl<T: BufRead> Parser<T> {
@@ -11,12 +12,35 @@ impl<T: BufRead> Parser<T> {
         name_node: BareNameNode,
         initial: LexemeNode,
     ) -> Result<StatementNode, ParserError> {
-        let mut args: Vec<ExpressionNode> = vec![];
+        self.demand_sub_call_remaining_args(name_node, vec![], initial)
+    }
+
+    /// Parses the remaining arguments of a sub call, after the first argument
+    /// has already been parsed, e.g. `Log("message")`.
+    pub fn demand_sub_call_after_first_arg(
+        &mut self,
+        name_node: BareNameNode,
+        first_arg: ExpressionNode,
+    ) -> Result<StatementNode, ParserError> {
+        let next = self.read_skipping_whitespace()?;
+        self.demand_sub_call_remaining_args(name_node, vec![first_arg], next)
+    }
+
+    fn demand_sub_call_remaining_args(
+        &mut self,
+        name_node: BareNameNode,
+        mut args: ArgumentNodes,
+        initial: LexemeNode,
+    ) -> Result<StatementNode, ParserError> {
         const STATE_INITIAL: u8 = 0;
         const STATE_EOL_OR_EOF: u8 = 1;
         const STATE_ARG: u8 = 2;
         const STATE_COMMA: u8 = 3;
-        let mut state = STATE_INITIAL;
+        let mut state = if args.is_empty() {
+            STATE_INITIAL
+        } else {
+            STATE_ARG
+        };
         let mut next = initial;
         while state != STATE_EOL_OR_EOF {
             match next {
@@ -27,6 +51,13 @@ impl<T: BufRead> Parser<T> {
                         return unexpected("Expected argument after comma", next);
                     }
                 }
+                LexemeNode::Keyword(Keyword::Else, _, _)
+                    if state == STATE_INITIAL || state == STATE_ARG =>
+                {
+                    // the statement after THEN of a single line IF ends at ELSE
+                    self.buf_lexer.undo(next);
+                    break;
+                }
                 LexemeNode::Symbol(',', _) => {
                     if state == STATE_ARG {
                         state = STATE_COMMA;
@@ -35,6 +66,12 @@ impl<T: BufRead> Parser<T> {
                         return unexpected("Syntax error", next);
                     }
                 }
+                LexemeNode::Symbol('#', _) if state == STATE_INITIAL => {
+                    // a file number can only be the first argument, e.g. PRINT #1, A$
+                    args.push(self.demand_file_handle(next)?);
+                    state = STATE_ARG;
+                    next = self.read_skipping_whitespace()?;
+                }
                 _ => {
                     if state == STATE_INITIAL || state == STATE_COMMA {
                         args.push(self.demand_expression(next)?);
@@ -70,7 +107,13 @@ mod tests {
         let program = parse(input).demand_single_statement();
         assert_eq!(
             program,
-            Statement::SubCall("PRINT".into(), vec!["Hello, world!".as_lit_expr(1, 7)])
+            Statement::SubCall(
+                "PRINT".into(),
+                vec![
+                    print_arg_kind(PrintArg::Expression, 1, 7),
+                    "Hello, world!".as_lit_expr(1, 7)
+                ]
+            )
         );
     }
 
@@ -79,7 +122,13 @@ mod tests {
         let program = parse_file("HELLO1.BAS").demand_single_statement();
         assert_eq!(
             program,
-            Statement::SubCall("PRINT".into(), vec!["Hello, world!".as_lit_expr(1, 7)])
+            Statement::SubCall(
+                "PRINT".into(),
+                vec![
+                    print_arg_kind(PrintArg::Expression, 1, 7),
+                    "Hello, world!".as_lit_expr(1, 7)
+                ]
+            )
         );
     }
 
@@ -90,7 +139,13 @@ mod tests {
             program,
             Statement::SubCall(
                 "PRINT".into(),
-                vec!["Hello".as_lit_expr(1, 7), "world!".as_lit_expr(1, 16)]
+                vec![
+                    print_arg_kind(PrintArg::Expression, 1, 7),
+                    "Hello".as_lit_expr(1, 7),
+                    print_arg_kind(PrintArg::Comma, 1, 14),
+                    print_arg_kind(PrintArg::Expression, 1, 16),
+                    "world!".as_lit_expr(1, 16)
+                ]
             )
         );
     }
@@ -103,7 +158,10 @@ mod tests {
             vec![
                 TopLevelToken::Statement(Statement::SubCall(
                     "PRINT".into(),
-                    vec!["Hello, world!".as_lit_expr(1, 7)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 1, 7),
+                        "Hello, world!".as_lit_expr(1, 7)
+                    ]
                 )),
                 TopLevelToken::Statement(Statement::SubCall("SYSTEM".into(), vec![])),
             ],
@@ -118,11 +176,18 @@ mod tests {
             vec![
                 TopLevelToken::Statement(Statement::SubCall(
                     "INPUT".into(),
-                    vec!["N".as_var_expr(1, 7)]
+                    vec![
+                        input_prompt_kind(InputPrompt::QuestionMark, 1, 1),
+                        "".as_lit_expr(1, 1),
+                        "N".as_var_expr(1, 7)
+                    ]
                 )),
                 TopLevelToken::Statement(Statement::SubCall(
                     "PRINT".into(),
-                    vec!["N".as_var_expr(2, 7)]
+                    vec![
+                        print_arg_kind(PrintArg::Expression, 2, 7),
+                        "N".as_var_expr(2, 7)
+                    ]
                 )),
             ],
         );
@@ -135,11 +200,14 @@ mod tests {
             program,
             vec![TopLevelToken::Statement(Statement::SubCall(
                 "PRINT".into(),
-                vec![Expression::FunctionCall(
-                    Name::from("ENVIRON$"),
-                    vec!["PATH".as_lit_expr(1, 16)]
-                )
-                .at_rc(1, 7)]
+                vec![
+                    print_arg_kind(PrintArg::Expression, 1, 7),
+                    Expression::FunctionCall(
+                        Name::from("ENVIRON$"),
+                        vec!["PATH".as_lit_expr(1, 16)]
+                    )
+                    .at_rc(1, 7)
+                ]
             ))]
         );
     }
@@ -190,7 +258,7 @@ mod tests {
                 // DECLARE SUB Hello
                 TopLevelToken::SubDeclaration(
                     "Hello".as_bare_name(2, 21),
-                    vec!["N$".as_name(2, 27), "V$".as_name(2, 31)],
+                    vec!["N$".as_param(2, 27), "V$".as_param(2, 31)],
                 ),
                 // Hello
                 TopLevelToken::Statement(Statement::SubCall(
@@ -200,22 +268,22 @@ mod tests {
                 // SUB Hello
                 TopLevelToken::SubImplementation(
                     "Hello".as_bare_name(4, 13),
-                    vec!["N$".as_name(4, 19), "V$".as_name(4, 23)],
+                    vec!["N$".as_param(4, 19), "V$".as_param(4, 23)],
                     vec![Statement::SubCall(
                         "ENVIRON".into(),
                         vec![Expression::BinaryExpression(
                             Operand::Plus,
-                            Box::new("N$".as_var_expr(5, 21)),
                             Box::new(
                                 Expression::BinaryExpression(
                                     Operand::Plus,
-                                    Box::new("=".as_lit_expr(5, 26)),
-                                    Box::new("V$".as_var_expr(5, 32))
+                                    Box::new("N$".as_var_expr(5, 21)),
+                                    Box::new("=".as_lit_expr(5, 26))
                                 )
-                                .at_rc(5, 30)
-                            )
+                                .at_rc(5, 24)
+                            ),
+                            Box::new("V$".as_var_expr(5, 32))
                         )
-                        .at_rc(5, 24)]
+                        .at_rc(5, 30)]
                     )
                     .at_rc(5, 13)],
                 )
@@ -1,6 +1,6 @@
 use super::{
-    BareNameNode, Expression, ExpressionNode, Name, NameNode, Parser, ParserError, ProgramNode,
-    Statement, TopLevelToken, TopLevelTokenNode,
+    BareNameNode, DimType, Expression, ExpressionNode, Name, NameNode, ParamName, ParamNameNode,
+    Parser, ParserError, ProgramNode, Statement, TopLevelToken, TopLevelTokenNode,
 };
 use crate::common::*;
 use std::fs::File;
@@ -57,6 +57,7 @@ impl ProgramNodeHelper for ProgramNode {
 pub trait NameNodeFactory {
     fn as_name(&self, row: u32, col: u32) -> NameNode;
     fn as_bare_name(&self, row: u32, col: u32) -> BareNameNode;
+    fn as_param(&self, row: u32, col: u32) -> ParamNameNode;
 }
 
 impl NameNodeFactory for str {
@@ -70,6 +71,15 @@ impl NameNodeFactory for str {
             Location::new(row, col),
         )
     }
+
+    fn as_param(&self, row: u32, col: u32) -> ParamNameNode {
+        ParamName {
+            name: Name::from(self),
+            is_array: false,
+            param_type: DimType::Bare,
+        }
+        .at(Location::new(row, col))
+    }
 }
 
 //
@@ -81,6 +91,16 @@ pub trait ExpressionNodeLiteralFactory {
     fn as_lit_expr(&self, row: u32, col: u32) -> ExpressionNode;
 }
 
+/// Creates the argument that the parser puts before each argument of `PRINT`,
+/// holding its kind.
+pub fn print_arg_kind(kind: PrintArg, row: u32, col: u32) -> ExpressionNode {
+    Expression::IntegerLiteral(kind.into()).at_rc(row, col)
+}
+
+pub fn input_prompt_kind(kind: InputPrompt, row: u32, col: u32) -> ExpressionNode {
+    Expression::IntegerLiteral(kind.into()).at_rc(row, col)
+}
+
 impl ExpressionNodeLiteralFactory for &str {
     fn as_lit_expr(&self, row: u32, col: u32) -> ExpressionNode {
         Expression::StringLiteral(self.to_string()).at_rc(row, col)
@@ -1,27 +1,33 @@
 mod bare_name_node;
 mod def_type;
+mod dim_name;
 mod expression_node;
 mod has_qualifier;
 mod letter_range;
 mod name;
 mod name_node;
 mod name_trait;
+mod param_name;
 mod qualified_name;
 mod statement_node;
 mod top_level_token_node;
 mod type_qualifier;
 mod type_resolver;
+mod user_defined_type;
 
 pub use self::bare_name_node::*;
 pub use self::def_type::*;
+pub use self::dim_name::*;
 pub use self::expression_node::*;
 pub use self::has_qualifier::*;
 pub use self::letter_range::*;
 pub use self::name::*;
 pub use self::name_node::*;
 pub use self::name_trait::*;
+pub use self::param_name::*;
 pub use self::qualified_name::*;
 pub use self::statement_node::*;
 pub use self::top_level_token_node::*;
 pub use self::type_qualifier::*;
 pub use self::type_resolver::*;
+pub use self::user_defined_type::*;
@@ -0,0 +1,50 @@
+use super::{BareNameNode, ExpressionNode, Name, NameTrait, TypeQualifier};
+use crate::common::{CaseInsensitiveString, Locatable};
+
+/// A dimension of an array, e.g. `10` or `1 TO 10`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrayDimension {
+    /// The lower bound. If missing, it is determined by `OPTION BASE`.
+    pub lbound: Option<ExpressionNode>,
+    pub ubound: ExpressionNode,
+}
+
+/// The type of a `DIM` declaration or of a parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DimType {
+    /// No `AS` clause, the type comes from the name, e.g. `DIM A$(10)`.
+    Bare,
+    /// A built-in type, e.g. `DIM A(10) AS INTEGER`.
+    BuiltIn(TypeQualifier),
+    /// A fixed length string, e.g. `DIM A AS STRING * 5`.
+    FixedLengthString(u16),
+    /// A user defined type, e.g. `DIM P AS Card`.
+    UserDefined(BareNameNode),
+}
+
+/// A variable or an array declared by a `DIM` or `REDIM` statement,
+/// e.g. `X AS STRING` or `A(1 TO 5, 1 TO 5) AS INTEGER`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DimName {
+    pub name: Name,
+    /// The dimensions of an array. It is empty for a variable.
+    pub dimensions: Vec<ArrayDimension>,
+    pub dim_type: DimType,
+}
+
+pub type DimNameNode = Locatable<DimName>;
+
+impl NameTrait for DimName {
+    fn bare_name(&self) -> &CaseInsensitiveString {
+        self.name.bare_name()
+    }
+
+    fn opt_qualifier(&self) -> Option<TypeQualifier> {
+        match &self.dim_type {
+            DimType::Bare => self.name.opt_qualifier(),
+            DimType::BuiltIn(q) => Some(*q),
+            DimType::FixedLengthString(_) => Some(TypeQualifier::DollarString),
+            DimType::UserDefined(_) => None,
+        }
+    }
+}
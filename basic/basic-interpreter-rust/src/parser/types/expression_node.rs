@@ -9,8 +9,56 @@ pub enum Operand {
     Equal,
     GreaterOrEqual,
     Greater,
+    NotEqual,
     Plus,
     Minus,
+    Multiply,
+    Divide,
+    IntegerDivide,
+    Modulo,
+    Power,
+    And,
+    Or,
+    Xor,
+    Eqv,
+    Imp,
+}
+
+impl Operand {
+    pub fn is_relational(&self) -> bool {
+        matches!(
+            self,
+            Self::Less
+                | Self::LessOrEqual
+                | Self::Equal
+                | Self::GreaterOrEqual
+                | Self::Greater
+                | Self::NotEqual
+        )
+    }
+
+    /// Gets the priority of this operand. Higher priority operands bind
+    /// tighter than lower priority ones.
+    pub fn priority(&self) -> u8 {
+        match self {
+            Self::Power => PRIORITY_POWER,
+            Self::Multiply | Self::Divide => 12,
+            Self::IntegerDivide => 11,
+            Self::Modulo => 10,
+            Self::Plus | Self::Minus => 9,
+            Self::Less
+            | Self::LessOrEqual
+            | Self::Equal
+            | Self::GreaterOrEqual
+            | Self::Greater
+            | Self::NotEqual => PRIORITY_RELATIONAL,
+            Self::And => 6,
+            Self::Or => 5,
+            Self::Xor => 4,
+            Self::Eqv => 3,
+            Self::Imp => 2,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -20,6 +68,22 @@ pub enum UnaryOperand {
     Not,
 }
 
+const PRIORITY_POWER: u8 = 14;
+const PRIORITY_RELATIONAL: u8 = 8;
+
+impl UnaryOperand {
+    /// Gets the priority of this operand, comparable to the priority of
+    /// binary operands (see `Operand::priority`).
+    pub fn priority(&self) -> u8 {
+        match self {
+            // -2 ^ 2 is -(2 ^ 2) but -2 * 3 is (-2) * 3
+            Self::Minus => PRIORITY_POWER - 1,
+            // NOT A < B is NOT (A < B)
+            Self::Not => PRIORITY_RELATIONAL - 1,
+        }
+    }
+}
+
 pub type ArgumentNodes = Vec<ExpressionNode>;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -32,9 +96,13 @@ pub enum Expression {
     LongLiteral(i64),
     VariableName(Name),
     FunctionCall(Name, ArgumentNodes),
+    /// A member of an array element of a user defined type, e.g. `A(1).Suit`
+    Member(Box<ExpressionNode>, Name),
     BinaryExpression(Operand, Box<ExpressionNode>, Box<ExpressionNode>),
     UnaryExpression(UnaryOperand, Box<ExpressionNode>),
     Parenthesis(Box<ExpressionNode>),
+    /// A file number, e.g. `#1` in `PRINT #1, A$`
+    FileHandle(Box<ExpressionNode>),
 }
 
 pub type ExpressionNode = Locatable<Expression>;
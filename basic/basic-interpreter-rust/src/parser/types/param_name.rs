@@ -0,0 +1,36 @@
+use super::{DimType, Name, NameTrait, TypeQualifier};
+use crate::common::{CaseInsensitiveString, Locatable};
+
+/// A parameter of a function or sub, e.g. `N$`, `A()` or `X AS INTEGER`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamName {
+    pub name: Name,
+    pub is_array: bool,
+    /// The type of the parameter. Fixed length strings are not allowed.
+    pub param_type: DimType,
+}
+
+pub type ParamNameNode = Locatable<ParamName>;
+
+impl NameTrait for ParamName {
+    fn bare_name(&self) -> &CaseInsensitiveString {
+        self.name.bare_name()
+    }
+
+    fn opt_qualifier(&self) -> Option<TypeQualifier> {
+        match &self.param_type {
+            DimType::BuiltIn(q) => Some(*q),
+            _ => self.name.opt_qualifier(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq<str> for ParamNameNode {
+    fn eq(&self, other: &str) -> bool {
+        let my_param: &ParamName = self.as_ref();
+        !my_param.is_array
+            && my_param.param_type == DimType::Bare
+            && my_param.name == Name::from(other)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ArgumentNodes, BareName, ExpressionNode, Name, NameNode, Operand};
+use super::{ArgumentNodes, BareName, DimNameNode, ExpressionNode, Name, NameNode, Operand};
 use crate::common::*;
 
 pub type StatementNodes = Vec<StatementNode>;
@@ -7,7 +7,14 @@ pub type StatementNode = Locatable<Statement>;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Statement {
     Assignment(Name, ExpressionNode),
+    /// Assignment to an array element, e.g. `A(1, 2) = 3`
+    ArrayElementAssignment(Name, ArgumentNodes, ExpressionNode),
+    /// Assignment to a member of an array element, e.g. `A(1).Suit = "Hearts"`
+    MemberAssignment(ExpressionNode, ExpressionNode),
     Const(NameNode, ExpressionNode),
+    Dim(Vec<DimNameNode>),
+    ReDim(Vec<DimNameNode>),
+    Erase(Vec<NameNode>),
     SubCall(BareName, ArgumentNodes),
 
     IfBlock(IfBlockNode),
@@ -15,10 +22,27 @@ pub enum Statement {
 
     ForLoop(ForLoopNode),
     While(ConditionalBlockNode),
+    DoLoop(DoLoopNode),
+    Exit(ExitObject),
 
-    ErrorHandler(CaseInsensitiveString),
+    /// `ON ERROR GOTO label`, or `ON ERROR GOTO 0` (`None`) to disable error handling.
+    ErrorHandler(Option<CaseInsensitiveString>),
+    /// `ERROR n`, raises the runtime error with the given code.
+    Error(ExpressionNode),
+    /// `RESUME`, `RESUME NEXT` or `RESUME label`, returns from an error handler.
+    Resume(ResumeOption),
     Label(CaseInsensitiveString),
     GoTo(CaseInsensitiveString),
+    GoSub(CaseInsensitiveString),
+    /// `RETURN`, with an optional label to jump to instead of the statement after `GOSUB`.
+    Return(Option<CaseInsensitiveString>),
+    /// Computed `GOTO`, e.g. `ON N GOTO Alpha, Beta`
+    OnGoTo(ExpressionNode, Vec<CaseInsensitiveString>),
+    /// Computed `GOSUB`, e.g. `ON N GOSUB Alpha, Beta`
+    OnGoSub(ExpressionNode, Vec<CaseInsensitiveString>),
+    /// A comment that starts with `'` or `REM`, without them.
+    /// A comment at the end of a line follows the statement of the line.
+    Comment(String),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -31,6 +55,53 @@ pub struct ForLoopNode {
     pub next_counter: Option<NameNode>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoLoopNode {
+    /// The optional condition, e.g. `DO WHILE A < 5` or `LOOP UNTIL A >= 5`.
+    /// Without a condition, the loop runs until `EXIT DO`.
+    pub condition: Option<DoLoopCondition>,
+    pub statements: StatementNodes,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoLoopCondition {
+    pub position: DoLoopConditionPosition,
+    pub kind: DoLoopConditionKind,
+    pub expr: ExpressionNode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoLoopConditionPosition {
+    /// The condition is next to `DO` and is evaluated before each iteration.
+    Top,
+    /// The condition is next to `LOOP` and is evaluated after each iteration.
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoLoopConditionKind {
+    While,
+    Until,
+}
+
+/// The loop that is exited by an `EXIT` statement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExitObject {
+    Do,
+    For,
+}
+
+/// Where execution continues after `RESUME`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResumeOption {
+    /// `RESUME` or `RESUME 0`, retries the statement that caused the error.
+    Bare,
+    /// `RESUME NEXT`, continues with the statement after the one that caused the error.
+    Next,
+    /// `RESUME label`, continues at the given label.
+    Label(CaseInsensitiveString),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConditionalBlockNode {
     pub condition: ExpressionNode,
@@ -48,6 +119,8 @@ pub struct IfBlockNode {
 pub struct SelectCaseNode {
     /// The expression been matched
     pub expr: ExpressionNode,
+    /// The comments between SELECT CASE and the first CASE
+    pub comments: StatementNodes,
     /// The case statements
     pub case_blocks: Vec<CaseBlockNode>,
     /// An optional CASE ELSE block
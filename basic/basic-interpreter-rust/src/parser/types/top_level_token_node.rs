@@ -1,9 +1,11 @@
-use super::{BareNameNode, DefType, NameNode, Statement, StatementNodes};
+use super::{
+    BareNameNode, DefType, NameNode, ParamNameNode, Statement, StatementNodes, UserDefinedType,
+};
 use crate::common::*;
 
 pub type ProgramNode = Vec<TopLevelTokenNode>;
 pub type TopLevelTokenNode = Locatable<TopLevelToken>;
-pub type ParamNodes = Vec<NameNode>;
+pub type ParamNodes = Vec<ParamNameNode>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum TopLevelToken {
@@ -16,6 +18,9 @@ pub enum TopLevelToken {
     /// A function implementation
     FunctionImplementation(NameNode, ParamNodes, StatementNodes),
 
+    /// The lower bound of arrays declared without one, e.g. `OPTION BASE 1`
+    OptionBase(u8),
+
     /// A simple or compound statement
     Statement(Statement),
 
@@ -24,6 +29,9 @@ pub enum TopLevelToken {
 
     /// A sub implementation
     SubImplementation(BareNameNode, ParamNodes, StatementNodes),
+
+    /// A user defined type, e.g. `TYPE Card ... END TYPE`
+    UserDefinedType(UserDefinedType),
 }
 
 impl From<Statement> for TopLevelToken {
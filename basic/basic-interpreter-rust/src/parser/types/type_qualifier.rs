@@ -25,6 +25,24 @@ impl TypeQualifier {
             _ => other != Self::DollarString,
         }
     }
+
+    /// Gets the qualifier of the result of an arithmetic operation between
+    /// two numeric qualifiers, which is the most precise of the two
+    /// (integer, long, single, double in increasing precision).
+    pub fn promote(&self, other: Self) -> Self {
+        match self {
+            Self::PercentInteger => other,
+            Self::AmpersandLong => match other {
+                Self::PercentInteger => *self,
+                _ => other,
+            },
+            Self::BangSingle => match other {
+                Self::HashDouble => other,
+                _ => *self,
+            },
+            _ => *self,
+        }
+    }
 }
 
 impl Display for TypeQualifier {
@@ -0,0 +1,31 @@
+use super::{BareName, BareNameNode, TypeQualifier};
+use crate::common::Locatable;
+
+/// A user defined type, e.g. `TYPE Card ... END TYPE`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserDefinedType {
+    pub name: BareNameNode,
+    pub elements: Vec<ElementNode>,
+    /// The comments inside the type, whose location tells where they are
+    pub comments: Vec<Locatable<String>>,
+}
+
+/// An element of a user defined type, e.g. `Value AS INTEGER`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: BareName,
+    pub element_type: ElementType,
+}
+
+pub type ElementNode = Locatable<Element>;
+
+/// The type of an element of a user defined type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElementType {
+    /// A numeric type, e.g. `AS INTEGER`.
+    BuiltIn(TypeQualifier),
+    /// A fixed length string, e.g. `AS STRING * 5`. Variable length strings are not allowed.
+    FixedLengthString(u16),
+    /// Another user defined type, e.g. `AS Address`.
+    UserDefined(BareNameNode),
+}
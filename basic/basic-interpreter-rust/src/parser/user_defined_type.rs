@@ -0,0 +1,212 @@
+use super::{
+    unexpected, BareNameNode, Element, ElementNode, ElementType, Parser, ParserError,
+    TopLevelToken, TypeQualifier, UserDefinedType,
+};
+use crate::common::*;
+use crate::lexer::{Keyword, LexemeNode};
+use std::io::BufRead;
+
+impl<T: BufRead> Parser<T> {
+    pub fn demand_user_defined_type(&mut self) -> Result<TopLevelToken, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after TYPE keyword")?;
+        let name = self.read_demand_bare_name_node("Expected type name")?;
+        self.read_demand_eol_skipping_whitespace()?;
+        let mut elements: Vec<ElementNode> = vec![];
+        let mut comments: Vec<Locatable<String>> = vec![];
+        loop {
+            let next = self.read_skipping_whitespace_and_eol()?;
+            match next {
+                LexemeNode::Keyword(Keyword::End, _, _) => {
+                    if elements.is_empty() {
+                        return unexpected("Expected element name", next);
+                    }
+                    break;
+                }
+                LexemeNode::Word(w, pos) => {
+                    let element_type = self.demand_element_type()?;
+                    elements.push(
+                        Element {
+                            name: CaseInsensitiveString::new(w),
+                            element_type,
+                        }
+                        .at(pos),
+                    );
+                }
+                LexemeNode::Comment(text, pos) => comments.push(text.at(pos)),
+                LexemeNode::EOF(_) => return unexpected("Type without End Type", next),
+                _ => return unexpected("Expected element name", next),
+            }
+        }
+        self.read_demand_whitespace("Expected whitespace after END keyword")?;
+        self.read_demand_keyword(Keyword::Type)?;
+        self.read_demand_eol_or_eof_skipping_whitespace()?;
+        Ok(TopLevelToken::UserDefinedType(UserDefinedType {
+            name,
+            elements,
+            comments,
+        }))
+    }
+
+    /// Parses the `AS` clause of an element, e.g. `AS INTEGER` or `AS STRING * 5`.
+    fn demand_element_type(&mut self) -> Result<ElementType, ParserError> {
+        self.read_demand_whitespace("Expected whitespace after element name")?;
+        self.read_demand_keyword(Keyword::As)?;
+        self.read_demand_whitespace("Expected whitespace after AS")?;
+        let next = self.buf_lexer.read()?;
+        let element_type = match next {
+            LexemeNode::Keyword(Keyword::Double, _, _) => {
+                ElementType::BuiltIn(TypeQualifier::HashDouble)
+            }
+            LexemeNode::Keyword(Keyword::Integer, _, _) => {
+                ElementType::BuiltIn(TypeQualifier::PercentInteger)
+            }
+            LexemeNode::Keyword(Keyword::Long, _, _) => {
+                ElementType::BuiltIn(TypeQualifier::AmpersandLong)
+            }
+            LexemeNode::Keyword(Keyword::Single, _, _) => {
+                ElementType::BuiltIn(TypeQualifier::BangSingle)
+            }
+            LexemeNode::Keyword(Keyword::String, _, _) => match self.try_parse_string_length()? {
+                Some(len) => ElementType::FixedLengthString(len),
+                None => {
+                    // only fixed length strings are allowed in a type
+                    let next = self.read_skipping_whitespace()?;
+                    return unexpected("Expected *", next);
+                }
+            },
+            LexemeNode::Word(w, pos) => {
+                ElementType::UserDefined(BareNameNode::new(CaseInsensitiveString::new(w), pos))
+            }
+            _ => {
+                return unexpected(
+                    "Expected INTEGER, LONG, SINGLE, DOUBLE, STRING or type name",
+                    next,
+                )
+            }
+        };
+        self.read_demand_eol_skipping_whitespace()?;
+        Ok(element_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use crate::common::*;
+    use crate::lexer::{Keyword, LexemeNode};
+    use crate::parser::{
+        Element, ElementType, ParserError, TopLevelToken, TypeQualifier, UserDefinedType,
+    };
+
+    #[test]
+    fn test_user_defined_type() {
+        let input = "
+        TYPE Card
+            Suit AS STRING * 9
+            Value AS INTEGER
+        END TYPE
+        ";
+        let program = parse(input).demand_single();
+        assert_eq!(
+            program,
+            TopLevelToken::UserDefinedType(UserDefinedType {
+                name: "Card".as_bare_name(2, 14),
+                elements: vec![
+                    Element {
+                        name: "Suit".into(),
+                        element_type: ElementType::FixedLengthString(9)
+                    }
+                    .at_rc(3, 13),
+                    Element {
+                        name: "Value".into(),
+                        element_type: ElementType::BuiltIn(TypeQualifier::PercentInteger)
+                    }
+                    .at_rc(4, 13)
+                ],
+                comments: vec![]
+            })
+            .at_rc(2, 9)
+        );
+    }
+
+    #[test]
+    fn test_user_defined_type_with_comments() {
+        let input = "
+        TYPE Card ' a card
+            ' the value
+            Value AS INTEGER
+        END TYPE
+        ";
+        let program = parse(input).demand_single();
+        assert_eq!(
+            program,
+            TopLevelToken::UserDefinedType(UserDefinedType {
+                name: "Card".as_bare_name(2, 14),
+                elements: vec![Element {
+                    name: "Value".into(),
+                    element_type: ElementType::BuiltIn(TypeQualifier::PercentInteger)
+                }
+                .at_rc(4, 13)],
+                comments: vec![
+                    " a card".to_string().at_rc(2, 19),
+                    " the value".to_string().at_rc(3, 13)
+                ]
+            })
+            .at_rc(2, 9)
+        );
+    }
+
+    #[test]
+    fn test_nested_user_defined_type() {
+        let input = "
+        TYPE Person
+            Address AS Address
+        END TYPE
+        ";
+        let program = parse(input).demand_single();
+        assert_eq!(
+            program,
+            TopLevelToken::UserDefinedType(UserDefinedType {
+                name: "Person".as_bare_name(2, 14),
+                elements: vec![Element {
+                    name: "Address".into(),
+                    element_type: ElementType::UserDefined("Address".as_bare_name(3, 24))
+                }
+                .at_rc(3, 13)],
+                comments: vec![]
+            })
+            .at_rc(2, 9)
+        );
+    }
+
+    #[test]
+    fn test_variable_length_string_element_is_error() {
+        let input = "
+        TYPE Card
+            Suit AS STRING
+        END TYPE
+        ";
+        assert_eq!(
+            parse_err(input),
+            ParserError::Unexpected(
+                "Expected *".to_string(),
+                LexemeNode::EOL("\n".to_string(), Location::new(3, 27))
+            )
+        );
+    }
+
+    #[test]
+    fn test_type_without_elements_is_error() {
+        let input = "
+        TYPE Card
+        END TYPE
+        ";
+        assert_eq!(
+            parse_err(input),
+            ParserError::Unexpected(
+                "Expected element name".to_string(),
+                LexemeNode::Keyword(Keyword::End, "END".to_string(), Location::new(3, 9))
+            )
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::ast_printer;
+use crate::common::HasLocation;
+use crate::error::Error;
+use crate::host::HostRegistry;
+use crate::instruction_generator;
+use crate::instruction_generator::InstructionNode;
+use crate::interpreter;
+use crate::interpreter::{Debugger, Stdlib};
+use crate::linter;
+use crate::parser;
+use crate::parser::NameTrait;
+use crate::parser::Parser;
+use std::io::{BufReader, Read};
+use std::rc::Rc;
+
+/// A program that has been parsed, but not checked yet.
+#[derive(Debug)]
+pub struct Program(parser::ProgramNode);
+
+impl Program {
+    /// Parses the source code of a program.
+    pub fn parse<R: Read>(reader: R) -> Result<Self, Error> {
+        let mut parser = Parser::from_reader(BufReader::new(reader));
+        Ok(Self(parser.parse()?))
+    }
+
+    /// Checks the program for the errors that QBasic reports before running it,
+    /// e.g. type mismatches, and resolves the types of all names.
+    pub fn lint(self) -> Result<LintedProgram, Error> {
+        Ok(LintedProgram(linter::lint(self.0)?))
+    }
+
+    /// Like `lint`, for a program that can call the functions and subs of the host.
+    pub fn lint_with_host(self, host: &HostRegistry) -> Result<LintedProgram, Error> {
+        Ok(LintedProgram(linter::lint_with_host(self.0, host)?))
+    }
+
+    /// Prints the program as source code in the canonical style,
+    /// which keeps the comments and uppercases the keywords.
+    pub fn format(&self) -> String {
+        ast_printer::print_program(&self.0)
+    }
+}
+
+/// A program that has been checked and can be compiled.
+#[derive(Debug)]
+pub struct LintedProgram(linter::ProgramNode);
+
+impl LintedProgram {
+    /// The row where each top level part of the program starts, in the order of the source,
+    /// with the name of the SUB or FUNCTION that the part implements, or `None` for the
+    /// other parts, e.g. the statements of the main program.
+    pub fn outline(&self) -> Vec<(u32, Option<String>)> {
+        self.0
+            .iter()
+            .map(|t| {
+                let name = match t.as_ref() {
+                    linter::TopLevelToken::FunctionImplementation(f) => {
+                        Some(f.name.bare_name().to_string())
+                    }
+                    linter::TopLevelToken::SubImplementation(s) => {
+                        Some(s.name.bare_name().to_string())
+                    }
+                    _ => None,
+                };
+                (t.location().row(), name)
+            })
+            .collect()
+    }
+
+    /// Compiles the program to the instructions that the interpreter runs.
+    pub fn compile(self) -> CompiledProgram {
+        CompiledProgram(instruction_generator::generate_instructions(self.0))
+    }
+}
+
+/// A program that is ready to run. It can run many times.
+#[derive(Debug)]
+pub struct CompiledProgram(Vec<InstructionNode>);
+
+/// Runs compiled programs.
+///
+/// The standard library is the interface of the program with the world,
+/// e.g. the screen, the keyboard and the files.
+#[derive(Debug)]
+pub struct Interpreter<S: Stdlib>(interpreter::Interpreter<S>);
+
+impl<S: Stdlib> Interpreter<S> {
+    pub fn new(stdlib: S) -> Self {
+        Self(interpreter::Interpreter::new(stdlib))
+    }
+
+    /// Creates an interpreter for programs that were linted with `Program::lint_with_host`.
+    pub fn with_host(stdlib: S, host: Rc<HostRegistry>) -> Self {
+        Self(interpreter::Interpreter::with_host(stdlib, host))
+    }
+
+    /// Runs the program until it ends or fails with an error that it doesn't handle.
+    /// Variables and open files are kept between runs.
+    pub fn run(&mut self, program: &CompiledProgram) -> Result<(), Error> {
+        Ok(self.0.interpret(&program.0)?)
+    }
+
+    /// Runs the next programs under the control of the given debugger.
+    /// They stop before their first statement.
+    pub fn set_debugger(&mut self, debugger: Box<dyn Debugger>) {
+        self.0.set_debugger(debugger);
+    }
+
+    pub fn stdlib(&self) -> &S {
+        &self.0.stdlib
+    }
+
+    pub fn stdlib_mut(&mut self) -> &mut S {
+        &mut self.0.stdlib
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Location;
+    use crate::interpreter::test_utils::MockStdlib;
+    use crate::linter::{LinterError, TypeQualifier};
+    use crate::variant::Variant;
+
+    fn compile(source: &str) -> CompiledProgram {
+        Program::parse(source.as_bytes())
+            .unwrap()
+            .lint()
+            .unwrap()
+            .compile()
+    }
+
+    #[test]
+    fn test_run() {
+        let program = compile("PRINT \"hello\"; 1 + 2");
+        let mut interpreter = Interpreter::new(MockStdlib::new());
+        interpreter.run(&program).unwrap();
+        interpreter.run(&program).unwrap();
+        assert_eq!(interpreter.stdlib().output, vec!["hello 3 ", "hello 3 "]);
+    }
+
+    #[test]
+    fn test_parser_error() {
+        let err = Program::parse("PRINT (1".as_bytes()).unwrap_err();
+        assert!(matches!(err, Error::Parser(_)));
+        assert_eq!(err.location(), Some(Location::new(1, 9)));
+    }
+
+    #[test]
+    fn test_linter_error() {
+        let err = Program::parse("A = 1 + \"a\"".as_bytes())
+            .unwrap()
+            .lint()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::Linter(LinterError::TypeMismatch, Some(Location::new(1, 9)))
+        );
+        assert_eq!(err.to_string(), "Type mismatch at 1:9");
+    }
+
+    #[test]
+    fn test_host_sub() {
+        let mut host = HostRegistry::new();
+        host.add_sub("Greet", &[TypeQualifier::DollarString], |args| {
+            args[0] = Variant::VString("hello".to_string());
+            Ok(())
+        });
+        let program = Program::parse("Greet A$\nPRINT A$".as_bytes())
+            .unwrap()
+            .lint_with_host(&host)
+            .unwrap()
+            .compile();
+        let mut interpreter = Interpreter::with_host(MockStdlib::new(), Rc::new(host));
+        interpreter.run(&program).unwrap();
+        assert_eq!(interpreter.stdlib().output, vec!["hello"]);
+    }
+
+    #[test]
+    fn test_interpreter_error() {
+        let program = compile("A = 1\nERROR 53");
+        let mut interpreter = Interpreter::new(MockStdlib::new());
+        let err = interpreter.run(&program).unwrap_err();
+        assert_eq!(err.to_string(), "File not found at 2:1");
+    }
+
+    #[test]
+    fn test_interpreter_error_in_numbered_line() {
+        let program = compile("10 A = 0\n20 PRINT 1 / A");
+        let mut interpreter = Interpreter::new(MockStdlib::new());
+        let err = interpreter.run(&program).unwrap_err();
+        assert_eq!(
+            err.location(),
+            Some(Location::new(2, 12).with_line_number(20))
+        );
+        assert_eq!(err.to_string(), "Division by zero in line 20 at 2:12");
+    }
+
+    #[test]
+    fn test_linter_error_in_numbered_line() {
+        let err = Program::parse("10 A = 1 + \"a\"".as_bytes())
+            .unwrap()
+            .lint()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Type mismatch in line 10 at 1:12");
+    }
+
+    #[test]
+    fn test_outline() {
+        let input = r#"
+        DECLARE FUNCTION Twice(A)
+        ' SUB Fake
+        PRINT "SUB Fake"
+        sub  Greet
+            PRINT Twice(2)
+        end sub
+        PRINT 1
+        FUNCTION Twice(A)
+            Twice = A * 2
+        END FUNCTION
+        "#;
+        let program = Program::parse(input.as_bytes()).unwrap().lint().unwrap();
+        assert_eq!(
+            program.outline(),
+            vec![
+                (3, None),
+                (4, None),
+                (5, Some("Greet".to_string())),
+                (8, None),
+                (9, Some("Twice".to_string())),
+            ]
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use crate::common::{CaseInsensitiveString, HasLocation};
+use crate::error::Error;
+use crate::instruction_generator;
+use crate::instruction_generator::InstructionNode;
+use crate::interpreter::{io_error_message, Interpreter, Stdlib};
+use crate::linter;
+use crate::parser::{NameTrait, Parser, ProgramNode, Statement, TopLevelToken};
+
+/// An interactive session in the spirit of the GW-BASIC editor.
+///
+/// A line that starts with a line number is stored in the program, replacing the
+/// line with the same number. A line number alone deletes the line.
+/// `SUB`, `FUNCTION` and `TYPE` blocks are stored too, replacing the block with the same name.
+/// Anything else runs immediately. The variables are kept between the statements,
+/// until `RUN` or `NEW`. The `DIM`, `CONST` and `DEFINT` (etc) statements are
+/// remembered too, so that the types of the variables are known to the next statements.
+///
+/// A block that is not terminated yet, e.g. `FOR` without `NEXT`, continues on the next lines.
+///
+/// The commands are `LIST`, `RUN`, `NEW`, `LOAD "file"`, `SAVE "file"` and `SYSTEM`.
+/// Like in GW-BASIC, `.BAS` is added to a file name without an extension.
+#[derive(Debug)]
+pub struct Repl<S: Stdlib> {
+    interpreter: Interpreter<S>,
+    /// The lines of the main program. Numbered lines are sorted by line number.
+    lines: Vec<String>,
+    /// The `SUB`, `FUNCTION` and `TYPE` blocks, in the order they were entered
+    blocks: Vec<Block>,
+    /// The `DIM`, `CONST` and `DEFINT` (etc) statements that ran immediately
+    declarations: String,
+    /// The lines of a block that is not terminated yet
+    pending: String,
+}
+
+#[derive(Debug)]
+struct Block {
+    name: CaseInsensitiveString,
+    is_type: bool,
+    source: String,
+}
+
+/// What the user typed, once it is complete.
+enum Entry {
+    Line(u32, String),
+    Block(Block),
+    Statements(String, bool),
+}
+
+enum Command {
+    List,
+    Run,
+    New,
+    Load(String),
+    Save(String),
+    System,
+}
+
+impl<S: Stdlib> Repl<S> {
+    pub fn new(stdlib: S) -> Self {
+        Self {
+            interpreter: Interpreter::new(stdlib),
+            lines: vec![],
+            blocks: vec![],
+            declarations: String::new(),
+            pending: String::new(),
+        }
+    }
+
+    /// Reads and evaluates lines until `SYSTEM` or the end of the input.
+    pub fn run(&mut self) {
+        self.print_line("Ok");
+        while let Ok(line) = self.interpreter.stdlib.input() {
+            self.interpreter.screen_column = 0;
+            let was_pending = !self.pending.is_empty();
+            if !self.eval(&line) {
+                break;
+            }
+            if self.pending.is_empty() && (was_pending || !line.trim().is_empty()) {
+                self.print_line("Ok");
+            }
+        }
+    }
+
+    /// Evaluates a line that the user typed.
+    /// Returns false when the session should end.
+    pub fn eval(&mut self, line: &str) -> bool {
+        if self.pending.is_empty() {
+            if let Some(command) = parse_command(line) {
+                return self.run_command(command);
+            }
+        }
+        match self.read_entry(line) {
+            Ok(Some(Entry::Line(line_number, text))) => self.store_line(line_number, text),
+            Ok(Some(Entry::Block(block))) => {
+                if let Err(e) = self.check_block(&block) {
+                    self.print_line(&e.message());
+                } else {
+                    self.store_block(block);
+                }
+            }
+            Ok(Some(Entry::Statements(source, is_declaration))) => {
+                match self.run_immediate(&source) {
+                    Ok(_) if is_declaration => self.declarations.push_str(&source),
+                    Ok(_) => (),
+                    Err(e) => self.print_line(&e.message()),
+                }
+            }
+            Ok(None) => (),
+            Err(e) => self.print_line(&e.message()),
+        }
+        true
+    }
+
+    fn run_command(&mut self, command: Command) -> bool {
+        match command {
+            Command::List => {
+                for line in self.program_source().lines() {
+                    self.print_line(line);
+                }
+            }
+            Command::Run => {
+                self.clear_variables();
+                let source = self.program_source();
+                let result = compile(&source).and_then(|instructions| {
+                    self.interpreter
+                        .interpret(&instructions)
+                        .map_err(Error::from)
+                });
+                self.interpreter.unwind();
+                if let Err(e) = result {
+                    self.print_line(&program_error_message(&e));
+                }
+            }
+            Command::New => {
+                self.lines.clear();
+                self.blocks.clear();
+                self.clear_variables();
+            }
+            Command::Load(file_name) => {
+                if let Err(msg) = self.load(&file_name) {
+                    self.print_line(&msg);
+                }
+            }
+            Command::Save(file_name) => {
+                if let Err(e) = self.save(&file_name) {
+                    self.print_line(&io_error_message(e));
+                }
+            }
+            Command::System => return false,
+        }
+        true
+    }
+
+    /// Adds the line to the pending lines and returns the entry, if it is complete.
+    fn read_entry(&mut self, line: &str) -> Result<Option<Entry>, Error> {
+        if self.pending.is_empty() {
+            if let Some((line_number, text)) = split_line_number(line) {
+                return Ok(Some(Entry::Line(line_number, text)));
+            }
+        }
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        match Parser::from(self.pending.as_str()).parse() {
+            Ok(program) => {
+                let source = std::mem::take(&mut self.pending);
+                Ok(Some(match block_of(&program) {
+                    Some((name, is_type)) => Entry::Block(Block {
+                        name,
+                        is_type,
+                        source,
+                    }),
+                    None => Entry::Statements(source, is_declaration(&program)),
+                }))
+            }
+            Err(e) if e.is_unexpected_eof() => Ok(None),
+            Err(e) => {
+                self.pending.clear();
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Stores a numbered line, or deletes it if it has no text.
+    fn store_line(&mut self, line_number: u32, text: String) {
+        let existing = self
+            .lines
+            .iter()
+            .position(|l| split_line_number(l).map(|(n, _)| n) == Some(line_number));
+        match existing {
+            Some(idx) if text.is_empty() => {
+                self.lines.remove(idx);
+            }
+            Some(idx) => self.lines[idx] = format!("{} {}", line_number, text),
+            None if text.is_empty() => (),
+            None => {
+                let idx = self
+                    .lines
+                    .iter()
+                    .position(|l| matches!(split_line_number(l), Some((n, _)) if n > line_number))
+                    .unwrap_or(self.lines.len());
+                self.lines.insert(idx, format!("{} {}", line_number, text));
+            }
+        }
+    }
+
+    fn store_block(&mut self, block: Block) {
+        match self.blocks.iter().position(|b| b.name == block.name) {
+            Some(idx) => self.blocks[idx] = block,
+            None => self.blocks.push(block),
+        }
+    }
+
+    /// Checks that the stored blocks are still valid with the given block,
+    /// because they are part of every statement that runs.
+    fn check_block(&self, block: &Block) -> Result<(), Error> {
+        let mut source = String::new();
+        for b in self.blocks.iter().filter(|b| b.name != block.name) {
+            source.push_str(&b.source);
+        }
+        source.push_str(&block.source);
+        let program = Parser::from(self.types_first(&source).as_str()).parse()?;
+        linter::lint(program)?;
+        Ok(())
+    }
+
+    /// Runs statements with the stored blocks, keeping the variables.
+    ///
+    /// The previous declarations are linted with the statements, but they don't run again.
+    fn run_immediate(&mut self, source: &str) -> Result<(), Error> {
+        let types = self.types_first("");
+        let first_row = types.lines().count() + 1;
+        let replayed_rows = first_row..first_row + self.declarations.lines().count();
+        let mut program_source = types + &self.declarations + source;
+        for b in self.blocks.iter().filter(|b| !b.is_type) {
+            program_source.push_str(&b.source);
+        }
+        let program = Parser::from(program_source.as_str()).parse()?;
+        let mut linted_program = linter::lint(program)?;
+        linted_program.retain(|t| !replayed_rows.contains(&(t.location().row() as usize)));
+        let instructions = instruction_generator::generate_instructions(linted_program);
+        let result = self.interpreter.interpret(&instructions);
+        self.interpreter.unwind();
+        Ok(result?)
+    }
+
+    /// Writes the program to a file, as file number 0, which programs can't use.
+    fn save(&mut self, file_name: &str) -> std::io::Result<()> {
+        let source = self.program_source();
+        let stdlib = &mut self.interpreter.stdlib;
+        stdlib.open_file(0, file_name, false)?;
+        let result = stdlib.write_file(0, None, source.as_bytes());
+        result.and(stdlib.close_file(0))
+    }
+
+    fn load(&mut self, file_name: &str) -> Result<(), String> {
+        let contents = self
+            .interpreter
+            .stdlib
+            .read_file(file_name)
+            .map_err(io_error_message)?;
+        self.lines.clear();
+        self.blocks.clear();
+        self.clear_variables();
+        for line in String::from_utf8_lossy(&contents).lines() {
+            match self.read_entry(line).map_err(|e| e.message())? {
+                Some(Entry::Line(line_number, text)) => self.store_line(line_number, text),
+                Some(Entry::Block(block)) => self.store_block(block),
+                Some(Entry::Statements(source, _)) => {
+                    self.lines.extend(source.lines().map(|l| l.to_string()))
+                }
+                None => (),
+            }
+        }
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            let pending = std::mem::take(&mut self.pending);
+            match Parser::from(pending.as_str()).parse() {
+                Err(e) => Err(Error::from(e).message()),
+                Ok(_) => Ok(()),
+            }
+        }
+    }
+
+    /// The program, as `LIST` shows it and `SAVE` writes it.
+    /// The types come first, because they need to be defined before they are used.
+    fn program_source(&self) -> String {
+        let mut main = String::new();
+        for line in self.lines.iter() {
+            main.push_str(line);
+            main.push('\n');
+        }
+        let mut source = self.types_first(&main);
+        for b in self.blocks.iter().filter(|b| !b.is_type) {
+            source.push_str(&b.source);
+        }
+        source
+    }
+
+    fn types_first(&self, source: &str) -> String {
+        let mut result = String::new();
+        for b in self.blocks.iter().filter(|b| b.is_type) {
+            result.push_str(&b.source);
+        }
+        result.push_str(source);
+        result
+    }
+
+    fn clear_variables(&mut self) {
+        self.declarations.clear();
+        self.interpreter.clear();
+    }
+
+    /// Prints a line, starting a new line if the cursor is not at the start of one.
+    fn print_line(&mut self, text: &str) {
+        if self.interpreter.screen_column > 0 {
+            self.interpreter.stdlib.println();
+        }
+        self.interpreter.stdlib.print(text);
+        self.interpreter.stdlib.println();
+        self.interpreter.screen_column = 0;
+    }
+}
+
+fn compile(source: &str) -> Result<Vec<InstructionNode>, Error> {
+    let program = Parser::from(source).parse()?;
+    let linted_program = linter::lint(program)?;
+    Ok(instruction_generator::generate_instructions(linted_program))
+}
+
+fn parse_command(line: &str) -> Option<Command> {
+    let line = line.trim();
+    let upper = line.to_uppercase();
+    match upper.as_str() {
+        "LIST" => return Some(Command::List),
+        "RUN" => return Some(Command::Run),
+        "NEW" => return Some(Command::New),
+        "SYSTEM" => return Some(Command::System),
+        _ => (),
+    }
+    let (keyword, file_name) = line.split_at(line.find(char::is_whitespace)?);
+    let file_name = file_name.trim().trim_matches('"');
+    if file_name.is_empty() || file_name.contains(|ch: char| ch.is_whitespace() || ch == '"') {
+        return None;
+    }
+    let file_name = if file_name.contains('.') {
+        file_name.to_string()
+    } else {
+        format!("{}.BAS", file_name)
+    };
+    match keyword.to_uppercase().as_str() {
+        "LOAD" => Some(Command::Load(file_name)),
+        "SAVE" => Some(Command::Save(file_name)),
+        _ => None,
+    }
+}
+
+/// Splits a line into its line number and the rest of its text, e.g. `10 PRINT`.
+fn split_line_number(line: &str) -> Option<(u32, String)> {
+    let line = line.trim_start();
+    let digits = line
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(line.len());
+    let line_number = line[..digits].parse().ok()?;
+    Some((line_number, line[digits..].trim().to_string()))
+}
+
+/// The name of the block, if the program is a `SUB`, `FUNCTION` or `TYPE` block.
+fn block_of(program: &ProgramNode) -> Option<(CaseInsensitiveString, bool)> {
+    match program.first().map(|t| t.as_ref()) {
+        Some(TopLevelToken::SubImplementation(name, _, _)) => Some((name.as_ref().clone(), false)),
+        Some(TopLevelToken::FunctionImplementation(name, _, _)) => {
+            Some((name.bare_name().clone(), false))
+        }
+        Some(TopLevelToken::UserDefinedType(t)) => Some((t.name.as_ref().clone(), true)),
+        _ => None,
+    }
+}
+
+/// Whether the program only declares variables and constants, e.g. `DIM A AS STRING`.
+fn is_declaration(program: &ProgramNode) -> bool {
+    !program.is_empty()
+        && program.iter().all(|t| {
+            matches!(
+                t.as_ref(),
+                TopLevelToken::DefType(_)
+                    | TopLevelToken::Statement(Statement::Dim(_))
+                    | TopLevelToken::Statement(Statement::Const(_, _))
+            )
+        })
+}
+
+/// The message of an error of the program, with the line number if it has one,
+/// e.g. `Division by zero in 20`.
+fn program_error_message(e: &Error) -> String {
+    match e.location() {
+        Some(pos) => match pos.line_number() {
+            Some(line_number) => format!("{} in {}", e.message(), line_number),
+            None => e.to_string(),
+        },
+        None => e.message(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::test_utils::MockStdlib;
+
+    fn repl() -> Repl<MockStdlib> {
+        Repl::new(MockStdlib::new())
+    }
+
+    fn eval_all(repl: &mut Repl<MockStdlib>, lines: &[&str]) {
+        for line in lines {
+            assert!(repl.eval(line));
+        }
+    }
+
+    fn output(repl: &Repl<MockStdlib>) -> &Vec<String> {
+        &repl.interpreter.stdlib.output
+    }
+
+    #[test]
+    fn test_immediate_statements_keep_variables() {
+        let mut repl = repl();
+        eval_all(&mut repl, &["A = 2", "B$ = \"x\"", "PRINT A * 3; B$"]);
+        assert_eq!(output(&repl), &vec![" 6 x"]);
+    }
+
+    #[test]
+    fn test_declarations_are_remembered() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "DEFINT A-Z",
+                "CONST Max = 3",
+                "DIM N AS STRING",
+                "DIM Values(1 TO Max)",
+                "N = \"x\"",
+                "Values(2) = 7",
+                "A = 5 / 2",
+                "PRINT N; Values(2); A; Max",
+                "DIM Values(1 TO 2)",
+            ],
+        );
+        assert_eq!(output(&repl), &vec!["x 7  2  3 ", "Duplicate definition"]);
+    }
+
+    #[test]
+    fn test_multi_line_block() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "FOR I = 1 TO 3",
+                "IF I = 2 THEN",
+                "PRINT I",
+                "END IF",
+                "NEXT",
+            ],
+        );
+        assert_eq!(output(&repl), &vec![" 2 "]);
+    }
+
+    #[test]
+    fn test_define_sub_and_function_incrementally() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "FUNCTION Twice(X)",
+                "Twice = X * 2",
+                "END FUNCTION",
+                "SUB Hello(N$)",
+                "PRINT \"Hello \"; N$",
+                "END SUB",
+                "Hello \"world\"",
+                "PRINT Twice(4)",
+                "FUNCTION Twice(X)",
+                "Twice = X * 3",
+                "END FUNCTION",
+                "PRINT Twice(4)",
+            ],
+        );
+        assert_eq!(output(&repl), &vec!["Hello world", " 8 ", " 12 "]);
+    }
+
+    #[test]
+    fn test_type_block() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "TYPE Card",
+                "Value AS INTEGER",
+                "END TYPE",
+                "DIM C AS Card",
+                "C.Value = 3",
+                "PRINT C.Value",
+            ],
+        );
+        assert_eq!(output(&repl), &vec![" 3 "]);
+    }
+
+    #[test]
+    fn test_errors_do_not_end_the_session() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "PRINT 1 +",
+                "A$ = 1",
+                "SUB Broken",
+                "X$ = 1",
+                "END SUB",
+                "ERROR 53",
+                "PRINT \"still here\"",
+            ],
+        );
+        assert_eq!(
+            output(&repl),
+            &vec![
+                "Expected expression",
+                "Type mismatch",
+                "Type mismatch",
+                "File not found",
+                "still here"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_inside_sub_returns_to_main_program() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &["SUB Fail", "ERROR 5", "END SUB", "A = 1", "Fail", "PRINT A"],
+        );
+        assert_eq!(output(&repl), &vec!["Illegal function call", " 1 "]);
+    }
+
+    #[test]
+    fn test_numbered_lines_list_and_run() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "20 PRINT \"world\"",
+                "10 PRINT \"hello\"",
+                "30 PRINT \"bye\"",
+                "30",
+                "20 PRINT A",
+                "A = 5",
+                "LIST",
+                "RUN",
+            ],
+        );
+        // RUN forgets the variables
+        assert_eq!(
+            output(&repl),
+            &vec!["10 PRINT \"hello\"", "20 PRINT A", "hello", " 0 "]
+        );
+    }
+
+    #[test]
+    fn test_run_error_shows_line_number() {
+        let mut repl = repl();
+        eval_all(&mut repl, &["10 A = 1", "20 PRINT 1 / 0", "RUN"]);
+        assert_eq!(output(&repl), &vec!["Division by zero in 20"]);
+    }
+
+    #[test]
+    fn test_new() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &["10 PRINT 1", "A = 1", "NEW", "LIST", "PRINT A"],
+        );
+        assert_eq!(output(&repl), &vec![" 0 "]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut repl = repl();
+        eval_all(
+            &mut repl,
+            &[
+                "SUB Hello",
+                "PRINT \"hello\"",
+                "END SUB",
+                "10 Hello",
+                "SAVE \"prog\"",
+                "NEW",
+                "LOAD prog",
+                "LIST",
+                "RUN",
+            ],
+        );
+        assert_eq!(
+            output(&repl),
+            &vec![
+                "10 Hello",
+                "SUB Hello",
+                "PRINT \"hello\"",
+                "END SUB",
+                "hello"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_unnumbered_program() {
+        let mut stdlib = MockStdlib::new();
+        stdlib.files.insert(
+            "PROG.BAS".to_string(),
+            b"DECLARE SUB Count (N)\r\nCount 2\r\n\r\nSUB Count (N)\r\nFOR I = 1 TO N\r\nPRINT I\r\nNEXT\r\nEND SUB\r\n".to_vec(),
+        );
+        let mut repl = Repl::new(stdlib);
+        eval_all(&mut repl, &["LOAD \"PROG\"", "RUN", "LOAD MISSING"]);
+        assert_eq!(output(&repl), &vec![" 1 ", " 2 ", "File not found"]);
+    }
+
+    #[test]
+    fn test_system_ends_the_session() {
+        let mut repl = repl();
+        assert!(!repl.eval("system"));
+    }
+
+    #[test]
+    fn test_run_reads_until_end_of_input() {
+        let mut stdlib = MockStdlib::new();
+        stdlib.add_next_input("PRINT \"a\";");
+        stdlib.add_next_input("IF 1 THEN");
+        stdlib.add_next_input("PRINT \"b\"");
+        stdlib.add_next_input("END IF");
+        let mut repl = Repl::new(stdlib);
+        repl.run();
+        assert_eq!(output(&repl), &vec!["Ok", "a", "Ok", "b", "Ok"]);
+    }
+}
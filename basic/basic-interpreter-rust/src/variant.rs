@@ -1,3 +1,5 @@
+use crate::casting::cast;
+use crate::common::CaseInsensitiveString;
 use crate::parser::{HasQualifier, TypeQualifier};
 use std::cmp::Ordering;
 use std::convert::TryFrom;
@@ -10,6 +12,31 @@ pub enum Variant {
     VString(String),
     VInteger(i32),
     VLong(i64),
+    VArray(Box<VArray>),
+    VUserDefined(Box<UserDefinedValue>),
+}
+
+/// An array of variants of the same type.
+#[derive(Clone, Debug)]
+pub struct VArray {
+    element_type: TypeQualifier,
+    /// The lower and upper bound of each dimension.
+    /// It is empty when a dynamic array has been erased.
+    dimensions: Vec<(i32, i32)>,
+    elements: Vec<Variant>,
+    dynamic: bool,
+    /// The length of the elements of a fixed length string array.
+    fixed_length: Option<u16>,
+    /// The value of the elements when the array is allocated or erased.
+    default_element: Variant,
+}
+
+/// A value of a user defined type, e.g. `DIM P AS Card`.
+/// The members are kept in the order they are declared in the type.
+#[derive(Clone, Debug)]
+pub struct UserDefinedValue {
+    type_name: CaseInsensitiveString,
+    members: Vec<(CaseInsensitiveString, Variant)>,
 }
 
 pub const V_TRUE: Variant = Variant::VInteger(-1);
@@ -50,6 +77,46 @@ impl ApproximateCmp for f64 {
     }
 }
 
+fn integer_result(n: i64) -> Result<Variant, String> {
+    if (MIN_INTEGER as i64..=MAX_INTEGER as i64).contains(&n) {
+        Ok(Variant::VInteger(n as i32))
+    } else {
+        Err("Overflow".to_string())
+    }
+}
+
+fn long_result(n: i64) -> Result<Variant, String> {
+    if (MIN_LONG..=MAX_LONG).contains(&n) {
+        Ok(Variant::VLong(n))
+    } else {
+        Err("Overflow".to_string())
+    }
+}
+
+fn single_result(f: f32) -> Result<Variant, String> {
+    if f.is_finite() {
+        Ok(Variant::VSingle(f))
+    } else {
+        Err("Overflow".to_string())
+    }
+}
+
+fn double_result(d: f64) -> Result<Variant, String> {
+    if d.is_finite() {
+        Ok(Variant::VDouble(d))
+    } else {
+        Err("Overflow".to_string())
+    }
+}
+
+fn divide_f64(left: f64, right: f64) -> Result<Variant, String> {
+    if right == 0.0 {
+        Err("Division by zero".to_string())
+    } else {
+        double_result(left / right)
+    }
+}
+
 impl Variant {
     pub fn cmp(&self, other: &Self) -> Result<Ordering, String> {
         match self {
@@ -79,6 +146,7 @@ impl Variant {
                 Variant::VLong(l_right) => Ok(l_left.cmp(l_right)),
                 _ => other.cmp(self).map(|x| x.reverse()),
             },
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 
@@ -104,6 +172,7 @@ impl Variant {
                 Variant::VLong(l_right) => Ok(l_left.cmp(l_right)),
                 _ => Err("Type mismatch".to_string()),
             },
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 
@@ -127,6 +196,7 @@ impl Variant {
                     Ok(Variant::VLong(-n))
                 }
             }
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 
@@ -137,22 +207,23 @@ impl Variant {
             Variant::VString(_) => Err("Type mismatch".to_string()),
             Variant::VInteger(n) => Ok(Variant::VInteger(-n - 1)),
             Variant::VLong(n) => Ok(Variant::VLong(-n - 1)),
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 
     pub fn plus(&self, other: &Self) -> Result<Self, String> {
         match self {
             Variant::VSingle(f_left) => match other {
-                Variant::VSingle(f_right) => Ok(Variant::VSingle(*f_left + *f_right)),
-                Variant::VDouble(d_right) => Ok(Variant::VDouble(*f_left as f64 + *d_right)),
-                Variant::VInteger(i_right) => Ok(Variant::VSingle(*f_left + *i_right as f32)),
-                Variant::VLong(l_right) => Ok(Variant::VSingle(*f_left + *l_right as f32)),
+                Variant::VSingle(f_right) => single_result(*f_left + *f_right),
+                Variant::VDouble(d_right) => double_result(*f_left as f64 + *d_right),
+                Variant::VInteger(i_right) => single_result(*f_left + *i_right as f32),
+                Variant::VLong(l_right) => single_result(*f_left + *l_right as f32),
                 _ => other.plus(self),
             },
             Variant::VDouble(d_left) => match other {
-                Variant::VDouble(d_right) => Ok(Variant::VDouble(*d_left + *d_right)),
-                Variant::VInteger(i_right) => Ok(Variant::VDouble(*d_left + *i_right as f64)),
-                Variant::VLong(l_right) => Ok(Variant::VDouble(*d_left + *l_right as f64)),
+                Variant::VDouble(d_right) => double_result(*d_left + *d_right),
+                Variant::VInteger(i_right) => double_result(*d_left + *i_right as f64),
+                Variant::VLong(l_right) => double_result(*d_left + *l_right as f64),
                 _ => other.plus(self),
             },
             Variant::VString(s_left) => match other {
@@ -160,42 +231,207 @@ impl Variant {
                 _ => Err("Type mismatch".to_string()),
             },
             Variant::VInteger(i_left) => match other {
-                Variant::VInteger(i_right) => Ok(Variant::VInteger(*i_left + *i_right)),
-                Variant::VLong(l_right) => Ok(Variant::VLong(*i_left as i64 + *l_right)),
+                Variant::VInteger(i_right) => integer_result(*i_left as i64 + *i_right as i64),
+                Variant::VLong(l_right) => long_result(*i_left as i64 + *l_right),
                 _ => other.plus(self),
             },
             Variant::VLong(l_left) => match other {
-                Variant::VLong(l_right) => Ok(Variant::VLong(*l_left + *l_right)),
+                Variant::VLong(l_right) => long_result(*l_left + *l_right),
                 _ => other.plus(self),
             },
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 
     pub fn minus(&self, other: &Self) -> Result<Self, String> {
         match self {
             Variant::VSingle(f_left) => match other {
-                Variant::VSingle(f_right) => Ok(Variant::VSingle(*f_left - *f_right)),
-                Variant::VDouble(d_right) => Ok(Variant::VDouble(*f_left as f64 - *d_right)),
-                Variant::VInteger(i_right) => Ok(Variant::VSingle(*f_left - *i_right as f32)),
-                Variant::VLong(l_right) => Ok(Variant::VSingle(*f_left - *l_right as f32)),
+                Variant::VSingle(f_right) => single_result(*f_left - *f_right),
+                Variant::VDouble(d_right) => double_result(*f_left as f64 - *d_right),
+                Variant::VInteger(i_right) => single_result(*f_left - *i_right as f32),
+                Variant::VLong(l_right) => single_result(*f_left - *l_right as f32),
                 _ => other.minus(self).and_then(|x| x.negate()),
             },
             Variant::VDouble(d_left) => match other {
-                Variant::VDouble(d_right) => Ok(Variant::VDouble(*d_left - *d_right)),
-                Variant::VInteger(i_right) => Ok(Variant::VDouble(*d_left - *i_right as f64)),
-                Variant::VLong(l_right) => Ok(Variant::VDouble(*d_left - *l_right as f64)),
+                Variant::VDouble(d_right) => double_result(*d_left - *d_right),
+                Variant::VInteger(i_right) => double_result(*d_left - *i_right as f64),
+                Variant::VLong(l_right) => double_result(*d_left - *l_right as f64),
                 _ => other.minus(self).and_then(|x| x.negate()),
             },
             Variant::VString(_) => Err("Type mismatch".to_string()),
             Variant::VInteger(i_left) => match other {
-                Variant::VInteger(i_right) => Ok(Variant::VInteger(*i_left - *i_right)),
-                Variant::VLong(l_right) => Ok(Variant::VLong(*i_left as i64 - *l_right)),
+                Variant::VInteger(i_right) => integer_result(*i_left as i64 - *i_right as i64),
+                Variant::VLong(l_right) => long_result(*i_left as i64 - *l_right),
                 _ => other.minus(self).and_then(|x| x.negate()),
             },
             Variant::VLong(l_left) => match other {
-                Variant::VLong(l_right) => Ok(Variant::VLong(*l_left - *l_right)),
+                Variant::VLong(l_right) => long_result(*l_left - *l_right),
                 _ => other.minus(self).and_then(|x| x.negate()),
             },
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
+        }
+    }
+
+    pub fn multiply(&self, other: &Self) -> Result<Self, String> {
+        match self {
+            Variant::VSingle(f_left) => match other {
+                Variant::VSingle(f_right) => single_result(*f_left * *f_right),
+                Variant::VDouble(d_right) => double_result(*f_left as f64 * *d_right),
+                Variant::VInteger(i_right) => single_result(*f_left * *i_right as f32),
+                Variant::VLong(l_right) => single_result(*f_left * *l_right as f32),
+                _ => other.multiply(self),
+            },
+            Variant::VDouble(d_left) => match other {
+                Variant::VDouble(d_right) => double_result(*d_left * *d_right),
+                Variant::VInteger(i_right) => double_result(*d_left * *i_right as f64),
+                Variant::VLong(l_right) => double_result(*d_left * *l_right as f64),
+                _ => other.multiply(self),
+            },
+            Variant::VString(_) => Err("Type mismatch".to_string()),
+            Variant::VInteger(i_left) => match other {
+                Variant::VInteger(i_right) => integer_result(*i_left as i64 * *i_right as i64),
+                Variant::VLong(l_right) => long_result(*i_left as i64 * *l_right),
+                _ => other.multiply(self),
+            },
+            Variant::VLong(l_left) => match other {
+                Variant::VLong(l_right) => long_result(*l_left * *l_right),
+                _ => other.multiply(self),
+            },
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
+        }
+    }
+
+    /// Floating point division. The result is a double if any of the operands
+    /// is a double, otherwise it is a single.
+    pub fn divide(&self, other: &Self) -> Result<Self, String> {
+        match self {
+            Variant::VDouble(_) => divide_f64(self.to_f64()?, other.to_f64()?),
+            Variant::VString(_) => Err("Type mismatch".to_string()),
+            _ => match other {
+                Variant::VDouble(_) => divide_f64(self.to_f64()?, other.to_f64()?),
+                Variant::VString(_) => Err("Type mismatch".to_string()),
+                _ => {
+                    let divisor = other.to_f64()? as f32;
+                    if divisor == 0.0 {
+                        Err("Division by zero".to_string())
+                    } else {
+                        single_result(self.to_f64()? as f32 / divisor)
+                    }
+                }
+            },
+        }
+    }
+
+    /// Integer division. The operands are rounded to integers before the
+    /// division and the result is truncated towards zero.
+    pub fn integer_divide(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        if l_right == 0 {
+            Err("Division by zero".to_string())
+        } else {
+            self.integer_operation_result(other, l_left / l_right)
+        }
+    }
+
+    /// Remainder of the integer division. The operands are rounded to
+    /// integers and the sign of the result follows the sign of the dividend.
+    pub fn modulo(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        if l_right == 0 {
+            Err("Division by zero".to_string())
+        } else {
+            self.integer_operation_result(other, l_left % l_right)
+        }
+    }
+
+    /// Exponentiation. The result is a double if any of the operands is a
+    /// double, otherwise it is a single.
+    pub fn power(&self, other: &Self) -> Result<Self, String> {
+        let base = self.to_f64()?;
+        let exponent = other.to_f64()?;
+        if base == 0.0 && exponent < 0.0 {
+            Err("Division by zero".to_string())
+        } else if base < 0.0 && exponent.fract() != 0.0 {
+            Err("Illegal function call".to_string())
+        } else {
+            match self {
+                Variant::VDouble(_) => double_result(base.powf(exponent)),
+                _ => match other {
+                    Variant::VDouble(_) => double_result(base.powf(exponent)),
+                    _ => single_result((base as f32).powf(exponent as f32)),
+                },
+            }
+        }
+    }
+
+    /// Bitwise AND. As with the other logical operators, the operands are
+    /// rounded to integers and the result is an integer if both operands are
+    /// integers, otherwise a long.
+    pub fn and(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        self.integer_operation_result(other, l_left & l_right)
+    }
+
+    pub fn or(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        self.integer_operation_result(other, l_left | l_right)
+    }
+
+    pub fn xor(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        self.integer_operation_result(other, l_left ^ l_right)
+    }
+
+    pub fn eqv(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        self.integer_operation_result(other, !(l_left ^ l_right))
+    }
+
+    pub fn imp(&self, other: &Self) -> Result<Self, String> {
+        let (l_left, l_right) = self.rounded_operands(other)?;
+        self.integer_operation_result(other, !l_left | l_right)
+    }
+
+    fn to_f64(&self) -> Result<f64, String> {
+        match self {
+            Variant::VSingle(f) => Ok(*f as f64),
+            Variant::VDouble(d) => Ok(*d),
+            Variant::VString(_) => Err("Type mismatch".to_string()),
+            Variant::VInteger(i) => Ok(*i as f64),
+            Variant::VLong(l) => Ok(*l as f64),
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
+        }
+    }
+
+    fn rounded_operands(&self, other: &Self) -> Result<(i64, i64), String> {
+        let l_left = cast(self.clone(), TypeQualifier::AmpersandLong)?;
+        let l_right = cast(other.clone(), TypeQualifier::AmpersandLong)?;
+        match (l_left, l_right) {
+            (Variant::VLong(l), Variant::VLong(r)) => Ok((l, r)),
+            _ => Err("Type mismatch".to_string()),
+        }
+    }
+
+    fn integer_operation_result(&self, other: &Self, result: i64) -> Result<Self, String> {
+        match (self, other) {
+            (Variant::VInteger(_), Variant::VInteger(_)) => integer_result(result),
+            _ => long_result(result),
+        }
+    }
+
+    /// Pads with spaces or truncates a string to the given length,
+    /// as it happens when assigning to a fixed length string.
+    pub fn into_fixed_length(self, len: u16) -> Self {
+        match self {
+            Variant::VString(s) => {
+                let len = len as usize;
+                let mut result: String = s.chars().take(len).collect();
+                while result.chars().count() < len {
+                    result.push(' ');
+                }
+                Variant::VString(result)
+            }
+            _ => self,
         }
     }
 
@@ -208,6 +444,345 @@ impl Variant {
             TypeQualifier::AmpersandLong => Variant::VLong(0),
         }
     }
+
+    /// Gets the number of bytes this value occupies in a random access or binary file.
+    /// Strings occupy one byte per character.
+    pub fn byte_len(&self) -> Result<usize, String> {
+        match self {
+            Variant::VInteger(_) => Ok(2),
+            Variant::VLong(_) | Variant::VSingle(_) => Ok(4),
+            Variant::VDouble(_) => Ok(8),
+            Variant::VString(s) => Ok(s.chars().count()),
+            Variant::VUserDefined(u) => u
+                .members
+                .iter()
+                .map(|(_, v)| v.byte_len())
+                .sum::<Result<usize, String>>(),
+            Variant::VArray(_) => Err("Type mismatch".to_string()),
+        }
+    }
+
+    /// Converts the value into the bytes that are stored in a random access or binary file.
+    /// The layout is the one of GW-BASIC: integers and longs are little endian,
+    /// singles and doubles are in the Microsoft Binary Format (MBF).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        match self {
+            Variant::VInteger(i) => Ok((*i as i16).to_le_bytes().to_vec()),
+            Variant::VLong(l) => Ok((*l as i32).to_le_bytes().to_vec()),
+            Variant::VSingle(f) => Ok(single_to_mbf(*f)?.to_vec()),
+            Variant::VDouble(d) => Ok(double_to_mbf(*d)?.to_vec()),
+            Variant::VString(s) => Ok(string_to_bytes(s)),
+            Variant::VUserDefined(u) => {
+                let mut result: Vec<u8> = vec![];
+                for (_, v) in u.members.iter() {
+                    result.extend(v.to_bytes()?);
+                }
+                Ok(result)
+            }
+            Variant::VArray(_) => Err("Type mismatch".to_string()),
+        }
+    }
+
+    /// Reads a value of the same type as this one from the bytes of a random access or
+    /// binary file. A string reads as many bytes as its current length.
+    /// The given bytes must be at least `byte_len` long.
+    pub fn read_bytes(&self, bytes: &[u8]) -> Result<Variant, String> {
+        match self {
+            Variant::VInteger(_) => Ok(Variant::VInteger(i32::from(i16::from_le_bytes([
+                bytes[0], bytes[1],
+            ])))),
+            Variant::VLong(_) => Ok(Variant::VLong(i64::from(i32::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3],
+            ])))),
+            Variant::VSingle(_) => Ok(Variant::VSingle(mbf_to_double(&bytes[..4]) as f32)),
+            Variant::VDouble(_) => Ok(Variant::VDouble(mbf_to_double(&bytes[..8]))),
+            Variant::VString(s) => Ok(Variant::VString(bytes_to_string(
+                &bytes[..s.chars().count()],
+            ))),
+            Variant::VUserDefined(u) => {
+                let mut members: Vec<(CaseInsensitiveString, Variant)> = vec![];
+                let mut offset: usize = 0;
+                for (name, v) in u.members.iter() {
+                    members.push((name.clone(), v.read_bytes(&bytes[offset..])?));
+                    offset += v.byte_len()?;
+                }
+                Ok(Variant::VUserDefined(Box::new(UserDefinedValue::new(
+                    u.type_name.clone(),
+                    members,
+                ))))
+            }
+            Variant::VArray(_) => Err("Type mismatch".to_string()),
+        }
+    }
+}
+
+/// Converts a string to bytes, one byte per character, as strings are stored in files.
+/// Characters that don't fit in a byte become `?`.
+pub fn string_to_bytes(s: &str) -> Vec<u8> {
+    s.chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
+
+/// Converts bytes read from a file to a string, one character per byte.
+pub fn bytes_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| *b as char).collect()
+}
+
+// In the Microsoft Binary Format, the last byte is the exponent with a bias of 129,
+// zero meaning that the number is zero. The byte before it holds the sign in its
+// highest bit, followed by the mantissa without its leading one, little endian.
+const MBF_EXPONENT_BIAS: i32 = 129;
+
+/// Converts a single to its 4 bytes in the Microsoft Binary Format.
+/// Numbers too small for the format become zero.
+fn single_to_mbf(f: f32) -> Result<[u8; 4], String> {
+    let bits = f.to_bits();
+    let ieee_exponent = ((bits >> 23) & 0xff) as i32;
+    if ieee_exponent == 0 {
+        return Ok([0; 4]);
+    }
+    let exponent = ieee_exponent - 127 + MBF_EXPONENT_BIAS;
+    if exponent > 255 {
+        return Err("Overflow".to_string());
+    }
+    let sign = ((bits >> 31) as u8) << 7;
+    let mantissa = bits & 0x7f_ffff;
+    Ok([
+        mantissa as u8,
+        (mantissa >> 8) as u8,
+        sign | (mantissa >> 16) as u8,
+        exponent as u8,
+    ])
+}
+
+/// Converts a double to its 8 bytes in the Microsoft Binary Format.
+/// Numbers too small for the format become zero.
+fn double_to_mbf(d: f64) -> Result<[u8; 8], String> {
+    let bits = d.to_bits();
+    let ieee_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let exponent = ieee_exponent - 1023 + MBF_EXPONENT_BIAS;
+    if ieee_exponent == 0 || exponent <= 0 {
+        return Ok([0; 8]);
+    }
+    if exponent > 255 {
+        return Err("Overflow".to_string());
+    }
+    // the mantissa has 52 bits in IEEE and 55 bits in MBF
+    let mantissa = (bits & 0xf_ffff_ffff_ffff) << 3;
+    let mut result = [0_u8; 8];
+    result[..7].copy_from_slice(&mantissa.to_le_bytes()[..7]);
+    result[6] |= ((bits >> 63) as u8) << 7;
+    result[7] = exponent as u8;
+    Ok(result)
+}
+
+/// Converts the 4 bytes of a single or the 8 bytes of a double
+/// in the Microsoft Binary Format to a number.
+fn mbf_to_double(bytes: &[u8]) -> f64 {
+    let len = bytes.len();
+    let exponent = bytes[len - 1] as i32;
+    if exponent == 0 {
+        return 0.0;
+    }
+    let mantissa_bits = (len as i32 - 1) * 8 - 1;
+    let mut buf = [0_u8; 8];
+    buf[..len - 1].copy_from_slice(&bytes[..len - 1]);
+    buf[len - 2] &= 0x7f;
+    // the leading one of the mantissa is implied
+    let mantissa = u64::from_le_bytes(buf) | (1 << mantissa_bits);
+    let result = mantissa as f64 * 2_f64.powi(exponent - MBF_EXPONENT_BIAS - mantissa_bits);
+    if bytes[len - 2] & 0x80 == 0 {
+        result
+    } else {
+        -result
+    }
+}
+
+pub(crate) const SUBSCRIPT_OUT_OF_RANGE: &str = "Subscript out of range";
+
+impl VArray {
+    pub fn new(
+        element_type: TypeQualifier,
+        dimensions: Vec<(i32, i32)>,
+        dynamic: bool,
+        fixed_length: Option<u16>,
+    ) -> Result<Self, String> {
+        let default_element = match fixed_length {
+            Some(len) => Variant::default_variant(element_type).into_fixed_length(len),
+            None => Variant::default_variant(element_type),
+        };
+        Self::with_default_element(
+            element_type,
+            dimensions,
+            dynamic,
+            fixed_length,
+            default_element,
+        )
+    }
+
+    /// Creates an array of a user defined type. The elements are initialized
+    /// to the given default value.
+    pub fn new_user_defined(
+        element_type: TypeQualifier,
+        dimensions: Vec<(i32, i32)>,
+        dynamic: bool,
+        default_element: Variant,
+    ) -> Result<Self, String> {
+        Self::with_default_element(element_type, dimensions, dynamic, None, default_element)
+    }
+
+    fn with_default_element(
+        element_type: TypeQualifier,
+        dimensions: Vec<(i32, i32)>,
+        dynamic: bool,
+        fixed_length: Option<u16>,
+        default_element: Variant,
+    ) -> Result<Self, String> {
+        let mut len: usize = 1;
+        for (lbound, ubound) in dimensions.iter() {
+            if lbound > ubound {
+                return Err(SUBSCRIPT_OUT_OF_RANGE.to_string());
+            }
+            len *= (ubound - lbound + 1) as usize;
+        }
+        Ok(Self {
+            element_type,
+            dimensions,
+            elements: vec![default_element.clone(); len],
+            dynamic,
+            fixed_length,
+            default_element,
+        })
+    }
+
+    pub fn element_type(&self) -> TypeQualifier {
+        self.element_type
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.dynamic
+    }
+
+    pub fn get_element(&self, indices: &[Variant]) -> Result<&Variant, String> {
+        let index = self.element_index(indices)?;
+        Ok(&self.elements[index])
+    }
+
+    pub fn get_element_mut(&mut self, indices: &[Variant]) -> Result<&mut Variant, String> {
+        let index = self.element_index(indices)?;
+        Ok(&mut self.elements[index])
+    }
+
+    pub fn set_element(&mut self, indices: &[Variant], value: Variant) -> Result<(), String> {
+        let index = self.element_index(indices)?;
+        let v = cast(value, self.element_type)?;
+        self.elements[index] = match self.fixed_length {
+            Some(len) => v.into_fixed_length(len),
+            None => v,
+        };
+        Ok(())
+    }
+
+    /// Gets the lower bound of the given dimension. Dimensions start at 1.
+    pub fn lbound(&self, dimension: usize) -> Result<i32, String> {
+        self.bounds(dimension).map(|(lbound, _)| lbound)
+    }
+
+    /// Gets the upper bound of the given dimension. Dimensions start at 1.
+    pub fn ubound(&self, dimension: usize) -> Result<i32, String> {
+        self.bounds(dimension).map(|(_, ubound)| ubound)
+    }
+
+    /// Erases the array. A static array is reset to its default values,
+    /// a dynamic array is deallocated.
+    pub fn erase(&mut self) {
+        if self.dynamic {
+            self.dimensions.clear();
+            self.elements.clear();
+        } else {
+            for element in self.elements.iter_mut() {
+                *element = self.default_element.clone();
+            }
+        }
+    }
+
+    fn bounds(&self, dimension: usize) -> Result<(i32, i32), String> {
+        if dimension >= 1 && dimension <= self.dimensions.len() {
+            Ok(self.dimensions[dimension - 1])
+        } else {
+            Err(SUBSCRIPT_OUT_OF_RANGE.to_string())
+        }
+    }
+
+    fn element_index(&self, indices: &[Variant]) -> Result<usize, String> {
+        if indices.len() != self.dimensions.len() {
+            return Err(SUBSCRIPT_OUT_OF_RANGE.to_string());
+        }
+        let mut index: usize = 0;
+        for (v, (lbound, ubound)) in indices.iter().zip(self.dimensions.iter()) {
+            let i = match cast(v.clone(), TypeQualifier::AmpersandLong)? {
+                Variant::VLong(l) => l,
+                _ => return Err("Type mismatch".to_string()),
+            };
+            if i < *lbound as i64 || i > *ubound as i64 {
+                return Err(SUBSCRIPT_OUT_OF_RANGE.to_string());
+            }
+            index = index * (ubound - lbound + 1) as usize + (i - *lbound as i64) as usize;
+        }
+        Ok(index)
+    }
+}
+
+impl UserDefinedValue {
+    pub fn new(
+        type_name: CaseInsensitiveString,
+        members: Vec<(CaseInsensitiveString, Variant)>,
+    ) -> Self {
+        Self { type_name, members }
+    }
+
+    pub fn get_member(&self, name: &CaseInsensitiveString) -> Option<&Variant> {
+        self.members.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    fn get_member_mut(&mut self, name: &CaseInsensitiveString) -> Option<&mut Variant> {
+        self.members
+            .iter_mut()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v)
+    }
+
+    /// Stores a value into a member, e.g. `Suit` in `P.Suit`, or into a nested member,
+    /// e.g. `Address.City` in `P.Address.City`. The value is cast to the type of the
+    /// member. Strings are padded or truncated, as they are always fixed length in a type.
+    pub fn set_member(
+        &mut self,
+        path: &[CaseInsensitiveString],
+        value: Variant,
+    ) -> Result<(), String> {
+        let (name, rest) = path.split_first().expect("Member path should not be empty");
+        let member = match self.get_member_mut(name) {
+            Some(m) => m,
+            None => return Err("Element not defined".to_string()),
+        };
+        if !rest.is_empty() {
+            return match member {
+                Variant::VUserDefined(u) => u.set_member(rest, value),
+                _ => Err("Type mismatch".to_string()),
+            };
+        }
+        let new_value = match member {
+            Variant::VString(s) => {
+                let len = s.chars().count() as u16;
+                cast(value, TypeQualifier::DollarString)?.into_fixed_length(len)
+            }
+            Variant::VUserDefined(_) => value,
+            _ => cast(value, member.qualifier())?,
+        };
+        *member = new_value;
+        Ok(())
+    }
 }
 
 impl HasQualifier for Variant {
@@ -218,6 +793,8 @@ impl HasQualifier for Variant {
             Variant::VString(_) => TypeQualifier::DollarString,
             Variant::VInteger(_) => TypeQualifier::PercentInteger,
             Variant::VLong(_) => TypeQualifier::AmpersandLong,
+            Variant::VArray(a) => a.element_type,
+            Variant::VUserDefined(_) => panic!("A user defined type does not have a qualifier"),
         }
     }
 }
@@ -293,6 +870,7 @@ impl TryFrom<&Variant> for bool {
             Variant::VString(_) => Err("Type mismatch".to_string()),
             Variant::VInteger(n) => Ok(*n != 0),
             Variant::VLong(n) => Ok(*n != 0),
+            Variant::VArray(_) | Variant::VUserDefined(_) => Err("Type mismatch".to_string()),
         }
     }
 }
@@ -308,19 +886,146 @@ impl TryFrom<Variant> for bool {
 impl Display for Variant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Variant::VSingle(n) => write!(f, "{}", n),
-            Variant::VDouble(n) => write!(f, "{}", n),
+            Variant::VSingle(n) => write!(f, "{}", format_float(format!("{:.6e}", n), 7, 'E')),
+            Variant::VDouble(n) => write!(f, "{}", format_float(format!("{:.15e}", n), 16, 'D')),
             Variant::VString(s) => write!(f, "{}", s),
             Variant::VInteger(n) => write!(f, "{}", n),
             Variant::VLong(n) => write!(f, "{}", n),
+            Variant::VArray(_) => write!(f, "<array>"),
+            Variant::VUserDefined(u) => write!(f, "<{}>", u.type_name),
         }
     }
 }
 
+/// Formats a floating point number the way QBasic prints it, e.g. `.5`, `-1.5` or `1E+07`.
+///
+/// The number is given in Rust's scientific notation, already rounded to `max_digits`
+/// significant digits. The exponential format is used only when the fixed format
+/// would need more than `max_digits` digits, e.g. `.0000001` but `1E-08` for singles.
+fn format_float(scientific: String, max_digits: usize, exponent_letter: char) -> String {
+    let (mantissa, exponent) = match scientific.split_once('e') {
+        Some((m, e)) => (m, e.parse::<i32>().unwrap_or_default()),
+        // infinity or NaN
+        None => return scientific,
+    };
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let digits = digits.trim_end_matches('0');
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    // the number of digits before the decimal point
+    let point = exponent + 1;
+    let digit_count = digits.len() as i32;
+    let result =
+        if point > max_digits as i32 || (point <= 0 && digit_count - point > max_digits as i32) {
+            let (first, rest) = digits.split_at(1);
+            let dot = if rest.is_empty() { "" } else { "." };
+            let exponent_sign = if exponent < 0 { '-' } else { '+' };
+            format!(
+                "{}{}{}{}{}{:02}",
+                first,
+                dot,
+                rest,
+                exponent_letter,
+                exponent_sign,
+                exponent.abs()
+            )
+        } else if point <= 0 {
+            format!(".{}{}", "0".repeat(-point as usize), digits)
+        } else if digit_count <= point {
+            format!("{}{}", digits, "0".repeat((point - digit_count) as usize))
+        } else {
+            let (int_part, frac_part) = digits.split_at(point as usize);
+            format!("{}.{}", int_part, frac_part)
+        };
+    format!("{}{}", sign, result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    mod mbf {
+        use super::*;
+
+        fn bytes(v: Variant) -> Vec<u8> {
+            v.to_bytes().unwrap()
+        }
+
+        fn read(template: Variant, bytes: &[u8]) -> Variant {
+            template.read_bytes(bytes).unwrap()
+        }
+
+        #[test]
+        fn test_single_to_bytes() {
+            assert_eq!(bytes(Variant::VSingle(0.0)), vec![0x00, 0x00, 0x00, 0x00]);
+            assert_eq!(bytes(Variant::VSingle(1.0)), vec![0x00, 0x00, 0x00, 0x81]);
+            assert_eq!(bytes(Variant::VSingle(-1.0)), vec![0x00, 0x00, 0x80, 0x81]);
+            assert_eq!(bytes(Variant::VSingle(0.5)), vec![0x00, 0x00, 0x00, 0x80]);
+            assert_eq!(bytes(Variant::VSingle(10.0)), vec![0x00, 0x00, 0x20, 0x84]);
+            assert_eq!(bytes(Variant::VSingle(0.1)), vec![0xcd, 0xcc, 0x4c, 0x7d]);
+        }
+
+        #[test]
+        fn test_double_to_bytes() {
+            assert_eq!(bytes(Variant::VDouble(0.0)), vec![0; 8]);
+            assert_eq!(
+                bytes(Variant::VDouble(1.0)),
+                vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x81]
+            );
+            assert_eq!(
+                bytes(Variant::VDouble(-10.0)),
+                vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x84]
+            );
+            assert_eq!(
+                bytes(Variant::VDouble(0.1)),
+                vec![0xd0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0x4c, 0x7d]
+            );
+        }
+
+        #[test]
+        fn test_read_single() {
+            let template = Variant::VSingle(0.0);
+            assert_eq!(read(template.clone(), &[0, 0, 0, 0]), Variant::VSingle(0.0));
+            assert_eq!(
+                read(template.clone(), &[0x00, 0x00, 0x80, 0x81]),
+                Variant::VSingle(-1.0)
+            );
+            assert_eq!(
+                read(template.clone(), &[0xcd, 0xcc, 0x4c, 0x7d]),
+                Variant::VSingle(0.1)
+            );
+            // the sign and the mantissa don't matter when the exponent is zero
+            assert_eq!(read(template, &[1, 2, 0x83, 0]), Variant::VSingle(0.0));
+        }
+
+        #[test]
+        fn test_read_double() {
+            let template = Variant::VDouble(0.0);
+            assert_eq!(
+                read(template.clone(), &[0, 0, 0, 0, 0, 0, 0xa0, 0x84]),
+                Variant::VDouble(-10.0)
+            );
+            assert_eq!(
+                read(template, &[0xd0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0x4c, 0x7d]),
+                Variant::VDouble(0.1)
+            );
+        }
+
+        #[test]
+        fn test_double_out_of_range() {
+            assert_eq!(
+                Variant::VDouble(1e300).to_bytes(),
+                Err("Overflow".to_string())
+            );
+            assert_eq!(bytes(Variant::VDouble(1e-300)), vec![0; 8]);
+        }
+    }
+
     mod fmt {
         use super::*;
 
@@ -335,6 +1040,34 @@ mod tests {
             assert_eq!(Variant::VInteger(42).to_string(), "42");
             assert_eq!(Variant::VLong(42).to_string(), "42");
         }
+
+        #[test]
+        fn test_fmt_single() {
+            assert_eq!(Variant::VSingle(0.0).to_string(), "0");
+            assert_eq!(Variant::VSingle(0.5).to_string(), ".5");
+            assert_eq!(Variant::VSingle(-0.5).to_string(), "-.5");
+            assert_eq!(Variant::VSingle(100.0).to_string(), "100");
+            assert_eq!(Variant::VSingle(1.0 / 3.0).to_string(), ".3333333");
+            assert_eq!(Variant::VSingle(2.0_f32.sqrt()).to_string(), "1.414214");
+            assert_eq!(Variant::VSingle(1234567.0).to_string(), "1234567");
+            assert_eq!(Variant::VSingle(10000000.0).to_string(), "1E+07");
+            assert_eq!(Variant::VSingle(-12345678.0).to_string(), "-1.234568E+07");
+            assert_eq!(Variant::VSingle(0.0000001).to_string(), ".0000001");
+            assert_eq!(Variant::VSingle(0.00000001).to_string(), "1E-08");
+            assert_eq!(Variant::VSingle(0.000000015).to_string(), "1.5E-08");
+        }
+
+        #[test]
+        fn test_fmt_double() {
+            assert_eq!(Variant::VDouble(0.5).to_string(), ".5");
+            assert_eq!(Variant::VDouble(1.0 / 3.0).to_string(), ".3333333333333333");
+            assert_eq!(Variant::VDouble(1e16).to_string(), "1D+16");
+            assert_eq!(
+                Variant::VDouble(1234567890123456.0).to_string(),
+                "1234567890123456"
+            );
+            assert_eq!(Variant::VDouble(2.5e-20).to_string(), "2.5D-20");
+        }
     }
 
     mod from {
@@ -815,6 +1548,168 @@ mod tests {
         }
     }
 
+    mod multiply {
+        use super::*;
+
+        #[test]
+        fn test_promotion() {
+            assert_eq!(
+                Variant::VInteger(6)
+                    .multiply(&Variant::VInteger(7))
+                    .unwrap(),
+                Variant::VInteger(42)
+            );
+            assert_eq!(
+                Variant::VInteger(6).multiply(&Variant::VLong(7)).unwrap(),
+                Variant::VLong(42)
+            );
+            assert_eq!(
+                Variant::VLong(6).multiply(&Variant::VSingle(0.5)).unwrap(),
+                Variant::VSingle(3.0)
+            );
+            assert_eq!(
+                Variant::VSingle(1.5)
+                    .multiply(&Variant::VDouble(2.0))
+                    .unwrap(),
+                Variant::VDouble(3.0)
+            );
+        }
+
+        #[test]
+        fn test_overflow() {
+            assert_eq!(
+                Variant::VInteger(200)
+                    .multiply(&Variant::VInteger(200))
+                    .unwrap_err(),
+                "Overflow"
+            );
+            assert_eq!(
+                Variant::VLong(MAX_LONG)
+                    .multiply(&Variant::VInteger(2))
+                    .unwrap_err(),
+                "Overflow"
+            );
+        }
+
+        #[test]
+        fn test_string() {
+            Variant::from("hi")
+                .multiply(&Variant::VInteger(2))
+                .expect_err("Type mismatch");
+            Variant::VInteger(2)
+                .multiply(&"hi".into())
+                .expect_err("Type mismatch");
+        }
+    }
+
+    mod divide {
+        use super::*;
+
+        #[test]
+        fn test_promotion() {
+            assert_eq!(
+                Variant::VInteger(5).divide(&Variant::VInteger(2)).unwrap(),
+                Variant::VSingle(2.5)
+            );
+            assert_eq!(
+                Variant::VLong(1).divide(&Variant::VDouble(4.0)).unwrap(),
+                Variant::VDouble(0.25)
+            );
+        }
+
+        #[test]
+        fn test_division_by_zero() {
+            assert_eq!(
+                Variant::VSingle(1.0)
+                    .divide(&Variant::VInteger(0))
+                    .unwrap_err(),
+                "Division by zero"
+            );
+        }
+    }
+
+    mod integer_divide {
+        use super::*;
+
+        #[test]
+        fn test_integer_divide() {
+            assert_eq!(
+                Variant::VInteger(7)
+                    .integer_divide(&Variant::VInteger(2))
+                    .unwrap(),
+                Variant::VInteger(3)
+            );
+            assert_eq!(
+                Variant::VSingle(7.6)
+                    .integer_divide(&Variant::VInteger(2))
+                    .unwrap(),
+                Variant::VLong(4)
+            );
+            assert_eq!(
+                Variant::VInteger(1)
+                    .integer_divide(&Variant::VSingle(0.4))
+                    .unwrap_err(),
+                "Division by zero"
+            );
+        }
+    }
+
+    mod modulo {
+        use super::*;
+
+        #[test]
+        fn test_modulo() {
+            assert_eq!(
+                Variant::VInteger(-7).modulo(&Variant::VInteger(3)).unwrap(),
+                Variant::VInteger(-1)
+            );
+            assert_eq!(
+                Variant::VLong(19).modulo(&Variant::VSingle(6.7)).unwrap(),
+                Variant::VLong(5)
+            );
+            assert_eq!(
+                Variant::VInteger(1)
+                    .modulo(&Variant::VInteger(0))
+                    .unwrap_err(),
+                "Division by zero"
+            );
+        }
+    }
+
+    mod power {
+        use super::*;
+
+        #[test]
+        fn test_power() {
+            assert_eq!(
+                Variant::VInteger(2).power(&Variant::VInteger(10)).unwrap(),
+                Variant::VSingle(1024.0)
+            );
+            assert_eq!(
+                Variant::VInteger(2).power(&Variant::VDouble(-1.0)).unwrap(),
+                Variant::VDouble(0.5)
+            );
+            assert_eq!(
+                Variant::VInteger(0)
+                    .power(&Variant::VInteger(-1))
+                    .unwrap_err(),
+                "Division by zero"
+            );
+            assert_eq!(
+                Variant::VInteger(-8)
+                    .power(&Variant::VSingle(0.5))
+                    .unwrap_err(),
+                "Illegal function call"
+            );
+            assert_eq!(
+                Variant::VDouble(10.0)
+                    .power(&Variant::VInteger(400))
+                    .unwrap_err(),
+                "Overflow"
+            );
+        }
+    }
+
     mod compare {
         use super::*;
 
@@ -962,4 +1857,79 @@ mod tests {
             assert_greater(Variant::from(5_i64), Variant::from(4_i64));
         }
     }
+
+    mod varray {
+        use super::*;
+
+        #[test]
+        fn test_elements_are_stored_in_row_major_order() {
+            let mut a = VArray::new(
+                TypeQualifier::PercentInteger,
+                vec![(1, 2), (0, 2)],
+                false,
+                None,
+            )
+            .unwrap();
+            a.set_element(&[Variant::from(2), Variant::from(1)], Variant::from(42))
+                .unwrap();
+            assert_eq!(
+                *a.get_element(&[Variant::from(2), Variant::from(1)])
+                    .unwrap(),
+                Variant::from(42)
+            );
+            assert_eq!(
+                *a.get_element(&[Variant::from(1), Variant::from(1)])
+                    .unwrap(),
+                Variant::from(0)
+            );
+        }
+
+        #[test]
+        fn test_index_is_rounded() {
+            let a = VArray::new(TypeQualifier::BangSingle, vec![(0, 3)], false, None).unwrap();
+            assert!(a.get_element(&[Variant::from(3.4)]).is_ok());
+            a.get_element(&[Variant::from(3.5)])
+                .expect_err("Subscript out of range");
+        }
+
+        #[test]
+        fn test_element_is_cast_to_element_type() {
+            let mut a =
+                VArray::new(TypeQualifier::PercentInteger, vec![(0, 1)], false, None).unwrap();
+            a.set_element(&[Variant::from(0)], Variant::from(1.6))
+                .unwrap();
+            assert_eq!(
+                *a.get_element(&[Variant::from(0)]).unwrap(),
+                Variant::from(2)
+            );
+            a.set_element(&[Variant::from(0)], Variant::from("hi"))
+                .expect_err("Type mismatch");
+        }
+
+        #[test]
+        fn test_invalid_bounds() {
+            VArray::new(TypeQualifier::PercentInteger, vec![(2, 1)], false, None)
+                .expect_err("Subscript out of range");
+        }
+
+        #[test]
+        fn test_erase() {
+            let mut a =
+                VArray::new(TypeQualifier::PercentInteger, vec![(0, 1)], false, None).unwrap();
+            a.set_element(&[Variant::from(1)], Variant::from(5))
+                .unwrap();
+            a.erase();
+            assert_eq!(
+                *a.get_element(&[Variant::from(1)]).unwrap(),
+                Variant::from(0)
+            );
+
+            let mut a =
+                VArray::new(TypeQualifier::PercentInteger, vec![(0, 1)], true, None).unwrap();
+            a.erase();
+            a.get_element(&[Variant::from(1)])
+                .expect_err("Subscript out of range");
+            a.ubound(1).expect_err("Subscript out of range");
+        }
+    }
 }
@@ -8,6 +8,11 @@ use crate::options::{BasicMode, Options};
 use crate::temp_files::TempFiles;
 
 pub fn create_batch_file(options: &Options, temp_files: &TempFiles) -> Result<(), io::Error> {
+    let switch = match options.mode {
+        BasicMode::GWBasic => " ",
+        BasicMode::QBasic => " /RUN ",
+        BasicMode::Native => unreachable!("native mode does not use batch files"),
+    };
     let mut f = File::create(&temp_files.batch_file)?;
     copy_env(&mut f)?;
     write!(
@@ -27,14 +32,7 @@ pub fn create_batch_file(options: &Options, temp_files: &TempFiles) -> Result<()
     )?;
     // C:\BIN\GWBASIC.EXE
     write!(f, "{}", from_dos(&options.basic, &temp_files.batch_dir))?;
-    write!(
-        f,
-        "{}",
-        match options.mode {
-            BasicMode::GWBasic => " ",
-            BasicMode::QBasic => " /RUN ",
-        }
-    )?;
+    write!(f, "{}", switch)?;
     // PROGRAM.BAS
     write!(
         f,
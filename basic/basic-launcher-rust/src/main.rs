@@ -1,3 +1,4 @@
+use std::env;
 use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
@@ -7,32 +8,69 @@ use std::path::PathBuf;
 use std::process::Command;
 
 mod batch_file;
+mod native_stdlib;
 mod options;
 mod rand_file;
 mod temp_files;
 
 use batch_file::create_batch_file;
+use native_stdlib::NativeStdlib;
+use options::BasicMode;
+use rand_file::make_unique_random_filename;
 use temp_files::TempFiles;
 
 fn main() {
     let options = options::parse_options();
-    let temp_files = TempFiles::create(&options);
-    create_stdin(&options, &temp_files.stdin_file).expect("Could not create stdin");
-    create_batch_file(&options, &temp_files).expect("Could not create batch file");
-    run_dosbox(&options, &temp_files);
+    match options.mode {
+        BasicMode::Native => run_native(&options),
+        _ => run_with_dosbox(&options),
+    }
+}
+
+/// Runs the program with basic-interpreter-rust, in the directory of the program.
+/// The request body is written to a temporary file, which the `STDIN` environment
+/// variable points to, like in DOSBox. The response goes to stdout.
+fn run_native(options: &options::Options) {
+    let program_dir = options.program.parent().unwrap();
+    env::set_current_dir(program_dir).expect("Could not change to the directory of the program");
+    let stdin_file = make_unique_random_filename(program_dir, "INP");
+    create_stdin(options, stdin().lock(), &stdin_file).expect("Could not create stdin");
+    if let Err(e) = run_native_with_stdin(options, stdin_file) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run_native_with_stdin(
+    options: &options::Options,
+    stdin_file: PathBuf,
+) -> Result<(), basic_interpreter_rust::Error> {
+    env::set_var("STDIN", &stdin_file);
+    let program = File::open(&options.program).expect("Could not open program");
+    basic_interpreter_rust::run(program, NativeStdlib::new(stdin_file, options.cleanup))
+}
+
+fn run_with_dosbox(options: &options::Options) {
+    let temp_files = TempFiles::create(options);
+    create_stdin(options, stdin().lock(), &temp_files.stdin_file).expect("Could not create stdin");
+    create_batch_file(options, &temp_files).expect("Could not create batch file");
+    run_dosbox(options, &temp_files);
     print_stdout(&temp_files).expect("Could not read stdout");
     if options.cleanup {
         cleanup(&temp_files).expect("Could not cleanup files");
     }
 }
 
-fn create_stdin(options: &options::Options, stdin_file: &PathBuf) -> std::io::Result<()> {
+fn create_stdin<R: BufRead>(
+    options: &options::Options,
+    mut input: R,
+    stdin_file: &PathBuf,
+) -> std::io::Result<()> {
     let mut f = File::create(stdin_file)?;
     if options.needs_stdin {
-        let stdin = stdin();
         loop {
             let mut line = String::new();
-            let num_bytes = stdin.read_line(&mut line)?;
+            let num_bytes = input.read_line(&mut line)?;
             if num_bytes == 0 {
                 break;
             }
@@ -97,3 +135,53 @@ fn remove_if_exists(p: &PathBuf) -> std::io::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_native_with_post_body() {
+        // arrange
+        let dir = make_unique_random_filename(&env::temp_dir(), "DIR");
+        fs::create_dir(&dir).unwrap();
+        let program = dir.join("PROGRAM.BAS");
+        let output = dir.join("OUTPUT.TXT");
+        fs::write(
+            &program,
+            format!(
+                "OPEN ENVIRON$(\"STDIN\") FOR INPUT AS #1\n\
+                 LINE INPUT #1, T$\n\
+                 CLOSE #1\n\
+                 OPEN \"{}\" FOR OUTPUT AS #1\n\
+                 PRINT #1, T$\n\
+                 CLOSE #1\n",
+                output.display()
+            ),
+        )
+        .unwrap();
+        let options = options::Options {
+            dosbox: String::new(),
+            dosbox_conf: String::new(),
+            basic: PathBuf::new(),
+            mode: BasicMode::Native,
+            needs_stdin: true,
+            program,
+            cleanup: true,
+        };
+        let stdin_file = make_unique_random_filename(&dir, "INP");
+
+        // act
+        create_stdin(&options, "buy milk\n".as_bytes(), &stdin_file).unwrap();
+        let result = run_native_with_stdin(&options, stdin_file.clone());
+        let contents = fs::read_to_string(&output).unwrap();
+
+        // cleanup
+        fs::remove_dir_all(&dir).unwrap();
+
+        // assert
+        assert!(result.is_ok());
+        assert_eq!(contents.trim_end(), "buy milk");
+        assert!(!stdin_file.exists());
+    }
+}
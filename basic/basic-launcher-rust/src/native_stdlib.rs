@@ -0,0 +1,114 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use basic_interpreter_rust::{DefaultStdlib, Stdlib};
+
+/// The standard library of the native mode.
+///
+/// It removes the file with the request body when the program ends,
+/// either normally or with SYSTEM, which exits the process.
+pub struct NativeStdlib {
+    stdlib: DefaultStdlib,
+    stdin_file: PathBuf,
+    cleanup: bool,
+}
+
+impl NativeStdlib {
+    pub fn new(stdin_file: PathBuf, cleanup: bool) -> Self {
+        Self {
+            stdlib: DefaultStdlib::default(),
+            stdin_file,
+            cleanup,
+        }
+    }
+
+    fn remove_stdin_file(&self) {
+        if self.cleanup && self.stdin_file.exists() {
+            fs::remove_file(&self.stdin_file).unwrap_or_default();
+        }
+    }
+}
+
+impl Drop for NativeStdlib {
+    fn drop(&mut self) {
+        self.remove_stdin_file();
+    }
+}
+
+impl Stdlib for NativeStdlib {
+    fn print(&mut self, text: &str) {
+        self.stdlib.print(text)
+    }
+
+    fn println(&mut self) {
+        self.stdlib.println()
+    }
+
+    fn lprint(&mut self, text: &str) {
+        self.stdlib.lprint(text)
+    }
+
+    fn system(&self) {
+        self.remove_stdin_file();
+        self.stdlib.system()
+    }
+
+    fn input(&mut self) -> io::Result<String> {
+        self.stdlib.input()
+    }
+
+    fn input_bytes(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        self.stdlib.input_bytes(count)
+    }
+
+    fn now(&self) -> SystemTime {
+        self.stdlib.now()
+    }
+
+    fn utc_offset(&self) -> i32 {
+        self.stdlib.utc_offset()
+    }
+
+    fn sleep(&mut self, seconds: u32) {
+        self.stdlib.sleep(seconds)
+    }
+
+    fn get_env_var(&self, name: &String) -> String {
+        self.stdlib.get_env_var(name)
+    }
+
+    fn set_env_var(&mut self, name: String, value: String) {
+        self.stdlib.set_env_var(name, value)
+    }
+
+    fn read_file(&self, file_name: &str) -> io::Result<Vec<u8>> {
+        self.stdlib.read_file(file_name)
+    }
+
+    fn open_file(&mut self, file_number: i32, file_name: &str, append: bool) -> io::Result<usize> {
+        self.stdlib.open_file(file_number, file_name, append)
+    }
+
+    fn write_file(
+        &mut self,
+        file_number: i32,
+        offset: Option<usize>,
+        contents: &[u8],
+    ) -> io::Result<()> {
+        self.stdlib.write_file(file_number, offset, contents)
+    }
+
+    fn close_file(&mut self, file_number: i32) -> io::Result<()> {
+        self.stdlib.close_file(file_number)
+    }
+
+    fn kill_file(&mut self, file_name: &str) -> io::Result<()> {
+        self.stdlib.kill_file(file_name)
+    }
+
+    fn rename_file(&mut self, old_name: &str, new_name: &str) -> io::Result<()> {
+        self.stdlib.rename_file(old_name, new_name)
+    }
+}
@@ -18,12 +18,15 @@ const EV_DOSBOX_CONF: &str = "BLR_DOSBOX_CONF";
 pub enum BasicMode {
     GWBasic,
     QBasic,
+    /// Runs the program in-process with basic-interpreter-rust, without DOSBox
+    Native,
 }
 
 #[derive(Debug)]
 pub struct Options {
     pub dosbox: String,
     pub dosbox_conf: String,
+    /// The BASIC interpreter that runs in DOSBox, empty in native mode
     pub basic: PathBuf,
     pub mode: BasicMode,
     pub needs_stdin: bool,
@@ -64,8 +67,16 @@ fn parse_dosbox_conf() -> String {
 }
 
 fn parse_basic() -> (PathBuf, BasicMode) {
+    parse_basic_with_mode(&get_redirect_env(EV_BASIC_MODE))
+}
+
+/// Parses the interpreter for the given value of `BLR_BASIC_MODE`.
+fn parse_basic_with_mode(basic_mode: &str) -> (PathBuf, BasicMode) {
+    if basic_mode == "native" {
+        return (PathBuf::new(), BasicMode::Native);
+    }
     let non_canonic =
-        parse_non_canonic().expect("Please specify the location of the basic interpreter");
+        parse_non_canonic(basic_mode).expect("Please specify the location of the basic interpreter");
     let exe = match fs::canonicalize(&non_canonic.0) {
         Ok(p) => p,
         Err(e) => panic!("Could not find interpreter {}: {}", &non_canonic.0, e),
@@ -74,19 +85,14 @@ fn parse_basic() -> (PathBuf, BasicMode) {
     (exe, non_canonic.1)
 }
 
-fn parse_non_canonic() -> Option<(String, BasicMode)> {
-    if is_explicit_qb() {
+fn parse_non_canonic(basic_mode: &str) -> Option<(String, BasicMode)> {
+    if basic_mode == "qbasic" {
         parse_qbasic()
     } else {
         parse_gwbasic().or_else(parse_qbasic)
     }
 }
 
-fn is_explicit_qb() -> bool {
-    let v = get_redirect_env(EV_BASIC_MODE);
-    v == "qbasic"
-}
-
 fn parse_gwbasic() -> Option<(String, BasicMode)> {
     let gwbasic = get_redirect_env(EV_GWBASIC);
     if gwbasic.is_empty() {
@@ -224,6 +230,16 @@ mod tests {
         assert_eq!(dos_box, "dosbox");
     }
 
+    #[test]
+    fn test_parse_basic_native() {
+        let b = parse_basic_with_mode("native");
+        assert_eq!(b.0, PathBuf::new());
+        assert!(match b.1 {
+            BasicMode::Native => true,
+            _ => false,
+        });
+    }
+
     #[test]
     fn test_parse_basic_gwbasic() {
         env::set_var(EV_GWBASIC, "..\\bin\\GWBASIC.EXE");